@@ -0,0 +1,123 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::LinkedList;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use radonkv::mem::QuickList;
+
+const LIST_LEN: usize = 1_000_000;
+
+fn new_quick_list() -> QuickList {
+    (0..LIST_LEN).map(|i| format!("element-{i}")).collect()
+}
+
+fn new_linked_list() -> LinkedList<Vec<u8>> {
+    (0..LIST_LEN)
+        .map(|i| format!("element-{i}").into_bytes())
+        .collect()
+}
+
+fn bench_push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push 1M");
+    group.sample_size(10);
+    group.bench_function("quick list", |b| {
+        b.iter(|| {
+            let mut list = QuickList::new();
+            for i in 0..LIST_LEN {
+                list.push_back(format!("element-{i}").as_bytes());
+            }
+            black_box(list)
+        });
+    });
+    group.bench_function("linked list", |b| {
+        b.iter(|| {
+            let mut list = LinkedList::new();
+            for i in 0..LIST_LEN {
+                list.push_back(format!("element-{i}").into_bytes());
+            }
+            black_box(list)
+        });
+    });
+    group.finish();
+}
+
+fn bench_index(c: &mut Criterion) {
+    let quick_list = new_quick_list();
+    let linked_list = new_linked_list();
+    let mut group = c.benchmark_group("index 1M");
+    group.bench_function("quick list", |b| {
+        b.iter(|| black_box(quick_list.get(black_box(LIST_LEN / 3))));
+    });
+    group.bench_function("linked list", |b| {
+        b.iter(|| black_box(linked_list.iter().nth(black_box(LIST_LEN / 3))));
+    });
+    group.finish();
+}
+
+fn bench_range(c: &mut Criterion) {
+    let quick_list = new_quick_list();
+    let linked_list = new_linked_list();
+    let mut group = c.benchmark_group("range 100 of 1M");
+    group.bench_function("quick list", |b| {
+        b.iter(|| {
+            quick_list
+                .iter_from(black_box(LIST_LEN / 3))
+                .take(100)
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>()
+        });
+    });
+    group.bench_function("linked list", |b| {
+        b.iter(|| {
+            linked_list
+                .iter()
+                .skip(black_box(LIST_LEN / 3))
+                .take(100)
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+    });
+    group.finish();
+}
+
+fn bench_set(c: &mut Criterion) {
+    let mut quick_list = new_quick_list();
+    let mut group = c.benchmark_group("set 1M");
+    group.bench_function("quick list", |b| {
+        b.iter(|| quick_list.set(black_box(LIST_LEN / 3), b"new-element"));
+    });
+    group.finish();
+}
+
+fn bench_insert_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert and remove 1M");
+    group.sample_size(10);
+    group.bench_function("quick list", |b| {
+        b.iter_batched_ref(
+            new_quick_list,
+            |list| {
+                for _i in 0..1000 {
+                    list.insert(LIST_LEN / 2, b"new-element");
+                }
+                for _i in 0..1000 {
+                    black_box(list.remove(LIST_LEN / 2));
+                }
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_push,
+    bench_index,
+    bench_range,
+    bench_set,
+    bench_insert_remove
+);
+criterion_main!(benches);
@@ -9,6 +9,7 @@ use crate::commands::{ClusterToDispatcherCmd, DispatcherToClusterCmd};
 mod commands;
 mod dispatcher;
 pub mod run;
+pub mod slot;
 
 #[derive(Debug)]
 pub struct Cluster {
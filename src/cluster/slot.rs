@@ -0,0 +1,62 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Hash slots of keys, same as redis cluster.
+//!
+//! Keys and shard channels are partitioned into hash slots, a partition owns
+//! a range of slots.
+
+/// Number of hash slots.
+pub const SLOT_COUNT: u16 = 16384;
+
+/// Returns hash slot of key.
+///
+/// If key contains a non-empty hash tag like `{user1000}.following`, only the
+/// hash tag is hashed, so that multiple keys can be placed in the same slot.
+#[must_use]
+pub fn key_slot(key: &[u8]) -> u16 {
+    let tag = key.iter().position(|&c| c == b'{').and_then(|start| {
+        key[start + 1..]
+            .iter()
+            .position(|&c| c == b'}')
+            .filter(|&len| len > 0)
+            .map(|len| &key[start + 1..start + 1 + len])
+    });
+    crc16(tag.unwrap_or(key)) % SLOT_COUNT
+}
+
+/// CRC16 with XMODEM variant.
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in bytes {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cluster::slot::{crc16, key_slot};
+
+    #[test]
+    fn test_key_slot() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"bar"), 5061);
+        assert_eq!(key_slot(b"{foo}.bar"), key_slot(b"foo"));
+        assert_eq!(
+            key_slot(b"{user1000}.following"),
+            key_slot(b"{user1000}.followers")
+        );
+        // Empty hash tag is ignored.
+        assert_eq!(key_slot(b"{}.foo"), crc16(b"{}.foo") % 16384);
+    }
+}
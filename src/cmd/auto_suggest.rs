@@ -0,0 +1,139 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Default number of suggestions returned by `FT.SUGGET`.
+pub const DEFAULT_MAX: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddOptions {
+    pub key: String,
+    pub string: String,
+    pub score: f64,
+    /// Increment the existing score instead of replacing it.
+    pub incr: bool,
+    pub payload: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GetOptions {
+    pub key: String,
+    pub prefix: String,
+    /// Match prefixes with Levenshtein distance of 1.
+    pub fuzzy: bool,
+    pub max: usize,
+    pub with_scores: bool,
+    pub with_payloads: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum AutoSuggestCommand {
+    Add(Box<AddOptions>),
+    Del(String, String),
+    Get(Box<GetOptions>),
+    Len(String),
+}
+
+impl AutoSuggestCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let auto_suggest_cmd = match cmd_name {
+            "ft.sugadd" => Self::Add(Box::new(Self::parse_add(parser)?)),
+            "ft.sugdel" => Self::Del(parser.next_string()?, parser.next_string()?),
+            "ft.sugget" => Self::Get(Box::new(Self::parse_get(parser)?)),
+            "ft.suglen" => Self::Len(parser.next_string()?),
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::AutoSuggest(auto_suggest_cmd)))
+    }
+
+    /// Parse `key string score [INCR] [PAYLOAD payload]` arguments.
+    fn parse_add(parser: &mut Parser) -> Result<AddOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let string = parser.next_string()?;
+        let score = parser.next_f64()?;
+        if !score.is_finite() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let mut options = AddOptions {
+            key,
+            string,
+            score,
+            incr: false,
+            payload: None,
+        };
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "incr" => options.incr = true,
+                "payload" => options.payload = Some(parser.next_string()?),
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Parse `key prefix [FUZZY] [WITHSCORES] [WITHPAYLOADS] [MAX max]` arguments.
+    fn parse_get(parser: &mut Parser) -> Result<GetOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let prefix = parser.next_string()?;
+        let mut options = GetOptions {
+            key,
+            prefix,
+            fuzzy: false,
+            max: DEFAULT_MAX,
+            with_scores: false,
+            with_payloads: false,
+        };
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "fuzzy" => options.fuzzy = true,
+                "max" => options.max = parser.next_usize()?,
+                "withscores" => options.with_scores = true,
+                "withpayloads" => options.with_payloads = true,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::auto_suggest::{AutoSuggestCommand, DEFAULT_MAX};
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        AutoSuggestCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::AutoSuggest(AutoSuggestCommand::Add(options)))) = parse(
+            "ft.sugadd",
+            &["sug", "hello world", "2.5", "INCR", "PAYLOAD", "data"],
+        ) else {
+            panic!("expected FT.SUGADD command");
+        };
+        assert_eq!(options.string, "hello world");
+        assert!(options.incr);
+        assert_eq!(options.payload.as_deref(), Some("data"));
+        assert!(parse("ft.sugadd", &["sug", "hello", "abc"]).is_err());
+
+        let Ok(Some(Command::AutoSuggest(AutoSuggestCommand::Get(options)))) =
+            parse("ft.sugget", &["sug", "hel", "FUZZY", "WITHSCORES"])
+        else {
+            panic!("expected FT.SUGGET command");
+        };
+        assert!(options.fuzzy);
+        assert!(options.with_scores);
+        assert!(!options.with_payloads);
+        assert_eq!(options.max, DEFAULT_MAX);
+        assert!(parse("ft.sugget", &["sug", "hel", "MAX"]).is_err());
+    }
+}
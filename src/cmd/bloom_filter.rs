@@ -2,9 +2,85 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::f64::consts::LN_2;
+
 use crate::cmd::parse::{ParseCommandError, Parser};
 use crate::cmd::Command;
 
+/// Capacity of filter created implicitly by `BF.ADD` or `BF.INSERT`.
+pub const DEFAULT_CAPACITY: usize = 100;
+/// Default false positive rate.
+pub const DEFAULT_ERROR_RATE: f64 = 0.01;
+/// Default growth factor of capacity of new sub-filter.
+pub const DEFAULT_EXPANSION: usize = 2;
+/// Maximum capacity of each sub-filter.
+pub const MAX_CAPACITY: usize = 1 << 30;
+/// Maximum growth factor of capacity of new sub-filter.
+pub const MAX_EXPANSION: usize = 32768;
+/// Maximum number of bits of each sub-filter, which is 1GiB.
+pub const MAX_BITS: u64 = 8 << 30;
+
+/// Returns number of bits of a sub-filter with `capacity` and `error_rate`,
+/// rounded up to whole bytes.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_precision_loss)]
+#[allow(clippy::cast_sign_loss)]
+pub fn bitmap_bits(capacity: usize, error_rate: f64) -> u64 {
+    let num_bits = -(capacity as f64) * error_rate.ln() / (LN_2 * LN_2);
+    (num_bits.ceil() as u64)
+        .div_ceil(8)
+        .max(1)
+        .saturating_mul(8)
+}
+
+/// Returns true if a sub-filter with `capacity` and `error_rate` does not exceed
+/// `MAX_CAPACITY` and `MAX_BITS`.
+#[must_use]
+pub fn is_valid_size(capacity: usize, error_rate: f64) -> bool {
+    (1..=MAX_CAPACITY).contains(&capacity) && bitmap_bits(capacity, error_rate) <= MAX_BITS
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReserveOptions {
+    /// Desired probability for false positives, in range (0, 1).
+    pub error_rate: f64,
+    /// Number of items expected to be added to the first sub-filter.
+    pub capacity: usize,
+    /// Capacity of a new sub-filter is capacity of the last one multiplied by
+    /// expansion, 0 means filter can not be expanded.
+    pub expansion: usize,
+}
+
+impl Default for ReserveOptions {
+    fn default() -> Self {
+        Self {
+            error_rate: DEFAULT_ERROR_RATE,
+            capacity: DEFAULT_CAPACITY,
+            expansion: DEFAULT_EXPANSION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertOptions {
+    pub key: String,
+    /// Options of filter if it is created.
+    pub reserve: ReserveOptions,
+    /// Do not create filter if key does not exist.
+    pub no_create: bool,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InfoField {
+    Capacity,
+    Size,
+    Filters,
+    Items,
+    Expansion,
+}
+
 #[derive(Debug, Clone)]
 pub enum BloomFilterCommand {
     Add(String, String),
@@ -12,6 +88,13 @@ pub enum BloomFilterCommand {
     Exists(String, String),
     MultiExists(String, Vec<String>),
     Len(String),
+    Info(String, Option<InfoField>),
+    Insert(Box<InsertOptions>),
+    /// Key, iterator and data chunk.
+    LoadChunk(String, usize, Vec<u8>),
+    Reserve(String, ReserveOptions),
+    /// Key and iterator.
+    ScanDump(String, usize),
 }
 
 impl BloomFilterCommand {
@@ -35,7 +118,7 @@ impl BloomFilterCommand {
                 let item = parser.next_string()?;
                 Self::Exists(key, item)
             }
-            "BF.MEXISTS" => {
+            "bf.mexists" => {
                 let key = parser.next_string()?;
                 let items = parser.remaining_strings()?;
                 Self::MultiExists(key, items)
@@ -44,8 +127,195 @@ impl BloomFilterCommand {
                 let key = parser.next_string()?;
                 Self::Len(key)
             }
+            "bf.info" => {
+                let key = parser.next_string()?;
+                let field = match parser.try_next_string()? {
+                    Some(field) => Some(match field.to_ascii_lowercase().as_str() {
+                        "capacity" => InfoField::Capacity,
+                        "size" => InfoField::Size,
+                        "filters" => InfoField::Filters,
+                        "items" => InfoField::Items,
+                        "expansion" => InfoField::Expansion,
+                        _ => return Err(ParseCommandError::InvalidParameter),
+                    }),
+                    None => None,
+                };
+                Self::Info(key, field)
+            }
+            "bf.insert" => Self::Insert(Box::new(Self::parse_insert(parser)?)),
+            "bf.loadchunk" => {
+                let key = parser.next_string()?;
+                let iter = parser.next_usize()?;
+                let data = parser.next_bytes()?;
+                Self::LoadChunk(key, iter, data)
+            }
+            "bf.reserve" => {
+                let key = parser.next_string()?;
+                let options = Self::parse_reserve(parser)?;
+                Self::Reserve(key, options)
+            }
+            "bf.scandump" => {
+                let key = parser.next_string()?;
+                let iter = parser.next_usize()?;
+                Self::ScanDump(key, iter)
+            }
             _ => return Ok(None),
         };
         Ok(Some(Command::BloomFilter(bloom_filter_cmd)))
     }
+
+    /// Parse `error_rate capacity [EXPANSION expansion] [NONSCALING]` arguments.
+    fn parse_reserve(parser: &mut Parser) -> Result<ReserveOptions, ParseCommandError> {
+        let mut options = ReserveOptions {
+            error_rate: parser.next_f64()?,
+            capacity: parser.next_usize()?,
+            ..ReserveOptions::default()
+        };
+        let mut expansion = None;
+        let mut non_scaling = false;
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "expansion" => expansion = Some(parser.next_usize()?),
+                "nonscaling" => non_scaling = true,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        Self::apply_expansion(&mut options, expansion, non_scaling)?;
+        Ok(options)
+    }
+
+    /// Parse `key [CAPACITY capacity] [ERROR error] [EXPANSION expansion]
+    /// [NOCREATE] [NONSCALING] ITEMS item [item ...]` arguments.
+    fn parse_insert(parser: &mut Parser) -> Result<InsertOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut reserve = ReserveOptions::default();
+        let mut expansion = None;
+        let mut non_scaling = false;
+        let mut no_create = false;
+        let mut has_reserve_option = false;
+        loop {
+            let token = parser.next_string()?;
+            match token.to_ascii_lowercase().as_str() {
+                "capacity" => {
+                    reserve.capacity = parser.next_usize()?;
+                    has_reserve_option = true;
+                }
+                "error" => {
+                    reserve.error_rate = parser.next_f64()?;
+                    has_reserve_option = true;
+                }
+                "expansion" => expansion = Some(parser.next_usize()?),
+                "nocreate" => no_create = true,
+                "nonscaling" => non_scaling = true,
+                "items" => break,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        if no_create && has_reserve_option {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Self::apply_expansion(&mut reserve, expansion, non_scaling)?;
+        let items = parser.remaining_strings()?;
+        if items.is_empty() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(InsertOptions {
+            key,
+            reserve,
+            no_create,
+            items,
+        })
+    }
+
+    /// Validate options, `EXPANSION` and `NONSCALING` are mutually exclusive.
+    fn apply_expansion(
+        options: &mut ReserveOptions,
+        expansion: Option<usize>,
+        non_scaling: bool,
+    ) -> Result<(), ParseCommandError> {
+        match (expansion, non_scaling) {
+            (Some(_), true) | (Some(0), false) => {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+            (Some(expansion), false) if expansion > MAX_EXPANSION => {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+            (Some(expansion), false) => options.expansion = expansion,
+            (None, true) => options.expansion = 0,
+            (None, false) => (),
+        }
+        let error_rate = options.error_rate;
+        if !(error_rate > 0.0 && error_rate < 1.0 && is_valid_size(options.capacity, error_rate)) {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::{BloomFilterCommand, InfoField, DEFAULT_CAPACITY, MAX_CAPACITY};
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        BloomFilterCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::BloomFilter(BloomFilterCommand::Reserve(_key, options)))) =
+            parse("bf.reserve", &["bf", "0.001", "1000", "NONSCALING"])
+        else {
+            panic!("expected BF.RESERVE command");
+        };
+        assert_eq!(options.capacity, 1000);
+        assert_eq!(options.expansion, 0);
+        assert!(parse("bf.reserve", &["bf", "1.5", "1000"]).is_err());
+        assert!(parse(
+            "bf.reserve",
+            &["bf", "0.1", "10", "EXPANSION", "2", "NONSCALING"]
+        )
+        .is_err());
+
+        let Ok(Some(Command::BloomFilter(BloomFilterCommand::Insert(options)))) =
+            parse("bf.insert", &["bf", "ERROR", "0.1", "ITEMS", "a", "b"])
+        else {
+            panic!("expected BF.INSERT command");
+        };
+        assert_eq!(options.reserve.capacity, DEFAULT_CAPACITY);
+        assert_eq!(options.items, ["a", "b"]);
+        assert!(parse(
+            "bf.insert",
+            &["bf", "NOCREATE", "CAPACITY", "10", "ITEMS", "a"]
+        )
+        .is_err());
+
+        let Ok(Some(Command::BloomFilter(BloomFilterCommand::Info(_key, field)))) =
+            parse("bf.info", &["bf", "ITEMS"])
+        else {
+            panic!("expected BF.INFO command");
+        };
+        assert_eq!(field, Some(InfoField::Items));
+        let huge = (MAX_CAPACITY + 1).to_string();
+        assert!(parse("bf.reserve", &["bf", "0.01", &huge]).is_err());
+        assert!(parse("bf.reserve", &["bf", "1e-300", "100000000"]).is_err());
+        assert!(parse("bf.reserve", &["bf", "0.01", "100", "EXPANSION", "32769"]).is_err());
+        assert!(parse("bf.insert", &["bf", "CAPACITY", &huge, "ITEMS", "a"]).is_err());
+        assert!(matches!(
+            parse("bf.loadchunk", &["bf", "1", "data"]),
+            Ok(Some(Command::BloomFilter(BloomFilterCommand::LoadChunk(
+                _,
+                1,
+                _
+            ))))
+        ));
+        assert!(parse("bf.scandump", &["bf", "-1"]).is_err());
+        assert!(matches!(
+            parse("bf.mexists", &["bf", "a"]),
+            Ok(Some(Command::BloomFilter(BloomFilterCommand::MultiExists(
+                ..
+            ))))
+        ));
+    }
 }
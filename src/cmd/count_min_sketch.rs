@@ -0,0 +1,113 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+#[derive(Debug, Clone)]
+pub enum CountMinSketchCommand {
+    IncrBy(String, Vec<(String, usize)>),
+    Info(String),
+    /// Key, width and depth.
+    InitByDim(String, usize, usize),
+    /// Key, estimate error and probability of over estimation.
+    InitByProb(String, f64, f64),
+    /// Destination key, source keys and weight of each source.
+    Merge(String, Vec<String>, Vec<i64>),
+    Query(String, Vec<String>),
+}
+
+impl CountMinSketchCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let cms_cmd = match cmd_name {
+            "cms.incrby" => {
+                let key = parser.next_string()?;
+                let mut items = Vec::new();
+                while let Some(item) = parser.try_next_string()? {
+                    items.push((item, parser.next_usize()?));
+                }
+                if items.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::IncrBy(key, items)
+            }
+            "cms.info" => Self::Info(parser.next_string()?),
+            "cms.initbydim" => {
+                let key = parser.next_string()?;
+                let width = parser.next_usize()?;
+                let depth = parser.next_usize()?;
+                if width == 0 || depth == 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::InitByDim(key, width, depth)
+            }
+            "cms.initbyprob" => {
+                let key = parser.next_string()?;
+                let error = parser.next_f64()?;
+                let probability = parser.next_f64()?;
+                // Both must be in range (0, 1).
+                if !(error > 0.0 && error < 1.0 && probability > 0.0 && probability < 1.0) {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::InitByProb(key, error, probability)
+            }
+            "cms.merge" => {
+                let dest = parser.next_string()?;
+                let num_keys = parser.next_usize()?;
+                if num_keys == 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let sources = (0..num_keys)
+                    .map(|_| parser.next_string())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let weights = match parser.try_next_string()? {
+                    Some(token) if token.eq_ignore_ascii_case("weights") => (0..num_keys)
+                        .map(|_| parser.next_i64())
+                        .collect::<Result<Vec<_>, _>>()?,
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => vec![1; num_keys],
+                };
+                Self::Merge(dest, sources, weights)
+            }
+            "cms.query" => {
+                let key = parser.next_string()?;
+                let items = parser.remaining_strings()?;
+                Self::Query(key, items)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::CountMinSketch(cms_cmd)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::count_min_sketch::CountMinSketchCommand;
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        CountMinSketchCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::CountMinSketch(CountMinSketchCommand::Merge(dest, sources, weights)))) =
+            parse("cms.merge", &["dest", "2", "a", "b", "WEIGHTS", "1", "-2"])
+        else {
+            panic!("expected CMS.MERGE command");
+        };
+        assert_eq!(dest, "dest");
+        assert_eq!(sources, ["a", "b"]);
+        assert_eq!(weights, [1, -2]);
+        assert!(parse("cms.merge", &["dest", "2", "a"]).is_err());
+        assert!(parse("cms.merge", &["dest", "1", "a", "WEIGHTS"]).is_err());
+        assert!(parse("cms.initbyprob", &["key", "0.01", "1.5"]).is_err());
+        assert!(parse("cms.initbydim", &["key", "0", "5"]).is_err());
+        assert!(parse("cms.incrby", &["key", "a"]).is_err());
+    }
+}
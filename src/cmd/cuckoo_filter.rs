@@ -0,0 +1,214 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Capacity of filter created implicitly by `CF.ADD` or `CF.INSERT`.
+pub const DEFAULT_CAPACITY: usize = 1024;
+/// Default number of items in each bucket.
+pub const DEFAULT_BUCKET_SIZE: usize = 2;
+/// Default number of attempts to swap items between buckets before expanding.
+pub const DEFAULT_MAX_ITERATIONS: usize = 20;
+/// Default growth factor of capacity of new sub-filter.
+pub const DEFAULT_EXPANSION: usize = 1;
+/// Maximum capacity of each sub-filter.
+pub const MAX_CAPACITY: usize = 1 << 30;
+/// Maximum number of items in each bucket.
+pub const MAX_BUCKET_SIZE: usize = 255;
+/// Maximum number of attempts to swap items between buckets.
+pub const MAX_ITERATIONS: usize = 65535;
+/// Maximum growth factor of capacity of new sub-filter.
+pub const MAX_EXPANSION: usize = 32768;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ReserveOptions {
+    pub capacity: usize,
+    pub bucket_size: usize,
+    pub max_iterations: usize,
+    /// Capacity of a new sub-filter is capacity of the last one multiplied by
+    /// expansion, 0 means filter can not be expanded.
+    pub expansion: usize,
+}
+
+impl ReserveOptions {
+    #[must_use]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            bucket_size: DEFAULT_BUCKET_SIZE,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            expansion: DEFAULT_EXPANSION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InsertOptions {
+    pub key: String,
+    /// Capacity of filter if it is created.
+    pub capacity: Option<usize>,
+    /// Do not create filter if key does not exist.
+    pub no_create: bool,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CuckooFilterCommand {
+    Add(String, String),
+    AddNx(String, String),
+    Count(String, String),
+    Del(String, String),
+    Exists(String, String),
+    Info(String),
+    Insert(Box<InsertOptions>),
+    InsertNx(Box<InsertOptions>),
+    /// Key, iterator and data chunk.
+    LoadChunk(String, usize, Vec<u8>),
+    MultiExists(String, Vec<String>),
+    Reserve(String, ReserveOptions),
+    /// Key and iterator.
+    ScanDump(String, usize),
+}
+
+impl CuckooFilterCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let cuckoo_filter_cmd = match cmd_name {
+            "cf.add" => Self::Add(parser.next_string()?, parser.next_string()?),
+            "cf.addnx" => Self::AddNx(parser.next_string()?, parser.next_string()?),
+            "cf.count" => Self::Count(parser.next_string()?, parser.next_string()?),
+            "cf.del" => Self::Del(parser.next_string()?, parser.next_string()?),
+            "cf.exists" => Self::Exists(parser.next_string()?, parser.next_string()?),
+            "cf.info" => Self::Info(parser.next_string()?),
+            "cf.insert" => Self::Insert(Box::new(Self::parse_insert(parser)?)),
+            "cf.insertnx" => Self::InsertNx(Box::new(Self::parse_insert(parser)?)),
+            "cf.loadchunk" => {
+                let key = parser.next_string()?;
+                let iter = parser.next_usize()?;
+                let data = parser.next_bytes()?;
+                Self::LoadChunk(key, iter, data)
+            }
+            "cf.mexists" => {
+                let key = parser.next_string()?;
+                let items = parser.remaining_strings()?;
+                Self::MultiExists(key, items)
+            }
+            "cf.reserve" => {
+                let key = parser.next_string()?;
+                let options = Self::parse_reserve(parser)?;
+                Self::Reserve(key, options)
+            }
+            "cf.scandump" => {
+                let key = parser.next_string()?;
+                let iter = parser.next_usize()?;
+                Self::ScanDump(key, iter)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::CuckooFilter(cuckoo_filter_cmd)))
+    }
+
+    /// Parse `capacity [BUCKETSIZE bucketsize] [MAXITERATIONS maxiterations]
+    /// [EXPANSION expansion]` arguments.
+    fn parse_reserve(parser: &mut Parser) -> Result<ReserveOptions, ParseCommandError> {
+        let mut options = ReserveOptions::new(parser.next_usize()?);
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "bucketsize" => options.bucket_size = parser.next_usize()?,
+                "maxiterations" => options.max_iterations = parser.next_usize()?,
+                "expansion" => options.expansion = parser.next_usize()?,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        if !(1..=MAX_CAPACITY).contains(&options.capacity)
+            || !(1..=MAX_BUCKET_SIZE).contains(&options.bucket_size)
+            || !(1..=MAX_ITERATIONS).contains(&options.max_iterations)
+            || options.expansion > MAX_EXPANSION
+        {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(options)
+    }
+
+    /// Parse `key [CAPACITY capacity] [NOCREATE] ITEMS item [item ...]` arguments.
+    fn parse_insert(parser: &mut Parser) -> Result<InsertOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut capacity = None;
+        let mut no_create = false;
+        loop {
+            let token = parser.next_string()?;
+            match token.to_ascii_lowercase().as_str() {
+                "capacity" => {
+                    let value = parser.next_usize()?;
+                    if !(1..=MAX_CAPACITY).contains(&value) {
+                        return Err(ParseCommandError::InvalidParameter);
+                    }
+                    capacity = Some(value);
+                }
+                "nocreate" => no_create = true,
+                "items" => break,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        let items = parser.remaining_strings()?;
+        Ok(InsertOptions {
+            key,
+            capacity,
+            no_create,
+            items,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::{CuckooFilterCommand, MAX_CAPACITY};
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        CuckooFilterCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::CuckooFilter(CuckooFilterCommand::Reserve(_key, options)))) = parse(
+            "cf.reserve",
+            &["cf", "1000", "BUCKETSIZE", "4", "EXPANSION", "2"],
+        ) else {
+            panic!("expected CF.RESERVE command");
+        };
+        assert_eq!(options.capacity, 1000);
+        assert_eq!(options.bucket_size, 4);
+        assert_eq!(options.expansion, 2);
+        assert!(parse("cf.reserve", &["cf", "1000", "BUCKETSIZE", "0"]).is_err());
+        let huge = (MAX_CAPACITY + 1).to_string();
+        assert!(parse("cf.reserve", &["cf", &huge]).is_err());
+        assert!(parse("cf.insert", &["cf", "CAPACITY", &huge, "ITEMS", "a"]).is_err());
+
+        let Ok(Some(Command::CuckooFilter(CuckooFilterCommand::InsertNx(options)))) = parse(
+            "cf.insertnx",
+            &["cf", "CAPACITY", "100", "NOCREATE", "ITEMS", "a", "b"],
+        ) else {
+            panic!("expected CF.INSERTNX command");
+        };
+        assert_eq!(options.capacity, Some(100));
+        assert!(options.no_create);
+        assert_eq!(options.items, ["a", "b"]);
+        assert!(parse("cf.insert", &["cf", "ITEMS"]).is_err());
+        assert!(parse("cf.insert", &["cf", "a"]).is_err());
+
+        assert!(matches!(
+            parse("cf.scandump", &["cf", "0"]),
+            Ok(Some(Command::CuckooFilter(CuckooFilterCommand::ScanDump(
+                _,
+                0
+            ))))
+        ));
+        assert!(parse("cf.loadchunk", &["cf", "1"]).is_err());
+    }
+}
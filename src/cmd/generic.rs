@@ -13,6 +13,7 @@ pub enum GenericCommand {
     Delete(Vec<String>),
     Exists(Vec<String>),
     FlushDb(bool),
+    ObjectEncoding(String),
     RandomKey(usize),
     Rename(String, String),
     Type(String),
@@ -33,6 +34,14 @@ impl GenericCommand {
                 let keys = parser.remaining_strings()?;
                 Self::Exists(keys)
             }
+            "object" => {
+                let subcommand = parser.next_string()?;
+                if !subcommand.eq_ignore_ascii_case("encoding") {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let key = parser.next_string()?;
+                Self::ObjectEncoding(key)
+            }
             "randomkey" => {
                 let mut rng = rand::thread_rng();
                 let random_index = rng.gen::<usize>();
@@ -57,4 +66,25 @@ impl GenericCommand {
         };
         Ok(Some(Command::Generic(generic_cmd)))
     }
+
+    /// Returns keys which are replaced or removed by this command.
+    #[must_use]
+    pub fn signaled_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Delete(keys) => keys.iter().map(String::as_str).collect(),
+            Self::Rename(_key, new_key) => vec![new_key.as_str()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns keys which are removed by this command.
+    ///
+    /// Destination of `RENAME` is not included, as it is replaced by the renamed value.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Delete(keys) => keys.iter().map(String::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
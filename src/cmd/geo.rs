@@ -0,0 +1,481 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::zset::AddOptions;
+use crate::cmd::Command;
+
+/// Unit of distance.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum DistanceUnit {
+    #[default]
+    Meters,
+    Kilometers,
+    Miles,
+    Feet,
+}
+
+impl DistanceUnit {
+    /// Returns number of meters in one unit.
+    #[must_use]
+    pub const fn to_meters(self) -> f64 {
+        match self {
+            Self::Meters => 1.0,
+            Self::Kilometers => 1000.0,
+            Self::Miles => 1609.34,
+            Self::Feet => 0.3048,
+        }
+    }
+}
+
+impl TryFrom<String> for DistanceUnit {
+    type Error = ParseCommandError;
+
+    fn try_from(mut value: String) -> Result<Self, Self::Error> {
+        value.make_ascii_lowercase();
+        match value.as_str() {
+            "m" => Ok(Self::Meters),
+            "km" => Ok(Self::Kilometers),
+            "mi" => Ok(Self::Miles),
+            "ft" => Ok(Self::Feet),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+/// A member with its longitude and latitude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoPoint {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub member: Vec<u8>,
+}
+
+/// Center point of a search.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchOrigin {
+    /// Position of an existing member.
+    Member(Vec<u8>),
+    /// Longitude and latitude.
+    LonLat(f64, f64),
+}
+
+/// Search area, with sizes in the unit of search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchShape {
+    /// Circular area with radius.
+    Radius(f64),
+    /// Axis-aligned rectangle area with width and height.
+    Box(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOptions {
+    pub origin: SearchOrigin,
+    pub shape: SearchShape,
+    pub unit: DistanceUnit,
+    pub order: Option<SortOrder>,
+    pub count: Option<usize>,
+    /// Return as soon as enough matches are found, requires `count`.
+    pub any: bool,
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum GeoCommand {
+    Add(String, AddOptions, Vec<GeoPoint>),
+    Dist(String, Vec<u8>, Vec<u8>, DistanceUnit),
+    Hash(String, Vec<Vec<u8>>),
+    Pos(String, Vec<Vec<u8>>),
+    Search(String, Box<SearchOptions>),
+    /// destination, source, options and `STOREDIST` flag.
+    SearchStore(String, String, Box<SearchOptions>, bool),
+}
+
+impl GeoCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let geo_cmd = match cmd_name {
+            "geoadd" => Self::parse_add(parser)?,
+            "geodist" => {
+                let key = parser.next_string()?;
+                let member1 = parser.next_bytes()?;
+                let member2 = parser.next_bytes()?;
+                let unit = match parser.try_next_string()? {
+                    Some(unit) => DistanceUnit::try_from(unit)?,
+                    None => DistanceUnit::default(),
+                };
+                Self::Dist(key, member1, member2, unit)
+            }
+            "geohash" => {
+                let key = parser.next_string()?;
+                let members = parse_members(parser)?;
+                Self::Hash(key, members)
+            }
+            "geopos" => {
+                let key = parser.next_string()?;
+                let members = parse_members(parser)?;
+                Self::Pos(key, members)
+            }
+            "georadius" | "georadius_ro" => {
+                let key = parser.next_string()?;
+                let longitude = parser.next_f64()?;
+                let latitude = parser.next_f64()?;
+                let origin = SearchOrigin::LonLat(longitude, latitude);
+                Self::parse_radius(parser, key, origin, cmd_name == "georadius")?
+            }
+            "georadiusbymember" | "georadiusbymember_ro" => {
+                let key = parser.next_string()?;
+                let origin = SearchOrigin::Member(parser.next_bytes()?);
+                Self::parse_radius(parser, key, origin, cmd_name == "georadiusbymember")?
+            }
+            "geosearch" => {
+                let key = parser.next_string()?;
+                let (options, _store_dist) = parse_search(parser, false)?;
+                Self::Search(key, Box::new(options))
+            }
+            "geosearchstore" => {
+                let destination = parser.next_string()?;
+                let source = parser.next_string()?;
+                let (options, store_dist) = parse_search(parser, true)?;
+                Self::SearchStore(destination, source, Box::new(options), store_dist)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Command::Geo(geo_cmd)))
+    }
+
+    fn parse_add(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut options = AddOptions::default();
+        let mut points = Vec::new();
+        while let Some(token) = parser.try_next_string()? {
+            if points.is_empty() {
+                match token.to_ascii_lowercase().as_str() {
+                    "nx" => {
+                        options.not_exist = true;
+                        continue;
+                    }
+                    "xx" => {
+                        options.exist = true;
+                        continue;
+                    }
+                    "ch" => {
+                        options.changed = true;
+                        continue;
+                    }
+                    _ => (),
+                }
+            }
+            let longitude = token.parse::<f64>()?;
+            let latitude = parser.next_f64()?;
+            let member = parser.next_bytes()?;
+            points.push(GeoPoint {
+                longitude,
+                latitude,
+                member,
+            });
+        }
+
+        if points.is_empty() || (options.not_exist && options.exist) {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(Self::Add(key, options, points))
+    }
+
+    /// Parse arguments of deprecated `GEORADIUS` and `GEORADIUSBYMEMBER` commands,
+    /// which are converted to `GEOSEARCH` or `GEOSEARCHSTORE`.
+    fn parse_radius(
+        parser: &mut Parser,
+        key: String,
+        origin: SearchOrigin,
+        allow_store: bool,
+    ) -> Result<Self, ParseCommandError> {
+        let radius = parse_distance(parser.next_f64()?)?;
+        let unit = DistanceUnit::try_from(parser.next_string()?)?;
+        let mut flags = SearchFlags::default();
+        let mut store = None;
+        while let Some(option) = parser.try_next_string()? {
+            let option = option.to_ascii_lowercase();
+            if flags.parse_option(&option, parser, true)? {
+                continue;
+            }
+            match option.as_str() {
+                "store" if allow_store => store = Some((parser.next_string()?, false)),
+                "storedist" if allow_store => store = Some((parser.next_string()?, true)),
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+
+        if store.is_some() && (flags.with_coord || flags.with_dist || flags.with_hash) {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let options = Box::new(flags.into_options(origin, SearchShape::Radius(radius), unit)?);
+        match store {
+            Some((destination, store_dist)) => {
+                Ok(Self::SearchStore(destination, key, options, store_dist))
+            }
+            None => Ok(Self::Search(key, options)),
+        }
+    }
+
+    /// Returns sorted set keys which may have new members after this command
+    /// is executed.
+    #[must_use]
+    pub fn signaled_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Add(key, ..) | Self::SearchStore(key, ..) => vec![key.as_str()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns destination keys which are overwritten by this command.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::SearchStore(key, ..) => vec![key.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Options shared by `GEOSEARCH` and `GEORADIUS` commands.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default)]
+struct SearchFlags {
+    order: Option<SortOrder>,
+    count: Option<usize>,
+    any: bool,
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+}
+
+impl SearchFlags {
+    /// Returns false if `option` is not a shared option.
+    fn parse_option(
+        &mut self,
+        option: &str,
+        parser: &mut Parser,
+        allow_with: bool,
+    ) -> Result<bool, ParseCommandError> {
+        match option {
+            "asc" => self.order = Some(SortOrder::Asc),
+            "desc" => self.order = Some(SortOrder::Desc),
+            "count" => {
+                let count = parser.next_usize()?;
+                if count == 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                self.count = Some(count);
+            }
+            "any" => self.any = true,
+            "withcoord" if allow_with => self.with_coord = true,
+            "withdist" if allow_with => self.with_dist = true,
+            "withhash" if allow_with => self.with_hash = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn into_options(
+        self,
+        origin: SearchOrigin,
+        shape: SearchShape,
+        unit: DistanceUnit,
+    ) -> Result<SearchOptions, ParseCommandError> {
+        // ANY argument requires COUNT argument.
+        if self.any && self.count.is_none() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(SearchOptions {
+            origin,
+            shape,
+            unit,
+            order: self.order,
+            count: self.count,
+            any: self.any,
+            with_coord: self.with_coord,
+            with_dist: self.with_dist,
+            with_hash: self.with_hash,
+        })
+    }
+}
+
+/// Parse options of `GEOSEARCH` and `GEOSEARCHSTORE`, returns options and `STOREDIST` flag.
+///
+/// Exactly one of `FROMMEMBER` or `FROMLONLAT`, and one of `BYRADIUS` or `BYBOX`
+/// shall be specified.
+fn parse_search(
+    parser: &mut Parser,
+    is_store: bool,
+) -> Result<(SearchOptions, bool), ParseCommandError> {
+    let mut origin = None;
+    let mut shape = None;
+    let mut unit = DistanceUnit::default();
+    let mut flags = SearchFlags::default();
+    let mut store_dist = false;
+    while let Some(option) = parser.try_next_string()? {
+        let option = option.to_ascii_lowercase();
+        if flags.parse_option(&option, parser, !is_store)? {
+            continue;
+        }
+        match option.as_str() {
+            "frommember" if origin.is_none() => {
+                origin = Some(SearchOrigin::Member(parser.next_bytes()?));
+            }
+            "fromlonlat" if origin.is_none() => {
+                let longitude = parser.next_f64()?;
+                let latitude = parser.next_f64()?;
+                origin = Some(SearchOrigin::LonLat(longitude, latitude));
+            }
+            "byradius" if shape.is_none() => {
+                let radius = parse_distance(parser.next_f64()?)?;
+                unit = DistanceUnit::try_from(parser.next_string()?)?;
+                shape = Some(SearchShape::Radius(radius));
+            }
+            "bybox" if shape.is_none() => {
+                let width = parse_distance(parser.next_f64()?)?;
+                let height = parse_distance(parser.next_f64()?)?;
+                unit = DistanceUnit::try_from(parser.next_string()?)?;
+                shape = Some(SearchShape::Box(width, height));
+            }
+            "storedist" if is_store => store_dist = true,
+            _ => return Err(ParseCommandError::InvalidParameter),
+        }
+    }
+
+    let (Some(origin), Some(shape)) = (origin, shape) else {
+        return Err(ParseCommandError::InvalidParameter);
+    };
+    let options = flags.into_options(origin, shape, unit)?;
+    Ok((options, store_dist))
+}
+
+/// Radius, width and height shall not be negative.
+fn parse_distance(value: f64) -> Result<f64, ParseCommandError> {
+    if value >= 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(ParseCommandError::InvalidParameter)
+    }
+}
+
+/// Parse optional members, an empty list is allowed.
+fn parse_members(parser: &mut Parser) -> Result<Vec<Vec<u8>>, ParseCommandError> {
+    match parser.remaining() {
+        Ok(members) => Ok(members),
+        Err(ParseCommandError::InvalidParameter) => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use crate::cmd::geo::{DistanceUnit, GeoCommand, SearchOrigin, SearchShape, SortOrder};
+    use crate::cmd::parse::Parser;
+    use crate::cmd::Command;
+
+    fn parse(args: &[&str]) -> Option<GeoCommand> {
+        match GeoCommand::parse(args[0], &mut Parser::from_args(&args[1..])) {
+            Ok(Some(Command::Geo(cmd))) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size_of::<GeoCommand>(), 80);
+    }
+
+    #[test]
+    fn test_distance_unit() {
+        assert_eq!(
+            DistanceUnit::try_from("KM".to_owned()),
+            Ok(DistanceUnit::Kilometers)
+        );
+        assert!(DistanceUnit::try_from("yd".to_owned()).is_err());
+    }
+
+    #[test]
+    fn test_parse_search() {
+        let cmd = parse(&[
+            "geosearch",
+            "Sicily",
+            "FROMLONLAT",
+            "15",
+            "37",
+            "BYBOX",
+            "400",
+            "400",
+            "km",
+            "ASC",
+            "COUNT",
+            "2",
+            "ANY",
+            "WITHDIST",
+        ]);
+        let Some(GeoCommand::Search(key, options)) = cmd else {
+            panic!("expected GEOSEARCH command");
+        };
+        assert_eq!(key, "Sicily");
+        assert_eq!(options.origin, SearchOrigin::LonLat(15.0, 37.0));
+        assert_eq!(options.shape, SearchShape::Box(400.0, 400.0));
+        assert_eq!(options.unit, DistanceUnit::Kilometers);
+        assert_eq!(options.order, Some(SortOrder::Asc));
+        assert_eq!(options.count, Some(2));
+        assert!(options.any && options.with_dist);
+
+        let cmd = parse(&[
+            "georadiusbymember",
+            "Sicily",
+            "Palermo",
+            "200",
+            "km",
+            "STOREDIST",
+            "dest",
+        ]);
+        assert!(matches!(cmd, Some(GeoCommand::SearchStore(_, _, _, true))));
+
+        assert!(parse(&["geosearch", "Sicily", "FROMMEMBER", "a", "ASC"]).is_none());
+        assert!(parse(&[
+            "geosearchstore",
+            "dest",
+            "Sicily",
+            "FROMMEMBER",
+            "a",
+            "BYRADIUS",
+            "1",
+            "m",
+            "WITHDIST"
+        ])
+        .is_none());
+        assert!(parse(&[
+            "georadius_ro",
+            "Sicily",
+            "15",
+            "37",
+            "200",
+            "km",
+            "STORE",
+            "dest"
+        ])
+        .is_none());
+        assert!(parse(&["georadius", "Sicily", "15", "37", "-1", "km"]).is_none());
+    }
+}
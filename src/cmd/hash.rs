@@ -5,16 +5,93 @@
 use crate::cmd::parse::{ParseCommandError, Parser};
 use crate::cmd::Command;
 
+/// Condition to set time to live of hash fields.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExpireCondition {
+    /// For each specified field, set expiration only when the field has no expiration.
+    NotExist,
+    /// For each specified field, set expiration only when the field has an existing expiration.
+    Exist,
+    /// For each specified field, set expiration only when the new expiration is
+    /// greater than current one.
+    GreaterThan,
+    /// For each specified field, set expiration only when the new expiration is
+    /// less than current one.
+    LessThan,
+}
+
+impl TryFrom<String> for ExpireCondition {
+    type Error = ParseCommandError;
+
+    fn try_from(mut value: String) -> Result<Self, Self::Error> {
+        value.make_ascii_lowercase();
+        match value.as_str() {
+            "nx" => Ok(Self::NotExist),
+            "xx" => Ok(Self::Exist),
+            "gt" => Ok(Self::GreaterThan),
+            "lt" => Ok(Self::LessThan),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+/// Time to live of hash fields, converted to deadline when command is executed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExpireTime {
+    Seconds(i64),
+    Milliseconds(i64),
+    UnixSeconds(i64),
+    UnixMilliseconds(i64),
+}
+
+impl ExpireTime {
+    /// Returns deadline as unix time in milliseconds.
+    ///
+    /// Returns None if time is negative or overflows.
+    #[must_use]
+    pub const fn deadline(self, now: i64) -> Option<i64> {
+        match self {
+            Self::Seconds(seconds) if seconds >= 0 => match seconds.checked_mul(1000) {
+                Some(millis) => now.checked_add(millis),
+                None => None,
+            },
+            Self::Milliseconds(millis) if millis >= 0 => now.checked_add(millis),
+            Self::UnixSeconds(seconds) if seconds >= 0 => seconds.checked_mul(1000),
+            Self::UnixMilliseconds(millis) if millis >= 0 => Some(millis),
+            _ => None,
+        }
+    }
+}
+
+/// Options of `HGETEX` command.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GetExOption {
+    Expire(ExpireTime),
+    Persist,
+}
+
 #[derive(Debug, Clone)]
 pub enum HashCommand {
     Del(String, Vec<String>),
     Exists(String, String),
+    Expire(String, ExpireTime, Option<ExpireCondition>, Vec<String>),
     Get(String, String),
     GetAll(String),
+    GetDel(String, Vec<String>),
+    GetEx(String, Option<GetExOption>, Vec<String>),
+    IncrBy(String, String, i64),
+    IncrByFloat(String, String, f64),
     Keys(String),
     Len(String),
+    MultiGet(String, Vec<String>),
+    MultiSet(String, Vec<(String, Vec<u8>)>),
+    Persist(String, Vec<String>),
+    PTtl(String, Vec<String>),
+    RandomField(String, Option<(isize, bool)>),
     Set(String, Vec<(String, Vec<u8>)>),
+    SetNotExist(String, String, Vec<u8>),
     StrLen(String, String),
+    Ttl(String, Vec<String>),
     Values(String),
 }
 
@@ -34,6 +111,9 @@ impl HashCommand {
                 let field = parser.next_string()?;
                 Self::Exists(key, field)
             }
+            "hexpire" | "hpexpire" | "hexpireat" | "hpexpireat" => {
+                Self::parse_expire(cmd_name, parser)?
+            }
             "hget" => {
                 let key = parser.next_string()?;
                 let field = parser.next_string()?;
@@ -43,6 +123,24 @@ impl HashCommand {
                 let key = parser.next_string()?;
                 Self::GetAll(key)
             }
+            "hgetdel" => {
+                let key = parser.next_string()?;
+                let fields = parse_fields(parser)?;
+                Self::GetDel(key, fields)
+            }
+            "hgetex" => Self::parse_get_ex(parser)?,
+            "hincrby" => {
+                let key = parser.next_string()?;
+                let field = parser.next_string()?;
+                let increment = parser.next_i64()?;
+                Self::IncrBy(key, field, increment)
+            }
+            "hincrbyfloat" => {
+                let key = parser.next_string()?;
+                let field = parser.next_string()?;
+                let increment = parser.next_f64()?;
+                Self::IncrByFloat(key, field, increment)
+            }
             "hkeys" => {
                 let key = parser.next_string()?;
                 Self::Keys(key)
@@ -51,11 +149,37 @@ impl HashCommand {
                 let key = parser.next_string()?;
                 Self::Len(key)
             }
+            "hmget" => {
+                let key = parser.next_string()?;
+                let fields = parser.remaining_strings()?;
+                Self::MultiGet(key, fields)
+            }
+            "hmset" => {
+                let key = parser.next_string()?;
+                let pairs = parser.remaining_pairs()?;
+                Self::MultiSet(key, pairs)
+            }
+            "hpersist" | "httl" | "hpttl" => {
+                let key = parser.next_string()?;
+                let fields = parse_fields(parser)?;
+                match cmd_name {
+                    "hpersist" => Self::Persist(key, fields),
+                    "httl" => Self::Ttl(key, fields),
+                    _ => Self::PTtl(key, fields),
+                }
+            }
+            "hrandfield" => Self::parse_random_field(parser)?,
             "hset" => {
                 let key = parser.next_string()?;
                 let pairs = parser.remaining_pairs()?;
                 Self::Set(key, pairs)
             }
+            "hsetnx" => {
+                let key = parser.next_string()?;
+                let field = parser.next_string()?;
+                let value = parser.next_bytes()?;
+                Self::SetNotExist(key, field, value)
+            }
             "hstrlen" => {
                 let key = parser.next_string()?;
                 let field = parser.next_string()?;
@@ -69,6 +193,113 @@ impl HashCommand {
         };
         Ok(Some(Command::Hash(list_cmd)))
     }
+
+    fn parse_random_field(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let count = if let Some(count) = parser.try_next_isize()? {
+            let with_values = match parser.try_next_string()? {
+                Some(option) if option.eq_ignore_ascii_case("withvalues") => true,
+                Some(_) => return Err(ParseCommandError::InvalidParameter),
+                None => false,
+            };
+            Some((count, with_values))
+        } else {
+            None
+        };
+        Ok(Self::RandomField(key, count))
+    }
+
+    fn parse_expire(cmd_name: &str, parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let time = parser.next_i64()?;
+        let expire_time = match cmd_name {
+            "hexpire" => ExpireTime::Seconds(time),
+            "hpexpire" => ExpireTime::Milliseconds(time),
+            "hexpireat" => ExpireTime::UnixSeconds(time),
+            _ => ExpireTime::UnixMilliseconds(time),
+        };
+        let option = parser.next_string()?;
+        let (condition, fields) = if option.eq_ignore_ascii_case("fields") {
+            (None, parse_num_fields(parser)?)
+        } else {
+            let condition = ExpireCondition::try_from(option)?;
+            (Some(condition), parse_fields(parser)?)
+        };
+        Ok(Self::Expire(key, expire_time, condition, fields))
+    }
+
+    fn parse_get_ex(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut option = parser.next_string()?;
+        option.make_ascii_lowercase();
+        let get_ex_option = match option.as_str() {
+            "fields" => None,
+            "persist" => Some(GetExOption::Persist),
+            "ex" => Some(GetExOption::Expire(ExpireTime::Seconds(parser.next_i64()?))),
+            "px" => Some(GetExOption::Expire(ExpireTime::Milliseconds(
+                parser.next_i64()?,
+            ))),
+            "exat" => Some(GetExOption::Expire(ExpireTime::UnixSeconds(
+                parser.next_i64()?,
+            ))),
+            "pxat" => Some(GetExOption::Expire(ExpireTime::UnixMilliseconds(
+                parser.next_i64()?,
+            ))),
+            _ => return Err(ParseCommandError::InvalidParameter),
+        };
+        let fields = if get_ex_option.is_some() {
+            parse_fields(parser)?
+        } else {
+            parse_num_fields(parser)?
+        };
+        Ok(Self::GetEx(key, get_ex_option, fields))
+    }
+
+    /// Returns key of the hash object.
+    #[must_use]
+    pub const fn key(&self) -> &String {
+        match self {
+            Self::Del(key, ..)
+            | Self::Exists(key, ..)
+            | Self::Expire(key, ..)
+            | Self::Get(key, ..)
+            | Self::GetAll(key)
+            | Self::GetDel(key, ..)
+            | Self::GetEx(key, ..)
+            | Self::IncrBy(key, ..)
+            | Self::IncrByFloat(key, ..)
+            | Self::Keys(key)
+            | Self::Len(key)
+            | Self::MultiGet(key, ..)
+            | Self::MultiSet(key, ..)
+            | Self::Persist(key, ..)
+            | Self::PTtl(key, ..)
+            | Self::RandomField(key, ..)
+            | Self::Set(key, ..)
+            | Self::SetNotExist(key, ..)
+            | Self::StrLen(key, ..)
+            | Self::Ttl(key, ..)
+            | Self::Values(key) => key,
+        }
+    }
+}
+
+/// Parse `FIELDS numfields field [field ...]` arguments.
+fn parse_fields(parser: &mut Parser) -> Result<Vec<String>, ParseCommandError> {
+    if !parser.next_string()?.eq_ignore_ascii_case("fields") {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    parse_num_fields(parser)
+}
+
+/// Parse `numfields field [field ...]` arguments.
+fn parse_num_fields(parser: &mut Parser) -> Result<Vec<String>, ParseCommandError> {
+    let num_fields = parser.next_usize()?;
+    let fields = parser.remaining_strings()?;
+    if num_fields == 0 || num_fields != fields.len() {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    Ok(fields)
 }
 
 #[cfg(test)]
@@ -79,6 +310,6 @@ mod tests {
 
     #[test]
     fn test_hash_command() {
-        assert_eq!(size_of::<HashCommand>(), 56);
+        assert_eq!(size_of::<HashCommand>(), 80);
     }
 }
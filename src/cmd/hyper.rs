@@ -5,11 +5,25 @@
 use crate::cmd::parse::{ParseCommandError, Parser};
 use crate::cmd::Command;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DebugSubcommand {
+    /// Returns values of all registers, sparse encoding is converted to dense.
+    GetReg,
+    /// Returns opcodes of sparse encoding.
+    Decode,
+    /// Returns name of current encoding.
+    Encoding,
+    /// Converts sparse encoding to dense.
+    ToDense,
+}
+
 #[derive(Debug, Clone)]
 pub enum HyperLogLogCommand {
     Add(String, Vec<String>),
     Count(Vec<String>),
+    Debug(DebugSubcommand, String),
     Merge(String, Vec<String>),
+    SelfTest,
 }
 
 impl HyperLogLogCommand {
@@ -25,15 +39,58 @@ impl HyperLogLogCommand {
             }
             "pfcount" => {
                 let keys = parser.remaining_strings()?;
+                if keys.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
                 Self::Count(keys)
             }
+            "pfdebug" => {
+                let subcommand = match parser.next_string()?.to_ascii_lowercase().as_str() {
+                    "getreg" => DebugSubcommand::GetReg,
+                    "decode" => DebugSubcommand::Decode,
+                    "encoding" => DebugSubcommand::Encoding,
+                    "todense" => DebugSubcommand::ToDense,
+                    _ => return Err(ParseCommandError::InvalidParameter),
+                };
+                let key = parser.next_string()?;
+                Self::Debug(subcommand, key)
+            }
             "pfmerge" => {
                 let dest_key = parser.next_string()?;
                 let source_keys = parser.remaining_strings()?;
                 Self::Merge(dest_key, source_keys)
             }
+            "pfselftest" => Self::SelfTest,
             _ => return Ok(None),
         };
         Ok(Some(Command::HyperLogLog(hyper_cmd)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hyper::{DebugSubcommand, HyperLogLogCommand};
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        HyperLogLogCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::HyperLogLog(HyperLogLogCommand::Debug(subcommand, key)))) =
+            parse("pfdebug", &["GETREG", "hll"])
+        else {
+            panic!("expected PFDEBUG command");
+        };
+        assert_eq!(subcommand, DebugSubcommand::GetReg);
+        assert_eq!(key, "hll");
+        assert!(parse("pfdebug", &["unknown", "hll"]).is_err());
+        assert!(parse("pfcount", &[]).is_err());
+        assert!(matches!(
+            parse("pfselftest", &[]),
+            Ok(Some(Command::HyperLogLog(HyperLogLogCommand::SelfTest)))
+        ));
+    }
+}
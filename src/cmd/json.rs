@@ -0,0 +1,286 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Condition to set value in JSON document.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SetCondition {
+    /// Only set the value if the path does not already exist.
+    NotExist,
+    /// Only set the value if the path already exists.
+    Exist,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetOptions {
+    pub key: String,
+    pub path: String,
+    /// Value in JSON format.
+    pub value: String,
+    pub condition: Option<SetCondition>,
+}
+
+/// Format of JSON string returned by `JSON.GET`.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct FormatOptions {
+    /// Indentation string for nested levels.
+    pub indent: String,
+    /// String printed at the end of each line.
+    pub newline: String,
+    /// String put between a key and a value.
+    pub space: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum JsonCommand {
+    ArrAppend(String, String, Vec<String>),
+    ArrIndex(String, String, String, isize, isize),
+    ArrInsert(String, String, isize, Vec<String>),
+    ArrLen(String, Option<String>),
+    ArrPop(String, Option<String>, isize),
+    ArrTrim(String, String, isize, isize),
+    Clear(String, Option<String>),
+    Del(String, Option<String>),
+    Get(String, Box<FormatOptions>, Vec<String>),
+    Merge(String, String, String),
+    MGet(Vec<String>, String),
+    NumIncrBy(String, String, String),
+    NumMultBy(String, String, String),
+    ObjKeys(String, Option<String>),
+    ObjLen(String, Option<String>),
+    Set(Box<SetOptions>),
+    StrAppend(String, Option<String>, String),
+    StrLen(String, Option<String>),
+    Toggle(String, String),
+    Type(String, Option<String>),
+}
+
+impl JsonCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let json_cmd = match cmd_name {
+            "json.arrappend" => {
+                let key = parser.next_string()?;
+                let path = parser.next_string()?;
+                let values = parser.remaining_strings()?;
+                Self::ArrAppend(key, path, values)
+            }
+            "json.arrindex" => {
+                let (key, path, value) = Self::parse_key_path_value(parser)?;
+                let start = parser.try_next_isize()?.unwrap_or(0);
+                let stop = parser.try_next_isize()?.unwrap_or(0);
+                Self::ArrIndex(key, path, value, start, stop)
+            }
+            "json.arrinsert" => {
+                let key = parser.next_string()?;
+                let path = parser.next_string()?;
+                let index = parser.next_isize()?;
+                let values = parser.remaining_strings()?;
+                Self::ArrInsert(key, path, index, values)
+            }
+            "json.arrlen" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::ArrLen(key, path)
+            }
+            "json.arrpop" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                let index = parser.try_next_isize()?.unwrap_or(-1);
+                Self::ArrPop(key, path, index)
+            }
+            "json.arrtrim" => {
+                let key = parser.next_string()?;
+                let path = parser.next_string()?;
+                let start = parser.next_isize()?;
+                let stop = parser.next_isize()?;
+                Self::ArrTrim(key, path, start, stop)
+            }
+            "json.clear" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::Clear(key, path)
+            }
+            "json.del" | "json.forget" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::Del(key, path)
+            }
+            "json.get" => Self::parse_get(parser)?,
+            "json.mget" => {
+                let mut keys = parser.remaining_strings()?;
+                let path = keys.pop().ok_or(ParseCommandError::InvalidParameter)?;
+                if keys.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::MGet(keys, path)
+            }
+            "json.merge" => {
+                let (key, path, value) = Self::parse_key_path_value(parser)?;
+                Self::Merge(key, path, value)
+            }
+            "json.numincrby" => {
+                let (key, path, value) = Self::parse_key_path_value(parser)?;
+                Self::NumIncrBy(key, path, value)
+            }
+            "json.nummultby" => {
+                let (key, path, value) = Self::parse_key_path_value(parser)?;
+                Self::NumMultBy(key, path, value)
+            }
+            "json.objkeys" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::ObjKeys(key, path)
+            }
+            "json.objlen" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::ObjLen(key, path)
+            }
+            "json.set" => Self::parse_set(parser)?,
+            "json.strappend" => {
+                let key = parser.next_string()?;
+                let first = parser.next_string()?;
+                match parser.try_next_string()? {
+                    Some(value) => Self::StrAppend(key, Some(first), value),
+                    None => Self::StrAppend(key, None, first),
+                }
+            }
+            "json.strlen" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::StrLen(key, path)
+            }
+            "json.toggle" => Self::Toggle(parser.next_string()?, parser.next_string()?),
+            "json.type" => {
+                let (key, path) = Self::parse_key_path(parser)?;
+                Self::Type(key, path)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::Json(json_cmd)))
+    }
+
+    /// Parse `key [path]` arguments.
+    fn parse_key_path(parser: &mut Parser) -> Result<(String, Option<String>), ParseCommandError> {
+        let key = parser.next_string()?;
+        let path = parser.try_next_string()?;
+        Ok((key, path))
+    }
+
+    /// Parse `key path value` arguments.
+    fn parse_key_path_value(
+        parser: &mut Parser,
+    ) -> Result<(String, String, String), ParseCommandError> {
+        let key = parser.next_string()?;
+        let path = parser.next_string()?;
+        let value = parser.next_string()?;
+        Ok((key, path, value))
+    }
+
+    /// Parse `key [INDENT indent] [NEWLINE newline] [SPACE space] [path ...]` arguments.
+    fn parse_get(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut options = FormatOptions::default();
+        let mut paths = Vec::new();
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "indent" => options.indent = parser.next_string()?,
+                "newline" => options.newline = parser.next_string()?,
+                "space" => options.space = parser.next_string()?,
+                _ => paths.push(token),
+            }
+        }
+        Ok(Self::Get(key, Box::new(options), paths))
+    }
+
+    /// Parse `key path value [NX | XX]` arguments.
+    fn parse_set(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let path = parser.next_string()?;
+        let value = parser.next_string()?;
+        let condition = match parser.try_next_string()? {
+            Some(token) => match token.to_ascii_lowercase().as_str() {
+                "nx" => Some(SetCondition::NotExist),
+                "xx" => Some(SetCondition::Exist),
+                _ => return Err(ParseCommandError::InvalidParameter),
+            },
+            None => None,
+        };
+        if parser.try_next_string()?.is_some() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(Self::Set(Box::new(SetOptions {
+            key,
+            path,
+            value,
+            condition,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::json::{JsonCommand, SetCondition};
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        JsonCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::Json(JsonCommand::Set(options)))) =
+            parse("json.set", &["doc", "$", "{}", "nx"])
+        else {
+            panic!("expected JSON.SET command");
+        };
+        assert_eq!(options.condition, Some(SetCondition::NotExist));
+        assert!(parse("json.set", &["doc", "$", "{}", "nx", "xx"]).is_err());
+        assert!(parse("json.set", &["doc", "$"]).is_err());
+
+        let Ok(Some(Command::Json(JsonCommand::Get(key, options, paths)))) = parse(
+            "json.get",
+            &["doc", "INDENT", "\t", "NEWLINE", "\n", "$.a", "$.b"],
+        ) else {
+            panic!("expected JSON.GET command");
+        };
+        assert_eq!(key, "doc");
+        assert_eq!(options.indent, "\t");
+        assert_eq!(options.newline, "\n");
+        assert!(options.space.is_empty());
+        assert_eq!(paths, vec!["$.a".to_owned(), "$.b".to_owned()]);
+
+        assert!(matches!(
+            parse("json.mget", &["a", "b", "$"]),
+            Ok(Some(Command::Json(JsonCommand::MGet(ref keys, _)))) if keys.len() == 2
+        ));
+        assert!(parse("json.mget", &["a"]).is_err());
+        assert!(matches!(
+            parse("json.forget", &["a"]),
+            Ok(Some(Command::Json(JsonCommand::Del(_, None))))
+        ));
+    }
+
+    #[test]
+    fn test_parse_mutations() {
+        assert!(matches!(
+            parse("json.arrindex", &["doc", "$", "1"]),
+            Ok(Some(Command::Json(JsonCommand::ArrIndex(_, _, _, 0, 0))))
+        ));
+        assert!(matches!(
+            parse("json.arrpop", &["doc"]),
+            Ok(Some(Command::Json(JsonCommand::ArrPop(_, None, -1))))
+        ));
+        assert!(parse("json.arrinsert", &["doc", "$", "x", "1"]).is_err());
+        assert!(parse("json.arrappend", &["doc", "$"]).is_err());
+        assert!(matches!(
+            parse("json.strappend", &["doc", "\"a\""]),
+            Ok(Some(Command::Json(JsonCommand::StrAppend(_, None, _))))
+        ));
+        assert!(matches!(
+            parse("json.strappend", &["doc", "$", "\"a\""]),
+            Ok(Some(Command::Json(JsonCommand::StrAppend(_, Some(_), _))))
+        ));
+        assert!(parse("json.toggle", &["doc"]).is_err());
+    }
+}
@@ -2,37 +2,57 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use crate::cmd::auto_suggest::AutoSuggestCommand;
 use crate::cmd::bitmap::BitmapCommand;
 use crate::cmd::bloom_filter::BloomFilterCommand;
 use crate::cmd::cluster_mgmt::ClusterManagementCommand;
 use crate::cmd::conn_mgmt::ConnectManagementCommand;
+use crate::cmd::count_min_sketch::CountMinSketchCommand;
+use crate::cmd::cuckoo_filter::CuckooFilterCommand;
 use crate::cmd::frame::Frame;
 use crate::cmd::generic::GenericCommand;
+use crate::cmd::geo::GeoCommand;
 use crate::cmd::hash::HashCommand;
 use crate::cmd::hyper::HyperLogLogCommand;
+use crate::cmd::json::JsonCommand;
 use crate::cmd::list::ListCommand;
 use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::pub_sub::PubSubCommand;
 use crate::cmd::server_mgmt::ServerManagementCommand;
 use crate::cmd::set::SetCommand;
 use crate::cmd::storage_mgmt::StorageManagementCommand;
+use crate::cmd::stream::StreamCommand;
 use crate::cmd::string::StringCommand;
+use crate::cmd::time_series::TimeSeriesCommand;
+use crate::cmd::top_k::TopKCommand;
+use crate::cmd::zset::SortedSetCommand;
 
+pub mod auto_suggest;
 pub mod bitmap;
 pub mod bloom_filter;
 pub mod cluster_mgmt;
 pub mod command_scheme;
 pub mod conn_mgmt;
+pub mod count_min_sketch;
+pub mod cuckoo_filter;
 pub mod frame;
 pub mod generic;
+pub mod geo;
 pub mod hash;
 pub mod hyper;
+pub mod json;
 pub mod list;
 mod parse;
+pub mod pub_sub;
 pub mod reply_frame;
 pub mod server_mgmt;
 pub mod set;
 pub mod storage_mgmt;
+pub mod stream;
 pub mod string;
+pub mod time_series;
+pub mod top_k;
+pub mod zset;
 
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -41,9 +61,13 @@ pub enum Command {
     List(ListCommand),
     Hash(HashCommand),
     Set(SetCommand),
+    SortedSet(SortedSetCommand),
+    Geo(GeoCommand),
+    Stream(StreamCommand),
     Bitmap(BitmapCommand),
     HyperLogLog(HyperLogLogCommand),
     Generic(GenericCommand),
+    PubSub(PubSubCommand),
     // Management commands.
     ClusterManagement(ClusterManagementCommand),
     ConnManagement(ConnectManagementCommand),
@@ -51,6 +75,12 @@ pub enum Command {
     StorageManagement(StorageManagementCommand),
     // Stack commands
     BloomFilter(BloomFilterCommand),
+    Json(JsonCommand),
+    TimeSeries(TimeSeriesCommand),
+    TopK(TopKCommand),
+    CountMinSketch(CountMinSketchCommand),
+    CuckooFilter(CuckooFilterCommand),
+    AutoSuggest(AutoSuggestCommand),
 }
 
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
@@ -70,10 +100,20 @@ impl Command {
             | Self::List(_)
             | Self::Hash(_)
             | Self::Set(_)
+            | Self::SortedSet(_)
+            | Self::Geo(_)
+            | Self::Stream(_)
             | Self::Generic(_)
             | Self::Bitmap(_)
             | Self::HyperLogLog(_)
-            | Self::BloomFilter(_) => CommandCategory::Mem,
+            | Self::PubSub(_)
+            | Self::BloomFilter(_)
+            | Self::Json(_)
+            | Self::TimeSeries(_)
+            | Self::TopK(_)
+            | Self::CountMinSketch(_)
+            | Self::CuckooFilter(_)
+            | Self::AutoSuggest(_) => CommandCategory::Mem,
             Self::ClusterManagement(_)
             | Self::ConnManagement(_)
             | Self::StorageManagement(_)
@@ -81,6 +121,63 @@ impl Command {
         }
     }
 
+    /// Returns timeout in seconds if this is a blocking command, 0 means to block indefinitely.
+    #[must_use]
+    pub fn block_timeout(&self) -> Option<f64> {
+        match self {
+            Self::SortedSet(command) => command.timeout(),
+            Self::Stream(command) => command.timeout(),
+            _ => None,
+        }
+    }
+
+    /// Returns keys watched by a blocking command, like `BZPOPMIN` and `XREAD`.
+    #[must_use]
+    pub fn blocking_keys(&self) -> Vec<&str> {
+        match self {
+            Self::SortedSet(command) => command.blocking_keys(),
+            Self::Stream(command) => command.blocking_keys(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns keys written by this command which may serve clients blocked on them,
+    /// like `ZADD` and `XADD`.
+    #[must_use]
+    pub fn signaled_keys(&self) -> Vec<&str> {
+        match self {
+            Self::SortedSet(command) => command.signaled_keys(),
+            Self::Stream(command) => command.signaled_keys(),
+            Self::Generic(command) => command.signaled_keys(),
+            Self::Geo(command) => command.signaled_keys(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns keys whose values may be removed or replaced by values of another type,
+    /// like `DEL` and `SET`.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Str(command) => command.overwritten_keys(),
+            Self::Set(command) => command.overwritten_keys(),
+            Self::SortedSet(command) => command.overwritten_keys(),
+            Self::Geo(command) => command.overwritten_keys(),
+            Self::Generic(command) => command.overwritten_keys(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns true if this command is replied with frames pushed to the client,
+    /// like `SUBSCRIBE`.
+    #[must_use]
+    pub const fn is_subscription(&self) -> bool {
+        match self {
+            Self::PubSub(command) => command.is_subscription(),
+            _ => false,
+        }
+    }
+
     #[must_use]
     #[inline]
     pub fn is_mem(&self) -> bool {
@@ -119,6 +216,15 @@ impl TryFrom<Frame> for Command {
         if command.is_none() {
             command = SetCommand::parse(&cmd_name, &mut parser)?;
         }
+        if command.is_none() {
+            command = SortedSetCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = GeoCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = StreamCommand::parse(&cmd_name, &mut parser)?;
+        }
         if command.is_none() {
             command = BitmapCommand::parse(&cmd_name, &mut parser)?;
         }
@@ -128,11 +234,32 @@ impl TryFrom<Frame> for Command {
         if command.is_none() {
             command = GenericCommand::parse(&cmd_name, &mut parser)?;
         }
+        if command.is_none() {
+            command = PubSubCommand::parse(&cmd_name, &mut parser)?;
+        }
 
         // Parse stack commands.
         if command.is_none() {
             command = BloomFilterCommand::parse(&cmd_name, &mut parser)?;
         }
+        if command.is_none() {
+            command = JsonCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = TimeSeriesCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = TopKCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = CountMinSketchCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = CuckooFilterCommand::parse(&cmd_name, &mut parser)?;
+        }
+        if command.is_none() {
+            command = AutoSuggestCommand::parse(&cmd_name, &mut parser)?;
+        }
 
         // Parse management commands.
         if command.is_none() {
@@ -41,6 +41,17 @@ impl Parser {
         Self { iter }
     }
 
+    /// Create a parser of bulk frames, used to test parsing of command arguments.
+    #[cfg(test)]
+    #[must_use]
+    pub fn from_args(args: &[&str]) -> Self {
+        let frames: Vec<Frame> = args
+            .iter()
+            .map(|arg| Frame::Bulk(bytes::Bytes::copy_from_slice(arg.as_bytes())))
+            .collect();
+        Self::new(frames.into_iter())
+    }
+
     pub fn next(&mut self) -> Result<Frame, ParseCommandError> {
         self.iter.next().ok_or(ParseCommandError::InvalidParameter)
     }
@@ -0,0 +1,175 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+#[derive(Debug, Clone)]
+pub enum PubSubCommand {
+    Channels(Option<String>),
+    NumPat,
+    NumSub(Vec<String>),
+    PSubscribe(Vec<String>),
+    Publish(String, Vec<u8>),
+    PUnsubscribe(Vec<String>),
+    ShardChannels(Option<String>),
+    ShardNumSub(Vec<String>),
+    SPublish(String, Vec<u8>),
+    SSubscribe(Vec<String>),
+    Subscribe(Vec<String>),
+    SUnsubscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+impl PubSubCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let pub_sub_cmd = match cmd_name {
+            "psubscribe" => {
+                let patterns = parser.remaining_strings()?;
+                Self::PSubscribe(patterns)
+            }
+            "publish" => {
+                let channel = parser.next_string()?;
+                let message = parser.next_bytes()?;
+                Self::Publish(channel, message)
+            }
+            "pubsub" => Self::parse_pub_sub(parser)?,
+            "punsubscribe" => {
+                let patterns = parse_optional_strings(parser)?;
+                Self::PUnsubscribe(patterns)
+            }
+            "spublish" => {
+                let channel = parser.next_string()?;
+                let message = parser.next_bytes()?;
+                Self::SPublish(channel, message)
+            }
+            "ssubscribe" => {
+                let channels = parser.remaining_strings()?;
+                Self::SSubscribe(channels)
+            }
+            "subscribe" => {
+                let channels = parser.remaining_strings()?;
+                Self::Subscribe(channels)
+            }
+            "sunsubscribe" => {
+                let channels = parse_optional_strings(parser)?;
+                Self::SUnsubscribe(channels)
+            }
+            "unsubscribe" => {
+                let channels = parse_optional_strings(parser)?;
+                Self::Unsubscribe(channels)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::PubSub(pub_sub_cmd)))
+    }
+
+    fn parse_pub_sub(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let sub_cmd = parser.next_string()?.to_ascii_lowercase();
+        match sub_cmd.as_str() {
+            "channels" => {
+                let pattern = parser.try_next_string()?;
+                Ok(Self::Channels(pattern))
+            }
+            "numpat" => Ok(Self::NumPat),
+            "numsub" => {
+                let channels = parse_optional_strings(parser)?;
+                Ok(Self::NumSub(channels))
+            }
+            "shardchannels" => {
+                let pattern = parser.try_next_string()?;
+                Ok(Self::ShardChannels(pattern))
+            }
+            "shardnumsub" => {
+                let channels = parse_optional_strings(parser)?;
+                Ok(Self::ShardNumSub(channels))
+            }
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+
+    /// Subscription commands are replied with frames pushed to the client,
+    /// instead of replies to requests.
+    #[must_use]
+    pub const fn is_subscription(&self) -> bool {
+        matches!(
+            self,
+            Self::PSubscribe(_)
+                | Self::PUnsubscribe(_)
+                | Self::SSubscribe(_)
+                | Self::Subscribe(_)
+                | Self::SUnsubscribe(_)
+                | Self::Unsubscribe(_)
+        )
+    }
+}
+
+/// Parse remaining arguments as strings, which may be empty.
+fn parse_optional_strings(parser: &mut Parser) -> Result<Vec<String>, ParseCommandError> {
+    let mut list = Vec::new();
+    while let Some(s) = parser.try_next_string()? {
+        list.push(s);
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::pub_sub::PubSubCommand;
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        PubSubCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let command = parse("subscribe", &["a", "b"]).unwrap();
+        assert!(matches!(
+            command,
+            Some(Command::PubSub(PubSubCommand::Subscribe(ref channels))) if channels.len() == 2
+        ));
+        assert!(command.unwrap().is_subscription());
+        assert!(parse("subscribe", &[]).is_err());
+
+        let command = parse("unsubscribe", &[]).unwrap();
+        assert!(matches!(
+            command,
+            Some(Command::PubSub(PubSubCommand::Unsubscribe(ref channels))) if channels.is_empty()
+        ));
+
+        let command = parse("publish", &["a", "hello"]).unwrap().unwrap();
+        assert!(!command.is_subscription());
+        assert!(matches!(
+            parse("pubsub", &["NUMPAT"]),
+            Ok(Some(Command::PubSub(PubSubCommand::NumPat)))
+        ));
+        assert!(matches!(
+            parse("pubsub", &["channels", "a*"]),
+            Ok(Some(Command::PubSub(PubSubCommand::Channels(Some(_)))))
+        ));
+        assert!(matches!(
+            parse("pubsub", &["SHARDNUMSUB", "a", "b"]),
+            Ok(Some(Command::PubSub(PubSubCommand::ShardNumSub(ref channels)))) if channels.len() == 2
+        ));
+        assert!(parse("ssubscribe", &["a"])
+            .unwrap()
+            .unwrap()
+            .is_subscription());
+        assert!(parse("sunsubscribe", &[])
+            .unwrap()
+            .unwrap()
+            .is_subscription());
+        assert!(!parse("spublish", &["a", "hi"])
+            .unwrap()
+            .unwrap()
+            .is_subscription());
+        assert!(parse("pubsub", &["foo"]).is_err());
+        assert!(matches!(parse("get", &["a"]), Ok(None)));
+    }
+}
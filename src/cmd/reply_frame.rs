@@ -114,6 +114,7 @@ pub enum ReplyFrame {
     /// world\r\n
     /// ```
     Array(Vec<ReplyFrame>),
+    /// Array with no elements, encoded as `*0\r\n`.
     EmptyArray,
     // TODO(Shaohua): Add NullArray
     //NullArray,
@@ -223,7 +224,7 @@ impl ReplyFrame {
             }
 
             Self::EmptyArray => {
-                todo!()
+                bytes.put_slice(b"*0\r\n");
             }
 
             Self::Bulk(val) => {
@@ -257,9 +258,13 @@ impl ReplyFrame {
                 bytes.put_u8(b':');
                 Self::write_usize(bytes, *num);
             }
-            Self::Double(_num) => {
-                // TODO(Shaohua): Convert as bulk reply
-                todo!()
+            Self::Double(num) => {
+                // RESP2 has no double type, send as bulk string.
+                let s = num.to_string();
+                bytes.put_u8(b'$');
+                Self::write_usize(bytes, s.len());
+                bytes.put(s.as_bytes());
+                bytes.put_slice(b"\r\n");
             }
         }
     }
@@ -398,6 +403,10 @@ pub const NO_KEY_ERR: &str = "ERR no such key";
 pub const SYNTAX_ERR: &str = "ERR syntax error";
 pub const SAME_OBJECT_ERR: &str = "ERR source and destination objects are the same";
 pub const OUT_OF_RANGE_ERR: &str = "ERR index out of range";
+pub const VALUE_OUT_OF_RANGE_ERR: &str = "ERR value is out of range";
+pub const NOT_FLOAT_ERR: &str = "ERR value is not a valid float";
+pub const OVERFLOW_ERR: &str = "ERR increment or decrement would overflow";
+pub const NAN_OR_INFINITY_ERR: &str = "ERR increment would produce NaN or Infinity";
 pub const NO_SCRIPT_ERR: &str = "NOSCRIPT No matching script. Please use EVAL.";
 pub const LOADING_ERR: &str = "LOADING Server is loading the dataset in memory";
 pub const SLOW_EVAL_ERR: &str =
@@ -11,11 +11,18 @@ pub enum SetCommand {
     Len(String),
     Members(String),
     IsMember(String, Vec<u8>),
+    MultiIsMember(String, Vec<Vec<u8>>),
+    Move(String, String, Vec<u8>),
+    Pop(String, Option<usize>),
     RandomMember(String, Option<isize>),
     Remove(String, Vec<Vec<u8>>),
     Intersect(Vec<String>),
+    IntersectCard(Vec<String>, usize),
+    IntersectStore(String, Vec<String>),
     Union(Vec<String>),
+    UnionStore(String, Vec<String>),
     Diff(Vec<String>),
+    DiffStore(String, Vec<String>),
 }
 
 impl SetCommand {
@@ -42,6 +49,22 @@ impl SetCommand {
                 let member = parser.next_bytes()?;
                 Self::IsMember(key, member)
             }
+            "smismember" => {
+                let key = parser.next_string()?;
+                let members = parser.remaining()?;
+                Self::MultiIsMember(key, members)
+            }
+            "smove" => {
+                let source = parser.next_string()?;
+                let destination = parser.next_string()?;
+                let member = parser.next_bytes()?;
+                Self::Move(source, destination, member)
+            }
+            "spop" => {
+                let key = parser.next_string()?;
+                let count = parser.try_next_usize()?;
+                Self::Pop(key, count)
+            }
             "srandmember" => {
                 let key = parser.next_string()?;
                 let count = parser.try_next_isize()?;
@@ -56,6 +79,27 @@ impl SetCommand {
                 let keys = parser.remaining_strings()?;
                 Self::Intersect(keys)
             }
+            "sintercard" => {
+                let num_keys = parser.next_usize()?;
+                if num_keys == 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let mut keys = Vec::with_capacity(num_keys);
+                for _i in 0..num_keys {
+                    keys.push(parser.next_string()?);
+                }
+                let limit = match parser.try_next_string()? {
+                    Some(option) if option.eq_ignore_ascii_case("limit") => parser.next_usize()?,
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => 0,
+                };
+                Self::IntersectCard(keys, limit)
+            }
+            "sinterstore" => {
+                let destination = parser.next_string()?;
+                let keys = parser.remaining_strings()?;
+                Self::IntersectStore(destination, keys)
+            }
             "sunion" => {
                 let keys = parser.remaining_strings()?;
                 Self::Union(keys)
@@ -64,8 +108,29 @@ impl SetCommand {
                 let keys = parser.remaining_strings()?;
                 Self::Diff(keys)
             }
+            "sdiffstore" => {
+                let destination = parser.next_string()?;
+                let keys = parser.remaining_strings()?;
+                Self::DiffStore(destination, keys)
+            }
+            "sunionstore" => {
+                let destination = parser.next_string()?;
+                let keys = parser.remaining_strings()?;
+                Self::UnionStore(destination, keys)
+            }
             _ => return Ok(None),
         };
         Ok(Some(Command::Set(set_cmd)))
     }
+
+    /// Returns destination keys which are overwritten by this command.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::IntersectStore(key, _) | Self::UnionStore(key, _) | Self::DiffStore(key, _) => {
+                vec![key.as_str()]
+            }
+            _ => Vec::new(),
+        }
+    }
 }
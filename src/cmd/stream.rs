@@ -0,0 +1,857 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::fmt;
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Stream entry ID, made of a millisecond timestamp and a sequence number.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    pub const MIN: Self = Self::new(0, 0);
+    pub const MAX: Self = Self::new(u64::MAX, u64::MAX);
+
+    #[must_use]
+    #[inline]
+    pub const fn new(ms: u64, seq: u64) -> Self {
+        Self { ms, seq }
+    }
+
+    /// Returns the smallest ID greater than this one.
+    #[must_use]
+    pub const fn next(self) -> Option<Self> {
+        if self.seq < u64::MAX {
+            Some(Self::new(self.ms, self.seq + 1))
+        } else if self.ms < u64::MAX {
+            Some(Self::new(self.ms + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the greatest ID less than this one.
+    #[must_use]
+    pub const fn prev(self) -> Option<Self> {
+        if self.seq > 0 {
+            Some(Self::new(self.ms, self.seq - 1))
+        } else if self.ms > 0 {
+            Some(Self::new(self.ms - 1, u64::MAX))
+        } else {
+            None
+        }
+    }
+
+    /// Parse ID in `<ms>-<seq>` or `<ms>` format, `missing_seq` is used if sequence part
+    /// is omitted.
+    pub fn parse(value: &str, missing_seq: u64) -> Result<Self, ParseCommandError> {
+        match value.split_once('-') {
+            Some((ms, seq)) => Ok(Self::new(ms.parse()?, seq.parse()?)),
+            None => Ok(Self::new(value.parse()?, missing_seq)),
+        }
+    }
+
+    /// Parse start or end of a range, `-` and `+` are the minimum and maximum IDs,
+    /// and IDs prefixed with `(` are exclusive.
+    fn parse_bound(value: &str, is_start: bool) -> Result<Self, ParseCommandError> {
+        match value {
+            "-" => Ok(Self::MIN),
+            "+" => Ok(Self::MAX),
+            _ => {
+                if let Some(value) = value.strip_prefix('(') {
+                    let id = Self::parse(value, if is_start { 0 } else { u64::MAX })?;
+                    let id = if is_start { id.next() } else { id.prev() };
+                    id.ok_or(ParseCommandError::InvalidParameter)
+                } else {
+                    Self::parse(value, if is_start { 0 } else { u64::MAX })
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// ID of new entry in `XADD`.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum AddId {
+    /// `*`, generate both parts of ID.
+    #[default]
+    Auto,
+    /// `<ms>-*`, generate the sequence part only.
+    AutoSeq(u64),
+    Explicit(StreamId),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TrimStrategy {
+    /// Evicts entries as long as the stream's length exceeds the specified threshold.
+    MaxLen(usize),
+    /// Evicts entries with IDs lower than threshold.
+    MinId(StreamId),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TrimOptions {
+    pub strategy: TrimStrategy,
+    /// `~` modifier, only remove whole nodes of entries.
+    pub approx: bool,
+    /// Max number of entries to evict, only valid with `~`.
+    ///
+    /// Defaults to 100 nodes of entries, 0 means no limit.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct AddOptions {
+    /// Do not create a new stream if key does not exist.
+    pub no_mkstream: bool,
+    pub trim: Option<TrimOptions>,
+    pub id: AddId,
+}
+
+/// Last delivered ID of consumer group in `XGROUP CREATE` and `XGROUP SETID`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GroupId {
+    /// `$`, ID of the last entry in the stream.
+    Last,
+    Explicit(StreamId),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GroupOptions {
+    pub group: String,
+    pub id: GroupId,
+    /// Create an empty stream if key does not exist, only valid in `XGROUP CREATE`.
+    pub mkstream: bool,
+    /// Number of entries read by the group, used to compute lag of group.
+    pub entries_read: Option<u64>,
+}
+
+/// ID of stream in `XREAD`, only entries with greater IDs are returned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReadId {
+    /// `$`, ID of the last entry in the stream when the command is called.
+    Last,
+    /// `+`, returns the last entry in the stream.
+    LastEntry,
+    Explicit(StreamId),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReadOptions {
+    pub count: Option<usize>,
+    /// Block in milliseconds if no entries are available, 0 means to block indefinitely.
+    pub block: Option<u64>,
+    pub streams: Vec<(String, ReadId)>,
+}
+
+/// ID of stream in `XREADGROUP`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReadGroupId {
+    /// `>`, entries never delivered to any other consumer.
+    New,
+    /// Pending entries of consumer with IDs greater than this one.
+    Pending(StreamId),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReadGroupOptions {
+    pub group: String,
+    pub consumer: String,
+    pub count: Option<usize>,
+    /// Block in milliseconds if no entries are available, 0 means to block indefinitely.
+    pub block: Option<u64>,
+    /// Do not add delivered entries to the pending entries list.
+    pub no_ack: bool,
+    pub streams: Vec<(String, ReadGroupId)>,
+}
+
+/// Options of extended form of `XPENDING`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PendingOptions {
+    /// Minimal idle time in milliseconds.
+    pub min_idle: Option<i64>,
+    pub start: StreamId,
+    pub end: StreamId,
+    pub count: usize,
+    pub consumer: Option<String>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ClaimOptions {
+    pub consumer: String,
+    /// Minimal idle time in milliseconds.
+    pub min_idle: i64,
+    pub ids: Vec<StreamId>,
+    /// Set idle time of entries in milliseconds.
+    pub idle: Option<i64>,
+    /// Set delivery time of entries to a unix time in milliseconds.
+    pub time: Option<i64>,
+    /// Set delivery count of entries.
+    pub retry_count: Option<u64>,
+    /// Create the pending entries even if they are not in the pending entries list.
+    pub force: bool,
+    /// Return just IDs of claimed entries, and do not increment delivery count.
+    pub just_id: bool,
+    /// Update last delivered ID of group.
+    pub last_id: Option<StreamId>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AutoClaimOptions {
+    pub consumer: String,
+    /// Minimal idle time in milliseconds.
+    pub min_idle: i64,
+    pub start: StreamId,
+    pub count: usize,
+    /// Return just IDs of claimed entries, and do not increment delivery count.
+    pub just_id: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum StreamCommand {
+    /// Key, group and IDs.
+    Ack(String, String, Vec<StreamId>),
+    Add(String, Box<AddOptions>, Vec<(Vec<u8>, Vec<u8>)>),
+    /// Key, group and options.
+    AutoClaim(String, String, Box<AutoClaimOptions>),
+    /// Key, group and options.
+    Claim(String, String, Box<ClaimOptions>),
+    Delete(String, Vec<StreamId>),
+    GroupCreate(String, Box<GroupOptions>),
+    /// Key, group and consumer.
+    GroupCreateConsumer(String, String, String),
+    /// Key, group and consumer.
+    GroupDelConsumer(String, String, String),
+    /// Key and group.
+    GroupDestroy(String, String),
+    GroupSetId(String, Box<GroupOptions>),
+    /// Key and group.
+    InfoConsumers(String, String),
+    InfoGroups(String),
+    /// Key and number of entries to return with `FULL` option, 0 means all entries.
+    InfoStream(String, Option<usize>),
+    Len(String),
+    /// Key, group and options of extended form.
+    Pending(String, String, Option<Box<PendingOptions>>),
+    Read(Box<ReadOptions>),
+    ReadGroup(Box<ReadGroupOptions>),
+    /// Key, start, end and count.
+    Range(String, StreamId, StreamId, Option<usize>),
+    /// Key, start, end and count, entries are returned in reverse order.
+    RevRange(String, StreamId, StreamId, Option<usize>),
+    Trim(String, TrimOptions),
+}
+
+impl StreamCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let stream_cmd = match cmd_name {
+            "xack" => {
+                let key = parser.next_string()?;
+                let group = parser.next_string()?;
+                let ids = parser
+                    .remaining_strings()?
+                    .iter()
+                    .map(|id| StreamId::parse(id, 0))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Self::Ack(key, group, ids)
+            }
+            "xadd" => Self::parse_add(parser)?,
+            "xautoclaim" => Self::parse_auto_claim(parser)?,
+            "xclaim" => Self::parse_claim(parser)?,
+            "xdel" => {
+                let key = parser.next_string()?;
+                let ids = parser
+                    .remaining_strings()?
+                    .iter()
+                    .map(|id| StreamId::parse(id, 0))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Self::Delete(key, ids)
+            }
+            "xgroup" => Self::parse_group(parser)?,
+            "xinfo" => Self::parse_info(parser)?,
+            "xlen" => {
+                let key = parser.next_string()?;
+                Self::Len(key)
+            }
+            "xpending" => Self::parse_pending(parser)?,
+            "xrange" => {
+                let key = parser.next_string()?;
+                let start = StreamId::parse_bound(&parser.next_string()?, true)?;
+                let end = StreamId::parse_bound(&parser.next_string()?, false)?;
+                let count = parse_count(parser)?;
+                Self::Range(key, start, end, count)
+            }
+            "xread" => Self::parse_read(parser)?,
+            "xreadgroup" => Self::parse_read_group(parser)?,
+            "xrevrange" => {
+                let key = parser.next_string()?;
+                let end = StreamId::parse_bound(&parser.next_string()?, false)?;
+                let start = StreamId::parse_bound(&parser.next_string()?, true)?;
+                let count = parse_count(parser)?;
+                Self::RevRange(key, start, end, count)
+            }
+            "xtrim" => {
+                let key = parser.next_string()?;
+                let strategy = parser.next_string()?;
+                let mut options = parse_trim(parser, &strategy)?;
+                match parser.try_next_string()? {
+                    Some(token) if token.eq_ignore_ascii_case("limit") => {
+                        set_limit(Some(&mut options), parser.next_usize()?)?;
+                    }
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => (),
+                }
+                Self::Trim(key, options)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Command::Stream(stream_cmd)))
+    }
+
+    /// Parse `key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    /// <* | id> field value [field value ...]` arguments.
+    fn parse_add(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut options = AddOptions::default();
+        loop {
+            let token = parser.next_string()?;
+            match token.to_ascii_lowercase().as_str() {
+                "nomkstream" => options.no_mkstream = true,
+                "maxlen" | "minid" => options.trim = Some(parse_trim(parser, &token)?),
+                "limit" => set_limit(options.trim.as_mut(), parser.next_usize()?)?,
+                "*" => break,
+                _ => {
+                    options.id = match token.strip_suffix("-*") {
+                        Some(ms) => AddId::AutoSeq(ms.parse()?),
+                        None => AddId::Explicit(StreamId::parse(&token, 0)?),
+                    };
+                    break;
+                }
+            }
+        }
+
+        let values = parser.remaining()?;
+        if values.len() % 2 != 0 {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let mut fields = Vec::with_capacity(values.len() / 2);
+        let mut iter = values.into_iter();
+        while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+            fields.push((field, value));
+        }
+        Ok(Self::Add(key, Box::new(options), fields))
+    }
+
+    /// Parse `<STREAM key [FULL [COUNT count]] | GROUPS key | CONSUMERS key group>` arguments.
+    fn parse_info(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let sub_cmd = parser.next_string()?.to_ascii_lowercase();
+        match sub_cmd.as_str() {
+            "stream" => {
+                let key = parser.next_string()?;
+                let full = match parser.try_next_string()? {
+                    Some(option) if option.eq_ignore_ascii_case("full") => {
+                        Some(parse_count(parser)?.unwrap_or(10))
+                    }
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => None,
+                };
+                Ok(Self::InfoStream(key, full))
+            }
+            "groups" => {
+                let key = parser.next_string()?;
+                Ok(Self::InfoGroups(key))
+            }
+            "consumers" => {
+                let key = parser.next_string()?;
+                let group = parser.next_string()?;
+                Ok(Self::InfoConsumers(key, group))
+            }
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+
+    /// Parse `key group consumer min-idle-time start [COUNT count] [JUSTID]` arguments.
+    fn parse_auto_claim(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let group = parser.next_string()?;
+        let consumer = parser.next_string()?;
+        let min_idle = parser.next_i64()?.max(0);
+        let start = StreamId::parse_bound(&parser.next_string()?, true)?;
+        let mut options = AutoClaimOptions {
+            consumer,
+            min_idle,
+            start,
+            count: 100,
+            just_id: false,
+        };
+        while let Some(token) = parser.try_next_string()? {
+            match token.to_ascii_lowercase().as_str() {
+                "count" => {
+                    let count = parser.next_usize()?;
+                    // Max number of attempts is ten times the count.
+                    if count == 0 || count > usize::MAX / 10 {
+                        return Err(ParseCommandError::InvalidParameter);
+                    }
+                    options.count = count;
+                }
+                "justid" => options.just_id = true,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        Ok(Self::AutoClaim(key, group, Box::new(options)))
+    }
+
+    /// Parse `key group consumer min-idle-time id [id ...] [IDLE ms] [TIME unix-time-milliseconds]
+    /// [RETRYCOUNT count] [FORCE] [JUSTID] [LASTID lastid]` arguments.
+    fn parse_claim(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let group = parser.next_string()?;
+        let consumer = parser.next_string()?;
+        let min_idle = parser.next_i64()?.max(0);
+        let mut options = ClaimOptions {
+            consumer,
+            min_idle,
+            ids: Vec::new(),
+            idle: None,
+            time: None,
+            retry_count: None,
+            force: false,
+            just_id: false,
+            last_id: None,
+        };
+        let mut parsing_ids = true;
+        while let Some(token) = parser.try_next_string()? {
+            if parsing_ids {
+                if let Ok(id) = StreamId::parse(&token, 0) {
+                    options.ids.push(id);
+                    continue;
+                }
+                parsing_ids = false;
+            }
+            match token.to_ascii_lowercase().as_str() {
+                "idle" => options.idle = Some(parse_non_negative(parser)?),
+                "time" => options.time = Some(parse_non_negative(parser)?),
+                "retrycount" => options.retry_count = Some(parser.next_string()?.parse()?),
+                "force" => options.force = true,
+                "justid" => options.just_id = true,
+                "lastid" => options.last_id = Some(StreamId::parse(&parser.next_string()?, 0)?),
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        if options.ids.is_empty() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(Self::Claim(key, group, Box::new(options)))
+    }
+
+    /// Parse `<CREATE | SETID | DESTROY | CREATECONSUMER | DELCONSUMER> key group ...`
+    /// arguments.
+    fn parse_group(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let sub_cmd = parser.next_string()?.to_ascii_lowercase();
+        let key = parser.next_string()?;
+        let group = parser.next_string()?;
+        let command = match sub_cmd.as_str() {
+            "create" | "setid" => {
+                let id = match parser.next_string()?.as_str() {
+                    "$" => GroupId::Last,
+                    id => GroupId::Explicit(StreamId::parse(id, 0)?),
+                };
+                let mut options = GroupOptions {
+                    group,
+                    id,
+                    mkstream: false,
+                    entries_read: None,
+                };
+                while let Some(token) = parser.try_next_string()? {
+                    match token.to_ascii_lowercase().as_str() {
+                        "mkstream" if sub_cmd == "create" => options.mkstream = true,
+                        "entriesread" => {
+                            // -1 means the number of entries read is unknown.
+                            let entries_read = parser.next_i64()?;
+                            if entries_read < -1 {
+                                return Err(ParseCommandError::InvalidParameter);
+                            }
+                            options.entries_read = u64::try_from(entries_read).ok();
+                        }
+                        _ => return Err(ParseCommandError::InvalidParameter),
+                    }
+                }
+                return if sub_cmd == "create" {
+                    Ok(Self::GroupCreate(key, Box::new(options)))
+                } else {
+                    Ok(Self::GroupSetId(key, Box::new(options)))
+                };
+            }
+            "createconsumer" => Self::GroupCreateConsumer(key, group, parser.next_string()?),
+            "delconsumer" => Self::GroupDelConsumer(key, group, parser.next_string()?),
+            "destroy" => Self::GroupDestroy(key, group),
+            _ => return Err(ParseCommandError::InvalidParameter),
+        };
+        if parser.try_next_string()?.is_some() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(command)
+    }
+
+    /// Parse `key group [[IDLE min-idle-time] start end count [consumer]]` arguments.
+    fn parse_pending(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let group = parser.next_string()?;
+        let Some(mut token) = parser.try_next_string()? else {
+            return Ok(Self::Pending(key, group, None));
+        };
+        let mut min_idle = None;
+        if token.eq_ignore_ascii_case("idle") {
+            min_idle = Some(parser.next_i64()?);
+            token = parser.next_string()?;
+        }
+        let start = StreamId::parse_bound(&token, true)?;
+        let end = StreamId::parse_bound(&parser.next_string()?, false)?;
+        // Negative count is treated as 0.
+        let count = usize::try_from(parser.next_isize()?).unwrap_or(0);
+        let consumer = parser.try_next_string()?;
+        if parser.try_next_string()?.is_some() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let options = PendingOptions {
+            min_idle,
+            start,
+            end,
+            count,
+            consumer,
+        };
+        Ok(Self::Pending(key, group, Some(Box::new(options))))
+    }
+
+    /// Parse `GROUP group consumer [COUNT count] [NOACK] STREAMS key [key ...] id [id ...]`
+    /// arguments.
+    fn parse_read_group(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        if !parser.next_string()?.eq_ignore_ascii_case("group") {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let group = parser.next_string()?;
+        let consumer = parser.next_string()?;
+        let mut count = None;
+        let mut block = None;
+        let mut no_ack = false;
+        loop {
+            let token = parser.next_string()?;
+            match token.to_ascii_lowercase().as_str() {
+                // Count 0 means no limit.
+                "count" => count = Some(parser.next_usize()?).filter(|&count| count > 0),
+                "block" => block = Some(parse_block(parser)?),
+                "noack" => no_ack = true,
+                "streams" => break,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        let streams = parse_streams(parser)?
+            .into_iter()
+            .map(|(key, id)| {
+                let id = if id == ">" {
+                    ReadGroupId::New
+                } else {
+                    ReadGroupId::Pending(StreamId::parse(&id, 0)?)
+                };
+                Ok((key, id))
+            })
+            .collect::<Result<Vec<_>, ParseCommandError>>()?;
+        Ok(Self::ReadGroup(Box::new(ReadGroupOptions {
+            group,
+            consumer,
+            count,
+            block,
+            no_ack,
+            streams,
+        })))
+    }
+
+    /// Parse `[COUNT count] [BLOCK milliseconds] STREAMS key [key ...] id [id ...]` arguments.
+    fn parse_read(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let mut count = None;
+        let mut block = None;
+        loop {
+            let token = parser.next_string()?;
+            match token.to_ascii_lowercase().as_str() {
+                // Count 0 means no limit.
+                "count" => count = Some(parser.next_usize()?).filter(|&count| count > 0),
+                "block" => block = Some(parse_block(parser)?),
+                "streams" => break,
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+        }
+        let streams = parse_streams(parser)?
+            .into_iter()
+            .map(|(key, id)| {
+                let id = match id.as_str() {
+                    "$" => ReadId::Last,
+                    "+" => ReadId::LastEntry,
+                    _ => ReadId::Explicit(StreamId::parse(&id, 0)?),
+                };
+                Ok((key, id))
+            })
+            .collect::<Result<Vec<_>, ParseCommandError>>()?;
+        Ok(Self::Read(Box::new(ReadOptions {
+            count,
+            block,
+            streams,
+        })))
+    }
+
+    /// Returns timeout in seconds of blocking commands.
+    #[must_use]
+    pub fn timeout(&self) -> Option<f64> {
+        let block = match self {
+            Self::Read(options) => options.block,
+            Self::ReadGroup(options) => options.block,
+            _ => None,
+        };
+        #[allow(clippy::cast_precision_loss)]
+        block.map(|block| block as f64 / 1000.0)
+    }
+
+    /// Returns keys watched by blocking commands.
+    #[must_use]
+    pub fn blocking_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Read(options) => options
+                .streams
+                .iter()
+                .map(|(key, _id)| key.as_str())
+                .collect(),
+            Self::ReadGroup(options) => options
+                .streams
+                .iter()
+                .map(|(key, _id)| key.as_str())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns keys which may have new entries, or whose consumer groups are changed
+    /// after this command is executed.
+    #[must_use]
+    pub fn signaled_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Add(key, ..) | Self::GroupDestroy(key, _) | Self::GroupSetId(key, _) => {
+                vec![key.as_str()]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Parse timeout in milliseconds after `BLOCK` keyword, it shall not be negative.
+fn parse_block(parser: &mut Parser) -> Result<u64, ParseCommandError> {
+    u64::try_from(parser.next_i64()?).map_err(|_err| ParseCommandError::InvalidParameter)
+}
+
+/// Parse milliseconds after `IDLE` or `TIME` keyword, it shall not be negative.
+fn parse_non_negative(parser: &mut Parser) -> Result<i64, ParseCommandError> {
+    let millis = parser.next_i64()?;
+    if millis < 0 {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    Ok(millis)
+}
+
+/// Parse `[= | ~] threshold` after `MAXLEN` or `MINID` keyword.
+fn parse_trim(parser: &mut Parser, strategy: &str) -> Result<TrimOptions, ParseCommandError> {
+    let mut threshold = parser.next_string()?;
+    let mut approx = false;
+    if threshold == "~" || threshold == "=" {
+        approx = threshold == "~";
+        threshold = parser.next_string()?;
+    }
+    let strategy = match strategy.to_ascii_lowercase().as_str() {
+        "maxlen" => TrimStrategy::MaxLen(threshold.parse()?),
+        "minid" => TrimStrategy::MinId(StreamId::parse(&threshold, 0)?),
+        _ => return Err(ParseCommandError::InvalidParameter),
+    };
+    Ok(TrimOptions {
+        strategy,
+        approx,
+        limit: None,
+    })
+}
+
+/// `LIMIT` follows trim strategy, and cannot be used without the special `~` option.
+fn set_limit(trim: Option<&mut TrimOptions>, limit: usize) -> Result<(), ParseCommandError> {
+    match trim {
+        Some(trim) if trim.approx && trim.limit.is_none() => {
+            trim.limit = Some(limit);
+            Ok(())
+        }
+        _ => Err(ParseCommandError::InvalidParameter),
+    }
+}
+
+/// Parse `key [key ...] id [id ...]` arguments after `STREAMS` keyword.
+fn parse_streams(parser: &mut Parser) -> Result<Vec<(String, String)>, ParseCommandError> {
+    let mut keys = parser.remaining_strings()?;
+    if keys.len() % 2 != 0 {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    let ids = keys.split_off(keys.len() / 2);
+    Ok(keys.into_iter().zip(ids).collect())
+}
+
+/// Parse optional `COUNT count` arguments.
+fn parse_count(parser: &mut Parser) -> Result<Option<usize>, ParseCommandError> {
+    match parser.try_next_string()? {
+        Some(option) if option.eq_ignore_ascii_case("count") => {
+            let count = parser.next_usize()?;
+            if parser.try_next_string()?.is_some() {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+            Ok(Some(count))
+        }
+        Some(_) => Err(ParseCommandError::InvalidParameter),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use crate::cmd::parse::Parser;
+    use crate::cmd::stream::{ReadGroupId, ReadId, StreamCommand, StreamId};
+    use crate::cmd::Command;
+
+    fn parse(args: &[&str]) -> Option<StreamCommand> {
+        match StreamCommand::parse(args[0], &mut Parser::from_args(&args[1..])) {
+            Ok(Some(Command::Stream(cmd))) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size_of::<StreamCommand>(), 80);
+    }
+
+    #[test]
+    fn test_stream_id() {
+        assert_eq!(
+            StreamId::parse("1526919030474-55", 0),
+            Ok(StreamId::new(1_526_919_030_474, 55))
+        );
+        assert_eq!(
+            StreamId::parse("12", u64::MAX),
+            Ok(StreamId::new(12, u64::MAX))
+        );
+        assert!(StreamId::parse("12-", 0).is_err());
+        assert_eq!(StreamId::parse_bound("-", true), Ok(StreamId::MIN));
+        assert_eq!(
+            StreamId::parse_bound("(5-0", false),
+            Ok(StreamId::new(4, u64::MAX))
+        );
+        assert_eq!(StreamId::parse_bound("(5", true), Ok(StreamId::new(5, 1)));
+        assert!(StreamId::parse_bound("(0-0", false).is_err());
+        assert_eq!(StreamId::new(3, 7).to_string(), "3-7");
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let cmd = parse(&[
+            "xreadgroup",
+            "GROUP",
+            "mygroup",
+            "alice",
+            "COUNT",
+            "0",
+            "NOACK",
+            "STREAMS",
+            "s1",
+            "s2",
+            ">",
+            "5-1",
+        ]);
+        let Some(StreamCommand::ReadGroup(options)) = cmd else {
+            panic!("expected XREADGROUP command");
+        };
+        assert_eq!(options.count, None);
+        assert_eq!(options.block, None);
+        assert!(options.no_ack);
+        assert_eq!(
+            options.streams,
+            vec![
+                ("s1".to_owned(), ReadGroupId::New),
+                ("s2".to_owned(), ReadGroupId::Pending(StreamId::new(5, 1))),
+            ]
+        );
+        assert!(parse(&["xreadgroup", "GROUP", "g", "c", "STREAMS", "s1", "s2", ">"]).is_none());
+
+        let cmd = parse(&[
+            "xclaim", "s", "g", "c", "10", "1-0", "2", "JUSTID", "LASTID", "3-0",
+        ]);
+        let Some(StreamCommand::Claim(_, _, options)) = cmd else {
+            panic!("expected XCLAIM command");
+        };
+        assert_eq!(options.ids, vec![StreamId::new(1, 0), StreamId::new(2, 0)]);
+        assert!(options.just_id);
+        assert_eq!(options.last_id, Some(StreamId::new(3, 0)));
+
+        assert!(parse(&["xclaim", "s", "g", "c", "10", "1-0", "IDLE", "-1"]).is_none());
+        assert!(parse(&[
+            "xclaim",
+            "s",
+            "g",
+            "c",
+            "10",
+            "1-0",
+            "TIME",
+            "-9223372036854775808"
+        ])
+        .is_none());
+
+        assert!(parse(&["xgroup", "setid", "s", "g", "$", "MKSTREAM"]).is_none());
+        assert!(parse(&["xgroup", "create", "s", "g", "$", "ENTRIESREAD", "-2"]).is_none());
+        assert!(parse(&["xautoclaim", "s", "g", "c", "0", "0", "COUNT", "0"]).is_none());
+        assert!(matches!(
+            parse(&["xpending", "s", "g", "IDLE", "9", "-", "+", "10", "c"]),
+            Some(StreamCommand::Pending(_, _, Some(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_read() {
+        let cmd = parse(&[
+            "xread", "COUNT", "2", "BLOCK", "500", "STREAMS", "s1", "s2", "s3", "$", "+", "0",
+        ]);
+        let Some(StreamCommand::Read(options)) = cmd.clone() else {
+            panic!("expected XREAD command");
+        };
+        assert_eq!(options.count, Some(2));
+        assert_eq!(options.block, Some(500));
+        assert_eq!(
+            options.streams,
+            vec![
+                ("s1".to_owned(), ReadId::Last),
+                ("s2".to_owned(), ReadId::LastEntry),
+                ("s3".to_owned(), ReadId::Explicit(StreamId::MIN)),
+            ]
+        );
+        assert_eq!(cmd.and_then(|cmd| cmd.timeout()), Some(0.5));
+        assert!(parse(&["xread", "BLOCK", "-1", "STREAMS", "s1", "0"]).is_none());
+        assert!(parse(&["xreadgroup", "GROUP", "g", "c", "STREAMS", "s1", "$"]).is_none());
+    }
+}
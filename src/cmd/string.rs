@@ -84,6 +84,16 @@ impl StringCommand {
 
         Ok(Some(Command::Str(str_cmd)))
     }
+
+    /// Returns keys which are overwritten by this command, whatever their old types are.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Set(key, _) => vec![key.as_str()],
+            Self::MultiSet(pairs) => pairs.iter().map(|(key, _value)| key.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
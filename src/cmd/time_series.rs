@@ -0,0 +1,814 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Policy to handle multiple samples with identical timestamps.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Ignore any newly reported value and reply with an error.
+    Block,
+    /// Ignore any newly reported value.
+    First,
+    /// Override with the newly reported value.
+    Last,
+    /// Only override if the value is lower than the existing value.
+    Min,
+    /// Only override if the value is higher than the existing value.
+    Max,
+    /// If a previous sample exists, add the new sample to it.
+    Sum,
+}
+
+impl TryFrom<&str> for DuplicatePolicy {
+    type Error = ParseCommandError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(Self::Block),
+            "first" => Ok(Self::First),
+            "last" => Ok(Self::Last),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "sum" => Ok(Self::Sum),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+/// Aggregation type of samples in a time bucket.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Aggregator {
+    Avg,
+    Sum,
+    Min,
+    Max,
+    /// Difference between the maximum and the minimum value.
+    Range,
+    Count,
+    First,
+    Last,
+    /// Population standard deviation.
+    StdP,
+    /// Sample standard deviation.
+    StdS,
+    /// Population variance.
+    VarP,
+    /// Sample variance.
+    VarS,
+    /// Time-weighted average.
+    Twa,
+}
+
+impl TryFrom<&str> for Aggregator {
+    type Error = ParseCommandError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "avg" => Ok(Self::Avg),
+            "sum" => Ok(Self::Sum),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "range" => Ok(Self::Range),
+            "count" => Ok(Self::Count),
+            "first" => Ok(Self::First),
+            "last" => Ok(Self::Last),
+            "std.p" => Ok(Self::StdP),
+            "std.s" => Ok(Self::StdS),
+            "var.p" => Ok(Self::VarP),
+            "var.s" => Ok(Self::VarS),
+            "twa" => Ok(Self::Twa),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+impl Aggregator {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Avg => "avg",
+            Self::Sum => "sum",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Range => "range",
+            Self::Count => "count",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::StdP => "std.p",
+            Self::StdS => "std.s",
+            Self::VarP => "var.p",
+            Self::VarS => "var.s",
+            Self::Twa => "twa",
+        }
+    }
+}
+
+/// Timestamp reported for each aggregated bucket.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum BucketTimestamp {
+    #[default]
+    Start,
+    End,
+    Mid,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Aggregation {
+    pub aggregator: Aggregator,
+    /// Duration of each bucket, in milliseconds.
+    pub bucket_duration: i64,
+    pub bucket_timestamp: BucketTimestamp,
+    /// Report aggregations for empty buckets too.
+    pub empty: bool,
+}
+
+/// Alignment of aggregation buckets.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Align {
+    #[default]
+    Zero,
+    /// Align to the start of range.
+    Start,
+    /// Align to the end of range.
+    End,
+    Timestamp(i64),
+}
+
+/// Options of a time series, used to create or alter a key.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SeriesOptions {
+    /// Maximum age of samples compared to the highest reported timestamp, in milliseconds.
+    pub retention: Option<i64>,
+    /// Memory size of each data chunk, in bytes.
+    pub chunk_size: Option<usize>,
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    pub labels: Option<Vec<(String, String)>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddOptions {
+    pub key: String,
+    /// None means the current time.
+    pub timestamp: Option<i64>,
+    pub value: f64,
+    /// Overwrite duplicate policy of this sample.
+    pub on_duplicate: Option<DuplicatePolicy>,
+    pub series: SeriesOptions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncrByOptions {
+    pub key: String,
+    pub value: f64,
+    /// None means the current time.
+    pub timestamp: Option<i64>,
+    pub series: SeriesOptions,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RangeOptions {
+    /// None means the earliest sample.
+    pub from: Option<i64>,
+    /// None means the latest sample.
+    pub to: Option<i64>,
+    pub filter_by_ts: Vec<i64>,
+    pub filter_by_value: Option<(f64, f64)>,
+    pub count: Option<usize>,
+    pub align: Align,
+    pub aggregation: Option<Aggregation>,
+}
+
+/// Filter expression to select time series by labels.
+///
+/// Value of an absent label is treated as empty string, so that `label=`
+/// matches series without the label and `label!=` matches series with it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LabelFilter {
+    pub label: String,
+    /// Label value equals to any of these values, like `label=(v1,v2)`.
+    pub values: Vec<String>,
+    /// Label value does not equal to any of values, like `label!=value`.
+    pub negate: bool,
+}
+
+impl TryFrom<&str> for LabelFilter {
+    type Error = ParseCommandError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (label, value) = s
+            .split_once('=')
+            .ok_or(ParseCommandError::InvalidParameter)?;
+        let (label, negate) = label
+            .strip_suffix('!')
+            .map_or((label, false), |label| (label, true));
+        if label.is_empty() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let values = value
+            .strip_prefix('(')
+            .and_then(|value| value.strip_suffix(')'))
+            .map_or_else(
+                || vec![value.to_owned()],
+                |list| list.split(',').map(str::to_owned).collect(),
+            );
+        Ok(Self {
+            label: label.to_owned(),
+            values,
+            negate,
+        })
+    }
+}
+
+impl LabelFilter {
+    /// Check whether value of label matches this filter, `None` if label is absent.
+    #[must_use]
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        let value = value.unwrap_or_default();
+        self.values.iter().any(|expected| expected == value) != self.negate
+    }
+
+    /// Returns true for `label=value` and `label=(v1,v2)` filters, which only match
+    /// series with the label.
+    #[must_use]
+    pub fn is_positive(&self) -> bool {
+        !self.negate && self.values.iter().all(|value| !value.is_empty())
+    }
+}
+
+/// Labels reported for each time series.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub enum LabelsReply {
+    #[default]
+    Empty,
+    All,
+    Selected(Vec<String>),
+}
+
+/// Options to query multiple time series selected by label filters.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct MultiSeriesOptions {
+    pub filters: Vec<LabelFilter>,
+    pub labels: LabelsReply,
+    /// Group series by value of label, and reduce samples of each group.
+    pub group_by: Option<(String, Aggregator)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TimeSeriesCommand {
+    Add(Box<AddOptions>),
+    Alter(String, Box<SeriesOptions>),
+    Create(String, Box<SeriesOptions>),
+    /// Source key, destination key, aggregator, bucket duration and align timestamp.
+    CreateRule(String, String, Aggregator, i64, i64),
+    DecrBy(Box<IncrByOptions>),
+    Del(String, Option<i64>, Option<i64>),
+    DeleteRule(String, String),
+    Get(String),
+    IncrBy(Box<IncrByOptions>),
+    MAdd(Vec<(String, Option<i64>, f64)>),
+    MGet(Box<MultiSeriesOptions>),
+    MRange(Box<RangeOptions>, Box<MultiSeriesOptions>),
+    MRevRange(Box<RangeOptions>, Box<MultiSeriesOptions>),
+    QueryIndex(Vec<LabelFilter>),
+    Range(String, Box<RangeOptions>),
+    RevRange(String, Box<RangeOptions>),
+}
+
+impl TimeSeriesCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let ts_cmd = match cmd_name {
+            "ts.add" => Self::Add(Box::new(Self::parse_add(parser)?)),
+            "ts.alter" => {
+                let key = parser.next_string()?;
+                let options = Self::parse_series_options(parser)?;
+                Self::Alter(key, Box::new(options))
+            }
+            "ts.create" => {
+                let key = parser.next_string()?;
+                let options = Self::parse_series_options(parser)?;
+                Self::Create(key, Box::new(options))
+            }
+            "ts.createrule" => {
+                let source = parser.next_string()?;
+                let dest = parser.next_string()?;
+                if !parser.next_string()?.eq_ignore_ascii_case("aggregation") {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let aggregator = Aggregator::try_from(parser.next_string()?.as_str())?;
+                let bucket_duration = parser.next_i64()?;
+                if bucket_duration <= 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let align = match parser.try_next_string()? {
+                    Some(align) => align.parse::<i64>()?,
+                    None => 0,
+                };
+                Self::CreateRule(source, dest, aggregator, bucket_duration, align)
+            }
+            "ts.decrby" => Self::DecrBy(Box::new(Self::parse_incr_by(parser)?)),
+            "ts.del" => {
+                let key = parser.next_string()?;
+                let from = parse_range_timestamp(&parser.next_string()?)?;
+                let to = parse_range_timestamp(&parser.next_string()?)?;
+                Self::Del(key, from, to)
+            }
+            "ts.deleterule" => Self::DeleteRule(parser.next_string()?, parser.next_string()?),
+            "ts.get" => Self::Get(parser.next_string()?),
+            "ts.incrby" => Self::IncrBy(Box::new(Self::parse_incr_by(parser)?)),
+            "ts.madd" => {
+                let mut samples = Vec::new();
+                while let Some(key) = parser.try_next_string()? {
+                    let timestamp = parse_timestamp(&parser.next_string()?)?;
+                    let value = parser.next_f64()?;
+                    samples.push((key, timestamp, value));
+                }
+                if samples.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::MAdd(samples)
+            }
+            "ts.mget" => Self::MGet(Box::new(Self::parse_mget(parser)?)),
+            "ts.mrange" => {
+                let (options, multi) = Self::parse_multi_range(parser)?;
+                Self::MRange(Box::new(options), Box::new(multi))
+            }
+            "ts.mrevrange" => {
+                let (options, multi) = Self::parse_multi_range(parser)?;
+                Self::MRevRange(Box::new(options), Box::new(multi))
+            }
+            "ts.queryindex" => {
+                let filters = parser
+                    .remaining_strings()?
+                    .iter()
+                    .map(|filter| LabelFilter::try_from(filter.as_str()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                check_filters(&filters)?;
+                Self::QueryIndex(filters)
+            }
+            "ts.range" => {
+                let key = parser.next_string()?;
+                Self::Range(key, Box::new(Self::parse_range(parser, None)?))
+            }
+            "ts.revrange" => {
+                let key = parser.next_string()?;
+                Self::RevRange(key, Box::new(Self::parse_range(parser, None)?))
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::TimeSeries(ts_cmd)))
+    }
+
+    /// Parse `[RETENTION retentionPeriod] [CHUNK_SIZE size] [DUPLICATE_POLICY policy]
+    /// [LABELS label value ...]` arguments.
+    fn parse_series_options(parser: &mut Parser) -> Result<SeriesOptions, ParseCommandError> {
+        let mut options = SeriesOptions::default();
+        while let Some(token) = parser.try_next_string()? {
+            if !Self::parse_series_option(&token, parser, &mut options)? {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+        }
+        Ok(options)
+    }
+
+    /// Parse one of series options, returns false if `token` is not a series option.
+    fn parse_series_option(
+        token: &str,
+        parser: &mut Parser,
+        options: &mut SeriesOptions,
+    ) -> Result<bool, ParseCommandError> {
+        match token.to_ascii_lowercase().as_str() {
+            "retention" => {
+                let retention = parser.next_i64()?;
+                if retention < 0 {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                options.retention = Some(retention);
+            }
+            "chunk_size" => options.chunk_size = Some(parser.next_usize()?),
+            "duplicate_policy" => {
+                let policy = DuplicatePolicy::try_from(parser.next_string()?.as_str())?;
+                options.duplicate_policy = Some(policy);
+            }
+            "labels" => {
+                // Labels are the last arguments.
+                let mut labels = Vec::new();
+                while let Some(label) = parser.try_next_string()? {
+                    labels.push((label, parser.next_string()?));
+                }
+                options.labels = Some(labels);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Parse `key timestamp value [ON_DUPLICATE policy] [series options]` arguments.
+    fn parse_add(parser: &mut Parser) -> Result<AddOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let timestamp = parse_timestamp(&parser.next_string()?)?;
+        let value = parser.next_f64()?;
+        let mut on_duplicate = None;
+        let mut series = SeriesOptions::default();
+        while let Some(token) = parser.try_next_string()? {
+            if token.eq_ignore_ascii_case("on_duplicate") {
+                on_duplicate = Some(DuplicatePolicy::try_from(parser.next_string()?.as_str())?);
+            } else if !Self::parse_series_option(&token, parser, &mut series)? {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+        }
+        Ok(AddOptions {
+            key,
+            timestamp,
+            value,
+            on_duplicate,
+            series,
+        })
+    }
+
+    /// Parse `key value [TIMESTAMP timestamp] [series options]` arguments.
+    fn parse_incr_by(parser: &mut Parser) -> Result<IncrByOptions, ParseCommandError> {
+        let key = parser.next_string()?;
+        let value = parser.next_f64()?;
+        let mut timestamp = None;
+        let mut series = SeriesOptions::default();
+        while let Some(token) = parser.try_next_string()? {
+            if token.eq_ignore_ascii_case("timestamp") {
+                timestamp = parse_timestamp(&parser.next_string()?)?;
+            } else if !Self::parse_series_option(&token, parser, &mut series)? {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+        }
+        Ok(IncrByOptions {
+            key,
+            value,
+            timestamp,
+            series,
+        })
+    }
+
+    /// Parse `fromTimestamp toTimestamp [FILTER_BY_TS ts...] [FILTER_BY_VALUE min max]
+    /// [COUNT count] [ALIGN align] [AGGREGATION aggregator bucketDuration
+    /// [BUCKETTIMESTAMP bt] [EMPTY]]` arguments.
+    ///
+    /// Options of multiple series are accepted too if `multi` is specified.
+    fn parse_range(
+        parser: &mut Parser,
+        mut multi: Option<&mut MultiSeriesOptions>,
+    ) -> Result<RangeOptions, ParseCommandError> {
+        let mut options = RangeOptions {
+            from: parse_range_timestamp(&parser.next_string()?)?,
+            to: parse_range_timestamp(&parser.next_string()?)?,
+            ..RangeOptions::default()
+        };
+        let mut token = parser.try_next_string()?;
+        while let Some(current) = token.take() {
+            match current.to_ascii_lowercase().as_str() {
+                "filter_by_ts" => {
+                    // Timestamps are followed by other options or nothing.
+                    while let Some(next) = parser.try_next_string()? {
+                        if let Ok(timestamp) = next.parse::<i64>() {
+                            options.filter_by_ts.push(timestamp);
+                        } else {
+                            token = Some(next);
+                            break;
+                        }
+                    }
+                    if options.filter_by_ts.is_empty() {
+                        return Err(ParseCommandError::InvalidParameter);
+                    }
+                    continue;
+                }
+                "filter_by_value" => {
+                    let min = parser.next_f64()?;
+                    let max = parser.next_f64()?;
+                    options.filter_by_value = Some((min, max));
+                }
+                "count" => options.count = Some(parser.next_usize()?),
+                "align" => options.align = parse_align(&parser.next_string()?)?,
+                "aggregation" => {
+                    let (aggregation, next) = Self::parse_aggregation(parser)?;
+                    options.aggregation = Some(aggregation);
+                    token = next;
+                    continue;
+                }
+                "withlabels" | "selected_labels" | "filter" | "groupby" => {
+                    let multi = multi
+                        .as_deref_mut()
+                        .ok_or(ParseCommandError::InvalidParameter)?;
+                    token = Self::parse_multi_option(&current, parser, multi)?;
+                    continue;
+                }
+                _ => return Err(ParseCommandError::InvalidParameter),
+            }
+            token = parser.try_next_string()?;
+        }
+        Ok(options)
+    }
+
+    /// Parse `aggregator bucketDuration [BUCKETTIMESTAMP bt] [EMPTY]` arguments,
+    /// returns aggregation and the next token.
+    fn parse_aggregation(
+        parser: &mut Parser,
+    ) -> Result<(Aggregation, Option<String>), ParseCommandError> {
+        let aggregator = Aggregator::try_from(parser.next_string()?.as_str())?;
+        let bucket_duration = parser.next_i64()?;
+        if bucket_duration <= 0 {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let mut aggregation = Aggregation {
+            aggregator,
+            bucket_duration,
+            bucket_timestamp: BucketTimestamp::Start,
+            empty: false,
+        };
+        loop {
+            let Some(token) = parser.try_next_string()? else {
+                return Ok((aggregation, None));
+            };
+            match token.to_ascii_lowercase().as_str() {
+                "buckettimestamp" => {
+                    aggregation.bucket_timestamp =
+                        match parser.next_string()?.to_ascii_lowercase().as_str() {
+                            "-" | "start" => BucketTimestamp::Start,
+                            "+" | "end" => BucketTimestamp::End,
+                            "~" | "mid" => BucketTimestamp::Mid,
+                            _ => return Err(ParseCommandError::InvalidParameter),
+                        };
+                }
+                "empty" => aggregation.empty = true,
+                _ => return Ok((aggregation, Some(token))),
+            }
+        }
+    }
+
+    /// Parse `[WITHLABELS | SELECTED_LABELS label...] FILTER filterExpr...` arguments.
+    fn parse_mget(parser: &mut Parser) -> Result<MultiSeriesOptions, ParseCommandError> {
+        let mut multi = MultiSeriesOptions::default();
+        let mut token = parser.try_next_string()?;
+        while let Some(current) = token.take() {
+            token = Self::parse_multi_option(&current, parser, &mut multi)?;
+        }
+        if multi.group_by.is_some() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        check_filters(&multi.filters)?;
+        Ok(multi)
+    }
+
+    /// Parse `fromTimestamp toTimestamp [range options] [WITHLABELS | SELECTED_LABELS label...]
+    /// FILTER filterExpr... [GROUPBY label REDUCE reducer]` arguments.
+    fn parse_multi_range(
+        parser: &mut Parser,
+    ) -> Result<(RangeOptions, MultiSeriesOptions), ParseCommandError> {
+        let mut multi = MultiSeriesOptions::default();
+        let options = Self::parse_range(parser, Some(&mut multi))?;
+        check_filters(&multi.filters)?;
+        Ok((options, multi))
+    }
+
+    /// Parse one of options of multiple series, returns the next token.
+    fn parse_multi_option(
+        token: &str,
+        parser: &mut Parser,
+        multi: &mut MultiSeriesOptions,
+    ) -> Result<Option<String>, ParseCommandError> {
+        match token.to_ascii_lowercase().as_str() {
+            "withlabels" => multi.labels = LabelsReply::All,
+            "selected_labels" => {
+                let mut labels = Vec::new();
+                while let Some(label) = parser.try_next_string()? {
+                    if is_range_keyword(&label) {
+                        multi.labels = LabelsReply::Selected(labels);
+                        return Ok(Some(label));
+                    }
+                    labels.push(label);
+                }
+                if labels.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                multi.labels = LabelsReply::Selected(labels);
+                return Ok(None);
+            }
+            "filter" => {
+                // Filter expressions always contain `=`.
+                while let Some(filter) = parser.try_next_string()? {
+                    if !filter.contains('=') {
+                        return Ok(Some(filter));
+                    }
+                    multi.filters.push(LabelFilter::try_from(filter.as_str())?);
+                }
+                return Ok(None);
+            }
+            "groupby" => {
+                let label = parser.next_string()?;
+                if !parser.next_string()?.eq_ignore_ascii_case("reduce") {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                let reducer = Aggregator::try_from(parser.next_string()?.as_str())?;
+                multi.group_by = Some((label, reducer));
+            }
+            _ => return Err(ParseCommandError::InvalidParameter),
+        }
+        parser.try_next_string()
+    }
+}
+
+/// Check that filters contain at least one `label=value` or `label=(v1,v2)` expression.
+fn check_filters(filters: &[LabelFilter]) -> Result<(), ParseCommandError> {
+    if filters.iter().any(LabelFilter::is_positive) {
+        Ok(())
+    } else {
+        Err(ParseCommandError::InvalidParameter)
+    }
+}
+
+fn is_range_keyword(s: &str) -> bool {
+    matches!(
+        s.to_ascii_lowercase().as_str(),
+        "filter_by_ts"
+            | "filter_by_value"
+            | "count"
+            | "align"
+            | "aggregation"
+            | "withlabels"
+            | "selected_labels"
+            | "filter"
+            | "groupby"
+    )
+}
+
+/// Parse timestamp in milliseconds, `*` means the current time.
+fn parse_timestamp(s: &str) -> Result<Option<i64>, ParseCommandError> {
+    if s == "*" {
+        return Ok(None);
+    }
+    let timestamp = s.parse::<i64>()?;
+    if timestamp < 0 {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    Ok(Some(timestamp))
+}
+
+/// Parse timestamp of range, `-` means the earliest and `+` means the latest.
+fn parse_range_timestamp(s: &str) -> Result<Option<i64>, ParseCommandError> {
+    match s {
+        "-" | "+" => Ok(None),
+        _ => Ok(Some(s.parse::<i64>()?)),
+    }
+}
+
+fn parse_align(s: &str) -> Result<Align, ParseCommandError> {
+    match s.to_ascii_lowercase().as_str() {
+        "-" | "start" => Ok(Align::Start),
+        "+" | "end" => Ok(Align::End),
+        _ => Ok(Align::Timestamp(s.parse::<i64>()?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::time_series::{
+        Aggregator, Align, BucketTimestamp, DuplicatePolicy, LabelFilter, LabelsReply,
+        TimeSeriesCommand,
+    };
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        TimeSeriesCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse_create() {
+        let Ok(Some(Command::TimeSeries(TimeSeriesCommand::Create(key, options)))) = parse(
+            "ts.create",
+            &[
+                "temp",
+                "RETENTION",
+                "60000",
+                "DUPLICATE_POLICY",
+                "max",
+                "LABELS",
+                "room",
+                "a",
+            ],
+        ) else {
+            panic!("expected TS.CREATE command");
+        };
+        assert_eq!(key, "temp");
+        assert_eq!(options.retention, Some(60000));
+        assert_eq!(options.duplicate_policy, Some(DuplicatePolicy::Max));
+        assert_eq!(
+            options.labels,
+            Some(vec![("room".to_owned(), "a".to_owned())])
+        );
+        assert!(parse("ts.create", &["temp", "LABELS", "room"]).is_err());
+        assert!(parse("ts.create", &["temp", "ON_DUPLICATE", "max"]).is_err());
+
+        let Ok(Some(Command::TimeSeries(TimeSeriesCommand::Add(options)))) =
+            parse("ts.add", &["temp", "*", "1.5", "ON_DUPLICATE", "sum"])
+        else {
+            panic!("expected TS.ADD command");
+        };
+        assert_eq!(options.timestamp, None);
+        assert_eq!(options.on_duplicate, Some(DuplicatePolicy::Sum));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let Ok(Some(Command::TimeSeries(TimeSeriesCommand::Range(_key, options)))) = parse(
+            "ts.range",
+            &[
+                "temp",
+                "-",
+                "100",
+                "FILTER_BY_TS",
+                "10",
+                "20",
+                "COUNT",
+                "5",
+                "ALIGN",
+                "start",
+                "AGGREGATION",
+                "std.p",
+                "10",
+                "BUCKETTIMESTAMP",
+                "mid",
+                "EMPTY",
+            ],
+        ) else {
+            panic!("expected TS.RANGE command");
+        };
+        assert_eq!(options.from, None);
+        assert_eq!(options.to, Some(100));
+        assert_eq!(options.filter_by_ts, vec![10, 20]);
+        assert_eq!(options.count, Some(5));
+        assert_eq!(options.align, Align::Start);
+        let aggregation = options.aggregation.unwrap();
+        assert_eq!(aggregation.aggregator, Aggregator::StdP);
+        assert_eq!(aggregation.bucket_timestamp, BucketTimestamp::Mid);
+        assert!(aggregation.empty);
+
+        assert!(parse(
+            "ts.range",
+            &["temp", "-", "+", "AGGREGATION", "median", "10"]
+        )
+        .is_err());
+        assert!(parse("ts.range", &["temp", "-", "+", "FILTER_BY_TS"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_multi_series() {
+        let filter = LabelFilter::try_from("room!=(a,b)").unwrap();
+        assert_eq!(filter.label, "room");
+        assert_eq!(filter.values, ["a", "b"]);
+        assert!(filter.negate);
+        assert!(filter.matches(None));
+        assert!(!filter.matches(Some("a")));
+        assert!(!LabelFilter::try_from("room=").unwrap().is_positive());
+        assert!(parse("ts.queryindex", &["room="]).is_err());
+
+        let Ok(Some(Command::TimeSeries(TimeSeriesCommand::MRange(options, multi)))) = parse(
+            "ts.mrange",
+            &[
+                "-",
+                "+",
+                "SELECTED_LABELS",
+                "room",
+                "type",
+                "COUNT",
+                "10",
+                "FILTER",
+                "type=temp",
+                "room!=",
+                "GROUPBY",
+                "room",
+                "REDUCE",
+                "max",
+            ],
+        ) else {
+            panic!("expected TS.MRANGE command");
+        };
+        assert_eq!(options.count, Some(10));
+        assert_eq!(
+            multi.labels,
+            LabelsReply::Selected(vec!["room".to_owned(), "type".to_owned()])
+        );
+        assert_eq!(multi.filters.len(), 2);
+        assert_eq!(multi.group_by, Some(("room".to_owned(), Aggregator::Max)));
+
+        assert!(parse("ts.mget", &["WITHLABELS", "FILTER", "type=temp"]).is_ok());
+        assert!(parse(
+            "ts.mget",
+            &["FILTER", "type=temp", "GROUPBY", "room", "REDUCE", "max"]
+        )
+        .is_err());
+        assert!(parse("ts.range", &["temp", "-", "+", "FILTER", "type=temp"]).is_err());
+    }
+}
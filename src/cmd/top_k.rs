@@ -0,0 +1,154 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Default number of counters kept in each array.
+pub const DEFAULT_WIDTH: usize = 8;
+/// Default number of arrays.
+pub const DEFAULT_DEPTH: usize = 7;
+/// Default probability of reducing a counter in an occupied bucket.
+pub const DEFAULT_DECAY: f64 = 0.9;
+/// Maximum increment of `TOPK.INCRBY`.
+const MAX_INCREMENT: u64 = 100_000;
+/// Maximum number of top occurring items to keep.
+const MAX_K: usize = 100_000;
+/// Maximum number of buckets, which is `width * depth`.
+const MAX_BUCKETS: usize = 1 << 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReserveOptions {
+    /// Number of top occurring items to keep.
+    pub k: usize,
+    pub width: usize,
+    pub depth: usize,
+    pub decay: f64,
+}
+
+#[derive(Debug, Clone)]
+pub enum TopKCommand {
+    Add(String, Vec<String>),
+    IncrBy(String, Vec<(String, u64)>),
+    Info(String),
+    /// Key and whether to report counts.
+    List(String, bool),
+    Query(String, Vec<String>),
+    Reserve(String, ReserveOptions),
+}
+
+impl TopKCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let top_k_cmd = match cmd_name {
+            "topk.add" => {
+                let key = parser.next_string()?;
+                let items = parser.remaining_strings()?;
+                Self::Add(key, items)
+            }
+            "topk.incrby" => {
+                let key = parser.next_string()?;
+                let mut items = Vec::new();
+                while let Some(item) = parser.try_next_string()? {
+                    let increment = parser.next_usize()? as u64;
+                    if increment == 0 || increment > MAX_INCREMENT {
+                        return Err(ParseCommandError::InvalidParameter);
+                    }
+                    items.push((item, increment));
+                }
+                if items.is_empty() {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::IncrBy(key, items)
+            }
+            "topk.info" => Self::Info(parser.next_string()?),
+            "topk.list" => {
+                let key = parser.next_string()?;
+                let with_count = match parser.try_next_string()? {
+                    Some(option) if option.eq_ignore_ascii_case("withcount") => true,
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => false,
+                };
+                Self::List(key, with_count)
+            }
+            "topk.query" => {
+                let key = parser.next_string()?;
+                let items = parser.remaining_strings()?;
+                Self::Query(key, items)
+            }
+            "topk.reserve" => {
+                let key = parser.next_string()?;
+                let k = parser.next_usize()?;
+                let mut options = ReserveOptions {
+                    k,
+                    width: DEFAULT_WIDTH,
+                    depth: DEFAULT_DEPTH,
+                    decay: DEFAULT_DECAY,
+                };
+                if let Some(width) = parser.try_next_usize()? {
+                    options.width = width;
+                    options.depth = parser.next_usize()?;
+                    options.decay = parser.next_f64()?;
+                }
+                let num_buckets = options.width.checked_mul(options.depth);
+                if options.k == 0
+                    || options.k > MAX_K
+                    || options.width == 0
+                    || options.depth == 0
+                    || !num_buckets.is_some_and(|num_buckets| num_buckets <= MAX_BUCKETS)
+                    || !(options.decay > 0.0 && options.decay <= 1.0)
+                {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::Reserve(key, options)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(Command::TopK(top_k_cmd)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::parse::{ParseCommandError, Parser};
+    use crate::cmd::top_k::{TopKCommand, DEFAULT_DEPTH};
+    use crate::cmd::Command;
+
+    fn parse(cmd_name: &str, args: &[&str]) -> Result<Option<Command>, ParseCommandError> {
+        TopKCommand::parse(cmd_name, &mut Parser::from_args(args))
+    }
+
+    #[test]
+    fn test_parse() {
+        let Ok(Some(Command::TopK(TopKCommand::Reserve(key, options)))) =
+            parse("topk.reserve", &["urls", "10"])
+        else {
+            panic!("expected TOPK.RESERVE command");
+        };
+        assert_eq!(key, "urls");
+        assert_eq!(options.k, 10);
+        assert_eq!(options.depth, DEFAULT_DEPTH);
+        assert!(parse("topk.reserve", &["urls", "10", "50", "4", "1.5"]).is_err());
+        assert!(parse("topk.reserve", &["urls", "10", "50"]).is_err());
+        assert!(parse("topk.reserve", &["urls", "10", "50", "4", "0"]).is_err());
+        assert!(parse("topk.reserve", &["urls", "1000000000"]).is_err());
+        let huge = usize::MAX.to_string();
+        assert!(parse("topk.reserve", &["urls", "10", &huge, "2", "0.9"]).is_err());
+        assert!(parse("topk.reserve", &["urls", "10", "100000", "1000", "0.9"]).is_err());
+
+        let Ok(Some(Command::TopK(TopKCommand::IncrBy(_key, items)))) =
+            parse("topk.incrby", &["urls", "a", "3", "b", "1"])
+        else {
+            panic!("expected TOPK.INCRBY command");
+        };
+        assert_eq!(items, [("a".to_owned(), 3), ("b".to_owned(), 1)]);
+        assert!(parse("topk.incrby", &["urls", "a", "0"]).is_err());
+        assert!(matches!(
+            parse("topk.list", &["urls", "WITHCOUNT"]),
+            Ok(Some(Command::TopK(TopKCommand::List(_, true))))
+        ));
+    }
+}
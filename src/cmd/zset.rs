@@ -0,0 +1,649 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::parse::{ParseCommandError, Parser};
+use crate::cmd::Command;
+
+/// Options of `ZADD` command.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct AddOptions {
+    /// Only add new elements, don't update already existing elements.
+    pub not_exist: bool,
+    /// Only update elements that already exist, don't add new elements.
+    pub exist: bool,
+    /// Only update existing elements if the new score is greater than the current score.
+    pub greater_than: bool,
+    /// Only update existing elements if the new score is less than the current score.
+    pub less_than: bool,
+    /// Count both new elements added and elements whose score changed.
+    pub changed: bool,
+    /// Increment score of the element, acts like `ZINCRBY`.
+    pub increment: bool,
+}
+
+/// Min or max score of a range, `-inf` and `+inf` are represented as infinite values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    /// Returns true if `score` is greater than or equal to this min bound.
+    #[must_use]
+    #[inline]
+    pub fn gte_min(self, score: f64) -> bool {
+        match self {
+            Self::Inclusive(min) => score >= min,
+            Self::Exclusive(min) => score > min,
+        }
+    }
+
+    /// Returns true if `score` is less than or equal to this max bound.
+    #[must_use]
+    #[inline]
+    pub fn lte_max(self, score: f64) -> bool {
+        match self {
+            Self::Inclusive(max) => score <= max,
+            Self::Exclusive(max) => score < max,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ScoreBound {
+    type Error = ParseCommandError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match value.strip_prefix(b"(") {
+            Some(value) => Ok(Self::Exclusive(parse_score(value)?)),
+            None => Ok(Self::Inclusive(parse_score(value)?)),
+        }
+    }
+}
+
+/// Min or max member of a lexicographical range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexBound {
+    /// `-`, the negatively infinite string.
+    Min,
+    /// `+`, the positive infinite string.
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    /// Returns true if `member` is greater than or equal to this min bound.
+    #[must_use]
+    pub fn gte_min(&self, member: &[u8]) -> bool {
+        match self {
+            Self::Min => true,
+            Self::Max => false,
+            Self::Inclusive(min) => member >= min.as_slice(),
+            Self::Exclusive(min) => member > min.as_slice(),
+        }
+    }
+
+    /// Returns true if `member` is less than or equal to this max bound.
+    #[must_use]
+    pub fn lte_max(&self, member: &[u8]) -> bool {
+        match self {
+            Self::Min => false,
+            Self::Max => true,
+            Self::Inclusive(max) => member <= max.as_slice(),
+            Self::Exclusive(max) => member < max.as_slice(),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for LexBound {
+    type Error = ParseCommandError;
+
+    fn try_from(mut value: Vec<u8>) -> Result<Self, Self::Error> {
+        match value.first() {
+            Some(b'-') if value.len() == 1 => Ok(Self::Min),
+            Some(b'+') if value.len() == 1 => Ok(Self::Max),
+            Some(b'[') => {
+                value.remove(0);
+                Ok(Self::Inclusive(value))
+            }
+            Some(b'(') => {
+                value.remove(0);
+                Ok(Self::Exclusive(value))
+            }
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+/// How `start` and `stop` arguments of `ZRANGE` are interpreted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeBy {
+    /// Inclusive 0-based indexes, negative numbers are offsets from the end.
+    Rank(isize, isize),
+    Score(ScoreBound, ScoreBound),
+    Lex(LexBound, LexBound),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeOptions {
+    pub range_by: RangeBy,
+    /// Order elements from highest to lowest score, `start` and `stop` are swapped
+    /// for score and lex ranges.
+    pub reverse: bool,
+    /// `offset` and `count` of elements, only for score and lex ranges.
+    pub limit: Option<(isize, isize)>,
+    pub with_scores: bool,
+}
+
+/// How scores of the same member are aggregated in union and intersection.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+impl TryFrom<String> for Aggregate {
+    type Error = ParseCommandError;
+
+    fn try_from(mut value: String) -> Result<Self, Self::Error> {
+        value.make_ascii_lowercase();
+        match value.as_str() {
+            "sum" => Ok(Self::Sum),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+/// Arguments of `ZUNION`, `ZINTER` and their store variants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetOperation {
+    pub keys: Vec<String>,
+    /// Multiplication factor of each input sorted set, empty if not specified.
+    pub weights: Vec<f64>,
+    pub aggregate: Aggregate,
+    pub with_scores: bool,
+}
+
+/// Pop elements with the lowest or the highest scores.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PopSide {
+    Min,
+    Max,
+}
+
+impl TryFrom<String> for PopSide {
+    type Error = ParseCommandError;
+
+    fn try_from(mut value: String) -> Result<Self, Self::Error> {
+        value.make_ascii_lowercase();
+        match value.as_str() {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            _ => Err(ParseCommandError::InvalidParameter),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SortedSetCommand {
+    Add(String, AddOptions, Vec<(f64, Vec<u8>)>),
+    Card(String),
+    Count(String, ScoreBound, ScoreBound),
+    IncrBy(String, f64, Vec<u8>),
+    MultiScore(String, Vec<Vec<u8>>),
+    Range(String, Box<RangeOptions>),
+    RangeStore(String, String, Box<RangeOptions>),
+    Rank(String, Vec<u8>, bool),
+    RevRank(String, Vec<u8>, bool),
+    Remove(String, Vec<Vec<u8>>),
+    RemoveRange(String, Box<RangeBy>),
+    Score(String, Vec<u8>),
+    Union(SetOperation),
+    UnionStore(String, Box<SetOperation>),
+    Intersect(SetOperation),
+    IntersectStore(String, Box<SetOperation>),
+    IntersectCard(Vec<String>, usize),
+    Diff(Vec<String>, bool),
+    DiffStore(String, Vec<String>),
+    Pop(String, PopSide, Option<usize>),
+    MultiPop(Vec<String>, PopSide, usize),
+    RandomMember(String, Option<(isize, bool)>),
+    /// Blocking `ZPOPMIN` or `ZPOPMAX` with timeout in seconds.
+    BlockingPop(Vec<String>, PopSide, f64),
+    /// Blocking `ZMPOP` with timeout in seconds.
+    BlockingMultiPop(Vec<String>, PopSide, usize, f64),
+}
+
+impl SortedSetCommand {
+    pub(super) fn parse(
+        cmd_name: &str,
+        parser: &mut Parser,
+    ) -> Result<Option<Command>, ParseCommandError> {
+        let zset_cmd = match cmd_name {
+            "zadd" => Self::parse_add(parser)?,
+            "zcard" => {
+                let key = parser.next_string()?;
+                Self::Card(key)
+            }
+            "zcount" => {
+                let key = parser.next_string()?;
+                let min = ScoreBound::try_from(parser.next_bytes()?.as_slice())?;
+                let max = ScoreBound::try_from(parser.next_bytes()?.as_slice())?;
+                Self::Count(key, min, max)
+            }
+            "zincrby" => {
+                let key = parser.next_string()?;
+                let increment = parse_score(&parser.next_bytes()?)?;
+                let member = parser.next_bytes()?;
+                Self::IncrBy(key, increment, member)
+            }
+            "zmscore" => {
+                let key = parser.next_string()?;
+                let members = parser.remaining()?;
+                Self::MultiScore(key, members)
+            }
+            "zrange" => {
+                let key = parser.next_string()?;
+                let options = parse_range_options(parser)?;
+                Self::Range(key, Box::new(options))
+            }
+            "zrangestore" => {
+                let destination = parser.next_string()?;
+                let key = parser.next_string()?;
+                let options = parse_range_options(parser)?;
+                if options.with_scores {
+                    return Err(ParseCommandError::InvalidParameter);
+                }
+                Self::RangeStore(destination, key, Box::new(options))
+            }
+            "zrank" | "zrevrank" => {
+                let key = parser.next_string()?;
+                let member = parser.next_bytes()?;
+                let with_score = parse_flag(parser, "withscore")?;
+                if cmd_name == "zrank" {
+                    Self::Rank(key, member, with_score)
+                } else {
+                    Self::RevRank(key, member, with_score)
+                }
+            }
+            "zrem" => {
+                let key = parser.next_string()?;
+                let members = parser.remaining()?;
+                Self::Remove(key, members)
+            }
+            "zremrangebyrank" | "zremrangebyscore" | "zremrangebylex" => {
+                Self::parse_remove_range(cmd_name, parser)?
+            }
+            "zscore" => {
+                let key = parser.next_string()?;
+                let member = parser.next_bytes()?;
+                Self::Score(key, member)
+            }
+            "zunion" | "zunionstore" | "zinter" | "zinterstore" | "zintercard" | "zdiff"
+            | "zdiffstore" => Self::parse_set_command(cmd_name, parser)?,
+            "zpopmin" | "zpopmax" => {
+                let key = parser.next_string()?;
+                let count = parser.try_next_usize()?;
+                let side = if cmd_name == "zpopmin" {
+                    PopSide::Min
+                } else {
+                    PopSide::Max
+                };
+                Self::Pop(key, side, count)
+            }
+            "zmpop" => {
+                let (keys, side, count) = parse_multi_pop(parser)?;
+                Self::MultiPop(keys, side, count)
+            }
+            "zrandmember" => Self::parse_random_member(parser)?,
+            "bzpopmin" | "bzpopmax" => Self::parse_blocking_pop(cmd_name, parser)?,
+            "bzmpop" => {
+                let timeout = parse_timeout(&parser.next_string()?)?;
+                let (keys, side, count) = parse_multi_pop(parser)?;
+                Self::BlockingMultiPop(keys, side, count, timeout)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Command::SortedSet(zset_cmd)))
+    }
+
+    fn parse_add(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let mut options = AddOptions::default();
+        let mut elements = Vec::new();
+        while let Some(token) = parser.try_next_string()? {
+            if !elements.is_empty() {
+                let score = parse_score(token.as_bytes())?;
+                elements.push((score, parser.next_bytes()?));
+                continue;
+            }
+            match token.to_ascii_lowercase().as_str() {
+                "nx" => options.not_exist = true,
+                "xx" => options.exist = true,
+                "gt" => options.greater_than = true,
+                "lt" => options.less_than = true,
+                "ch" => options.changed = true,
+                "incr" => options.increment = true,
+                _ => {
+                    let score = parse_score(token.as_bytes())?;
+                    elements.push((score, parser.next_bytes()?));
+                }
+            }
+        }
+
+        let conflict = (options.not_exist
+            && (options.exist || options.greater_than || options.less_than))
+            || (options.greater_than && options.less_than)
+            || (options.increment && elements.len() != 1);
+        if elements.is_empty() || conflict {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        Ok(Self::Add(key, options, elements))
+    }
+
+    fn parse_remove_range(cmd_name: &str, parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let start = parser.next_bytes()?;
+        let stop = parser.next_bytes()?;
+        let range_by = match cmd_name {
+            "zremrangebyrank" => RangeBy::Rank(parse_isize(&start)?, parse_isize(&stop)?),
+            "zremrangebyscore" => RangeBy::Score(
+                ScoreBound::try_from(start.as_slice())?,
+                ScoreBound::try_from(stop.as_slice())?,
+            ),
+            _ => RangeBy::Lex(LexBound::try_from(start)?, LexBound::try_from(stop)?),
+        };
+        Ok(Self::RemoveRange(key, Box::new(range_by)))
+    }
+
+    fn parse_set_command(cmd_name: &str, parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let zset_cmd = match cmd_name {
+            "zunion" => Self::Union(parse_set_operation(parser, true)?),
+            "zunionstore" => {
+                let destination = parser.next_string()?;
+                let operation = parse_set_operation(parser, false)?;
+                Self::UnionStore(destination, Box::new(operation))
+            }
+            "zinter" => Self::Intersect(parse_set_operation(parser, true)?),
+            "zinterstore" => {
+                let destination = parser.next_string()?;
+                let operation = parse_set_operation(parser, false)?;
+                Self::IntersectStore(destination, Box::new(operation))
+            }
+            "zintercard" => {
+                let keys = parse_keys(parser)?;
+                let limit = match parser.try_next_string()? {
+                    Some(option) if option.eq_ignore_ascii_case("limit") => parser.next_usize()?,
+                    Some(_) => return Err(ParseCommandError::InvalidParameter),
+                    None => 0,
+                };
+                Self::IntersectCard(keys, limit)
+            }
+            "zdiff" => {
+                let keys = parse_keys(parser)?;
+                let with_scores = parse_flag(parser, "withscores")?;
+                Self::Diff(keys, with_scores)
+            }
+            _ => {
+                let destination = parser.next_string()?;
+                let keys = parse_keys(parser)?;
+                Self::DiffStore(destination, keys)
+            }
+        };
+        Ok(zset_cmd)
+    }
+
+    fn parse_blocking_pop(cmd_name: &str, parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let mut keys = parser.remaining_strings()?;
+        let timeout = keys.pop().ok_or(ParseCommandError::InvalidParameter)?;
+        let timeout = parse_timeout(&timeout)?;
+        if keys.is_empty() {
+            return Err(ParseCommandError::InvalidParameter);
+        }
+        let side = if cmd_name == "bzpopmin" {
+            PopSide::Min
+        } else {
+            PopSide::Max
+        };
+        Ok(Self::BlockingPop(keys, side, timeout))
+    }
+
+    fn parse_random_member(parser: &mut Parser) -> Result<Self, ParseCommandError> {
+        let key = parser.next_string()?;
+        let count = if let Some(count) = parser.try_next_isize()? {
+            Some((count, parse_flag(parser, "withscores")?))
+        } else {
+            None
+        };
+        Ok(Self::RandomMember(key, count))
+    }
+
+    /// Returns timeout in seconds of blocking commands.
+    #[must_use]
+    pub const fn timeout(&self) -> Option<f64> {
+        match self {
+            Self::BlockingPop(_, _, timeout) | Self::BlockingMultiPop(_, _, _, timeout) => {
+                Some(*timeout)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns keys watched by blocking commands.
+    #[must_use]
+    pub fn blocking_keys(&self) -> Vec<&str> {
+        match self {
+            Self::BlockingPop(keys, ..) | Self::BlockingMultiPop(keys, ..) => {
+                keys.iter().map(String::as_str).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns keys which may have new elements after this command is executed.
+    #[must_use]
+    pub fn signaled_keys(&self) -> Vec<&str> {
+        match self {
+            Self::Add(key, ..)
+            | Self::IncrBy(key, ..)
+            | Self::RangeStore(key, ..)
+            | Self::UnionStore(key, _)
+            | Self::IntersectStore(key, _)
+            | Self::DiffStore(key, _) => vec![key.as_str()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns destination keys which are overwritten by this command.
+    #[must_use]
+    pub fn overwritten_keys(&self) -> Vec<&str> {
+        match self {
+            Self::RangeStore(key, ..)
+            | Self::UnionStore(key, _)
+            | Self::IntersectStore(key, _)
+            | Self::DiffStore(key, _) => vec![key.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Parse `start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]` arguments.
+fn parse_range_options(parser: &mut Parser) -> Result<RangeOptions, ParseCommandError> {
+    let start = parser.next_bytes()?;
+    let stop = parser.next_bytes()?;
+    let mut by_score = false;
+    let mut by_lex = false;
+    let mut reverse = false;
+    let mut limit = None;
+    let mut with_scores = false;
+    while let Some(option) = parser.try_next_string()? {
+        match option.to_ascii_lowercase().as_str() {
+            "byscore" => by_score = true,
+            "bylex" => by_lex = true,
+            "rev" => reverse = true,
+            "limit" => {
+                let offset = parser.next_isize()?;
+                let count = parser.next_isize()?;
+                limit = Some((offset, count));
+            }
+            "withscores" => with_scores = true,
+            _ => return Err(ParseCommandError::InvalidParameter),
+        }
+    }
+
+    let range_by = match (by_score, by_lex) {
+        (true, true) => return Err(ParseCommandError::InvalidParameter),
+        (true, false) => RangeBy::Score(
+            ScoreBound::try_from(start.as_slice())?,
+            ScoreBound::try_from(stop.as_slice())?,
+        ),
+        (false, true) => {
+            if with_scores {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+            RangeBy::Lex(LexBound::try_from(start)?, LexBound::try_from(stop)?)
+        }
+        (false, false) => {
+            if limit.is_some() {
+                return Err(ParseCommandError::InvalidParameter);
+            }
+            RangeBy::Rank(parse_isize(&start)?, parse_isize(&stop)?)
+        }
+    };
+    Ok(RangeOptions {
+        range_by,
+        reverse,
+        limit,
+        with_scores,
+    })
+}
+
+/// Parse an optional trailing flag, like `WITHSCORES`.
+fn parse_flag(parser: &mut Parser, flag: &str) -> Result<bool, ParseCommandError> {
+    match parser.try_next_string()? {
+        Some(option) if option.eq_ignore_ascii_case(flag) => Ok(true),
+        Some(_) => Err(ParseCommandError::InvalidParameter),
+        None => Ok(false),
+    }
+}
+
+/// Parse `numkeys key [key ...]` arguments.
+fn parse_keys(parser: &mut Parser) -> Result<Vec<String>, ParseCommandError> {
+    let num_keys = parser.next_usize()?;
+    if num_keys == 0 {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    (0..num_keys).map(|_| parser.next_string()).collect()
+}
+
+/// Parse `numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE <SUM | MIN | MAX>]
+/// [WITHSCORES]` arguments.
+fn parse_set_operation(
+    parser: &mut Parser,
+    allow_with_scores: bool,
+) -> Result<SetOperation, ParseCommandError> {
+    let keys = parse_keys(parser)?;
+    let mut weights = Vec::new();
+    let mut aggregate = Aggregate::default();
+    let mut with_scores = false;
+    while let Some(option) = parser.try_next_string()? {
+        match option.to_ascii_lowercase().as_str() {
+            "weights" => {
+                weights = (0..keys.len())
+                    .map(|_| parse_score(&parser.next_bytes()?))
+                    .collect::<Result<_, _>>()?;
+            }
+            "aggregate" => aggregate = Aggregate::try_from(parser.next_string()?)?,
+            "withscores" if allow_with_scores => with_scores = true,
+            _ => return Err(ParseCommandError::InvalidParameter),
+        }
+    }
+    Ok(SetOperation {
+        keys,
+        weights,
+        aggregate,
+        with_scores,
+    })
+}
+
+/// Parse `numkeys key [key ...] <MIN | MAX> [COUNT count]` arguments.
+fn parse_multi_pop(
+    parser: &mut Parser,
+) -> Result<(Vec<String>, PopSide, usize), ParseCommandError> {
+    let keys = parse_keys(parser)?;
+    let side = PopSide::try_from(parser.next_string()?)?;
+    let count = match parser.try_next_string()? {
+        Some(option) if option.eq_ignore_ascii_case("count") => parser.next_usize()?,
+        Some(_) => return Err(ParseCommandError::InvalidParameter),
+        None => 1,
+    };
+    if count == 0 {
+        return Err(ParseCommandError::InvalidParameter);
+    }
+    Ok((keys, side, count))
+}
+
+/// Parse timeout of blocking commands in seconds, 0 means to block indefinitely.
+fn parse_timeout(value: &str) -> Result<f64, ParseCommandError> {
+    let timeout = value.parse::<f64>()?;
+    if timeout.is_finite() && timeout >= 0.0 {
+        Ok(timeout)
+    } else {
+        Err(ParseCommandError::InvalidParameter)
+    }
+}
+
+/// Parse score of element, `-inf` and `+inf` are accepted but `nan` is not.
+fn parse_score(value: &[u8]) -> Result<f64, ParseCommandError> {
+    let value = std::str::from_utf8(value).map_err(|_err| ParseCommandError::InvalidParameter)?;
+    let score = value.parse::<f64>()?;
+    if score.is_nan() {
+        Err(ParseCommandError::InvalidParameter)
+    } else {
+        Ok(score)
+    }
+}
+
+fn parse_isize(value: &[u8]) -> Result<isize, ParseCommandError> {
+    let value = std::str::from_utf8(value).map_err(|_err| ParseCommandError::InvalidParameter)?;
+    Ok(value.parse::<isize>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use crate::cmd::zset::{LexBound, ScoreBound, SortedSetCommand};
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size_of::<SortedSetCommand>(), 64);
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(
+            ScoreBound::try_from(&b"(1.5"[..]),
+            Ok(ScoreBound::Exclusive(1.5))
+        );
+        assert_eq!(
+            ScoreBound::try_from(&b"-inf"[..]),
+            Ok(ScoreBound::Inclusive(f64::NEG_INFINITY))
+        );
+        assert!(ScoreBound::try_from(&b"nan"[..]).is_err());
+        assert_eq!(LexBound::try_from(b"-".to_vec()), Ok(LexBound::Min));
+        assert_eq!(
+            LexBound::try_from(b"[a".to_vec()),
+            Ok(LexBound::Inclusive(b"a".to_vec()))
+        );
+        assert!(LexBound::try_from(b"a".to_vec()).is_err());
+    }
+}
@@ -13,6 +13,8 @@ use crate::listener::types::{SessionGroup, SessionId};
 pub struct ListenerToSessionCmd {
     pub session_id: SessionId,
     pub reply_frames: Vec<ReplyFrame>,
+    /// Frames pushed by server without matching requests, like messages of channels.
+    pub push: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -25,15 +27,20 @@ pub enum SessionToListenerCmd {
 }
 
 #[derive(Debug, Clone)]
-pub struct ListenerToDispatcherCmd {
-    pub session_group: SessionGroup,
-    pub commands: Vec<Command>,
+pub enum ListenerToDispatcherCmd {
+    Request {
+        session_group: SessionGroup,
+        commands: Vec<Command>,
+    },
+    Disconnect(SessionGroup),
 }
 
 #[derive(Debug, Clone)]
 pub struct DispatcherToListenerCmd {
     pub session_group: SessionGroup,
     pub reply_frames: Vec<ReplyFrame>,
+    /// Frames pushed by server without matching requests, like messages of channels.
+    pub push: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -61,15 +68,20 @@ pub struct StorageToDispatcherCmd {
 }
 
 #[derive(Debug, Clone)]
-pub struct DispatcherToMemCmd {
-    pub session_group: SessionGroup,
-    pub commands: Vec<Command>,
+pub enum DispatcherToMemCmd {
+    Request {
+        session_group: SessionGroup,
+        commands: Vec<Command>,
+    },
+    Disconnect(SessionGroup),
 }
 
 #[derive(Debug, Clone)]
 pub struct MemToDispatcherCmd {
     pub session_group: SessionGroup,
     pub reply_frames: Vec<ReplyFrame>,
+    /// Frames pushed by server without matching requests, like messages of channels.
+    pub push: bool,
 }
 
 #[derive(Debug, Clone)]
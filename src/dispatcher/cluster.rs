@@ -23,6 +23,7 @@ impl Dispatcher {
             let cmd = DispatcherToListenerCmd {
                 session_group: cmd.session_group,
                 reply_frames: vec![cmd.reply_frame],
+                push: false,
             };
             Ok(listener_sender.send(cmd).await?)
         } else {
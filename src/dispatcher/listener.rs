@@ -16,9 +16,17 @@ impl Dispatcher {
     ) -> Result<(), Error> {
         log::debug!("{}", function_name!());
         // Dispatch to mem module
-        let cmd = DispatcherToMemCmd {
-            session_group: cmd.session_group,
-            commands: cmd.commands,
+        let cmd = match cmd {
+            ListenerToDispatcherCmd::Request {
+                session_group,
+                commands,
+            } => DispatcherToMemCmd::Request {
+                session_group,
+                commands,
+            },
+            ListenerToDispatcherCmd::Disconnect(session_group) => {
+                DispatcherToMemCmd::Disconnect(session_group)
+            }
         };
         log::debug!(
             "{} proxy cmd from listener to mem, cmd: {cmd:?}",
@@ -19,6 +19,7 @@ impl Dispatcher {
         let cmd = DispatcherToListenerCmd {
             session_group: cmd.session_group,
             reply_frames: cmd.reply_frames,
+            push: cmd.push,
         };
         self.send_cmd_to_listener(listener_id, cmd).await
     }
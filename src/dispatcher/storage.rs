@@ -22,6 +22,7 @@ impl Dispatcher {
         let cmd = DispatcherToListenerCmd {
             session_group: cmd.session_group,
             reply_frames: vec![cmd.reply_frame],
+            push: false,
         };
         self.send_cmd_to_listener(listener_id, cmd).await
     }
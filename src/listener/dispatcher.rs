@@ -3,6 +3,7 @@
 // that can be found in the LICENSE file.
 
 use stdext::function_name;
+use tokio::sync::mpsc::error::TrySendError;
 
 use crate::commands::{DispatcherToListenerCmd, ListenerToSessionCmd};
 use crate::error::{Error, ErrorKind};
@@ -20,6 +21,7 @@ impl Listener {
         let DispatcherToListenerCmd {
             session_group,
             reply_frames,
+            push,
         } = cmd;
         assert_eq!(session_group.listener_id(), self.id);
         let session_id = session_group.session_id();
@@ -28,8 +30,31 @@ impl Listener {
                 let cmd = ListenerToSessionCmd {
                     session_id,
                     reply_frames,
+                    push,
                 };
-                Ok(session_sender.send(cmd).await?)
+                if !push {
+                    return Ok(session_sender.send(cmd).await?);
+                }
+                // Slow subscribers must not block publishers. Like the output buffer
+                // limit of pubsub clients in redis, the session is closed if it can not
+                // keep up with pushed frames, instead of dropping them silently.
+                match session_sender.try_send(cmd) {
+                    Ok(()) => Ok(()),
+                    Err(TrySendError::Full(_cmd)) => {
+                        log::warn!(
+                            "{} session {session_id} is too slow, close it",
+                            function_name!()
+                        );
+                        // Session exits once its receiver is closed, and then notifies
+                        // listener to release its resources.
+                        self.session_senders.remove(&session_id);
+                        Ok(())
+                    }
+                    Err(TrySendError::Closed(cmd)) => Err(Error::from_string(
+                        ErrorKind::ChannelError,
+                        format!("Session {session_id} is closed, cmd: {cmd:?}"),
+                    )),
+                }
             }
             None => Err(Error::from_string(
                 ErrorKind::ChannelError,
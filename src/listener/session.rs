@@ -22,7 +22,7 @@ impl Listener {
             } => {
                 // Pass cmd to dispatcher
                 let session_group = SessionGroup::new(self.id, session_id);
-                let cmd = ListenerToDispatcherCmd {
+                let cmd = ListenerToDispatcherCmd::Request {
                     session_group,
                     commands,
                 };
@@ -36,6 +36,10 @@ impl Listener {
             SessionToListenerCmd::Disconnect(session_id) => {
                 log::debug!("{} remove session: {session_id}", function_name!());
                 self.session_senders.remove_entry(&session_id);
+                // Notify mem module to release resources of this session, like subscriptions.
+                let session_group = SessionGroup::new(self.id, session_id);
+                let cmd = ListenerToDispatcherCmd::Disconnect(session_group);
+                self.dispatcher_sender.send(cmd).await?;
                 Ok(())
             }
         }
@@ -0,0 +1,61 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::auto_suggest::AddOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::auto_suggest::AutoSuggestObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Adds a suggestion string to an auto-complete suggestion dictionary.
+///
+/// Options:
+/// - INCR: increments the existing score of the suggestion by score,
+///   instead of replacing the score.
+/// - PAYLOAD: saves an extra payload with the suggestion, that can be fetched
+///   by adding the `WITHPAYLOADS` argument to `FT.SUGGET`.
+///
+/// Reply:
+/// - Integer reply: the current size of the suggestion dictionary.
+pub fn add(db: &mut Db, options: AddOptions) -> ReplyFrame {
+    let AddOptions {
+        key,
+        string,
+        score,
+        incr,
+        payload,
+    } = options;
+    let MemObject::AutoSuggest(suggest) = db
+        .entry(key)
+        .or_insert_with(|| MemObject::AutoSuggest(AutoSuggestObject::new()))
+    else {
+        return ReplyFrame::wrong_type_err();
+    };
+    suggest.add(string, score, incr, payload);
+    ReplyFrame::Usize(suggest.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::auto_suggest::AddOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::auto_suggest::add::add;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let mut options = AddOptions {
+            key: "sug".to_owned(),
+            string: "hello".to_owned(),
+            score: 1.0,
+            incr: false,
+            payload: None,
+        };
+        assert_eq!(add(&mut db, options.clone()), ReplyFrame::Usize(1));
+        options.incr = true;
+        assert_eq!(add(&mut db, options.clone()), ReplyFrame::Usize(1));
+        options.string = "world".to_owned();
+        assert_eq!(add(&mut db, options), ReplyFrame::Usize(2));
+    }
+}
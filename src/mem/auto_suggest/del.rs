@@ -0,0 +1,51 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Deletes a string from a suggestion dictionary.
+///
+/// Reply:
+/// - Integer reply: 1 if the string was found and deleted, 0 otherwise.
+pub fn del(db: &mut Db, key: &str, string: &str) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::AutoSuggest(suggest)) => {
+            let removed = suggest.remove(string);
+            if suggest.is_empty() {
+                db.remove(key);
+            }
+            ReplyFrame::from_bool(removed)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::auto_suggest::AddOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::auto_suggest::add::add;
+    use crate::mem::auto_suggest::del::del;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_del() {
+        let mut db = Db::new();
+        add(
+            &mut db,
+            AddOptions {
+                key: "sug".to_owned(),
+                string: "hello".to_owned(),
+                score: 1.0,
+                incr: false,
+                payload: None,
+            },
+        );
+        assert_eq!(del(&mut db, "sug", "world"), ReplyFrame::zero());
+        assert_eq!(del(&mut db, "sug", "hello"), ReplyFrame::one());
+        assert!(!db.contains_key("sug"));
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::auto_suggest::GetOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Gets completion suggestions for a prefix.
+///
+/// Options:
+/// - FUZZY: performs a fuzzy prefix search, including prefixes at Levenshtein
+///   distance of 1 from the prefix sent.
+/// - MAX: limits the results to a maximum of num, default is 5.
+/// - WITHSCORES: also returns the score of each suggestion.
+/// - WITHPAYLOADS: returns optional payloads saved along with the suggestions.
+///   If no payload is present for an entry, it returns a null reply.
+///
+/// Reply:
+/// - Array reply: a list of the top suggestions matching the prefix,
+///   optionally with score and payload after each entry.
+pub fn get(db: &Db, options: &GetOptions) -> ReplyFrame {
+    let suggest = match db.get(&options.key) {
+        Some(MemObject::AutoSuggest(suggest)) => suggest,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::EmptyArray,
+    };
+    let mut list = Vec::new();
+    for suggestion in suggest.get(&options.prefix, options.fuzzy, options.max) {
+        list.push(ReplyFrame::bulk(suggestion.string.as_bytes().to_vec()));
+        if options.with_scores {
+            list.push(ReplyFrame::Double(suggestion.score));
+        }
+        if options.with_payloads {
+            list.push(
+                suggestion
+                    .payload
+                    .as_ref()
+                    .map_or_else(ReplyFrame::null, |payload| {
+                        ReplyFrame::bulk(payload.as_bytes().to_vec())
+                    }),
+            );
+        }
+    }
+    ReplyFrame::Array(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::auto_suggest::{AddOptions, GetOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::auto_suggest::add::add;
+    use crate::mem::auto_suggest::get::get;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_get() {
+        let mut db = Db::new();
+        add(
+            &mut db,
+            AddOptions {
+                key: "sug".to_owned(),
+                string: "hello".to_owned(),
+                score: 2.0,
+                incr: false,
+                payload: Some("data".to_owned()),
+            },
+        );
+        let mut options = GetOptions {
+            key: "sug".to_owned(),
+            prefix: "he".to_owned(),
+            fuzzy: false,
+            max: 5,
+            with_scores: true,
+            with_payloads: true,
+        };
+        assert_eq!(
+            get(&db, &options),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"hello".to_vec()),
+                ReplyFrame::Double(2.0),
+                ReplyFrame::bulk(b"data".to_vec()),
+            ])
+        );
+        options.max = 0;
+        assert_eq!(get(&db, &options), ReplyFrame::Array(vec![]));
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Gets the size of an auto-complete suggestion dictionary.
+///
+/// Reply:
+/// - Integer reply: number of suggestions, or 0 if key does not exist.
+pub fn len(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::AutoSuggest(suggest)) => ReplyFrame::Usize(suggest.len()),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::auto_suggest::AddOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::auto_suggest::add::add;
+    use crate::mem::auto_suggest::len::len;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_len() {
+        let mut db = Db::new();
+        assert_eq!(len(&db, "sug"), ReplyFrame::zero());
+        add(
+            &mut db,
+            AddOptions {
+                key: "sug".to_owned(),
+                string: "hello".to_owned(),
+                score: 1.0,
+                incr: false,
+                payload: None,
+            },
+        );
+        assert_eq!(len(&db, "sug"), ReplyFrame::Usize(1));
+    }
+}
@@ -1,3 +1,240 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Auto-complete suggestion dictionary.
+//!
+//! Suggestions are stored in a trie, keyed by lowercase characters, so that
+//! prefix matching is case insensitive.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::cmd::auto_suggest::AutoSuggestCommand;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::Mem;
+
+pub mod add;
+pub mod del;
+pub mod get;
+pub mod len;
+
+/// Maximum Levenshtein distance of fuzzy prefix matching.
+const MAX_FUZZY_DISTANCE: usize = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub string: String,
+    pub score: f64,
+    pub payload: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct TrieNode {
+    children: BTreeMap<char, Self>,
+    suggestion: Option<Suggestion>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AutoSuggestObject {
+    root: TrieNode,
+    len: usize,
+}
+
+impl Mem {
+    pub fn handle_auto_suggest_command(&mut self, command: AutoSuggestCommand) -> ReplyFrame {
+        match command {
+            AutoSuggestCommand::Add(options) => add::add(&mut self.db, *options),
+            AutoSuggestCommand::Del(key, string) => del::del(&mut self.db, &key, &string),
+            AutoSuggestCommand::Get(options) => get::get(&self.db, &options),
+            AutoSuggestCommand::Len(key) => len::len(&self.db, &key),
+        }
+    }
+}
+
+fn normalize(string: &str) -> Vec<char> {
+    string.chars().flat_map(char::to_lowercase).collect()
+}
+
+impl TrieNode {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.suggestion.is_none()
+    }
+
+    /// Append all suggestions in this subtree.
+    fn collect<'a>(&'a self, distance: usize, out: &mut Vec<(usize, &'a Suggestion)>) {
+        if let Some(suggestion) = &self.suggestion {
+            out.push((distance, suggestion));
+        }
+        for child in self.children.values() {
+            child.collect(distance, out);
+        }
+    }
+
+    /// Remove suggestion at chars, and empty nodes on the path.
+    fn remove(&mut self, chars: &[char]) -> bool {
+        let Some((first, rest)) = chars.split_first() else {
+            return self.suggestion.take().is_some();
+        };
+        let Some(child) = self.children.get_mut(first) else {
+            return false;
+        };
+        let removed = child.remove(rest);
+        if child.is_empty() {
+            self.children.remove(first);
+        }
+        removed
+    }
+
+    /// Collect suggestions whose prefix is within `MAX_FUZZY_DISTANCE` of `prefix`.
+    ///
+    /// `row` is the row of Levenshtein distances between path to this node
+    /// and each prefix of `prefix`.
+    fn collect_fuzzy<'a>(
+        &'a self,
+        prefix: &[char],
+        row: &[usize],
+        out: &mut Vec<(usize, &'a Suggestion)>,
+    ) {
+        for (&c, child) in &self.children {
+            let mut next_row = Vec::with_capacity(row.len());
+            next_row.push(row[0] + 1);
+            for (j, &p) in prefix.iter().enumerate() {
+                let replace = row[j] + usize::from(p != c);
+                next_row.push(replace.min(row[j + 1] + 1).min(next_row[j] + 1));
+            }
+            let distance = next_row[prefix.len()];
+            if distance <= MAX_FUZZY_DISTANCE {
+                child.collect(distance, out);
+            } else if next_row.iter().any(|&d| d <= MAX_FUZZY_DISTANCE) {
+                child.collect_fuzzy(prefix, &next_row, out);
+            }
+        }
+    }
+}
+
+impl AutoSuggestObject {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns number of suggestions.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a suggestion, or update score of existing one.
+    ///
+    /// If `incr` is true, score is added to the existing score.
+    /// Payload of existing suggestion is kept if `payload` is None.
+    pub fn add(&mut self, string: String, score: f64, incr: bool, payload: Option<String>) {
+        let mut node = &mut self.root;
+        for c in normalize(&string) {
+            node = node.children.entry(c).or_default();
+        }
+        if let Some(old) = &mut node.suggestion {
+            old.string = string;
+            old.score = if incr { old.score + score } else { score };
+            if payload.is_some() {
+                old.payload = payload;
+            }
+        } else {
+            node.suggestion = Some(Suggestion {
+                string,
+                score,
+                payload,
+            });
+            self.len += 1;
+        }
+    }
+
+    /// Remove a suggestion, returns true if it exists.
+    pub fn remove(&mut self, string: &str) -> bool {
+        let removed = self.root.remove(&normalize(string));
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns at most `max` suggestions matching `prefix`, sorted by score.
+    ///
+    /// With `fuzzy`, suggestions with prefix of Levenshtein distance 1 are
+    /// also returned, after exact matches.
+    #[must_use]
+    pub fn get(&self, prefix: &str, fuzzy: bool, max: usize) -> Vec<&Suggestion> {
+        let prefix = normalize(prefix);
+        let mut matches = Vec::new();
+        if fuzzy {
+            let row: Vec<usize> = (0..=prefix.len()).collect();
+            if prefix.len() <= MAX_FUZZY_DISTANCE {
+                self.root.collect(prefix.len(), &mut matches);
+            } else {
+                self.root.collect_fuzzy(&prefix, &row, &mut matches);
+            }
+        } else {
+            let mut node = Some(&self.root);
+            for c in &prefix {
+                node = node.and_then(|node| node.children.get(c));
+            }
+            if let Some(node) = node {
+                node.collect(0, &mut matches);
+            }
+        }
+
+        matches.sort_by(|(distance1, a), (distance2, b)| {
+            distance1
+                .cmp(distance2)
+                .then_with(|| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal))
+                .then_with(|| a.string.cmp(&b.string))
+        });
+        matches
+            .into_iter()
+            .take(max)
+            .map(|(_distance, suggestion)| suggestion)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::auto_suggest::AutoSuggestObject;
+
+    fn strings(suggest: &AutoSuggestObject, prefix: &str, fuzzy: bool) -> Vec<String> {
+        suggest
+            .get(prefix, fuzzy, 10)
+            .into_iter()
+            .map(|suggestion| suggestion.string.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_suggest_object() {
+        let mut suggest = AutoSuggestObject::new();
+        suggest.add("hello".to_owned(), 1.0, false, None);
+        suggest.add("Help".to_owned(), 3.0, false, None);
+        suggest.add("world".to_owned(), 2.0, false, None);
+        suggest.add("hello".to_owned(), 5.0, true, None);
+        assert_eq!(suggest.len(), 3);
+        assert_eq!(strings(&suggest, "HEL", false), ["hello", "Help"]);
+        assert!(strings(&suggest, "hem", false).is_empty());
+        assert_eq!(strings(&suggest, "hem", true), ["hello", "Help"]);
+        assert_eq!(strings(&suggest, "wrld", true), ["world"]);
+        assert_eq!(strings(&suggest, "wo", true), ["world"]);
+
+        assert!(suggest.remove("HELLO"));
+        assert!(!suggest.remove("hello"));
+        assert_eq!(suggest.len(), 2);
+        assert_eq!(strings(&suggest, "hel", false), ["Help"]);
+    }
+}
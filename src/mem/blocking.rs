@@ -0,0 +1,349 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Clients blocked by commands like `BZPOPMIN` and `XREAD`.
+//!
+//! A blocking command which can not be served immediately is queued along with
+//! replies of previous commands in the same request, and indexed by keys it watches.
+//! Commands like `ZADD` and `XADD` signal keys they write as ready, and only clients
+//! blocked on ready keys are retried after each request, in FIFO order.
+//! Clients are replied with nil when timeout is reached.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use stdext::function_name;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::StreamCommand;
+use crate::cmd::Command;
+use crate::commands::MemToDispatcherCmd;
+use crate::error::Error;
+use crate::listener::types::SessionGroup;
+use crate::mem::stream::read;
+use crate::mem::Mem;
+
+#[derive(Debug)]
+pub struct BlockedClient {
+    session_group: SessionGroup,
+    reply_frames: Vec<ReplyFrame>,
+    command: Command,
+    /// Unix time in milliseconds, None to block indefinitely.
+    deadline: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct BlockedClients {
+    /// Id of next blocked client, ids are increasing so that clients are served in FIFO order.
+    next_id: u64,
+
+    clients: BTreeMap<u64, BlockedClient>,
+
+    /// Ids of clients blocked on each key.
+    keys: HashMap<String, BTreeSet<u64>>,
+
+    /// Keys with blocked clients which are written since last retry.
+    ready_keys: HashSet<String>,
+}
+
+impl BlockedClients {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    fn push(&mut self, client: BlockedClient) {
+        let id = self.next_id;
+        self.next_id += 1;
+        for key in client.command.blocking_keys() {
+            self.keys.entry(key.to_owned()).or_default().insert(id);
+        }
+        self.clients.insert(id, client);
+    }
+
+    fn remove(&mut self, id: u64) -> Option<BlockedClient> {
+        let client = self.clients.remove(&id)?;
+        for key in client.command.blocking_keys() {
+            if let Some(ids) = self.keys.get_mut(key) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.keys.remove(key);
+                }
+            }
+        }
+        Some(client)
+    }
+
+    /// Mark key as ready if any client is blocked on it.
+    pub fn signal(&mut self, key: &str) {
+        if self.keys.contains_key(key) && !self.ready_keys.contains(key) {
+            self.ready_keys.insert(key.to_owned());
+        }
+    }
+
+    /// Returns ids of clients blocked on ready keys in FIFO order, and reset ready keys.
+    fn take_ready(&mut self) -> Vec<u64> {
+        let ids: BTreeSet<u64> = self
+            .ready_keys
+            .drain()
+            .filter_map(|key| self.keys.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        ids.into_iter().collect()
+    }
+}
+
+impl Mem {
+    /// Queue a blocking command which returned nil reply.
+    pub(super) fn block_client(
+        &mut self,
+        session_group: SessionGroup,
+        reply_frames: Vec<ReplyFrame>,
+        command: Command,
+        timeout: f64,
+        now: i64,
+    ) {
+        let command = self.resolve_blocked_command(command);
+        #[allow(clippy::cast_possible_truncation)]
+        let deadline = (timeout > 0.0).then(|| now.saturating_add((timeout * 1000.0) as i64));
+        self.blocked_clients.push(BlockedClient {
+            session_group,
+            reply_frames,
+            command,
+            deadline,
+        });
+    }
+
+    /// Replace IDs relative to current state of streams, like `$` in `XREAD`,
+    /// so that retries of the command only serve new entries.
+    fn resolve_blocked_command(&self, command: Command) -> Command {
+        match command {
+            Command::Stream(StreamCommand::Read(mut options)) => {
+                read::resolve_ids(&self.db, &mut options);
+                Command::Stream(StreamCommand::Read(options))
+            }
+            _ => command,
+        }
+    }
+
+    /// Mark keys written by command as ready, if any client is blocked on them.
+    pub(super) fn signal_ready_keys(&mut self, command: &Command) {
+        if self.blocked_clients.is_empty() {
+            return;
+        }
+        for key in command.signaled_keys() {
+            self.blocked_clients.signal(key);
+        }
+    }
+
+    /// Retry commands blocked on ready keys, and reply to clients which are served.
+    pub(super) async fn serve_blocked_clients(&mut self) -> Result<(), Error> {
+        for id in self.blocked_clients.take_ready() {
+            let Some(client) = self.blocked_clients.clients.get(&id) else {
+                continue;
+            };
+            let reply = self.handle_db_command(client.command.clone());
+            if reply == ReplyFrame::Null {
+                continue;
+            }
+            if let Some(client) = self.blocked_clients.remove(id) {
+                self.reply_blocked_client(client, reply).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove blocked commands of a disconnected client.
+    pub(super) fn remove_blocked_client(&mut self, session_group: SessionGroup) {
+        let ids: Vec<u64> = self
+            .blocked_clients
+            .clients
+            .iter()
+            .filter(|(_id, client)| client.session_group == session_group)
+            .map(|(id, _client)| *id)
+            .collect();
+        for id in ids {
+            self.blocked_clients.remove(id);
+        }
+    }
+
+    /// Reply nil to clients which reached timeout.
+    pub(super) async fn expire_blocked_clients(&mut self, now: i64) -> Result<(), Error> {
+        let ids: Vec<u64> = self
+            .blocked_clients
+            .clients
+            .iter()
+            .filter(|(_id, client)| client.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(id, _client)| *id)
+            .collect();
+        for id in ids {
+            if let Some(client) = self.blocked_clients.remove(id) {
+                self.reply_blocked_client(client, ReplyFrame::Null).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn reply_blocked_client(
+        &self,
+        client: BlockedClient,
+        reply: ReplyFrame,
+    ) -> Result<(), Error> {
+        let BlockedClient {
+            session_group,
+            mut reply_frames,
+            ..
+        } = client;
+        reply_frames.push(reply);
+        let reply_cmd = MemToDispatcherCmd {
+            session_group,
+            reply_frames,
+            push: false,
+        };
+        log::debug!(
+            "{} send cmd to dispatcher, cmd: {reply_cmd:?}",
+            function_name!()
+        );
+        self.dispatcher_sender.send(reply_cmd).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::cmd::geo::{GeoCommand, GeoPoint};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{ReadId, ReadOptions, StreamCommand};
+    use crate::cmd::zset::{AddOptions, PopSide, SortedSetCommand};
+    use crate::cmd::Command;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::Mem;
+
+    fn zadd(key: &str, member: &[u8]) -> Command {
+        Command::SortedSet(SortedSetCommand::Add(
+            key.to_owned(),
+            AddOptions::default(),
+            vec![(1.0, member.to_vec())],
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_blocked_clients() {
+        let (dispatcher_sender, mut receiver) = mpsc::channel(4);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        let key = "zset".to_owned();
+        let command = Command::SortedSet(SortedSetCommand::BlockingPop(
+            vec![key.clone()],
+            PopSide::Min,
+            1.0,
+        ));
+        let now = 1_000_000;
+        mem.block_client(
+            SessionGroup::new(1, 1),
+            Vec::new(),
+            command.clone(),
+            1.0,
+            now,
+        );
+        mem.block_client(SessionGroup::new(1, 2), Vec::new(), command, 0.0, now);
+
+        mem.serve_blocked_clients().await.unwrap();
+        assert_eq!(mem.blocked_clients.len(), 2);
+
+        // Writes to other keys do not retry blocked clients.
+        let reply = mem.handle_db_command(zadd("other", b"a"));
+        assert_eq!(reply, ReplyFrame::one());
+        assert!(mem.blocked_clients.ready_keys.is_empty());
+
+        let reply = mem.handle_db_command(zadd(&key, b"a"));
+        assert_eq!(reply, ReplyFrame::one());
+        mem.serve_blocked_clients().await.unwrap();
+        assert_eq!(mem.blocked_clients.len(), 1);
+        let reply_cmd = receiver.recv().await.unwrap();
+        assert_eq!(reply_cmd.session_group, SessionGroup::new(1, 1));
+        assert_eq!(reply_cmd.reply_frames.len(), 1);
+
+        mem.expire_blocked_clients(now + 1000).await.unwrap();
+        assert_eq!(mem.blocked_clients.len(), 1);
+
+        mem.remove_blocked_client(SessionGroup::new(1, 2));
+        assert!(mem.blocked_clients.is_empty());
+        assert!(mem.blocked_clients.keys.is_empty());
+        mem.handle_db_command(zadd(&key, b"b"));
+        assert!(mem.blocked_clients.ready_keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocked_stream_read() {
+        let (dispatcher_sender, mut receiver) = mpsc::channel(4);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        let key = "mystream".to_owned();
+        let add = Command::Stream(StreamCommand::Add(
+            key.clone(),
+            Box::default(),
+            vec![(b"a".to_vec(), b"1".to_vec())],
+        ));
+        mem.handle_db_command(add.clone());
+        let command = Command::Stream(StreamCommand::Read(Box::new(ReadOptions {
+            count: None,
+            block: Some(0),
+            streams: vec![(key, ReadId::Last)],
+        })));
+        assert_eq!(command.block_timeout(), Some(0.0));
+        mem.block_client(SessionGroup::new(1, 1), Vec::new(), command, 0.0, 0);
+        mem.serve_blocked_clients().await.unwrap();
+        assert_eq!(mem.blocked_clients.len(), 1);
+
+        mem.handle_db_command(add);
+        mem.serve_blocked_clients().await.unwrap();
+        assert!(mem.blocked_clients.is_empty());
+        let reply_cmd = receiver.recv().await.unwrap();
+        assert!(matches!(reply_cmd.reply_frames[0], ReplyFrame::Array(_)));
+    }
+
+    #[tokio::test]
+    async fn test_blocked_geo_add() {
+        let (dispatcher_sender, mut receiver) = mpsc::channel(4);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        let key = "places".to_owned();
+        let command = Command::SortedSet(SortedSetCommand::BlockingPop(
+            vec![key.clone()],
+            PopSide::Min,
+            0.0,
+        ));
+        mem.block_client(SessionGroup::new(1, 1), Vec::new(), command, 0.0, 0);
+
+        let add = Command::Geo(GeoCommand::Add(
+            key,
+            AddOptions::default(),
+            vec![GeoPoint {
+                longitude: 13.361_389,
+                latitude: 38.115_556,
+                member: b"Palermo".to_vec(),
+            }],
+        ));
+        assert_eq!(mem.handle_db_command(add), ReplyFrame::one());
+        mem.serve_blocked_clients().await.unwrap();
+        assert!(mem.blocked_clients.is_empty());
+        let reply_cmd = receiver.recv().await.unwrap();
+        assert!(matches!(reply_cmd.reply_frames[0], ReplyFrame::Array(_)));
+    }
+}
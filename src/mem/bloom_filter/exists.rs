@@ -12,7 +12,7 @@ use crate::mem::db::{Db, MemObject};
 ///   added to the filter, and 0 means that key does not exist or that item
 ///   had not been added to the filter.
 /// - [] on error (invalid arguments, wrong key type, etc.)
-pub fn exists(db: &Db, key: &str, item: &String) -> ReplyFrame {
+pub fn exists(db: &Db, key: &str, item: &str) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::BloomFilter(old_filter)) => {
             let is_set = old_filter.check(item);
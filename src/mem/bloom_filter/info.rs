@@ -0,0 +1,86 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::bloom_filter::InfoField;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::bloom_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::bloom_filter::BloomFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns information about a Bloom filter.
+///
+/// If field is specified, only value of that field is returned.
+///
+/// Reply:
+/// - Array reply: pairs of field names and values, or value of the specified field.
+///   Expansion rate of a non-scaling filter is null.
+/// - Error reply: if key does not exist.
+pub fn info(db: &Db, key: &str, field: Option<InfoField>) -> ReplyFrame {
+    let filter = match db.get(key) {
+        Some(MemObject::BloomFilter(filter)) => filter,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    };
+    if let Some(field) = field {
+        return ReplyFrame::Array(vec![field_value(filter, field)]);
+    }
+    let mut list = Vec::new();
+    for (name, field) in [
+        ("Capacity", InfoField::Capacity),
+        ("Size", InfoField::Size),
+        ("Number of filters", InfoField::Filters),
+        ("Number of items inserted", InfoField::Items),
+        ("Expansion rate", InfoField::Expansion),
+    ] {
+        list.push(ReplyFrame::ConstSimple(name));
+        list.push(field_value(filter, field));
+    }
+    ReplyFrame::Array(list)
+}
+
+fn field_value(filter: &BloomFilterObject, field: InfoField) -> ReplyFrame {
+    match field {
+        InfoField::Capacity => ReplyFrame::Usize(filter.capacity()),
+        InfoField::Size => ReplyFrame::Usize(filter.size()),
+        InfoField::Filters => ReplyFrame::Usize(filter.num_filters()),
+        InfoField::Items => ReplyFrame::Usize(filter.len()),
+        InfoField::Expansion => match filter.expansion() {
+            0 => ReplyFrame::null(),
+            expansion => ReplyFrame::Usize(expansion),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::{InfoField, ReserveOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::bloom_filter::add::add;
+    use crate::mem::bloom_filter::info::info;
+    use crate::mem::bloom_filter::reserve::reserve;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_info() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            error_rate: 0.01,
+            capacity: 1000,
+            expansion: 0,
+        };
+        reserve(&mut db, "bf".to_owned(), &options);
+        add(&mut db, "bf".to_owned(), "a");
+        let ReplyFrame::Array(fields) = info(&db, "bf", None) else {
+            panic!("expected array");
+        };
+        assert_eq!(fields[1], ReplyFrame::Usize(1000));
+        assert_eq!(fields[5], ReplyFrame::Usize(1));
+        assert_eq!(fields[7], ReplyFrame::Usize(1));
+        assert_eq!(fields[9], ReplyFrame::null());
+        assert_eq!(
+            info(&db, "bf", Some(InfoField::Filters)),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(1)])
+        );
+    }
+}
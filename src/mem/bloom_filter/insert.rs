@@ -0,0 +1,83 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::bloom_filter::InsertOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::bloom_filter::add::add_item;
+use crate::mem::bloom_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::bloom_filter::BloomFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Adds one or more items to a Bloom filter, creates the filter if key
+/// does not exist.
+///
+/// Options:
+/// - CAPACITY: capacity of the filter if it is created.
+/// - ERROR: error rate of the filter if it is created.
+/// - EXPANSION: growth factor of new sub-filters if it is created.
+/// - NOCREATE: do not create filter if key does not exist.
+/// - NONSCALING: prevents the filter from creating additional sub-filters.
+///
+/// Reply:
+/// - Array reply: for each item, 1 if item was added, 0 if item may exist,
+///   or an error if filter is full.
+/// - Error reply: if key does not exist and `NOCREATE` is specified.
+pub fn insert(db: &mut Db, options: &InsertOptions) -> ReplyFrame {
+    if !db.contains_key(&options.key) {
+        if options.no_create {
+            return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR);
+        }
+        let filter = BloomFilterObject::new(&options.reserve);
+        db.insert(options.key.clone(), MemObject::BloomFilter(filter));
+    }
+    let Some(MemObject::BloomFilter(filter)) = db.get_mut(&options.key) else {
+        return ReplyFrame::wrong_type_err();
+    };
+    let replies = options
+        .items
+        .iter()
+        .map(|item| add_item(filter, item))
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::{InsertOptions, ReserveOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::bloom_filter::consts::{FILTER_FULL_ERR, KEY_NOT_EXIST_ERR};
+    use crate::mem::bloom_filter::insert::insert;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_insert() {
+        let mut db = Db::new();
+        let mut options = InsertOptions {
+            key: "bf".to_owned(),
+            reserve: ReserveOptions {
+                error_rate: 0.01,
+                capacity: 2,
+                expansion: 0,
+            },
+            no_create: true,
+            items: vec!["a".to_owned(), "b".to_owned()],
+        };
+        assert_eq!(
+            insert(&mut db, &options),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        options.no_create = false;
+        assert_eq!(
+            insert(&mut db, &options),
+            ReplyFrame::Array(vec![ReplyFrame::one(), ReplyFrame::one()])
+        );
+        // New items are either false positives or rejected as filter is full.
+        options.items = (0..10).map(|index| format!("item-{index}")).collect();
+        let ReplyFrame::Array(replies) = insert(&mut db, &options) else {
+            panic!("expected array");
+        };
+        assert!(!replies.contains(&ReplyFrame::one()));
+        assert!(replies.contains(&ReplyFrame::ConstError(FILTER_FULL_ERR)));
+    }
+}
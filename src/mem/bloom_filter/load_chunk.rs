@@ -0,0 +1,133 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::bloom_filter::consts::{KEY_EXISTS_ERR, KEY_NOT_EXIST_ERR};
+use crate::mem::bloom_filter::BloomFilterObject;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::dump::{self, ChunkDump, BAD_DATA_ERR, HEADER_ITER};
+
+/// Restores a bloom filter previously saved using `BF.SCANDUMP`.
+///
+/// The header chunk creates the filter, so it must be loaded first.
+/// Other chunks can be loaded in any order.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if data is invalid, or key already exists when loading
+///   the header chunk, or key does not exist when loading other chunks.
+pub fn load_chunk(db: &mut Db, key: String, iter: usize, data: &[u8]) -> ReplyFrame {
+    if iter == HEADER_ITER {
+        if db.contains_key(&key) {
+            return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+        }
+        return BloomFilterObject::from_header(data).map_or(
+            ReplyFrame::ConstError(BAD_DATA_ERR),
+            |filter| {
+                db.insert(key, MemObject::BloomFilter(filter));
+                ReplyFrame::ok()
+            },
+        );
+    }
+    match db.get_mut(&key) {
+        Some(MemObject::BloomFilter(filter)) => {
+            if dump::load_chunk(filter, iter, data) {
+                ReplyFrame::ok()
+            } else {
+                ReplyFrame::ConstError(BAD_DATA_ERR)
+            }
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::{bitmap_bits, ReserveOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::bloom_filter::exists::exists;
+    use crate::mem::bloom_filter::len::len;
+    use crate::mem::bloom_filter::load_chunk::load_chunk;
+    use crate::mem::bloom_filter::multi_add::multi_add;
+    use crate::mem::bloom_filter::reserve::reserve;
+    use crate::mem::bloom_filter::scan_dump::scan_dump;
+    use crate::mem::db::Db;
+    use crate::mem::dump::{HeaderWriter, BAD_DATA_ERR};
+
+    #[test]
+    fn test_load_chunk() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            error_rate: 0.01,
+            capacity: 10,
+            expansion: 2,
+        };
+        reserve(&mut db, "bf".to_owned(), &options);
+        let items: Vec<String> = (0..50).map(|index| format!("item-{index}")).collect();
+        multi_add(&mut db, "bf".to_owned(), &items);
+
+        let mut iter = 0;
+        loop {
+            let ReplyFrame::Array(reply) = scan_dump(&db, "bf", iter) else {
+                panic!("expected array");
+            };
+            let [ReplyFrame::Usize(next), ReplyFrame::Bulk(data)] = &reply[..] else {
+                break;
+            };
+            iter = *next;
+            assert_eq!(
+                load_chunk(&mut db, "copy".to_owned(), iter, data),
+                ReplyFrame::ok()
+            );
+        }
+        assert_eq!(len(&db, "copy"), len(&db, "bf"));
+        for item in &items {
+            assert_eq!(exists(&db, "copy", item), ReplyFrame::one());
+        }
+        assert_eq!(
+            load_chunk(&mut db, "new".to_owned(), 1, b"invalid"),
+            ReplyFrame::ConstError(BAD_DATA_ERR)
+        );
+    }
+
+    /// Header of sub-filters with `(capacity, num_bits)` and error rate 0.01.
+    fn header(filters: &[(usize, u64)]) -> Vec<u8> {
+        let mut writer = HeaderWriter::new();
+        for value in [2, 0, filters.len()] {
+            writer.put_usize(value);
+        }
+        for &(capacity, num_bits) in filters {
+            writer.put_usize(capacity);
+            writer.put_f64(0.01);
+            writer.put_usize(0);
+            writer.put_u64(num_bits);
+            writer.put_u64(7);
+        }
+        writer.into_bytes()
+    }
+
+    #[test]
+    fn test_load_oversized_header() {
+        let mut db = Db::new();
+        let large = (1 << 29, bitmap_bits(1 << 29, 0.01));
+        for filters in [
+            &[(1000, u64::MAX - 7)][..],
+            &[(1 << 40, bitmap_bits(1 << 40, 0.01))],
+            &[large; 7],
+            &[],
+        ] {
+            assert_eq!(
+                load_chunk(&mut db, "bf".to_owned(), 1, &header(filters)),
+                ReplyFrame::ConstError(BAD_DATA_ERR)
+            );
+        }
+        assert_eq!(
+            load_chunk(&mut db, "bf".to_owned(), 1, &header(&[large; 2])),
+            ReplyFrame::ok()
+        );
+        // Bitmaps are not loaded yet.
+        assert_eq!(exists(&db, "bf", "a"), ReplyFrame::zero());
+    }
+}
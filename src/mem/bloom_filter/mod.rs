@@ -2,22 +2,57 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use bloomfilter::Bloom;
+//! Scalable bloom filter.
+//!
+//! Items are added to the last sub-filter. When it reaches its capacity,
+//! a new sub-filter is stacked, with capacity multiplied by expansion and
+//! error rate tightened, so that the overall error rate stays bounded.
 
-use crate::cmd::bloom_filter::BloomFilterCommand;
+use crate::cmd::bloom_filter::{
+    bitmap_bits, is_valid_size, BloomFilterCommand, ReserveOptions, MAX_BITS, MAX_EXPANSION,
+};
 use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::dump::{
+    alloc_segment, is_valid_dump_size, ChunkDump, HeaderReader, HeaderWriter, MAX_SEGMENTS,
+};
+use crate::mem::util::hash_with_seed;
 use crate::mem::Mem;
 
 pub mod add;
+mod consts;
 pub mod exists;
+pub mod info;
+pub mod insert;
 pub mod len;
+pub mod load_chunk;
 pub mod multi_add;
 pub mod multi_exists;
+pub mod reserve;
+pub mod scan_dump;
+
+/// Error rate of a new sub-filter is the last one multiplied by this ratio.
+const TIGHTENING_RATIO: f64 = 0.5;
+
+/// Seed of the first hash of items, same as `RedisBloom`.
+const HASH_SEED: u64 = 0xc6a4_a793_5bd1_e995;
+
+#[derive(Debug, Clone)]
+struct SubFilter {
+    /// Bitmap of `num_bits` bits, empty until the first bit is set
+    /// if sub-filter is restored from a dump.
+    bits: Vec<u8>,
+    num_bits: u64,
+    num_hashes: u32,
+    capacity: usize,
+    error_rate: f64,
+    len: usize,
+}
 
 #[derive(Debug, Clone)]
 pub struct BloomFilterObject {
+    expansion: usize,
+    filters: Vec<SubFilter>,
     len: usize,
-    bloom: Bloom<String>,
 }
 
 impl Mem {
@@ -32,36 +67,281 @@ impl Mem {
             BloomFilterCommand::MultiExists(key, items) => {
                 multi_exists::multi_exists(&self.db, &key, &items)
             }
+            BloomFilterCommand::Info(key, field) => info::info(&self.db, &key, field),
+            BloomFilterCommand::Insert(options) => insert::insert(&mut self.db, &options),
+            BloomFilterCommand::LoadChunk(key, iter, data) => {
+                load_chunk::load_chunk(&mut self.db, key, iter, &data)
+            }
+            BloomFilterCommand::Reserve(key, options) => {
+                reserve::reserve(&mut self.db, key, &options)
+            }
+            BloomFilterCommand::ScanDump(key, iter) => scan_dump::scan_dump(&self.db, &key, iter),
         }
     }
 }
 
-impl BloomFilterObject {
-    #[must_use]
+/// Returns number of hash functions of sub-filter with `error_rate`.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn num_hashes(error_rate: f64) -> u32 {
+    (-error_rate.log2()).ceil().max(1.0) as u32
+}
+
+impl SubFilter {
+    fn new(capacity: usize, error_rate: f64) -> Self {
+        Self {
+            bits: Vec::new(),
+            num_bits: bitmap_bits(capacity, error_rate),
+            num_hashes: num_hashes(error_rate),
+            capacity,
+            error_rate,
+            len: 0,
+        }
+    }
+
     #[inline]
-    pub fn new() -> Self {
-        // TODO(Shaohua): Check size of bloom filter.
-        let bloom = Bloom::new(u32::MAX as usize, u32::MAX as usize / 2);
-        Self { len: 0, bloom }
+    const fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn size(&self) -> usize {
+        (self.num_bits / 8) as usize
+    }
+
+    /// Returns positions of bits of item, with double hashing.
+    fn positions(&self, item: &str) -> impl Iterator<Item = usize> {
+        let hash1 = hash_with_seed(item, HASH_SEED);
+        let hash2 = hash_with_seed(item, hash1);
+        let num_bits = self.num_bits;
+        #[allow(clippy::cast_possible_truncation)]
+        (0..u64::from(self.num_hashes))
+            .map(move |i| (hash1.wrapping_add(i.wrapping_mul(hash2)) % num_bits) as usize)
     }
 
+    fn set(&mut self, item: &str) {
+        if self.bits.is_empty() {
+            self.bits = vec![0; self.size()];
+        }
+        for pos in self.positions(item) {
+            self.bits[pos / 8] |= 1 << (pos % 8);
+        }
+    }
+
+    fn check(&self, item: &str) -> bool {
+        !self.bits.is_empty()
+            && self
+                .positions(item)
+                .all(|pos| self.bits[pos / 8] & (1 << (pos % 8)) != 0)
+    }
+}
+
+impl BloomFilterObject {
     #[must_use]
-    #[inline]
-    pub fn check_and_set(&mut self, item: &String) -> bool {
-        let is_set = self.bloom.check_and_set(item);
+    pub fn new(options: &ReserveOptions) -> Self {
+        Self {
+            expansion: options.expansion,
+            filters: vec![SubFilter::new(options.capacity, options.error_rate)],
+            len: 0,
+        }
+    }
+
+    /// Add item to filter.
+    ///
+    /// Returns true if item is added, false if item may already exist,
+    /// or None if filter is full and can not be expanded, or new sub-filter
+    /// would exceed the size limit.
+    pub fn add(&mut self, item: &str) -> Option<bool> {
+        if self.check(item) {
+            return Some(false);
+        }
+        let last = self.filters.last()?;
+        if last.is_full() {
+            if self.expansion == 0 {
+                return None;
+            }
+            let capacity = last.capacity.checked_mul(self.expansion)?;
+            let error_rate = last.error_rate * TIGHTENING_RATIO;
+            if !is_valid_size(capacity, error_rate) {
+                return None;
+            }
+            self.filters.push(SubFilter::new(capacity, error_rate));
+        }
+        let last = self.filters.last_mut()?;
+        last.set(item);
+        last.len += 1;
         self.len += 1;
-        is_set
+        Some(true)
     }
 
     #[must_use]
-    #[inline]
-    pub fn check(&self, item: &String) -> bool {
-        self.bloom.check(item)
+    pub fn check(&self, item: &str) -> bool {
+        self.filters.iter().any(|filter| filter.check(item))
     }
 
+    /// Returns number of unique items added.
     #[must_use]
     #[inline]
     pub const fn len(&self) -> usize {
         self.len
     }
+
+    /// Returns number of items that can be stored before the next expansion.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.filters.iter().map(|filter| filter.capacity).sum()
+    }
+
+    /// Returns memory size of bitmaps of all sub-filters, in bytes.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.filters.iter().map(SubFilter::size).sum()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn num_filters(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Returns growth factor of capacity, 0 means filter is not scalable.
+    #[must_use]
+    #[inline]
+    pub const fn expansion(&self) -> usize {
+        self.expansion
+    }
+}
+
+impl ChunkDump for BloomFilterObject {
+    fn header(&self) -> Vec<u8> {
+        let mut writer = HeaderWriter::new();
+        writer.put_usize(self.expansion);
+        writer.put_usize(self.len);
+        writer.put_usize(self.filters.len());
+        for filter in &self.filters {
+            writer.put_usize(filter.capacity);
+            writer.put_f64(filter.error_rate);
+            writer.put_usize(filter.len);
+            writer.put_u64(filter.num_bits);
+            writer.put_u64(u64::from(filter.num_hashes));
+        }
+        writer.into_bytes()
+    }
+
+    fn from_header(header: &[u8]) -> Option<Self> {
+        let mut reader = HeaderReader::new(header);
+        let expansion = reader.usize()?;
+        let len = reader.usize()?;
+        let num_filters = reader.usize()?;
+        if expansion > MAX_EXPANSION || num_filters > MAX_SEGMENTS {
+            return None;
+        }
+        let mut filters = Vec::new();
+        for _ in 0..num_filters {
+            let capacity = reader.usize()?;
+            let error_rate = reader.f64()?;
+            let filter_len = reader.usize()?;
+            let num_bits = reader.u64()?;
+            let num_hashes = reader.u64()?;
+            // Layout must be the same as a sub-filter created by `BF.RESERVE`.
+            if !(error_rate > 0.0 && error_rate < 1.0 && is_valid_size(capacity, error_rate)) {
+                return None;
+            }
+            // Bitmap is allocated when its data is loaded.
+            let filter = SubFilter {
+                len: filter_len,
+                ..SubFilter::new(capacity, error_rate)
+            };
+            if filter.num_bits != num_bits || u64::from(filter.num_hashes) != num_hashes {
+                return None;
+            }
+            filters.push(filter);
+        }
+        if filters.is_empty()
+            || !reader.is_empty()
+            || !is_valid_dump_size(
+                filters.iter().map(|filter| filter.num_bits / 8),
+                MAX_BITS / 8,
+            )
+        {
+            return None;
+        }
+        Some(Self {
+            expansion,
+            filters,
+            len,
+        })
+    }
+
+    fn segments(&self) -> Vec<usize> {
+        self.filters.iter().map(SubFilter::size).collect()
+    }
+
+    fn read_segment(&self, index: usize, start: usize, len: usize) -> Vec<u8> {
+        let bits = &self.filters[index].bits;
+        if bits.is_empty() {
+            vec![0; len]
+        } else {
+            bits[start..start + len].to_vec()
+        }
+    }
+
+    fn write_segment(&mut self, index: usize, start: usize, data: &[u8]) -> bool {
+        let filter = &mut self.filters[index];
+        if filter.bits.is_empty() {
+            let Some(bits) = alloc_segment(filter.size(), 0) else {
+                return false;
+            };
+            filter.bits = bits;
+        }
+        filter.bits[start..start + data.len()].copy_from_slice(data);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::ReserveOptions;
+    use crate::mem::bloom_filter::BloomFilterObject;
+
+    #[test]
+    fn test_bloom_filter_object() {
+        let options = ReserveOptions {
+            error_rate: 0.01,
+            capacity: 10,
+            expansion: 2,
+        };
+        let mut filter = BloomFilterObject::new(&options);
+        assert_eq!(filter.add("a"), Some(true));
+        assert_eq!(filter.add("a"), Some(false));
+        assert_eq!(filter.len(), 1);
+
+        let added = (0..100)
+            .filter(|index| filter.add(&format!("item-{index}")) == Some(true))
+            .count();
+        assert_eq!(filter.len(), added + 1);
+        assert!(filter.num_filters() > 1);
+        assert!(filter.capacity() >= filter.len());
+        assert!((0..100).all(|index| filter.check(&format!("item-{index}"))));
+
+        let mut filter = BloomFilterObject::new(&ReserveOptions {
+            expansion: 0,
+            ..options
+        });
+        let results: Vec<Option<bool>> = (0..20)
+            .map(|index| filter.add(&format!("item-{index}")))
+            .collect();
+        assert!(results.contains(&None));
+        assert_eq!(filter.num_filters(), 1);
+        assert!(filter.len() <= 10);
+
+        let mut filter = BloomFilterObject::new(&ReserveOptions {
+            capacity: 1 << 16,
+            expansion: 1 << 15,
+            ..options
+        });
+        filter.filters[0].len = filter.filters[0].capacity;
+        assert_eq!(filter.add("a"), None);
+        assert_eq!(filter.num_filters(), 1);
+    }
 }
@@ -0,0 +1,53 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::bloom_filter::ReserveOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::bloom_filter::consts::KEY_EXISTS_ERR;
+use crate::mem::bloom_filter::BloomFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Creates an empty Bloom filter with a single sub-filter for the initial
+/// specified capacity and with an upper bound `error_rate`.
+///
+/// Options:
+/// - EXPANSION: when capacity is reached, an additional sub-filter is created.
+///   The size of the new sub-filter is the size of the last sub-filter
+///   multiplied by expansion, default is 2.
+/// - NONSCALING: prevents the filter from creating additional sub-filters
+///   if initial capacity is reached.
+///
+/// Reply:
+/// - Simple string reply: `OK` if filter created successfully.
+/// - Error reply: if key already exists.
+pub fn reserve(db: &mut Db, key: String, options: &ReserveOptions) -> ReplyFrame {
+    if db.contains_key(&key) {
+        return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+    }
+    db.insert(key, MemObject::BloomFilter(BloomFilterObject::new(options)));
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::bloom_filter::ReserveOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::bloom_filter::consts::KEY_EXISTS_ERR;
+    use crate::mem::bloom_filter::reserve::reserve;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_reserve() {
+        let mut db = Db::new();
+        let options = ReserveOptions::default();
+        assert_eq!(
+            reserve(&mut db, "bf".to_owned(), &options),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            reserve(&mut db, "bf".to_owned(), &options),
+            ReplyFrame::ConstError(KEY_EXISTS_ERR)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::bloom_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::dump::{self, MAX_CHUNK_SIZE};
+
+/// Begins an incremental save of the bloom filter.
+///
+/// This is useful for large bloom filters which cannot fit into the normal
+/// `DUMP` and `RESTORE` model. The first time this command is called,
+/// the value of `iter` should be 0.
+///
+/// Reply:
+/// - Array reply: iterator to pass to the next call and a data chunk.
+///   The iterator is 0 and data is empty when all data has been dumped.
+/// - Error reply: if key does not exist.
+pub fn scan_dump(db: &Db, key: &str, iter: usize) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::BloomFilter(filter)) => dump::scan_dump(filter, iter, MAX_CHUNK_SIZE),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::bloom_filter::add::add;
+    use crate::mem::bloom_filter::consts::KEY_NOT_EXIST_ERR;
+    use crate::mem::bloom_filter::scan_dump::scan_dump;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_scan_dump() {
+        let mut db = Db::new();
+        assert_eq!(
+            scan_dump(&db, "bf", 0),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        add(&mut db, "bf".to_owned(), "a");
+        let ReplyFrame::Array(reply) = scan_dump(&db, "bf", 0) else {
+            panic!("expected array");
+        };
+        assert_eq!(reply[0], ReplyFrame::Usize(1));
+        let ReplyFrame::Array(reply) = scan_dump(&db, "bf", 1) else {
+            panic!("expected array");
+        };
+        let ReplyFrame::Usize(iter) = reply[0] else {
+            panic!("expected iterator");
+        };
+        assert_eq!(
+            scan_dump(&db, "bf", iter),
+            ReplyFrame::Array(vec![ReplyFrame::zero(), ReplyFrame::EmptyBulk])
+        );
+    }
+}
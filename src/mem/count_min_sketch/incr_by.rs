@@ -0,0 +1,59 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::count_min_sketch::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+
+/// Increases the count of item by increment.
+///
+/// Multiple items can be increased with one call.
+///
+/// Reply:
+/// - Array reply: estimated count of each item after increment.
+/// - Error reply: if key does not exist.
+pub fn incr_by(db: &mut Db, key: &str, items: &[(String, usize)]) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::CountMinSketch(sketch)) => ReplyFrame::Array(
+            items
+                .iter()
+                .map(|(item, increment)| ReplyFrame::Usize(sketch.incr_by(item, *increment)))
+                .collect(),
+        ),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::count_min_sketch::consts::KEY_NOT_EXIST_ERR;
+    use crate::mem::count_min_sketch::incr_by::incr_by;
+    use crate::mem::count_min_sketch::init::init_by_dim;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_incr_by() {
+        let mut db = Db::new();
+        let items = [
+            ("a".to_owned(), 5),
+            ("b".to_owned(), 1),
+            ("a".to_owned(), 2),
+        ];
+        assert_eq!(
+            incr_by(&mut db, "events", &items),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        init_by_dim(&mut db, "events".to_owned(), 100, 5);
+        assert_eq!(
+            incr_by(&mut db, "events", &items),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Usize(5),
+                ReplyFrame::Usize(1),
+                ReplyFrame::Usize(7),
+            ])
+        );
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::count_min_sketch::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns width, depth and total count of the sketch.
+///
+/// Reply:
+/// - Array reply: pairs of field names and values.
+/// - Error reply: if key does not exist.
+pub fn info(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CountMinSketch(sketch)) => ReplyFrame::Array(vec![
+            ReplyFrame::ConstSimple("width"),
+            ReplyFrame::Usize(sketch.width()),
+            ReplyFrame::ConstSimple("depth"),
+            ReplyFrame::Usize(sketch.depth()),
+            ReplyFrame::ConstSimple("count"),
+            ReplyFrame::Usize(sketch.count()),
+        ]),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::count_min_sketch::incr_by::incr_by;
+    use crate::mem::count_min_sketch::info::info;
+    use crate::mem::count_min_sketch::init::init_by_dim;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_info() {
+        let mut db = Db::new();
+        init_by_dim(&mut db, "events".to_owned(), 20, 4);
+        incr_by(
+            &mut db,
+            "events",
+            &[("a".to_owned(), 3), ("b".to_owned(), 2)],
+        );
+        assert_eq!(
+            info(&db, "events"),
+            ReplyFrame::Array(vec![
+                ReplyFrame::ConstSimple("width"),
+                ReplyFrame::Usize(20),
+                ReplyFrame::ConstSimple("depth"),
+                ReplyFrame::Usize(4),
+                ReplyFrame::ConstSimple("count"),
+                ReplyFrame::Usize(5),
+            ])
+        );
+    }
+}
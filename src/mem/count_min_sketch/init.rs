@@ -0,0 +1,79 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::count_min_sketch::consts::{KEY_EXISTS_ERR, TOO_LARGE_ERR};
+use crate::mem::count_min_sketch::CountMinSketchObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Initializes a Count-Min Sketch to dimensions specified by user.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key already exists, or sketch is too large.
+pub fn init_by_dim(db: &mut Db, key: String, width: usize, depth: usize) -> ReplyFrame {
+    insert(db, key, CountMinSketchObject::new(width, depth))
+}
+
+/// Initializes a Count-Min Sketch to accommodate requested tolerances.
+///
+/// - error: estimate size of error, as a percent of total counted items.
+/// - probability: the desired probability for inflated count, should be a decimal
+///   value between 0 and 1.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key already exists, or sketch is too large.
+pub fn init_by_prob(db: &mut Db, key: String, error: f64, probability: f64) -> ReplyFrame {
+    insert(
+        db,
+        key,
+        CountMinSketchObject::with_error(error, probability),
+    )
+}
+
+fn insert(db: &mut Db, key: String, sketch: Option<CountMinSketchObject>) -> ReplyFrame {
+    if db.contains_key(&key) {
+        return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+    }
+    let Some(sketch) = sketch else {
+        return ReplyFrame::ConstError(TOO_LARGE_ERR);
+    };
+    db.insert(key, MemObject::CountMinSketch(sketch));
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::count_min_sketch::consts::{KEY_EXISTS_ERR, TOO_LARGE_ERR};
+    use crate::mem::count_min_sketch::init::{init_by_dim, init_by_prob};
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_init() {
+        let mut db = Db::new();
+        assert_eq!(
+            init_by_dim(&mut db, "a".to_owned(), 10, 5),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            init_by_prob(&mut db, "b".to_owned(), 0.01, 0.01),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            init_by_dim(&mut db, "b".to_owned(), 10, 5),
+            ReplyFrame::ConstError(KEY_EXISTS_ERR)
+        );
+        assert_eq!(
+            init_by_dim(&mut db, "c".to_owned(), usize::MAX, 5),
+            ReplyFrame::ConstError(TOO_LARGE_ERR)
+        );
+        assert_eq!(
+            init_by_prob(&mut db, "c".to_owned(), 1e-300, 0.01),
+            ReplyFrame::ConstError(TOO_LARGE_ERR)
+        );
+        assert!(!db.contains_key("c"));
+    }
+}
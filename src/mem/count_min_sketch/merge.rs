@@ -0,0 +1,75 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::count_min_sketch::consts::{KEY_NOT_EXIST_ERR, SIZE_NOT_EQUAL_ERR};
+use crate::mem::count_min_sketch::CountMinSketchObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Merges several sketches into one sketch.
+///
+/// All sketches must have identical width and depth. Counters of destination
+/// are replaced by weighted sum of counters of source sketches, weights
+/// are 1 by default.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if any key does not exist, or sketches have different sizes.
+pub fn merge(db: &mut Db, dest: &str, sources: &[String], weights: &[i64]) -> ReplyFrame {
+    let mut sketches = Vec::with_capacity(sources.len());
+    for (source, weight) in sources.iter().zip(weights) {
+        match db.get(source) {
+            Some(MemObject::CountMinSketch(sketch)) => sketches.push((sketch, *weight)),
+            Some(_) => return ReplyFrame::wrong_type_err(),
+            None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+        }
+    }
+    let mut merged: CountMinSketchObject = match db.get(dest) {
+        Some(MemObject::CountMinSketch(sketch)) => sketch.clone(),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    };
+    if !sketches
+        .iter()
+        .all(|(sketch, _weight)| merged.has_same_size(sketch))
+    {
+        return ReplyFrame::ConstError(SIZE_NOT_EQUAL_ERR);
+    }
+    merged.merge(&sketches);
+    db.insert(dest.to_owned(), MemObject::CountMinSketch(merged));
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::count_min_sketch::consts::SIZE_NOT_EQUAL_ERR;
+    use crate::mem::count_min_sketch::incr_by::incr_by;
+    use crate::mem::count_min_sketch::init::init_by_dim;
+    use crate::mem::count_min_sketch::merge::merge;
+    use crate::mem::count_min_sketch::query::query;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_merge() {
+        let mut db = Db::new();
+        for key in ["a", "b", "dest"] {
+            init_by_dim(&mut db, key.to_owned(), 100, 5);
+        }
+        init_by_dim(&mut db, "small".to_owned(), 10, 5);
+        incr_by(&mut db, "a", &[("x".to_owned(), 3)]);
+        incr_by(&mut db, "b", &[("x".to_owned(), 1), ("y".to_owned(), 2)]);
+
+        let sources = ["a".to_owned(), "b".to_owned()];
+        assert_eq!(merge(&mut db, "dest", &sources, &[1, 2]), ReplyFrame::ok());
+        assert_eq!(
+            query(&db, "dest", &["x".to_owned(), "y".to_owned()]),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(5), ReplyFrame::Usize(4)])
+        );
+        assert_eq!(
+            merge(&mut db, "small", &sources, &[1, 1]),
+            ReplyFrame::ConstError(SIZE_NOT_EQUAL_ERR)
+        );
+    }
+}
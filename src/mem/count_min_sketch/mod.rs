@@ -1,3 +1,188 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Count-min sketch, estimates frequency of items in a stream.
+//!
+//! Items are counted in `depth` arrays of `width` counters, and estimated
+//! frequency of an item is the minimum of its counters, which may over-estimate
+//! but never under-estimate the real frequency.
+
+use crate::cmd::count_min_sketch::CountMinSketchCommand;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::util::hash_with_seed;
+use crate::mem::Mem;
+
+mod consts;
+pub mod incr_by;
+pub mod info;
+pub mod init;
+pub mod merge;
+pub mod query;
+
+/// Maximum number of counters in a sketch, which is `width * depth`.
+pub const MAX_COUNTERS: usize = 1 << 24;
+
+#[derive(Debug, Clone)]
+pub struct CountMinSketchObject {
+    width: usize,
+    depth: usize,
+    /// Total count of all items.
+    count: usize,
+    /// `depth` arrays of `width` counters.
+    counters: Vec<usize>,
+}
+
+impl Mem {
+    pub fn handle_count_min_sketch_command(
+        &mut self,
+        command: CountMinSketchCommand,
+    ) -> ReplyFrame {
+        match command {
+            CountMinSketchCommand::IncrBy(key, items) => {
+                incr_by::incr_by(&mut self.db, &key, &items)
+            }
+            CountMinSketchCommand::Info(key) => info::info(&self.db, &key),
+            CountMinSketchCommand::InitByDim(key, width, depth) => {
+                init::init_by_dim(&mut self.db, key, width, depth)
+            }
+            CountMinSketchCommand::InitByProb(key, error, probability) => {
+                init::init_by_prob(&mut self.db, key, error, probability)
+            }
+            CountMinSketchCommand::Merge(dest, sources, weights) => {
+                merge::merge(&mut self.db, &dest, &sources, &weights)
+            }
+            CountMinSketchCommand::Query(key, items) => query::query(&self.db, &key, &items),
+        }
+    }
+}
+
+impl CountMinSketchObject {
+    /// Create a sketch with `depth` arrays of `width` counters.
+    ///
+    /// Returns None if number of counters exceeds `MAX_COUNTERS`.
+    #[must_use]
+    pub fn new(width: usize, depth: usize) -> Option<Self> {
+        let num_counters = width
+            .checked_mul(depth)
+            .filter(|&num_counters| num_counters <= MAX_COUNTERS)?;
+        Some(Self {
+            width,
+            depth,
+            count: 0,
+            counters: vec![0; num_counters],
+        })
+    }
+
+    /// Create a sketch whose estimate error is `error` of total count,
+    /// with probability of over estimation `probability`.
+    ///
+    /// Returns None if number of counters exceeds `MAX_COUNTERS`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn with_error(error: f64, probability: f64) -> Option<Self> {
+        let width = (2.0 / error).ceil();
+        let depth = (-probability.log2()).ceil().max(1.0);
+        if width * depth > MAX_COUNTERS as f64 {
+            return None;
+        }
+        Self::new(width as usize, depth as usize)
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns index of counter of item in each row.
+    #[allow(clippy::cast_possible_truncation)]
+    fn indexes<'a>(&'a self, item: &'a str) -> impl Iterator<Item = usize> + 'a {
+        (0..self.depth).map(move |row| {
+            let column = hash_with_seed(item, row as u64) % self.width as u64;
+            row * self.width + column as usize
+        })
+    }
+
+    /// Increase count of item, returns its estimated count.
+    pub fn incr_by(&mut self, item: &str, increment: usize) -> usize {
+        let indexes: Vec<usize> = self.indexes(item).collect();
+        for index in indexes {
+            self.counters[index] = self.counters[index].saturating_add(increment);
+        }
+        self.count = self.count.saturating_add(increment);
+        self.query(item)
+    }
+
+    /// Returns estimated count of item.
+    #[must_use]
+    pub fn query(&self, item: &str) -> usize {
+        self.indexes(item)
+            .map(|index| self.counters[index])
+            .min()
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub const fn has_same_size(&self, other: &Self) -> bool {
+        self.width == other.width && self.depth == other.depth
+    }
+
+    /// Replace counters with weighted sum of counters of sketches.
+    ///
+    /// All sketches must have the same width and depth as this one.
+    pub fn merge(&mut self, sketches: &[(&Self, i64)]) {
+        let weighted_sum = |value: fn(&Self, usize) -> usize, index: usize| {
+            let sum: i128 = sketches
+                .iter()
+                .map(|(sketch, weight)| value(sketch, index) as i128 * i128::from(*weight))
+                .sum();
+            usize::try_from(sum.max(0)).unwrap_or(usize::MAX)
+        };
+        for index in 0..self.counters.len() {
+            self.counters[index] = weighted_sum(|sketch, index| sketch.counters[index], index);
+        }
+        self.count = weighted_sum(|sketch, _index| sketch.count, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::count_min_sketch::{CountMinSketchObject, MAX_COUNTERS};
+
+    #[test]
+    fn test_count_min_sketch_object() {
+        let sketch = CountMinSketchObject::with_error(0.001, 0.01).unwrap();
+        assert_eq!(sketch.width(), 2000);
+        assert_eq!(sketch.depth(), 7);
+        assert!(CountMinSketchObject::with_error(f64::MIN_POSITIVE, 0.01).is_none());
+        assert!(CountMinSketchObject::new(usize::MAX, 2).is_none());
+        assert!(CountMinSketchObject::new(MAX_COUNTERS, 2).is_none());
+
+        let mut sketch = CountMinSketchObject::new(100, 5).unwrap();
+        assert_eq!(sketch.incr_by("a", 3), 3);
+        assert_eq!(sketch.incr_by("a", 2), 5);
+        assert_eq!(sketch.incr_by("b", 1), 1);
+        assert_eq!(sketch.query("c"), 0);
+        assert_eq!(sketch.count(), 6);
+
+        let other = sketch.clone();
+        sketch.merge(&[(&other, 2), (&other, -1)]);
+        assert_eq!(sketch.query("a"), 5);
+        assert_eq!(sketch.count(), 6);
+    }
+}
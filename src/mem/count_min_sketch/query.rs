@@ -0,0 +1,45 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::count_min_sketch::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the count for one or more items in a sketch.
+///
+/// Reply:
+/// - Array reply: estimated count of each item.
+/// - Error reply: if key does not exist.
+pub fn query(db: &Db, key: &str, items: &[String]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CountMinSketch(sketch)) => ReplyFrame::Array(
+            items
+                .iter()
+                .map(|item| ReplyFrame::Usize(sketch.query(item)))
+                .collect(),
+        ),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::count_min_sketch::incr_by::incr_by;
+    use crate::mem::count_min_sketch::init::init_by_dim;
+    use crate::mem::count_min_sketch::query::query;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_query() {
+        let mut db = Db::new();
+        init_by_dim(&mut db, "events".to_owned(), 100, 5);
+        incr_by(&mut db, "events", &[("a".to_owned(), 3)]);
+        assert_eq!(
+            query(&db, "events", &["a".to_owned(), "b".to_owned()]),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(3), ReplyFrame::Usize(0)])
+        );
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::cuckoo_filter::{ReserveOptions, DEFAULT_CAPACITY};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::FILTER_FULL_ERR;
+use crate::mem::cuckoo_filter::CuckooFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Adds an item to the cuckoo filter, creates the filter if key does not exist.
+///
+/// Cuckoo filters can contain the same item multiple times.
+///
+/// Reply:
+/// - Integer reply: 1 for successfully adding an item to the filter.
+/// - Error reply: if filter is full.
+pub fn add(db: &mut Db, key: String, item: &str) -> ReplyFrame {
+    with_filter(db, key, |filter| {
+        if filter.add(item) {
+            ReplyFrame::one()
+        } else {
+            ReplyFrame::ConstError(FILTER_FULL_ERR)
+        }
+    })
+}
+
+/// Adds an item to the cuckoo filter only if it does not exist yet.
+///
+/// Reply:
+/// - Integer reply: 1 for successfully adding an item, or 0 if item may exist.
+/// - Error reply: if filter is full.
+pub fn add_nx(db: &mut Db, key: String, item: &str) -> ReplyFrame {
+    with_filter(db, key, |filter| {
+        if filter.contains(item) {
+            ReplyFrame::zero()
+        } else if filter.add(item) {
+            ReplyFrame::one()
+        } else {
+            ReplyFrame::ConstError(FILTER_FULL_ERR)
+        }
+    })
+}
+
+/// Call `f` with filter at key, a filter with default capacity is created
+/// if key does not exist.
+fn with_filter<F>(db: &mut Db, key: String, f: F) -> ReplyFrame
+where
+    F: FnOnce(&mut CuckooFilterObject) -> ReplyFrame,
+{
+    match db.entry(key) {
+        Entry::Occupied(mut occupied) => match occupied.get_mut() {
+            MemObject::CuckooFilter(old_filter) => f(old_filter),
+            _ => ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(vacant) => {
+            let mut new_filter = CuckooFilterObject::new(&ReserveOptions::new(DEFAULT_CAPACITY));
+            let reply = f(&mut new_filter);
+            vacant.insert(MemObject::CuckooFilter(new_filter));
+            reply
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::add::{add, add_nx};
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let key = "cf".to_owned();
+        assert_eq!(add(&mut db, key.clone(), "a"), ReplyFrame::one());
+        assert_eq!(add(&mut db, key.clone(), "a"), ReplyFrame::one());
+        assert_eq!(add_nx(&mut db, key.clone(), "a"), ReplyFrame::zero());
+        assert_eq!(add_nx(&mut db, key, "b"), ReplyFrame::one());
+    }
+}
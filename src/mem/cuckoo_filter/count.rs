@@ -0,0 +1,39 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns an estimation of the number of times a given item was added
+/// to a cuckoo filter.
+///
+/// Reply:
+/// - Integer reply: number of times the item may have been added, or 0 if
+///   key does not exist.
+pub fn count(db: &Db, key: &str, item: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CuckooFilter(filter)) => ReplyFrame::Usize(filter.count(item)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::add::add;
+    use crate::mem::cuckoo_filter::count::count;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_count() {
+        let mut db = Db::new();
+        for _ in 0..3 {
+            add(&mut db, "cf".to_owned(), "a");
+        }
+        assert_eq!(count(&db, "cf", "a"), ReplyFrame::Usize(3));
+        assert_eq!(count(&db, "cf", "b"), ReplyFrame::Usize(0));
+        assert_eq!(count(&db, "other", "a"), ReplyFrame::zero());
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+
+/// Deletes an item once from the filter.
+///
+/// If the item exists only once, it will be removed from the filter.
+/// If the item was added multiple times, it will still be present.
+///
+/// Reply:
+/// - Integer reply: 1 if item has been deleted, or 0 if item was not found.
+/// - Error reply: if key does not exist.
+pub fn del(db: &mut Db, key: &str, item: &str) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::CuckooFilter(filter)) => ReplyFrame::from_bool(filter.remove(item)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::add::add;
+    use crate::mem::cuckoo_filter::del::del;
+    use crate::mem::cuckoo_filter::exists::exists;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_del() {
+        let mut db = Db::new();
+        add(&mut db, "cf".to_owned(), "a");
+        add(&mut db, "cf".to_owned(), "a");
+        assert_eq!(del(&mut db, "cf", "a"), ReplyFrame::one());
+        assert_eq!(exists(&db, "cf", "a"), ReplyFrame::one());
+        assert_eq!(del(&mut db, "cf", "a"), ReplyFrame::one());
+        assert_eq!(exists(&db, "cf", "a"), ReplyFrame::zero());
+        assert_eq!(del(&mut db, "cf", "a"), ReplyFrame::zero());
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Determines whether an item may exist in the cuckoo filter.
+///
+/// Reply:
+/// - Integer reply: 1 if item may exist in the filter, or 0 if item does not
+///   exist or key does not exist.
+pub fn exists(db: &Db, key: &str, item: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CuckooFilter(filter)) => ReplyFrame::from_bool(filter.contains(item)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+/// Determines whether one or more items may exist in the cuckoo filter.
+///
+/// Reply:
+/// - Array reply: for each item, 1 if item may exist in the filter, or 0 if
+///   item does not exist or key does not exist.
+pub fn multi_exists(db: &Db, key: &str, items: &[String]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CuckooFilter(filter)) => ReplyFrame::Array(
+            items
+                .iter()
+                .map(|item| ReplyFrame::from_bool(filter.contains(item)))
+                .collect(),
+        ),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(items.iter().map(|_item| ReplyFrame::zero()).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::add::add;
+    use crate::mem::cuckoo_filter::exists::{exists, multi_exists};
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_exists() {
+        let mut db = Db::new();
+        assert_eq!(exists(&db, "cf", "a"), ReplyFrame::zero());
+        add(&mut db, "cf".to_owned(), "a");
+        assert_eq!(exists(&db, "cf", "a"), ReplyFrame::one());
+        assert_eq!(
+            multi_exists(&db, "cf", &["a".to_owned(), "b".to_owned()]),
+            ReplyFrame::Array(vec![ReplyFrame::one(), ReplyFrame::zero()])
+        );
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns information about a cuckoo filter.
+///
+/// Reply:
+/// - Array reply: pairs of field names and values.
+/// - Error reply: if key does not exist.
+pub fn info(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CuckooFilter(filter)) => ReplyFrame::Array(vec![
+            ReplyFrame::ConstSimple("Size"),
+            ReplyFrame::Usize(filter.size()),
+            ReplyFrame::ConstSimple("Number of buckets"),
+            ReplyFrame::Usize(filter.num_buckets()),
+            ReplyFrame::ConstSimple("Number of filters"),
+            ReplyFrame::Usize(filter.num_filters()),
+            ReplyFrame::ConstSimple("Number of items inserted"),
+            ReplyFrame::Usize(filter.num_items()),
+            ReplyFrame::ConstSimple("Number of items deleted"),
+            ReplyFrame::Usize(filter.num_deleted()),
+            ReplyFrame::ConstSimple("Bucket size"),
+            ReplyFrame::Usize(filter.bucket_size()),
+            ReplyFrame::ConstSimple("Expansion rate"),
+            ReplyFrame::Usize(filter.expansion()),
+            ReplyFrame::ConstSimple("Max iterations"),
+            ReplyFrame::Usize(filter.max_iterations()),
+        ]),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::ReserveOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::add::add;
+    use crate::mem::cuckoo_filter::info::info;
+    use crate::mem::cuckoo_filter::reserve::reserve;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_info() {
+        let mut db = Db::new();
+        reserve(&mut db, "cf".to_owned(), &ReserveOptions::new(1000));
+        add(&mut db, "cf".to_owned(), "a");
+        let ReplyFrame::Array(fields) = info(&db, "cf") else {
+            panic!("expected array");
+        };
+        // 1000 items in buckets of 2 items.
+        assert_eq!(fields[1], ReplyFrame::Usize(1024));
+        assert_eq!(fields[3], ReplyFrame::Usize(512));
+        assert_eq!(fields[7], ReplyFrame::Usize(1));
+    }
+}
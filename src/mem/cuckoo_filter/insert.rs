@@ -0,0 +1,99 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::cuckoo_filter::{InsertOptions, ReserveOptions, DEFAULT_CAPACITY};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::cuckoo_filter::CuckooFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Adds one or more items to a cuckoo filter, creates the filter if key does
+/// not exist.
+///
+/// Options:
+/// - CAPACITY: capacity of the filter if it is created.
+/// - NOCREATE: do not create filter if key does not exist.
+///
+/// Reply:
+/// - Array reply: for each item, 1 if item was added, or -1 if filter is full.
+/// - Error reply: if key does not exist and `NOCREATE` is specified.
+pub fn insert(db: &mut Db, options: &InsertOptions) -> ReplyFrame {
+    insert_items(db, options, false)
+}
+
+/// Adds one or more items to a cuckoo filter if they do not exist yet.
+///
+/// Reply:
+/// - Array reply: for each item, 1 if item was added, 0 if item may exist,
+///   or -1 if filter is full.
+/// - Error reply: if key does not exist and `NOCREATE` is specified.
+pub fn insert_nx(db: &mut Db, options: &InsertOptions) -> ReplyFrame {
+    insert_items(db, options, true)
+}
+
+fn insert_items(db: &mut Db, options: &InsertOptions, not_exists: bool) -> ReplyFrame {
+    if !db.contains_key(&options.key) {
+        if options.no_create {
+            return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR);
+        }
+        let capacity = options.capacity.unwrap_or(DEFAULT_CAPACITY);
+        let filter = CuckooFilterObject::new(&ReserveOptions::new(capacity));
+        db.insert(options.key.clone(), MemObject::CuckooFilter(filter));
+    }
+    let Some(MemObject::CuckooFilter(filter)) = db.get_mut(&options.key) else {
+        return ReplyFrame::wrong_type_err();
+    };
+    let replies = options
+        .items
+        .iter()
+        .map(|item| {
+            if not_exists && filter.contains(item) {
+                ReplyFrame::zero()
+            } else if filter.add(item) {
+                ReplyFrame::one()
+            } else {
+                ReplyFrame::I64(-1)
+            }
+        })
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::InsertOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+    use crate::mem::cuckoo_filter::insert::{insert, insert_nx};
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_insert() {
+        let mut db = Db::new();
+        let mut options = InsertOptions {
+            key: "cf".to_owned(),
+            capacity: Some(100),
+            no_create: true,
+            items: vec!["a".to_owned(), "b".to_owned()],
+        };
+        assert_eq!(
+            insert(&mut db, &options),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        options.no_create = false;
+        assert_eq!(
+            insert(&mut db, &options),
+            ReplyFrame::Array(vec![ReplyFrame::one(), ReplyFrame::one()])
+        );
+        options.items.push("c".to_owned());
+        assert_eq!(
+            insert_nx(&mut db, &options),
+            ReplyFrame::Array(vec![
+                ReplyFrame::zero(),
+                ReplyFrame::zero(),
+                ReplyFrame::one()
+            ])
+        );
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::{KEY_EXISTS_ERR, KEY_NOT_EXIST_ERR};
+use crate::mem::cuckoo_filter::CuckooFilterObject;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::dump::{self, ChunkDump, BAD_DATA_ERR, HEADER_ITER};
+
+/// Restores a cuckoo filter previously saved using `CF.SCANDUMP`.
+///
+/// The header chunk creates the filter, so it must be loaded first.
+/// Other chunks can be loaded in any order.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if data is invalid, or key already exists when loading
+///   the header chunk, or key does not exist when loading other chunks.
+pub fn load_chunk(db: &mut Db, key: String, iter: usize, data: &[u8]) -> ReplyFrame {
+    if iter == HEADER_ITER {
+        if db.contains_key(&key) {
+            return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+        }
+        return CuckooFilterObject::from_header(data).map_or(
+            ReplyFrame::ConstError(BAD_DATA_ERR),
+            |filter| {
+                db.insert(key, MemObject::CuckooFilter(filter));
+                ReplyFrame::ok()
+            },
+        );
+    }
+    match db.get_mut(&key) {
+        Some(MemObject::CuckooFilter(filter)) => {
+            if dump::load_chunk(filter, iter, data) {
+                ReplyFrame::ok()
+            } else {
+                ReplyFrame::ConstError(BAD_DATA_ERR)
+            }
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::{InsertOptions, ReserveOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+    use crate::mem::cuckoo_filter::count::count;
+    use crate::mem::cuckoo_filter::info::info;
+    use crate::mem::cuckoo_filter::insert::insert;
+    use crate::mem::cuckoo_filter::load_chunk::load_chunk;
+    use crate::mem::cuckoo_filter::reserve::reserve;
+    use crate::mem::cuckoo_filter::scan_dump::scan_dump;
+    use crate::mem::db::Db;
+    use crate::mem::dump::{HeaderWriter, BAD_DATA_ERR};
+
+    #[test]
+    fn test_load_chunk() {
+        let mut db = Db::new();
+        reserve(&mut db, "cf".to_owned(), &ReserveOptions::new(16));
+        let items: Vec<String> = (0..50).map(|index| format!("item-{index}")).collect();
+        let options = InsertOptions {
+            key: "cf".to_owned(),
+            capacity: None,
+            no_create: true,
+            items: items.clone(),
+        };
+        insert(&mut db, &options);
+
+        assert_eq!(
+            load_chunk(&mut db, "copy".to_owned(), 2, &[1]),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        let mut iter = 0;
+        loop {
+            let ReplyFrame::Array(reply) = scan_dump(&db, "cf", iter) else {
+                panic!("expected array");
+            };
+            let [ReplyFrame::Usize(next), ReplyFrame::Bulk(data)] = &reply[..] else {
+                break;
+            };
+            iter = *next;
+            assert_eq!(
+                load_chunk(&mut db, "copy".to_owned(), iter, data),
+                ReplyFrame::ok()
+            );
+        }
+        assert_eq!(info(&db, "copy"), info(&db, "cf"));
+        for item in &items {
+            assert_eq!(count(&db, "copy", item), count(&db, "cf", item));
+        }
+    }
+
+    fn header(num_buckets: &[usize]) -> Vec<u8> {
+        let mut writer = HeaderWriter::new();
+        for value in [2, 20, 1, 0, 0, num_buckets.len()] {
+            writer.put_usize(value);
+        }
+        for &value in num_buckets {
+            writer.put_usize(value);
+        }
+        writer.into_bytes()
+    }
+
+    #[test]
+    fn test_load_oversized_header() {
+        let mut db = Db::new();
+        for num_buckets in [&[1 << 62][..], &[1 << 29; 5], &[]] {
+            assert_eq!(
+                load_chunk(&mut db, "cf".to_owned(), 1, &header(num_buckets)),
+                ReplyFrame::ConstError(BAD_DATA_ERR)
+            );
+        }
+        assert_eq!(
+            load_chunk(&mut db, "cf".to_owned(), 1, &header(&[1 << 4])),
+            ReplyFrame::ok()
+        );
+        // Slots are not loaded yet.
+        assert_eq!(count(&db, "cf", "a"), ReplyFrame::zero());
+    }
+}
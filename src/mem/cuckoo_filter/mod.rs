@@ -1,3 +1,458 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Cuckoo filter, a probabilistic set which supports deletion.
+//!
+//! Each item is stored as a one-byte fingerprint in one of two candidate buckets.
+//! When both buckets are full, existing fingerprints are kicked to their
+//! alternate buckets. If no free slot is found in max iterations, a new
+//! sub-filter is appended, whose capacity is expanded from the last one.
+
+use rand::Rng;
+
+use crate::cmd::cuckoo_filter::{
+    CuckooFilterCommand, ReserveOptions, MAX_BUCKET_SIZE, MAX_CAPACITY, MAX_EXPANSION,
+    MAX_ITERATIONS,
+};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::dump::{
+    alloc_segment, is_valid_dump_size, ChunkDump, HeaderReader, HeaderWriter, MAX_SEGMENTS,
+};
+use crate::mem::util::hash_with_seed;
+use crate::mem::Mem;
+
+pub mod add;
+mod consts;
+pub mod count;
+pub mod del;
+pub mod exists;
+pub mod info;
+pub mod insert;
+pub mod load_chunk;
+pub mod reserve;
+pub mod scan_dump;
+
+/// Fingerprint 0 marks an empty slot.
+const EMPTY_SLOT: u8 = 0;
+
+#[derive(Debug, Clone)]
+struct SubFilter {
+    /// Number of buckets, always power of 2, so that alternate bucket index
+    /// can be computed with XOR.
+    num_buckets: usize,
+    bucket_size: usize,
+    /// Slots of all buckets, empty until the first fingerprint is written
+    /// if sub-filter is restored from a dump.
+    slots: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CuckooFilterObject {
+    bucket_size: usize,
+    max_iterations: usize,
+    expansion: usize,
+    filters: Vec<SubFilter>,
+    num_items: usize,
+    num_deleted: usize,
+}
+
+impl Mem {
+    pub fn handle_cuckoo_filter_command(&mut self, command: CuckooFilterCommand) -> ReplyFrame {
+        match command {
+            CuckooFilterCommand::Add(key, item) => add::add(&mut self.db, key, &item),
+            CuckooFilterCommand::AddNx(key, item) => add::add_nx(&mut self.db, key, &item),
+            CuckooFilterCommand::Count(key, item) => count::count(&self.db, &key, &item),
+            CuckooFilterCommand::Del(key, item) => del::del(&mut self.db, &key, &item),
+            CuckooFilterCommand::Exists(key, item) => exists::exists(&self.db, &key, &item),
+            CuckooFilterCommand::Info(key) => info::info(&self.db, &key),
+            CuckooFilterCommand::Insert(options) => insert::insert(&mut self.db, &options),
+            CuckooFilterCommand::InsertNx(options) => insert::insert_nx(&mut self.db, &options),
+            CuckooFilterCommand::LoadChunk(key, iter, data) => {
+                load_chunk::load_chunk(&mut self.db, key, iter, &data)
+            }
+            CuckooFilterCommand::MultiExists(key, items) => {
+                exists::multi_exists(&self.db, &key, &items)
+            }
+            CuckooFilterCommand::Reserve(key, options) => {
+                reserve::reserve(&mut self.db, key, &options)
+            }
+            CuckooFilterCommand::ScanDump(key, iter) => scan_dump::scan_dump(&self.db, &key, iter),
+        }
+    }
+}
+
+/// Returns fingerprint and hash of item.
+#[allow(clippy::cast_possible_truncation)]
+fn fingerprint(item: &str) -> (u8, u64) {
+    let hash = hash_with_seed(item, 0);
+    let fingerprint = ((hash >> 56) as u8) % 255 + 1;
+    (fingerprint, hash)
+}
+
+impl SubFilter {
+    fn new(capacity: usize, bucket_size: usize) -> Self {
+        let num_buckets = capacity.div_ceil(bucket_size).next_power_of_two();
+        Self {
+            num_buckets,
+            bucket_size,
+            slots: vec![EMPTY_SLOT; num_buckets * bucket_size],
+        }
+    }
+
+    #[inline]
+    const fn capacity(&self) -> usize {
+        self.num_buckets * self.bucket_size
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn index(&self, hash: u64) -> usize {
+        (hash as usize) & (self.num_buckets - 1)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn alt_index(&self, index: usize, fingerprint: u8) -> usize {
+        let hash = (fingerprint as u64).wrapping_mul(0x5bd1_e995);
+        (index ^ hash as usize) & (self.num_buckets - 1)
+    }
+
+    /// Returns the two candidate bucket indexes.
+    const fn indexes(&self, fingerprint: u8, hash: u64) -> (usize, usize) {
+        let index = self.index(hash);
+        (index, self.alt_index(index, fingerprint))
+    }
+
+    fn bucket(&self, index: usize) -> &[u8] {
+        self.slots
+            .get(index * self.bucket_size..(index + 1) * self.bucket_size)
+            .unwrap_or_default()
+    }
+
+    fn bucket_mut(&mut self, index: usize) -> &mut [u8] {
+        if self.slots.is_empty() {
+            self.slots = vec![EMPTY_SLOT; self.capacity()];
+        }
+        &mut self.slots[index * self.bucket_size..(index + 1) * self.bucket_size]
+    }
+
+    fn insert_into(&mut self, index: usize, fingerprint: u8) -> bool {
+        self.bucket_mut(index)
+            .iter_mut()
+            .find(|slot| **slot == EMPTY_SLOT)
+            .map(|slot| *slot = fingerprint)
+            .is_some()
+    }
+
+    /// Insert fingerprint, kicking existing fingerprints to their alternate buckets
+    /// if both buckets are full.
+    ///
+    /// Returns false and keeps the filter unchanged if no free slot is found.
+    fn insert(&mut self, fingerprint: u8, hash: u64, max_iterations: usize) -> bool {
+        let (index1, index2) = self.indexes(fingerprint, hash);
+        if self.insert_into(index1, fingerprint) || self.insert_into(index2, fingerprint) {
+            return true;
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut index = if rng.gen() { index1 } else { index2 };
+        let mut victim = fingerprint;
+        let mut kicked = Vec::with_capacity(max_iterations);
+        for _ in 0..max_iterations {
+            let pos = index * self.bucket_size + rng.gen_range(0..self.bucket_size);
+            kicked.push((pos, self.slots[pos]));
+            std::mem::swap(&mut victim, &mut self.slots[pos]);
+            index = self.alt_index(index, victim);
+            if self.insert_into(index, victim) {
+                return true;
+            }
+        }
+        // Undo kicks so that no fingerprint is lost.
+        for (pos, old) in kicked.into_iter().rev() {
+            self.slots[pos] = old;
+        }
+        false
+    }
+
+    #[allow(clippy::naive_bytecount)]
+    fn count(&self, fingerprint: u8, hash: u64) -> usize {
+        let (index1, index2) = self.indexes(fingerprint, hash);
+        let count_in = |index| {
+            self.bucket(index)
+                .iter()
+                .filter(|slot| **slot == fingerprint)
+                .count()
+        };
+        if index1 == index2 {
+            count_in(index1)
+        } else {
+            count_in(index1) + count_in(index2)
+        }
+    }
+
+    fn remove_from(&mut self, index: usize, fingerprint: u8) -> bool {
+        self.bucket_mut(index)
+            .iter_mut()
+            .find(|slot| **slot == fingerprint)
+            .map(|slot| *slot = EMPTY_SLOT)
+            .is_some()
+    }
+
+    fn remove(&mut self, fingerprint: u8, hash: u64) -> bool {
+        if self.slots.is_empty() {
+            return false;
+        }
+        let (index1, index2) = self.indexes(fingerprint, hash);
+        self.remove_from(index1, fingerprint) || self.remove_from(index2, fingerprint)
+    }
+}
+
+impl CuckooFilterObject {
+    #[must_use]
+    pub fn new(options: &ReserveOptions) -> Self {
+        Self {
+            bucket_size: options.bucket_size,
+            max_iterations: options.max_iterations,
+            expansion: options.expansion,
+            filters: vec![SubFilter::new(options.capacity, options.bucket_size)],
+            num_items: 0,
+            num_deleted: 0,
+        }
+    }
+
+    /// Add item to filter, item may be added multiple times.
+    ///
+    /// Returns false if filter is full and can not be expanded, or capacity of
+    /// new sub-filter exceeds `MAX_CAPACITY`.
+    pub fn add(&mut self, item: &str) -> bool {
+        let (fingerprint, hash) = fingerprint(item);
+        let max_iterations = self.max_iterations;
+        let last = self.filters.len() - 1;
+        if !self.filters[last].insert(fingerprint, hash, max_iterations) {
+            if self.expansion == 0 {
+                return false;
+            }
+            let Some(capacity) = self.filters[last]
+                .capacity()
+                .checked_mul(self.expansion)
+                .filter(|&capacity| capacity <= MAX_CAPACITY)
+            else {
+                return false;
+            };
+            let mut filter = SubFilter::new(capacity, self.bucket_size);
+            let inserted = filter.insert(fingerprint, hash, max_iterations);
+            debug_assert!(inserted);
+            self.filters.push(filter);
+        }
+        self.num_items += 1;
+        true
+    }
+
+    #[must_use]
+    pub fn contains(&self, item: &str) -> bool {
+        let (fingerprint, hash) = fingerprint(item);
+        self.filters
+            .iter()
+            .any(|filter| filter.count(fingerprint, hash) > 0)
+    }
+
+    /// Returns number of times item may be added.
+    #[must_use]
+    pub fn count(&self, item: &str) -> usize {
+        let (fingerprint, hash) = fingerprint(item);
+        self.filters
+            .iter()
+            .map(|filter| filter.count(fingerprint, hash))
+            .sum()
+    }
+
+    /// Remove one occurrence of item, the newest sub-filter is checked first.
+    pub fn remove(&mut self, item: &str) -> bool {
+        let (fingerprint, hash) = fingerprint(item);
+        let removed = self
+            .filters
+            .iter_mut()
+            .rev()
+            .any(|filter| filter.remove(fingerprint, hash));
+        if removed {
+            self.num_items -= 1;
+            self.num_deleted += 1;
+        }
+        removed
+    }
+
+    /// Returns memory size of all sub-filters, in bytes.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.filters.iter().map(SubFilter::capacity).sum()
+    }
+
+    #[must_use]
+    pub fn num_buckets(&self) -> usize {
+        self.filters.iter().map(|filter| filter.num_buckets).sum()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn num_filters(&self) -> usize {
+        self.filters.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn num_items(&self) -> usize {
+        self.num_items
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn num_deleted(&self) -> usize {
+        self.num_deleted
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn bucket_size(&self) -> usize {
+        self.bucket_size
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn expansion(&self) -> usize {
+        self.expansion
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+}
+
+impl ChunkDump for CuckooFilterObject {
+    fn header(&self) -> Vec<u8> {
+        let mut writer = HeaderWriter::new();
+        writer.put_usize(self.bucket_size);
+        writer.put_usize(self.max_iterations);
+        writer.put_usize(self.expansion);
+        writer.put_usize(self.num_items);
+        writer.put_usize(self.num_deleted);
+        writer.put_usize(self.filters.len());
+        for filter in &self.filters {
+            writer.put_usize(filter.num_buckets);
+        }
+        writer.into_bytes()
+    }
+
+    fn from_header(header: &[u8]) -> Option<Self> {
+        let mut reader = HeaderReader::new(header);
+        let bucket_size = reader.usize()?;
+        let max_iterations = reader.usize()?;
+        let expansion = reader.usize()?;
+        let num_items = reader.usize()?;
+        let num_deleted = reader.usize()?;
+        let num_filters = reader.usize()?;
+        if !(1..=MAX_BUCKET_SIZE).contains(&bucket_size)
+            || expansion > MAX_EXPANSION
+            || !(1..=MAX_ITERATIONS).contains(&max_iterations)
+            || num_filters > MAX_SEGMENTS
+        {
+            return None;
+        }
+        // Same as buckets of a sub-filter with `MAX_CAPACITY`.
+        let max_buckets = MAX_CAPACITY.div_ceil(bucket_size).next_power_of_two();
+        let mut buckets = Vec::new();
+        for _ in 0..num_filters {
+            let num_buckets = reader.usize()?;
+            if !num_buckets.is_power_of_two() {
+                return None;
+            }
+            buckets.push(num_buckets);
+        }
+        let segment_size = |num_buckets: usize| num_buckets as u64 * bucket_size as u64;
+        if buckets.is_empty()
+            || !reader.is_empty()
+            || !is_valid_dump_size(
+                buckets.iter().map(|&num_buckets| segment_size(num_buckets)),
+                segment_size(max_buckets),
+            )
+        {
+            return None;
+        }
+        // Slots are allocated when their data is loaded.
+        let filters = buckets
+            .into_iter()
+            .map(|num_buckets| SubFilter {
+                num_buckets,
+                bucket_size,
+                slots: Vec::new(),
+            })
+            .collect();
+        Some(Self {
+            bucket_size,
+            max_iterations,
+            expansion,
+            filters,
+            num_items,
+            num_deleted,
+        })
+    }
+
+    fn segments(&self) -> Vec<usize> {
+        self.filters.iter().map(SubFilter::capacity).collect()
+    }
+
+    fn read_segment(&self, index: usize, start: usize, len: usize) -> Vec<u8> {
+        let slots = &self.filters[index].slots;
+        if slots.is_empty() {
+            vec![EMPTY_SLOT; len]
+        } else {
+            slots[start..start + len].to_vec()
+        }
+    }
+
+    fn write_segment(&mut self, index: usize, start: usize, data: &[u8]) -> bool {
+        let filter = &mut self.filters[index];
+        if filter.slots.is_empty() {
+            let Some(slots) = alloc_segment(filter.capacity(), EMPTY_SLOT) else {
+                return false;
+            };
+            filter.slots = slots;
+        }
+        filter.slots[start..start + data.len()].copy_from_slice(data);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::ReserveOptions;
+    use crate::mem::cuckoo_filter::CuckooFilterObject;
+
+    #[test]
+    fn test_cuckoo_filter_object() {
+        let mut filter = CuckooFilterObject::new(&ReserveOptions::new(64));
+        let items: Vec<String> = (0..200).map(|index| format!("item-{index}")).collect();
+        for item in &items {
+            assert!(filter.add(item));
+        }
+        assert!(filter.num_filters() > 1);
+        assert!(items.iter().all(|item| filter.contains(item)));
+        assert!(filter.count(&items[0]) >= 1);
+        assert!(filter.remove(&items[0]));
+        assert_eq!(filter.num_items(), 199);
+        assert_eq!(filter.num_deleted(), 1);
+
+        let mut options = ReserveOptions::new(4);
+        options.expansion = 0;
+        let mut filter = CuckooFilterObject::new(&options);
+        let added = items.iter().filter(|item| filter.add(item)).count();
+        assert!(added <= 4);
+        assert_eq!(filter.num_items(), added);
+
+        options.expansion = usize::MAX;
+        let mut filter = CuckooFilterObject::new(&options);
+        let added = items.iter().filter(|item| filter.add(item)).count();
+        assert!(added <= 4);
+        assert_eq!(filter.num_filters(), 1);
+    }
+}
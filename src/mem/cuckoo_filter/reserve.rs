@@ -0,0 +1,56 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::cuckoo_filter::ReserveOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::KEY_EXISTS_ERR;
+use crate::mem::cuckoo_filter::CuckooFilterObject;
+use crate::mem::db::{Db, MemObject};
+
+/// Creates an empty cuckoo filter with a single sub-filter for the initial
+/// specified capacity.
+///
+/// Options:
+/// - BUCKETSIZE: number of items in each bucket, default is 2.
+/// - MAXITERATIONS: number of attempts to swap items between buckets before
+///   declaring filter as full and creating an additional filter, default is 20.
+/// - EXPANSION: when a new filter is created, its size is the size of the current
+///   filter multiplied by expansion, default is 1.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key already exists.
+pub fn reserve(db: &mut Db, key: String, options: &ReserveOptions) -> ReplyFrame {
+    if db.contains_key(&key) {
+        return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+    }
+    db.insert(
+        key,
+        MemObject::CuckooFilter(CuckooFilterObject::new(options)),
+    );
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::ReserveOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::consts::KEY_EXISTS_ERR;
+    use crate::mem::cuckoo_filter::reserve::reserve;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_reserve() {
+        let mut db = Db::new();
+        let options = ReserveOptions::new(1000);
+        assert_eq!(
+            reserve(&mut db, "cf".to_owned(), &options),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            reserve(&mut db, "cf".to_owned(), &options),
+            ReplyFrame::ConstError(KEY_EXISTS_ERR)
+        );
+    }
+}
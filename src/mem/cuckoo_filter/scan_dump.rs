@@ -0,0 +1,49 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::cuckoo_filter::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::dump::{self, MAX_CHUNK_SIZE};
+
+/// Begins an incremental save of the cuckoo filter.
+///
+/// The first time this command is called, the value of `iter` should be 0.
+///
+/// Reply:
+/// - Array reply: iterator to pass to the next call and a data chunk.
+///   The iterator is 0 and data is empty when all data has been dumped.
+/// - Error reply: if key does not exist.
+pub fn scan_dump(db: &Db, key: &str, iter: usize) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::CuckooFilter(filter)) => dump::scan_dump(filter, iter, MAX_CHUNK_SIZE),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::cuckoo_filter::ReserveOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::cuckoo_filter::reserve::reserve;
+    use crate::mem::cuckoo_filter::scan_dump::scan_dump;
+    use crate::mem::db::Db;
+
+    #[test]
+    fn test_scan_dump() {
+        let mut db = Db::new();
+        reserve(&mut db, "cf".to_owned(), &ReserveOptions::new(1000));
+        let ReplyFrame::Array(reply) = scan_dump(&db, "cf", 1) else {
+            panic!("expected array");
+        };
+        // 512 buckets of 2 slots.
+        assert_eq!(reply[0], ReplyFrame::Usize(1025));
+        assert_eq!(reply[1], ReplyFrame::bulk(vec![0; 1024]));
+        assert_eq!(
+            scan_dump(&db, "cf", 1025),
+            ReplyFrame::Array(vec![ReplyFrame::zero(), ReplyFrame::EmptyBulk])
+        );
+    }
+}
@@ -6,16 +6,27 @@ use std::collections::HashMap;
 
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::cmd::Command;
+use crate::listener::types::SessionGroup;
+use crate::mem::auto_suggest::AutoSuggestObject;
 use crate::mem::bloom_filter::BloomFilterObject;
+use crate::mem::count_min_sketch::CountMinSketchObject;
+use crate::mem::cuckoo_filter::CuckooFilterObject;
 use crate::mem::hash::HashObject;
-use crate::mem::hyper::HyperObject;
+use crate::mem::json::JsonObject;
 use crate::mem::list::ListObject;
 use crate::mem::set::SetObject;
+use crate::mem::stream::StreamObject;
 use crate::mem::string::StrObject;
+use crate::mem::time_series::TimeSeriesObject;
+use crate::mem::top_k::TopKObject;
+use crate::mem::zset::SortedSetObject;
 use crate::mem::{list, Mem};
 
 pub type Db = HashMap<String, MemObject>;
 
+pub(super) const SUBSCRIBED_CONTEXT_ERR: &str =
+    "ERR only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE are allowed in this context";
+
 #[derive(Debug, Clone)]
 pub enum MemObject {
     // Core objects
@@ -23,32 +34,69 @@ pub enum MemObject {
     List(ListObject),
     Hash(HashObject),
     Set(SetObject),
-    Hyper(HyperObject),
+    SortedSet(SortedSetObject),
+    Stream(StreamObject),
 
     // Stack objects
     BloomFilter(BloomFilterObject),
+    Json(JsonObject),
+    TimeSeries(TimeSeriesObject),
+    TopK(TopKObject),
+    CountMinSketch(CountMinSketchObject),
+    CuckooFilter(CuckooFilterObject),
+    AutoSuggest(AutoSuggestObject),
 }
 
 impl Mem {
-    pub fn handle_db_commands(&mut self, commands: Vec<Command>) -> Vec<ReplyFrame> {
-        commands
-            .into_iter()
-            .map(|command| self.handle_db_command(command))
-            .collect()
+    /// Handle commands of a request from client.
+    ///
+    /// Once the client subscribed to any channel or pattern, only subscription
+    /// commands are allowed.
+    pub fn handle_db_commands(
+        &mut self,
+        session_group: SessionGroup,
+        commands: Vec<Command>,
+    ) -> Vec<ReplyFrame> {
+        let mut reply_frames = Vec::with_capacity(commands.len());
+        for command in commands {
+            if self.pub_sub.is_subscribed(session_group) && !command.is_subscription() {
+                reply_frames.push(ReplyFrame::ConstError(SUBSCRIBED_CONTEXT_ERR));
+                continue;
+            }
+            if let Command::PubSub(command) = command {
+                reply_frames.extend(self.handle_pub_sub_command(session_group, command));
+            } else {
+                reply_frames.push(self.handle_db_command(command));
+            }
+        }
+        reply_frames
     }
 
     pub fn handle_db_command(&mut self, command: Command) -> ReplyFrame {
-        match command {
+        self.signal_ready_keys(&command);
+        let time_series = self.overwritten_time_series(&command);
+        let reply = match command {
             Command::Str(command) => self.handle_string_command(command),
             Command::List(command) => self.handle_list_command(command),
             Command::Hash(command) => self.handle_hash_command(command),
             Command::Set(command) => self.handle_set_command(command),
+            Command::SortedSet(command) => self.handle_sorted_set_command(command),
+            Command::Geo(command) => self.handle_geo_command(command),
+            Command::Stream(command) => self.handle_stream_command(command),
             Command::Bitmap(command) => self.handle_bitmap_command(command),
             Command::HyperLogLog(command) => self.handle_hyper_command(command),
             Command::Generic(command) => self.handle_generic_command(command),
             Command::BloomFilter(command) => self.handle_bloom_filter_command(command),
+            Command::Json(command) => self.handle_json_command(command),
+            Command::TimeSeries(command) => self.handle_time_series_command(command),
+            Command::TopK(command) => self.handle_top_k_command(command),
+            Command::CountMinSketch(command) => self.handle_count_min_sketch_command(command),
+            Command::CuckooFilter(command) => self.handle_cuckoo_filter_command(command),
+            Command::AutoSuggest(command) => self.handle_auto_suggest_command(command),
             _ => unreachable!(),
-        }
+        };
+        self.unlink_time_series(time_series);
+        reply
     }
 }
 
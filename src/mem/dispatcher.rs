@@ -4,8 +4,13 @@
 
 use stdext::function_name;
 
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::Command;
 use crate::commands::{DispatcherToMemCmd, MemToDispatcherCmd};
 use crate::error::Error;
+use crate::listener::types::SessionGroup;
+use crate::mem::db::SUBSCRIBED_CONTEXT_ERR;
+use crate::mem::util::now_millis;
 use crate::mem::Mem;
 
 impl Mem {
@@ -14,20 +19,110 @@ impl Mem {
         cmd: DispatcherToMemCmd,
     ) -> Result<(), Error> {
         log::debug!("{}, cmd: {cmd:?}", function_name!());
-        let DispatcherToMemCmd {
-            session_group,
-            commands,
-        } = cmd;
-        let reply_frames = self.handle_db_commands(commands);
-        let reply_cmd = MemToDispatcherCmd {
-            session_group,
-            reply_frames,
-        };
-        log::debug!(
-            "{} send cmd to dispatcher, cmd: {reply_cmd:?}",
-            function_name!()
+        match cmd {
+            DispatcherToMemCmd::Request {
+                session_group,
+                commands,
+            } => self.handle_request(session_group, commands).await,
+            DispatcherToMemCmd::Disconnect(session_group) => {
+                self.pub_sub.remove_client(session_group);
+                self.remove_blocked_client(session_group);
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_request(
+        &mut self,
+        session_group: SessionGroup,
+        mut commands: Vec<Command>,
+    ) -> Result<(), Error> {
+        // Only the last command in a request may block the client,
+        // other blocking commands reply nil immediately if they can not be served.
+        let blocking = commands
+            .last()
+            .and_then(Command::block_timeout)
+            .and_then(|timeout| commands.pop().map(|command| (command, timeout)));
+        let mut reply_frames = self.handle_db_commands(session_group, commands);
+        if let Some((command, timeout)) = blocking {
+            // Blocking commands are not subscription commands.
+            if self.pub_sub.is_subscribed(session_group) {
+                reply_frames.push(ReplyFrame::ConstError(SUBSCRIBED_CONTEXT_ERR));
+                return self.send_reply_frames(session_group, reply_frames).await;
+            }
+            let reply = self.handle_db_command(command.clone());
+            if reply == ReplyFrame::Null {
+                self.block_client(session_group, reply_frames, command, timeout, now_millis());
+                self.send_push_frames().await?;
+                return self.serve_blocked_clients().await;
+            }
+            reply_frames.push(reply);
+        }
+        self.send_reply_frames(session_group, reply_frames).await
+    }
+
+    async fn send_reply_frames(
+        &mut self,
+        session_group: SessionGroup,
+        reply_frames: Vec<ReplyFrame>,
+    ) -> Result<(), Error> {
+        // Requests with only subscription commands are replied with push frames.
+        if !reply_frames.is_empty() {
+            let reply_cmd = MemToDispatcherCmd {
+                session_group,
+                reply_frames,
+                push: false,
+            };
+            log::debug!(
+                "{} send cmd to dispatcher, cmd: {reply_cmd:?}",
+                function_name!()
+            );
+            self.dispatcher_sender.send(reply_cmd).await?;
+        }
+        self.send_push_frames().await?;
+        self.serve_blocked_clients().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::cmd::pub_sub::PubSubCommand;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{PopSide, SortedSetCommand};
+    use crate::cmd::Command;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::db::SUBSCRIBED_CONTEXT_ERR;
+    use crate::mem::Mem;
+
+    #[tokio::test]
+    async fn test_blocking_command_in_subscribed_context() {
+        let (dispatcher_sender, mut receiver) = mpsc::channel(4);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        let session_group = SessionGroup::new(1, 1);
+        let subscribe = Command::PubSub(PubSubCommand::Subscribe(vec!["news".to_owned()]));
+        mem.handle_request(session_group, vec![subscribe])
+            .await
+            .unwrap();
+        let reply_cmd = receiver.recv().await.unwrap();
+        assert!(reply_cmd.push);
+
+        let command = Command::SortedSet(SortedSetCommand::BlockingPop(
+            vec!["zset".to_owned()],
+            PopSide::Min,
+            0.0,
+        ));
+        mem.handle_request(session_group, vec![command])
+            .await
+            .unwrap();
+        let reply_cmd = receiver.recv().await.unwrap();
+        assert!(!reply_cmd.push);
+        assert_eq!(
+            reply_cmd.reply_frames,
+            vec![ReplyFrame::ConstError(SUBSCRIBED_CONTEXT_ERR)]
         );
-        self.dispatcher_sender.send(reply_cmd).await?;
-        Ok(())
+        assert_eq!(mem.blocked_clients.len(), 0);
     }
 }
@@ -0,0 +1,262 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Chunked dump of probabilistic filters, used by `SCANDUMP` and `LOADCHUNK`.
+//!
+//! A dump starts with a header chunk at iterator 1, which describes the layout
+//! of the filter, followed by data chunks of its sub-filters.
+//! Iterator of a data chunk is the end offset of this chunk in all data plus 1,
+//! so that each data chunk can be located when it is loaded.
+
+use crate::cmd::reply_frame::ReplyFrame;
+
+/// Maximum number of bytes in a data chunk.
+pub const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Iterator of header chunk.
+pub const HEADER_ITER: usize = 1;
+
+/// Maximum number of segments, like sub-filters, restored from a header.
+pub const MAX_SEGMENTS: usize = 1 << 16;
+
+/// Total size of segments restored from a header is at most this number of
+/// segments with the maximum size.
+const MAX_FULL_SEGMENTS: u64 = 4;
+
+pub const BAD_DATA_ERR: &str = "ERR received bad data";
+
+pub trait ChunkDump: Sized {
+    /// Serialize metadata of object.
+    fn header(&self) -> Vec<u8>;
+
+    /// Create an empty object from header, returns None if header is invalid.
+    fn from_header(header: &[u8]) -> Option<Self>;
+
+    /// Returns number of bytes in each data segment, like a sub-filter.
+    fn segments(&self) -> Vec<usize>;
+
+    fn read_segment(&self, index: usize, start: usize, len: usize) -> Vec<u8>;
+
+    /// Returns false if memory of segment is not available.
+    fn write_segment(&mut self, index: usize, start: usize, data: &[u8]) -> bool;
+}
+
+/// Returns next iterator and chunk after `iter`.
+///
+/// Iterator 0 starts a new dump, and 0 is returned with an empty chunk
+/// when all data has been dumped.
+pub fn scan_dump<T: ChunkDump>(object: &T, iter: usize, max_chunk_size: usize) -> ReplyFrame {
+    if iter == 0 {
+        return ReplyFrame::Array(vec![
+            ReplyFrame::Usize(HEADER_ITER),
+            ReplyFrame::bulk(object.header()),
+        ]);
+    }
+    match locate(&object.segments(), iter - HEADER_ITER, max_chunk_size) {
+        Some((index, start, len)) => ReplyFrame::Array(vec![
+            ReplyFrame::Usize(iter + len),
+            ReplyFrame::bulk(object.read_segment(index, start, len)),
+        ]),
+        None => ReplyFrame::Array(vec![ReplyFrame::zero(), ReplyFrame::EmptyBulk]),
+    }
+}
+
+/// Write a data chunk returned by `scan_dump()` back to object.
+///
+/// Returns false if chunk does not match layout of object.
+pub fn load_chunk<T: ChunkDump>(object: &mut T, iter: usize, data: &[u8]) -> bool {
+    let Some(start) = iter
+        .checked_sub(HEADER_ITER)
+        .and_then(|end| end.checked_sub(data.len()))
+    else {
+        return false;
+    };
+    match locate(&object.segments(), start, usize::MAX) {
+        Some((index, start, len)) if !data.is_empty() && data.len() <= len => {
+            object.write_segment(index, start, data)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if each segment restored from a header is at most `max_size`
+/// bytes, and total size is at most `MAX_FULL_SEGMENTS` times of `max_size`.
+pub fn is_valid_dump_size(sizes: impl IntoIterator<Item = u64>, max_size: u64) -> bool {
+    sizes
+        .into_iter()
+        .try_fold(0_u64, |total, size| {
+            total.checked_add(size).filter(|_| size <= max_size)
+        })
+        .is_some_and(|total| total <= max_size.saturating_mul(MAX_FULL_SEGMENTS))
+}
+
+/// Allocate a segment of `len` bytes filled with `value`, when the first chunk
+/// of it is loaded.
+///
+/// Returns None if memory is not available.
+pub fn alloc_segment(len: usize, value: u8) -> Option<Vec<u8>> {
+    let mut segment = Vec::new();
+    segment.try_reserve_exact(len).ok()?;
+    segment.resize(len, value);
+    Some(segment)
+}
+
+/// Returns index of segment, start offset in that segment and length of chunk
+/// which starts at `offset` of all data.
+fn locate(
+    segments: &[usize],
+    offset: usize,
+    max_chunk_size: usize,
+) -> Option<(usize, usize, usize)> {
+    let mut start = offset;
+    for (index, &len) in segments.iter().enumerate() {
+        if start < len {
+            return Some((index, start, (len - start).min(max_chunk_size)));
+        }
+        start -= len;
+    }
+    None
+}
+
+/// Serialize integers in header, in little endian.
+#[derive(Debug, Default)]
+pub struct HeaderWriter {
+    bytes: Vec<u8>,
+}
+
+impl HeaderWriter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_usize(&mut self, value: usize) {
+        self.put_u64(value as u64);
+    }
+
+    pub fn put_f64(&mut self, value: f64) {
+        self.put_u64(value.to_bits());
+    }
+
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Read integers written by `HeaderWriter`.
+#[derive(Debug)]
+pub struct HeaderReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> HeaderReader<'a> {
+    #[must_use]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        let (head, tail) = self.bytes.split_first_chunk::<8>()?;
+        self.bytes = tail;
+        Some(u64::from_le_bytes(*head))
+    }
+
+    pub fn usize(&mut self) -> Option<usize> {
+        self.u64().and_then(|value| usize::try_from(value).ok())
+    }
+
+    pub fn f64(&mut self) -> Option<f64> {
+        self.u64().map(f64::from_bits)
+    }
+
+    /// Returns true if all bytes are read.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::dump::{
+        load_chunk, scan_dump, ChunkDump, HeaderReader, HeaderWriter, HEADER_ITER,
+    };
+
+    /// Object with segments of bytes.
+    #[derive(Debug, PartialEq)]
+    struct Segments(Vec<Vec<u8>>);
+
+    impl ChunkDump for Segments {
+        fn header(&self) -> Vec<u8> {
+            let mut writer = HeaderWriter::new();
+            writer.put_usize(self.0.len());
+            for segment in &self.0 {
+                writer.put_usize(segment.len());
+            }
+            writer.into_bytes()
+        }
+
+        fn from_header(header: &[u8]) -> Option<Self> {
+            let mut reader = HeaderReader::new(header);
+            let num = reader.usize()?;
+            let segments = (0..num)
+                .map(|_| reader.usize().map(|len| vec![0; len]))
+                .collect::<Option<Vec<_>>>()?;
+            reader.is_empty().then_some(Self(segments))
+        }
+
+        fn segments(&self) -> Vec<usize> {
+            self.0.iter().map(Vec::len).collect()
+        }
+
+        fn read_segment(&self, index: usize, start: usize, len: usize) -> Vec<u8> {
+            self.0[index][start..start + len].to_vec()
+        }
+
+        fn write_segment(&mut self, index: usize, start: usize, data: &[u8]) -> bool {
+            self.0[index][start..start + data.len()].copy_from_slice(data);
+            true
+        }
+    }
+
+    #[test]
+    fn test_scan_dump() {
+        let object = Segments(vec![vec![1, 2, 3, 4, 5], vec![6, 7]]);
+        let mut chunks = Vec::new();
+        let mut iter = 0;
+        loop {
+            let ReplyFrame::Array(reply) = scan_dump(&object, iter, 3) else {
+                panic!("expected array");
+            };
+            match &reply[..] {
+                [ReplyFrame::Usize(next), ReplyFrame::Bulk(data)] => {
+                    iter = *next;
+                    chunks.push((iter, data.clone()));
+                }
+                [_, ReplyFrame::EmptyBulk] => break,
+                _ => panic!("unexpected reply"),
+            }
+        }
+        // Header, and chunks which do not cross segments.
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[2], (6, vec![4, 5]));
+
+        let (header_iter, header) = &chunks[0];
+        assert_eq!(*header_iter, HEADER_ITER);
+        let mut loaded = Segments::from_header(header).unwrap();
+        for (iter, data) in chunks.iter().skip(1).rev() {
+            assert!(load_chunk(&mut loaded, *iter, data));
+        }
+        assert_eq!(loaded, object);
+        assert!(!load_chunk(&mut loaded, 20, &[1]));
+        assert!(!load_chunk(&mut loaded, 3, &[1, 2, 3]));
+        assert!(!load_chunk(&mut loaded, 0, &[1]));
+    }
+}
@@ -24,11 +24,17 @@ pub fn get_type(db: &Db, key: &str) -> ReplyFrame {
         Some(MemObject::List(_)) => "list",
         Some(MemObject::Hash(_)) => "hash",
         Some(MemObject::Set(_)) => "set",
-        // TODO(Shaohua): Returns "string" instead of "hyper"
-        Some(MemObject::Hyper(_)) => "hyper",
+        Some(MemObject::SortedSet(_)) => "zset",
+        Some(MemObject::Stream(_)) => "stream",
 
         // Stack objects
         Some(MemObject::BloomFilter(_)) => "bloom",
+        Some(MemObject::Json(_)) => "ReJSON-RL",
+        Some(MemObject::TimeSeries(_)) => "TSDB-TYPE",
+        Some(MemObject::TopK(_)) => "TopK-TYPE",
+        Some(MemObject::CountMinSketch(_)) => "CMSk-TYPE",
+        Some(MemObject::CuckooFilter(_)) => "MBbloomCF",
+        Some(MemObject::AutoSuggest(_)) => "trietype0",
 
         None => "none",
     };
@@ -51,7 +57,7 @@ mod tests {
         let reply = set(&mut db, key1.clone(), b"value".to_vec());
         assert_eq!(reply, ReplyFrame::ok());
         let key2 = "key2".to_owned();
-        let reply = push_front(&mut db, key2.clone(), vec![b"value".to_vec()]);
+        let reply = push_front(&mut db, key2.clone(), &[b"value".to_vec()]);
         assert_eq!(reply, ReplyFrame::one());
         let key3 = "key3".to_owned();
         let reply = add(&mut db, key3.clone(), vec![b"value".to_vec()]);
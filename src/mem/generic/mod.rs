@@ -5,6 +5,8 @@
 use crate::cmd::generic::GenericCommand;
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::generic::flush_db::flush_db;
+use crate::mem::time_series::index::LabelIndex;
+use crate::mem::time_series::rule;
 use crate::mem::Mem;
 
 mod db_size;
@@ -12,6 +14,7 @@ pub mod delete;
 pub mod exists;
 mod flush_db;
 pub mod get_type;
+pub mod object_encoding;
 pub mod random_key;
 pub mod rename;
 
@@ -21,12 +24,31 @@ impl Mem {
             GenericCommand::DbSize => db_size::db_size(&self.db),
             GenericCommand::Delete(keys) => delete::delete(&mut self.db, &keys),
             GenericCommand::Exists(keys) => exists::exists(&self.db, &keys),
+            GenericCommand::ObjectEncoding(key) => object_encoding::object_encoding(&self.db, &key),
             GenericCommand::RandomKey(random_index) => {
                 random_key::random_key(&self.db, random_index)
             }
-            GenericCommand::Rename(key, new_key) => rename::rename(&mut self.db, &key, new_key),
+            GenericCommand::Rename(key, new_key) => {
+                let overwritten = if key != new_key && self.db.contains_key(&key) {
+                    rule::rule_links(&self.db, &new_key)
+                } else {
+                    None
+                };
+                let reply = rename::rename(&mut self.db, &key, new_key.clone());
+                if let Some(links) = overwritten {
+                    rule::unlink_rules(&mut self.db, &links);
+                }
+                self.time_series_index.remove(&key);
+                self.time_series_index.remove(&new_key);
+                self.index_time_series(&new_key);
+                self.track_hash_expires(new_key);
+                reply
+            }
             GenericCommand::Type(key) => get_type::get_type(&self.db, &key),
-            GenericCommand::FlushDb(is_sync) => flush_db(&mut self.db, is_sync),
+            GenericCommand::FlushDb(is_sync) => {
+                self.time_series_index = LabelIndex::new();
+                flush_db(&mut self.db, is_sync)
+            }
         }
     }
 }
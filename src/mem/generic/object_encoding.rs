@@ -0,0 +1,114 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::string::StrObject;
+
+/// Max length of string to be reported as `embstr`, same as redis.
+const EMBSTR_SIZE_LIMIT: usize = 44;
+
+/// Returns the internal encoding for the object stored at key.
+///
+/// Objects can be encoded in different ways:
+/// - Strings can be encoded as `raw` (normal string encoding), `int`
+///   (strings representing integers in a 64-bit signed interval)
+///   or `embstr` (an embedded string, which is an object where the internal simple dynamic
+///   string, sds, is an unmodifiable string allocated in the same chuck as the object itself).
+/// - Lists are encoded as `quicklist`.
+/// - Sets can be encoded as `intset`, `listpack` or `hashtable`.
+///   The `intset` is a special encoding used for small sets composed solely of integers.
+/// - Hashes can be encoded as `listpack` or `hashtable`.
+/// - Sorted sets are encoded as `skiplist`.
+///
+/// Reply, one of the following:
+/// - Bulk string reply: the encoding of the object.
+/// - Null reply: if the key doesn't exist.
+pub fn object_encoding(db: &Db, key: &str) -> ReplyFrame {
+    let encoding = match db.get(key) {
+        Some(MemObject::Str(str_obj)) => string_encoding(str_obj),
+        Some(MemObject::List(_)) => "quicklist",
+        Some(MemObject::Hash(hash_obj)) => hash_obj.encoding(),
+        Some(MemObject::Set(set_obj)) => set_obj.encoding(),
+        Some(MemObject::SortedSet(zset)) => zset.encoding(),
+        Some(MemObject::Stream(_)) => "stream",
+        Some(
+            MemObject::BloomFilter(_)
+            | MemObject::Json(_)
+            | MemObject::TimeSeries(_)
+            | MemObject::TopK(_)
+            | MemObject::CountMinSketch(_)
+            | MemObject::CuckooFilter(_)
+            | MemObject::AutoSuggest(_),
+        ) => "raw",
+        None => return ReplyFrame::Null,
+    };
+    ReplyFrame::Bulk(encoding.as_bytes().to_vec())
+}
+
+fn string_encoding(str_obj: &StrObject) -> &'static str {
+    let is_int = std::str::from_utf8(&str_obj.vec)
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .is_some_and(|num| num.to_string().as_bytes() == str_obj.vec.as_slice());
+    if is_int {
+        "int"
+    } else if str_obj.vec.len() <= EMBSTR_SIZE_LIMIT {
+        "embstr"
+    } else {
+        "raw"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::generic::object_encoding::object_encoding;
+    use crate::mem::hash;
+    use crate::mem::list::push_back::push_back;
+    use crate::mem::set::add::add;
+    use crate::mem::string::set::set;
+
+    #[test]
+    fn test_object_encoding() {
+        let mut db = Db::new();
+        set(&mut db, "num".to_owned(), b"12345".to_vec());
+        set(&mut db, "short".to_owned(), b"Hello".to_vec());
+        set(&mut db, "long".to_owned(), vec![b'x'; 100]);
+        push_back(&mut db, "list".to_owned(), &[b"a".to_vec()]);
+        add(
+            &mut db,
+            "ints".to_owned(),
+            vec![b"1".to_vec(), b"2".to_vec()],
+        );
+        add(
+            &mut db,
+            "names".to_owned(),
+            vec![b"1".to_vec(), b"alice".to_vec()],
+        );
+        hash::set::set(
+            &mut db,
+            "hash".to_owned(),
+            vec![("name".to_owned(), b"alice".to_vec())],
+        );
+
+        let cases: [(&str, &[u8]); 7] = [
+            ("num", b"int"),
+            ("short", b"embstr"),
+            ("long", b"raw"),
+            ("list", b"quicklist"),
+            ("ints", b"intset"),
+            ("names", b"listpack"),
+            ("hash", b"listpack"),
+        ];
+        for (key, encoding) in cases {
+            assert_eq!(
+                object_encoding(&db, key),
+                ReplyFrame::Bulk(encoding.to_vec())
+            );
+        }
+        assert_eq!(object_encoding(&db, "nokey"), ReplyFrame::Null);
+    }
+}
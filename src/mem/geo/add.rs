@@ -0,0 +1,110 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::geo::GeoPoint;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::AddOptions;
+use crate::mem::db::Db;
+use crate::mem::geo::geohash;
+use crate::mem::zset;
+
+/// Adds the specified geospatial items (longitude, latitude, name) to the specified key.
+///
+/// Data is stored into the key as a sorted set, in a way that makes it possible
+/// to query the items with the `GEOSEARCH` command.
+///
+/// Options:
+/// - XX: Only update elements that already exist. Never add elements.
+/// - NX: Don't update already existing elements. Always add new elements.
+/// - CH: Modify the return value from the number of new elements added,
+///   to the total number of elements changed.
+///
+/// Reply:
+/// - Integer reply: When used without optional arguments, the number of elements
+///   added to the sorted set (excluding score updates).
+///   If the CH option is specified, the number of elements that were changed (added or updated).
+pub fn add(db: &mut Db, key: String, options: AddOptions, points: Vec<GeoPoint>) -> ReplyFrame {
+    let mut elements = Vec::with_capacity(points.len());
+    for point in points {
+        let Some(score) = geohash::to_score(point.longitude, point.latitude) else {
+            return ReplyFrame::Error(format!(
+                "ERR invalid longitude,latitude pair {:.6},{:.6}",
+                point.longitude, point.latitude
+            ));
+        };
+        elements.push((score, point.member));
+    }
+    zset::add::add(db, key, options, elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::GeoPoint;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+
+    fn point(longitude: f64, latitude: f64, member: &[u8]) -> GeoPoint {
+        GeoPoint {
+            longitude,
+            latitude,
+            member: member.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let key = "Sicily".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                point(13.361_389, 38.115_556, b"Palermo"),
+                point(15.087_269, 37.502_669, b"Catania"),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let options = AddOptions {
+            changed: true,
+            ..AddOptions::default()
+        };
+        let reply = add(
+            &mut db,
+            key.clone(),
+            options,
+            vec![
+                point(13.361_389, 38.115_556, b"Palermo"),
+                point(13.5, 38.0, b"Catania"),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(1));
+
+        let options = AddOptions {
+            exist: true,
+            ..AddOptions::default()
+        };
+        let reply = add(
+            &mut db,
+            key.clone(),
+            options,
+            vec![point(1.0, 1.0, b"Rome")],
+        );
+        assert_eq!(reply, ReplyFrame::zero());
+
+        let reply = add(
+            &mut db,
+            key,
+            AddOptions::default(),
+            vec![point(13.0, 86.0, b"North")],
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Error("ERR invalid longitude,latitude pair 13.000000,86.000000".to_owned())
+        );
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::geo::DistanceUnit;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::geo::geohash;
+
+/// Return the distance between two members in the geospatial index represented by the sorted set.
+///
+/// The unit must be one of the following, and defaults to meters:
+/// - m for meters.
+/// - km for kilometers.
+/// - mi for miles.
+/// - ft for feet.
+///
+/// Reply, one of the following:
+/// - Nil reply: one or both of the elements are missing.
+/// - Bulk string reply: distance as a double (represented as a string)
+///   in the specified units.
+pub fn dist(db: &Db, key: &str, member1: &[u8], member2: &[u8], unit: DistanceUnit) -> ReplyFrame {
+    let zset = match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => zset,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Null,
+    };
+    let (Some(score1), Some(score2)) = (zset.score(member1), zset.score(member2)) else {
+        return ReplyFrame::Null;
+    };
+    let (long1, lat1) = geohash::from_score(score1);
+    let (long2, lat2) = geohash::from_score(score2);
+    let distance = geohash::distance(long1, lat1, long2, lat2) / unit.to_meters();
+    ReplyFrame::Bulk(format_distance(distance).into_bytes())
+}
+
+/// Format distance with 4 decimals, like redis `addReplyDoubleDistance()`.
+///
+/// Rounding is done on integer with half to even, the same as `llrint()`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn format_distance(distance: f64) -> String {
+    let value = (distance * 10000.0).round_ties_even() as i64;
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+    format!("{sign}{}.{:04}", value / 10000, value % 10000)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::{DistanceUnit, GeoPoint};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+    use crate::mem::geo::dist::{dist, format_distance};
+
+    #[test]
+    fn test_dist() {
+        let mut db = Db::new();
+        let key = "Sicily".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                GeoPoint {
+                    longitude: 13.361_389,
+                    latitude: 38.115_556,
+                    member: b"Palermo".to_vec(),
+                },
+                GeoPoint {
+                    longitude: 15.087_269,
+                    latitude: 37.502_669,
+                    member: b"Catania".to_vec(),
+                },
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = dist(&db, &key, b"Palermo", b"Catania", DistanceUnit::Meters);
+        assert_eq!(reply, ReplyFrame::Bulk(b"166274.1516".to_vec()));
+        let reply = dist(&db, &key, b"Palermo", b"Catania", DistanceUnit::Kilometers);
+        assert_eq!(reply, ReplyFrame::Bulk(b"166.2742".to_vec()));
+        let reply = dist(&db, &key, b"Palermo", b"Catania", DistanceUnit::Miles);
+        assert_eq!(reply, ReplyFrame::Bulk(b"103.3182".to_vec()));
+        let reply = dist(&db, &key, b"Foo", b"Bar", DistanceUnit::Meters);
+        assert_eq!(reply, ReplyFrame::Null);
+        assert_eq!(format_distance(0.0), "0.0000");
+    }
+}
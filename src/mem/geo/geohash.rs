@@ -0,0 +1,277 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Geohash encoding, same as redis `geohash.c` and `geohash_helper.c`.
+//!
+//! Longitude and latitude are quantized to 26 bits each, and interleaved into a 52-bit
+//! integer, which is stored as score of a sorted set member.
+
+/// Max precision of each coordinate in bits.
+pub const GEO_STEP_MAX: u8 = 26;
+
+/// Limits of latitude from EPSG:900913 / EPSG:3785 / OSGEO:41001.
+pub const GEO_LAT_MIN: f64 = -85.051_128_78;
+pub const GEO_LAT_MAX: f64 = 85.051_128_78;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+
+/// Earth's quadratic mean radius for WGS-84.
+pub const EARTH_RADIUS_IN_METERS: f64 = 6_372_797.560_856;
+
+const GEO_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoHashRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Longitude range used to encode scores.
+pub const LONG_RANGE: GeoHashRange = GeoHashRange {
+    min: GEO_LONG_MIN,
+    max: GEO_LONG_MAX,
+};
+
+/// Latitude range used to encode scores.
+pub const LAT_RANGE: GeoHashRange = GeoHashRange {
+    min: GEO_LAT_MIN,
+    max: GEO_LAT_MAX,
+};
+
+/// Latitude range of the standard geohash string.
+const STANDARD_LAT_RANGE: GeoHashRange = GeoHashRange {
+    min: -90.0,
+    max: 90.0,
+};
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct GeoHashBits {
+    pub bits: u64,
+    pub step: u8,
+}
+
+/// Bounding box of a geohash cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoHashArea {
+    pub hash: GeoHashBits,
+    pub longitude: GeoHashRange,
+    pub latitude: GeoHashRange,
+}
+
+impl GeoHashArea {
+    /// Returns center of the area as `(longitude, latitude)`, clamped to valid ranges.
+    #[must_use]
+    pub fn center(&self) -> (f64, f64) {
+        let longitude =
+            ((self.longitude.min + self.longitude.max) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+        let latitude =
+            ((self.latitude.min + self.latitude.max) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+        (longitude, latitude)
+    }
+}
+
+/// Spread lower 32 bits to even bits of u64.
+const fn spread(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & 0x5555_5555_5555_5555
+}
+
+/// Squash even bits of u64 into u32.
+#[allow(clippy::cast_possible_truncation)]
+const fn squash(value: u64) -> u32 {
+    let mut x = value & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+    x as u32
+}
+
+/// Interleave bits of latitude (even bits) and longitude (odd bits).
+const fn interleave(latitude: u32, longitude: u32) -> u64 {
+    spread(latitude) | (spread(longitude) << 1)
+}
+
+/// Returns `(latitude, longitude)` bits.
+const fn deinterleave(bits: u64) -> (u32, u32) {
+    (squash(bits), squash(bits >> 1))
+}
+
+/// Returns true if longitude and latitude can be encoded.
+#[must_use]
+pub fn is_valid_coordinate(longitude: f64, latitude: f64) -> bool {
+    (GEO_LONG_MIN..=GEO_LONG_MAX).contains(&longitude)
+        && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&latitude)
+}
+
+/// Encode coordinate into geohash with `step` bits of precision for each coordinate.
+///
+/// Returns None if coordinate is out of range.
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn encode(
+    long_range: GeoHashRange,
+    lat_range: GeoHashRange,
+    longitude: f64,
+    latitude: f64,
+    step: u8,
+) -> Option<GeoHashBits> {
+    if step == 0 || step > 32 || !is_valid_coordinate(longitude, latitude) {
+        return None;
+    }
+    if longitude < long_range.min
+        || longitude > long_range.max
+        || latitude < lat_range.min
+        || latitude > lat_range.max
+    {
+        return None;
+    }
+
+    let cells = (1_u64 << step) as f64;
+    let lat_offset = (latitude - lat_range.min) / (lat_range.max - lat_range.min) * cells;
+    let long_offset = (longitude - long_range.min) / (long_range.max - long_range.min) * cells;
+    Some(GeoHashBits {
+        bits: interleave(lat_offset as u32, long_offset as u32),
+        step,
+    })
+}
+
+/// Encode coordinate into geohash with the ranges used by scores.
+#[must_use]
+pub fn encode_wgs84(longitude: f64, latitude: f64, step: u8) -> Option<GeoHashBits> {
+    encode(LONG_RANGE, LAT_RANGE, longitude, latitude, step)
+}
+
+/// Decode geohash into its bounding box.
+#[must_use]
+// Plain arithmetic is kept instead of `mul_add()` to get the same rounding as redis.
+#[allow(clippy::cast_precision_loss, clippy::suboptimal_flops)]
+pub fn decode(long_range: GeoHashRange, lat_range: GeoHashRange, hash: GeoHashBits) -> GeoHashArea {
+    let (lat_bits, long_bits) = deinterleave(hash.bits);
+    let cells = (1_u64 << hash.step) as f64;
+    let lat_scale = lat_range.max - lat_range.min;
+    let long_scale = long_range.max - long_range.min;
+    let lat_bits = f64::from(lat_bits);
+    let long_bits = f64::from(long_bits);
+    GeoHashArea {
+        hash,
+        latitude: GeoHashRange {
+            min: lat_range.min + (lat_bits / cells) * lat_scale,
+            max: lat_range.min + ((lat_bits + 1.0) / cells) * lat_scale,
+        },
+        longitude: GeoHashRange {
+            min: long_range.min + (long_bits / cells) * long_scale,
+            max: long_range.min + ((long_bits + 1.0) / cells) * long_scale,
+        },
+    }
+}
+
+/// Decode geohash with the ranges used by scores.
+#[must_use]
+pub fn decode_wgs84(hash: GeoHashBits) -> GeoHashArea {
+    decode(LONG_RANGE, LAT_RANGE, hash)
+}
+
+/// Convert coordinate to score of sorted set member.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn to_score(longitude: f64, latitude: f64) -> Option<f64> {
+    encode_wgs84(longitude, latitude, GEO_STEP_MAX).map(|hash| hash.bits as f64)
+}
+
+/// Convert score of sorted set member to `(longitude, latitude)`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn from_score(score: f64) -> (f64, f64) {
+    let hash = GeoHashBits {
+        bits: score as u64,
+        step: GEO_STEP_MAX,
+    };
+    decode_wgs84(hash).center()
+}
+
+/// Returns 11 characters standard geohash string of a coordinate.
+#[must_use]
+pub fn to_geohash_string(longitude: f64, latitude: f64) -> String {
+    let bits = encode(
+        LONG_RANGE,
+        STANDARD_LAT_RANGE,
+        longitude,
+        latitude,
+        GEO_STEP_MAX,
+    )
+    .map_or(0, |hash| hash.bits);
+    (0..11)
+        .map(|i| {
+            // The last character only has 2 bits left, which are padded with zeros.
+            let index = if i == 10 {
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            char::from(GEO_ALPHABET[usize::try_from(index).unwrap_or_default()])
+        })
+        .collect()
+}
+
+#[must_use]
+#[inline]
+fn deg_rad(degree: f64) -> f64 {
+    degree * (std::f64::consts::PI / 180.0)
+}
+
+/// Returns distance in meters between two latitudes on the same meridian.
+#[must_use]
+pub fn lat_distance(lat1: f64, lat2: f64) -> f64 {
+    EARTH_RADIUS_IN_METERS * (deg_rad(lat2) - deg_rad(lat1)).abs()
+}
+
+/// Returns distance in meters between two coordinates, using haversine formula.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn distance(long1: f64, lat1: f64, long2: f64, lat2: f64) -> f64 {
+    let v = ((deg_rad(long2) - deg_rad(long1)) / 2.0).sin();
+    // Avoid expensive math when longitudes are practically the same.
+    if v == 0.0 {
+        return lat_distance(lat1, lat2);
+    }
+    let lat1 = deg_rad(lat1);
+    let lat2 = deg_rad(lat2);
+    let u = ((lat2 - lat1) / 2.0).sin();
+    let a = u * u + lat1.cos() * lat2.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::geo::geohash::{distance, from_score, to_geohash_string, to_score};
+
+    #[test]
+    fn test_encode_decode() {
+        let score = to_score(13.361_389, 38.115_556).unwrap();
+        assert_eq!(score, 3_479_099_956_230_698.0);
+        let (longitude, latitude) = from_score(score);
+        assert_eq!(format!("{longitude:.17}"), "13.36138933897018433");
+        assert_eq!(format!("{latitude:.17}"), "38.11555639549629859");
+        assert_eq!(to_geohash_string(longitude, latitude), "sqc8b49rny0");
+        assert!(to_score(200.0, 100.0).is_none());
+    }
+
+    #[test]
+    fn test_distance() {
+        let (long1, lat1) = from_score(to_score(13.361_389, 38.115_556).unwrap());
+        let (long2, lat2) = from_score(to_score(15.087_269, 37.502_669).unwrap());
+        let dist = distance(long1, lat1, long2, lat2);
+        assert_eq!(format!("{dist:.4}"), "166274.1516");
+    }
+}
@@ -0,0 +1,351 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Helpers to find geohash cells covering a search area, same as redis `geohash_helper.c`.
+//!
+//! A search only scans the cell containing center of the search area,
+//! and its 8 neighbours, with cell size estimated from search radius.
+
+use crate::cmd::geo::SearchShape;
+use crate::mem::geo::geohash::{
+    self, GeoHashArea, GeoHashBits, EARTH_RADIUS_IN_METERS, GEO_STEP_MAX,
+};
+
+/// Max distance in meters of the mercator projection.
+const MERCATOR_MAX: f64 = 20_037_726.37;
+
+/// Search area with center point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoShape {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub shape: SearchShape,
+    /// Number of meters in unit of shape.
+    pub conversion: f64,
+}
+
+impl GeoShape {
+    /// Returns distance in meters to the point if it is inside of this area.
+    #[must_use]
+    pub fn distance_if_inside(&self, longitude: f64, latitude: f64) -> Option<f64> {
+        match self.shape {
+            SearchShape::Radius(radius) => {
+                let distance =
+                    geohash::distance(self.longitude, self.latitude, longitude, latitude);
+                (distance <= radius * self.conversion).then_some(distance)
+            }
+            SearchShape::Box(width, height) => {
+                // Latitude distance is less expensive to compute than longitude distance.
+                let lat_distance = geohash::lat_distance(latitude, self.latitude);
+                if lat_distance > height * self.conversion / 2.0 {
+                    return None;
+                }
+                let long_distance =
+                    geohash::distance(longitude, self.latitude, self.longitude, self.latitude);
+                if long_distance > width * self.conversion / 2.0 {
+                    return None;
+                }
+                Some(geohash::distance(
+                    self.longitude,
+                    self.latitude,
+                    longitude,
+                    latitude,
+                ))
+            }
+        }
+    }
+
+    /// Returns `(min_long, min_lat, max_long, max_lat)` of the bounding box.
+    fn bounding_box(&self) -> (f64, f64, f64, f64) {
+        let (width, height) = match self.shape {
+            SearchShape::Radius(radius) => (radius, radius),
+            SearchShape::Box(width, height) => (width / 2.0, height / 2.0),
+        };
+        let width = self.conversion * width;
+        let height = self.conversion * height;
+
+        let lat_delta = rad_deg(height / EARTH_RADIUS_IN_METERS);
+        let long_delta_top =
+            rad_deg(width / EARTH_RADIUS_IN_METERS / deg_rad(self.latitude + lat_delta).cos());
+        let long_delta_bottom =
+            rad_deg(width / EARTH_RADIUS_IN_METERS / deg_rad(self.latitude - lat_delta).cos());
+        // The directions of the northern and southern hemispheres are opposite,
+        // so different points are chosen as min/max longitude.
+        let long_delta = if self.latitude < 0.0 {
+            long_delta_bottom
+        } else {
+            long_delta_top
+        };
+        (
+            self.longitude - long_delta,
+            self.latitude - lat_delta,
+            self.longitude + long_delta,
+            self.latitude + lat_delta,
+        )
+    }
+
+    /// Returns distance in meters from center to the farthest point of the area.
+    #[allow(clippy::imprecise_flops)]
+    fn radius_meters(&self) -> f64 {
+        let radius = match self.shape {
+            SearchShape::Radius(radius) => radius,
+            SearchShape::Box(width, height) => {
+                ((width / 2.0) * (width / 2.0) + (height / 2.0) * (height / 2.0)).sqrt()
+            }
+        };
+        radius * self.conversion
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct GeoHashNeighbors {
+    pub north: GeoHashBits,
+    pub east: GeoHashBits,
+    pub west: GeoHashBits,
+    pub south: GeoHashBits,
+    pub north_east: GeoHashBits,
+    pub south_east: GeoHashBits,
+    pub north_west: GeoHashBits,
+    pub south_west: GeoHashBits,
+}
+
+impl GeoHashNeighbors {
+    #[must_use]
+    pub const fn new(hash: GeoHashBits) -> Self {
+        Self {
+            north: move_xy(hash, 0, 1),
+            east: move_xy(hash, 1, 0),
+            west: move_xy(hash, -1, 0),
+            south: move_xy(hash, 0, -1),
+            north_east: move_xy(hash, 1, 1),
+            south_east: move_xy(hash, 1, -1),
+            north_west: move_xy(hash, -1, 1),
+            south_west: move_xy(hash, -1, -1),
+        }
+    }
+}
+
+/// Cells to scan for a search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoHashRadius {
+    pub hash: GeoHashBits,
+    pub area: GeoHashArea,
+    pub neighbors: GeoHashNeighbors,
+}
+
+impl GeoHashRadius {
+    /// Find the cell containing center of `shape`, and its neighbours which overlap with
+    /// bounding box of the shape.
+    #[must_use]
+    pub fn new(shape: &GeoShape) -> Self {
+        let (min_long, min_lat, max_long, max_lat) = shape.bounding_box();
+        let mut step = estimate_steps_by_radius(shape.radius_meters(), shape.latitude);
+        let mut radius = Self::with_step(shape, step);
+
+        // Check if the step is enough at the limits of the covered area.
+        // Sometimes when the search area is near an edge of the area, the estimated step
+        // is not small enough, since one of the north / south / west / east square
+        // is too near to the search area to cover everything.
+        let north = geohash::decode_wgs84(radius.neighbors.north);
+        let south = geohash::decode_wgs84(radius.neighbors.south);
+        let east = geohash::decode_wgs84(radius.neighbors.east);
+        let west = geohash::decode_wgs84(radius.neighbors.west);
+        let decrease_step = north.latitude.max < max_lat
+            || south.latitude.min > min_lat
+            || east.longitude.max < max_long
+            || west.longitude.min > min_long;
+        if step > 1 && decrease_step {
+            step -= 1;
+            radius = Self::with_step(shape, step);
+        }
+
+        // Exclude the search areas that are useless.
+        if step >= 2 {
+            let zero = GeoHashBits::default();
+            let area = radius.area;
+            let neighbors = &mut radius.neighbors;
+            if area.latitude.min < min_lat {
+                neighbors.south = zero;
+                neighbors.south_west = zero;
+                neighbors.south_east = zero;
+            }
+            if area.latitude.max > max_lat {
+                neighbors.north = zero;
+                neighbors.north_east = zero;
+                neighbors.north_west = zero;
+            }
+            if area.longitude.min < min_long {
+                neighbors.west = zero;
+                neighbors.south_west = zero;
+                neighbors.north_west = zero;
+            }
+            if area.longitude.max > max_long {
+                neighbors.east = zero;
+                neighbors.south_east = zero;
+                neighbors.north_east = zero;
+            }
+        }
+        radius
+    }
+
+    fn with_step(shape: &GeoShape, step: u8) -> Self {
+        let hash = geohash::encode_wgs84(shape.longitude, shape.latitude, step).unwrap_or_default();
+        Self {
+            hash,
+            area: geohash::decode_wgs84(hash),
+            neighbors: GeoHashNeighbors::new(hash),
+        }
+    }
+
+    /// Returns center cell and its neighbours, in the same order as redis.
+    #[must_use]
+    pub const fn cells(&self) -> [GeoHashBits; 9] {
+        let n = &self.neighbors;
+        [
+            self.hash,
+            n.north,
+            n.south,
+            n.east,
+            n.west,
+            n.north_east,
+            n.north_west,
+            n.south_east,
+            n.south_west,
+        ]
+    }
+}
+
+/// Returns `[min, max)` scores of members inside of the cell.
+#[must_use]
+pub const fn cell_score_range(hash: GeoHashBits) -> (u64, u64) {
+    let shift = (GEO_STEP_MAX - hash.step) * 2;
+    (hash.bits << shift, (hash.bits + 1) << shift)
+}
+
+/// Estimate precision of geohash cells, so that a cell is large enough to cover the radius.
+#[must_use]
+#[allow(clippy::while_float)]
+pub fn estimate_steps_by_radius(mut range_meters: f64, latitude: f64) -> u8 {
+    if range_meters == 0.0 {
+        return GEO_STEP_MAX;
+    }
+    let mut step: i32 = 1;
+    while range_meters < MERCATOR_MAX {
+        range_meters *= 2.0;
+        step += 1;
+    }
+    // Make sure range is included in most of the base cases.
+    step -= 2;
+
+    // Wider range towards the poles.
+    if !(-66.0..=66.0).contains(&latitude) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&latitude) {
+            step -= 1;
+        }
+    }
+
+    u8::try_from(step.clamp(1, i32::from(GEO_STEP_MAX))).unwrap_or(GEO_STEP_MAX)
+}
+
+/// Move cell along longitude (x) and latitude (y) axes.
+///
+/// Longitude bits are odd bits and latitude bits are even bits.
+const fn move_xy(hash: GeoHashBits, dx: i8, dy: i8) -> GeoHashBits {
+    const ODD_BITS: u64 = 0xaaaa_aaaa_aaaa_aaaa;
+    const EVEN_BITS: u64 = 0x5555_5555_5555_5555;
+    let shift = 64 - hash.step as u32 * 2;
+    let x = move_bits(
+        hash.bits & ODD_BITS,
+        EVEN_BITS >> shift,
+        ODD_BITS >> shift,
+        dx,
+    );
+    let y = move_bits(
+        hash.bits & EVEN_BITS,
+        ODD_BITS >> shift,
+        EVEN_BITS >> shift,
+        dy,
+    );
+    GeoHashBits {
+        bits: x | y,
+        step: hash.step,
+    }
+}
+
+/// Add or subtract one to interleaved bits, other bits are filled with ones to
+/// propagate carries.
+const fn move_bits(bits: u64, other_mask: u64, mask: u64, delta: i8) -> u64 {
+    if delta == 0 {
+        return bits;
+    }
+    let bits = if delta > 0 {
+        bits.wrapping_add(other_mask + 1)
+    } else {
+        (bits | other_mask).wrapping_sub(other_mask + 1)
+    };
+    bits & mask
+}
+
+#[must_use]
+#[inline]
+fn deg_rad(degree: f64) -> f64 {
+    degree * (std::f64::consts::PI / 180.0)
+}
+
+#[must_use]
+#[inline]
+fn rad_deg(radian: f64) -> f64 {
+    radian / (std::f64::consts::PI / 180.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::SearchShape;
+    use crate::mem::geo::geohash::{self, GeoHashBits};
+    use crate::mem::geo::geohash_helper::{
+        cell_score_range, estimate_steps_by_radius, move_xy, GeoHashRadius, GeoShape,
+    };
+
+    #[test]
+    fn test_estimate_steps() {
+        assert_eq!(estimate_steps_by_radius(0.0, 0.0), 26);
+        assert_eq!(estimate_steps_by_radius(200_000.0, 38.0), 6);
+        assert_eq!(estimate_steps_by_radius(200_000.0, 70.0), 5);
+        assert_eq!(estimate_steps_by_radius(1.0, 0.0), 24);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let hash = GeoHashBits {
+            bits: 0b11,
+            step: 1,
+        };
+        assert_eq!(move_xy(hash, -1, 0).bits, 0b01);
+        assert_eq!(move_xy(hash, 0, -1).bits, 0b10);
+        assert_eq!(move_xy(hash, 1, 1).bits, 0b00);
+        assert_eq!(cell_score_range(hash), (3 << 50, 4 << 50));
+    }
+
+    #[test]
+    fn test_radius() {
+        let shape = GeoShape {
+            longitude: 15.0,
+            latitude: 37.0,
+            shape: SearchShape::Radius(200.0),
+            conversion: 1000.0,
+        };
+        let radius = GeoHashRadius::new(&shape);
+        let (long1, lat1) = geohash::from_score(geohash::to_score(13.361_389, 38.115_556).unwrap());
+        let score = geohash::to_score(long1, lat1).unwrap();
+        assert!(radius.cells().iter().any(|&cell| {
+            let (min, max) = cell_score_range(cell);
+            #[allow(clippy::cast_precision_loss)]
+            let (min, max) = (min as f64, max as f64);
+            (min..max).contains(&score)
+        }));
+        assert!(shape.distance_if_inside(long1, lat1).is_some());
+        assert!(shape.distance_if_inside(long1 + 10.0, lat1).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::geo::geohash;
+
+/// Return valid Geohash strings representing the position of one or more elements
+/// in a sorted set value representing a geospatial index.
+///
+/// Geohash strings are 11 characters long, and are compatible with the standard
+/// geohash representation, which uses latitude range of [-90, 90].
+///
+/// Reply:
+/// - Array reply: An array where each element is the Geohash corresponding to
+///   each member name passed as argument to the command.
+pub fn hash(db: &Db, key: &str, members: &[Vec<u8>]) -> ReplyFrame {
+    let zset = match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => Some(zset),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => None,
+    };
+
+    let arr = members
+        .iter()
+        .map(|member| {
+            zset.and_then(|zset| zset.score(member))
+                .map_or(ReplyFrame::Null, |score| {
+                    let (longitude, latitude) = geohash::from_score(score);
+                    ReplyFrame::Bulk(geohash::to_geohash_string(longitude, latitude).into_bytes())
+                })
+        })
+        .collect();
+    ReplyFrame::Array(arr)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::GeoPoint;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+    use crate::mem::geo::hash::hash;
+
+    #[test]
+    fn test_hash() {
+        let mut db = Db::new();
+        let key = "Sicily".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                GeoPoint {
+                    longitude: 13.361_389,
+                    latitude: 38.115_556,
+                    member: b"Palermo".to_vec(),
+                },
+                GeoPoint {
+                    longitude: 15.087_269,
+                    latitude: 37.502_669,
+                    member: b"Catania".to_vec(),
+                },
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = hash(
+            &db,
+            &key,
+            &[b"Palermo".to_vec(), b"Catania".to_vec(), b"Rome".to_vec()],
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"sqc8b49rny0".to_vec()),
+                ReplyFrame::Bulk(b"sqdtr74hyu0".to_vec()),
+                ReplyFrame::Null,
+            ])
+        );
+    }
+}
@@ -1,3 +1,46 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Geospatial indexes, stored as sorted sets with 52-bit geohash as scores.
+
+use crate::cmd::geo::GeoCommand;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::Mem;
+
+pub mod add;
+pub mod dist;
+pub mod geohash;
+pub mod geohash_helper;
+pub mod hash;
+pub mod pos;
+pub mod search;
+pub mod search_store;
+
+impl Mem {
+    pub fn handle_geo_command(&mut self, command: GeoCommand) -> ReplyFrame {
+        match command {
+            GeoCommand::Add(key, options, points) => add::add(&mut self.db, key, options, points),
+            GeoCommand::Dist(key, member1, member2, unit) => {
+                dist::dist(&self.db, &key, &member1, &member2, unit)
+            }
+            GeoCommand::Hash(key, members) => hash::hash(&self.db, &key, &members),
+            GeoCommand::Pos(key, members) => pos::pos(&self.db, &key, &members),
+            GeoCommand::Search(key, options) => search::search(&self.db, &key, &options),
+            GeoCommand::SearchStore(destination, key, options, store_dist) => {
+                search_store::search_store(&mut self.db, destination, &key, &options, store_dist)
+            }
+        }
+    }
+}
+
+/// Format coordinate like redis `addReplyHumanLongDouble()` with 17 digits precision.
+#[must_use]
+pub fn format_coordinate(value: f64) -> String {
+    let mut s = format!("{value:.17}");
+    if s.contains('.') {
+        let len = s.trim_end_matches('0').trim_end_matches('.').len();
+        s.truncate(len);
+    }
+    s
+}
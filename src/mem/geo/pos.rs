@@ -0,0 +1,77 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::geo::{format_coordinate, geohash};
+
+/// Return the positions (longitude,latitude) of all the specified members
+/// of the geospatial index represented by the sorted set at key.
+///
+/// Reply:
+/// - Array reply: An array where each element is a two elements array representing
+///   longitude and latitude (x,y) of each member name passed as argument to the command.
+///   Non-existing elements are reported as Nil reply elements of the array.
+pub fn pos(db: &Db, key: &str, members: &[Vec<u8>]) -> ReplyFrame {
+    let zset = match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => Some(zset),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => None,
+    };
+
+    let arr = members
+        .iter()
+        .map(|member| {
+            zset.and_then(|zset| zset.score(member))
+                .map_or(ReplyFrame::Null, |score| {
+                    let (longitude, latitude) = geohash::from_score(score);
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(format_coordinate(longitude).into_bytes()),
+                        ReplyFrame::Bulk(format_coordinate(latitude).into_bytes()),
+                    ])
+                })
+        })
+        .collect();
+    ReplyFrame::Array(arr)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::GeoPoint;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+    use crate::mem::geo::pos::pos;
+
+    #[test]
+    fn test_pos() {
+        let mut db = Db::new();
+        let key = "Sicily".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![GeoPoint {
+                longitude: 13.361_389,
+                latitude: 38.115_556,
+                member: b"Palermo".to_vec(),
+            }],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(1));
+        let reply = pos(&db, &key, &[b"Palermo".to_vec(), b"NonExisting".to_vec()]);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"13.36138933897018433".to_vec()),
+                    ReplyFrame::Bulk(b"38.11555639549629859".to_vec()),
+                ]),
+                ReplyFrame::Null,
+            ])
+        );
+        let reply = pos(&db, "NoKey", &[b"Palermo".to_vec()]);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::Null]));
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::geo::{SearchOptions, SearchOrigin, SortOrder};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::ScoreBound;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::geo::dist::format_distance;
+use crate::mem::geo::geohash_helper::{cell_score_range, GeoHashRadius, GeoShape};
+use crate::mem::geo::{format_coordinate, geohash};
+use crate::mem::zset::SortedSetObject;
+
+/// A member found in search area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPoint<'a> {
+    pub member: &'a [u8],
+    pub score: f64,
+    pub longitude: f64,
+    pub latitude: f64,
+    /// Distance to center of search area, in unit of search.
+    pub distance: f64,
+}
+
+/// Return the members of a sorted set populated with geospatial information using `GEOADD`,
+/// which are within the borders of the area specified by a given shape.
+///
+/// The query's center point is provided by one of these mandatory options:
+/// - FROMMEMBER: Use the position of the given existing member in the sorted set.
+/// - FROMLONLAT: Use the given longitude and latitude position.
+///
+/// The query's shape is provided by one of these mandatory options:
+/// - BYRADIUS: Similar to `GEORADIUS`, search inside circular area according to given radius.
+/// - BYBOX: Search inside an axis-aligned rectangle, determined by height and width.
+///
+/// Matching items are returned unsorted by default, use ASC or DESC to sort them
+/// by distance. COUNT limits number of returned items, with ANY option the command
+/// returns as soon as enough matches are found, so results may not be the closest ones.
+///
+/// Reply, one of the following:
+/// - If no WITH* option is specified, an Array reply of matched member names
+/// - If WITHCOORD, WITHDIST, or WITHHASH options are specified, the command returns
+///   an Array reply of arrays, where each sub-array represents a single item:
+///   - The distance from the center as a floating point number,
+///     in the same unit specified in the radius.
+///   - The Geohash integer.
+///   - The coordinates as a two items x,y array (longitude,latitude).
+pub fn search(db: &Db, key: &str, options: &SearchOptions) -> ReplyFrame {
+    let zset = match get_sorted_set(db, key, options) {
+        Ok(Some(zset)) => zset,
+        Ok(None) => return ReplyFrame::Array(Vec::new()),
+        Err(err) => return err,
+    };
+    let points = match search_points(zset, options) {
+        Ok(points) => points,
+        Err(err) => return err,
+    };
+
+    let has_extra = options.with_dist || options.with_hash || options.with_coord;
+    let arr = points
+        .into_iter()
+        .map(|point| {
+            let member = ReplyFrame::Bulk(point.member.to_vec());
+            if !has_extra {
+                return member;
+            }
+            let mut item = vec![member];
+            if options.with_dist {
+                item.push(ReplyFrame::Bulk(
+                    format_distance(point.distance).into_bytes(),
+                ));
+            }
+            if options.with_hash {
+                #[allow(clippy::cast_possible_truncation)]
+                item.push(ReplyFrame::I64(point.score as i64));
+            }
+            if options.with_coord {
+                item.push(ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(format_coordinate(point.longitude).into_bytes()),
+                    ReplyFrame::Bulk(format_coordinate(point.latitude).into_bytes()),
+                ]));
+            }
+            ReplyFrame::Array(item)
+        })
+        .collect();
+    ReplyFrame::Array(arr)
+}
+
+/// Returns sorted set at `key`, after checking that center point is valid.
+pub fn get_sorted_set<'a>(
+    db: &'a Db,
+    key: &str,
+    options: &SearchOptions,
+) -> Result<Option<&'a SortedSetObject>, ReplyFrame> {
+    if let SearchOrigin::LonLat(longitude, latitude) = options.origin {
+        if !geohash::is_valid_coordinate(longitude, latitude) {
+            return Err(ReplyFrame::Error(format!(
+                "ERR invalid longitude,latitude pair {longitude:.6},{latitude:.6}"
+            )));
+        }
+    }
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => Ok(Some(zset)),
+        Some(_) => Err(ReplyFrame::wrong_type_err()),
+        None => Ok(None),
+    }
+}
+
+/// Find members inside of search area, only cells around center point are scanned.
+///
+/// Points are sorted and truncated as specified in `options`.
+pub fn search_points<'a>(
+    zset: &'a SortedSetObject,
+    options: &SearchOptions,
+) -> Result<Vec<SearchPoint<'a>>, ReplyFrame> {
+    let (longitude, latitude) = match &options.origin {
+        SearchOrigin::Member(member) => match zset.score(member) {
+            Some(score) => geohash::from_score(score),
+            None => {
+                return Err(ReplyFrame::ConstError(
+                    "ERR could not decode requested zset member",
+                ))
+            }
+        },
+        SearchOrigin::LonLat(longitude, latitude) => (*longitude, *latitude),
+    };
+    let shape = GeoShape {
+        longitude,
+        latitude,
+        shape: options.shape,
+        conversion: options.unit.to_meters(),
+    };
+
+    // With ANY option, stop as soon as enough matches are found.
+    let limit = if options.any { options.count } else { None };
+    let mut points = Vec::new();
+    let radius = GeoHashRadius::new(&shape);
+    let cells = radius.cells();
+    let mut last_processed = 0;
+    for (index, cell) in cells.iter().enumerate() {
+        if cell.bits == 0 && cell.step == 0 {
+            continue;
+        }
+        // When a huge radius is used, adjacent neighbours can be the same,
+        // skip the cell which is the same as the one processed previously.
+        if last_processed != 0 && *cell == cells[last_processed] {
+            continue;
+        }
+        if limit.is_some_and(|limit| points.len() >= limit) {
+            break;
+        }
+        points_in_cell(zset, &shape, *cell, limit, &mut points);
+        last_processed = index;
+    }
+
+    let order = match options.order {
+        // COUNT without ordering returns the nearest items.
+        None if options.count.is_some() && !options.any => Some(SortOrder::Asc),
+        order => order,
+    };
+    match order {
+        Some(SortOrder::Asc) => points.sort_by(|a, b| a.distance.total_cmp(&b.distance)),
+        Some(SortOrder::Desc) => points.sort_by(|a, b| b.distance.total_cmp(&a.distance)),
+        None => (),
+    }
+    if let Some(count) = options.count {
+        points.truncate(count);
+    }
+    for point in &mut points {
+        point.distance /= shape.conversion;
+    }
+    Ok(points)
+}
+
+/// Append members in `cell` which are inside of `shape`.
+#[allow(clippy::cast_precision_loss)]
+fn points_in_cell<'a>(
+    zset: &'a SortedSetObject,
+    shape: &GeoShape,
+    cell: geohash::GeoHashBits,
+    limit: Option<usize>,
+    points: &mut Vec<SearchPoint<'a>>,
+) {
+    let (min, max) = cell_score_range(cell);
+    let min = ScoreBound::Inclusive(min as f64);
+    let max = ScoreBound::Exclusive(max as f64);
+    for (member, score) in zset.range_by_score(min, max, false) {
+        let (longitude, latitude) = geohash::from_score(score);
+        let Some(distance) = shape.distance_if_inside(longitude, latitude) else {
+            continue;
+        };
+        points.push(SearchPoint {
+            member,
+            score,
+            longitude,
+            latitude,
+            distance,
+        });
+        if limit.is_some_and(|limit| points.len() >= limit) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::{
+        DistanceUnit, GeoPoint, SearchOptions, SearchOrigin, SearchShape, SortOrder,
+    };
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+    use crate::mem::geo::search::search;
+
+    fn bulk(s: &str) -> ReplyFrame {
+        ReplyFrame::Bulk(s.as_bytes().to_vec())
+    }
+
+    fn sicily() -> Db {
+        let mut db = Db::new();
+        let points = [
+            (13.361_389, 38.115_556, "Palermo"),
+            (15.087_269, 37.502_669, "Catania"),
+            (12.758_489, 38.788_135, "edge1"),
+            (17.241_510, 38.788_135, "edge2"),
+        ];
+        let reply = add(
+            &mut db,
+            "Sicily".to_owned(),
+            AddOptions::default(),
+            points
+                .iter()
+                .map(|&(longitude, latitude, member)| GeoPoint {
+                    longitude,
+                    latitude,
+                    member: member.as_bytes().to_vec(),
+                })
+                .collect(),
+        );
+        assert_eq!(reply, ReplyFrame::Usize(4));
+        db
+    }
+
+    fn options(origin: SearchOrigin, shape: SearchShape) -> SearchOptions {
+        SearchOptions {
+            origin,
+            shape,
+            unit: DistanceUnit::Kilometers,
+            order: Some(SortOrder::Asc),
+            count: None,
+            any: false,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let db = sicily();
+        let opts = options(SearchOrigin::LonLat(15.0, 37.0), SearchShape::Radius(200.0));
+        let reply = search(&db, "Sicily", &opts);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![bulk("Catania"), bulk("Palermo")])
+        );
+
+        let mut opts = options(
+            SearchOrigin::LonLat(15.0, 37.0),
+            SearchShape::Box(400.0, 400.0),
+        );
+        opts.with_dist = true;
+        opts.with_coord = true;
+        let reply = search(&db, "Sicily", &opts);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    bulk("Catania"),
+                    bulk("56.4413"),
+                    ReplyFrame::Array(vec![
+                        bulk("15.08726745843887329"),
+                        bulk("37.50266842333162032"),
+                    ]),
+                ]),
+                ReplyFrame::Array(vec![
+                    bulk("Palermo"),
+                    bulk("190.4424"),
+                    ReplyFrame::Array(vec![
+                        bulk("13.36138933897018433"),
+                        bulk("38.11555639549629859"),
+                    ]),
+                ]),
+                ReplyFrame::Array(vec![
+                    bulk("edge2"),
+                    bulk("279.7403"),
+                    ReplyFrame::Array(vec![
+                        bulk("17.24151045083999634"),
+                        bulk("38.78813451624225195"),
+                    ]),
+                ]),
+                ReplyFrame::Array(vec![
+                    bulk("edge1"),
+                    bulk("279.7405"),
+                    ReplyFrame::Array(vec![
+                        bulk("12.7584877610206604"),
+                        bulk("38.78813451624225195"),
+                    ]),
+                ]),
+            ])
+        );
+
+        let mut opts = options(
+            SearchOrigin::Member(b"Palermo".to_vec()),
+            SearchShape::Radius(200.0),
+        );
+        opts.order = Some(SortOrder::Desc);
+        opts.count = Some(1);
+        let reply = search(&db, "Sicily", &opts);
+        assert_eq!(reply, ReplyFrame::Array(vec![bulk("Catania")]));
+
+        let opts = options(
+            SearchOrigin::Member(b"Rome".to_vec()),
+            SearchShape::Radius(200.0),
+        );
+        let reply = search(&db, "Sicily", &opts);
+        assert!(matches!(reply, ReplyFrame::ConstError(_)));
+        let reply = search(&db, "Italy", &opts);
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+    }
+}
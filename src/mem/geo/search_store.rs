@@ -0,0 +1,112 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::geo::SearchOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::geo::search::{get_sorted_set, search_points};
+use crate::mem::zset;
+
+/// This command is like `GEOSEARCH`, but stores the result in destination key.
+///
+/// By default, it stores the results in the destination sorted set with their
+/// geospatial information.
+///
+/// When using the STOREDIST option, the command stores the items in a sorted set
+/// populated with their distance from the center of the circle or box,
+/// as a floating-point number, in the same unit specified for that shape.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting set
+pub fn search_store(
+    db: &mut Db,
+    destination: String,
+    key: &str,
+    options: &SearchOptions,
+    store_dist: bool,
+) -> ReplyFrame {
+    let zset = match get_sorted_set(db, key, options) {
+        Ok(Some(zset)) => zset,
+        Ok(None) => return zset::store(db, destination, Vec::new()),
+        Err(err) => return err,
+    };
+    let elements = match search_points(zset, options) {
+        Ok(points) => points
+            .into_iter()
+            .map(|point| {
+                let score = if store_dist {
+                    point.distance
+                } else {
+                    point.score
+                };
+                (point.member.to_vec(), score)
+            })
+            .collect(),
+        Err(err) => return err,
+    };
+    zset::store(db, destination, elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::geo::{
+        DistanceUnit, GeoPoint, SearchOptions, SearchOrigin, SearchShape, SortOrder,
+    };
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::geo::add::add;
+    use crate::mem::geo::search_store::search_store;
+    use crate::mem::zset::score::score;
+
+    #[test]
+    fn test_search_store() {
+        let mut db = Db::new();
+        let reply = add(
+            &mut db,
+            "Sicily".to_owned(),
+            AddOptions::default(),
+            vec![
+                GeoPoint {
+                    longitude: 13.361_389,
+                    latitude: 38.115_556,
+                    member: b"Palermo".to_vec(),
+                },
+                GeoPoint {
+                    longitude: 15.087_269,
+                    latitude: 37.502_669,
+                    member: b"Catania".to_vec(),
+                },
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let options = SearchOptions {
+            origin: SearchOrigin::LonLat(15.0, 37.0),
+            shape: SearchShape::Radius(200.0),
+            unit: DistanceUnit::Kilometers,
+            order: Some(SortOrder::Asc),
+            count: Some(1),
+            any: false,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        };
+        let reply = search_store(&mut db, "dest".to_owned(), "Sicily", &options, false);
+        assert_eq!(reply, ReplyFrame::Usize(1));
+        let reply = score(&db, "dest", b"Catania");
+        assert_eq!(reply, ReplyFrame::Double(3_479_447_370_796_909.0));
+
+        let reply = search_store(&mut db, "dest".to_owned(), "Sicily", &options, true);
+        assert_eq!(reply, ReplyFrame::Usize(1));
+        let ReplyFrame::Double(distance) = score(&db, "dest", b"Catania") else {
+            panic!("expected double reply");
+        };
+        assert!((distance - 56.441_257).abs() < 1e-6);
+
+        let reply = search_store(&mut db, "dest".to_owned(), "Italy", &options, false);
+        assert_eq!(reply, ReplyFrame::zero());
+        assert_eq!(score(&db, "dest", b"Catania"), ReplyFrame::Null);
+    }
+}
@@ -0,0 +1,7 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const HASH_VALUE_NOT_INTEGER_ERR: &str = "ERR hash value is not an integer";
+pub const HASH_VALUE_NOT_FLOAT_ERR: &str = "ERR hash value is not a float";
+pub const INVALID_EXPIRE_TIME_ERR: &str = "ERR invalid expire time, must be >= 0";
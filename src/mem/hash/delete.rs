@@ -4,10 +4,12 @@
 
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::remove_if_empty;
 
 /// Removes the specified fields from the hash stored at key.
 ///
 /// Specified fields that do not exist within this hash are ignored.
+/// Deletes the hash if no fields remain.
 /// If key does not exist, it is treated as an empty hash and this command returns 0.
 ///
 /// Reply:
@@ -22,7 +24,7 @@ pub fn delete(db: &mut Db, key: &str, fields: &[String]) -> ReplyFrame {
                     count += 1;
                 }
             }
-
+            remove_if_empty(db, key);
             ReplyFrame::Usize(count)
         }
         Some(_) => ReplyFrame::wrong_type_err(),
@@ -49,6 +51,7 @@ mod tests {
         assert_eq!(reply, ReplyFrame::one());
         let reply = delete(&mut db, &key, &["field1".to_owned()]);
         assert_eq!(reply, ReplyFrame::one());
+        assert!(!db.contains_key(&key));
         let reply = delete(&mut db, &key, &["field2".to_owned()]);
         assert_eq!(reply, ReplyFrame::zero());
     }
@@ -0,0 +1,191 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::hash::{ExpireCondition, ExpireTime};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::consts::INVALID_EXPIRE_TIME_ERR;
+use crate::mem::hash::{remove_if_empty, HashObject};
+
+/// Set an expiration (TTL or time to live) on one or more fields of a given hash key.
+///
+/// You must specify at least one field. Field(s) will automatically be deleted
+/// from the hash key when their TTLs expire.
+///
+/// Field expirations will only be cleared by commands that delete or overwrite
+/// the contents of the hash fields, including HDEL and HSET commands.
+///
+/// Setting a deadline which is in the past, deletes the field immediately.
+/// The hash key is deleted if no fields remain.
+///
+/// Reply, one of the following:
+/// - Array reply: for each field
+///   - Integer reply: -2 if no such field exists in the provided hash key,
+///     or the provided key does not exist.
+///   - Integer reply: 0 if the specified NX | XX | GT | LT condition has not been met.
+///   - Integer reply: 1 if the expiration time was set/updated.
+///   - Integer reply: 2 when the command is called with 0 seconds, or the deadline is in the past.
+/// - Simple error reply: if parsed time is negative or overflows.
+pub fn expire(
+    db: &mut Db,
+    key: &str,
+    expire_time: ExpireTime,
+    condition: Option<ExpireCondition>,
+    fields: &[String],
+    now: i64,
+) -> ReplyFrame {
+    let Some(deadline) = expire_time.deadline(now) else {
+        return ReplyFrame::ConstError(INVALID_EXPIRE_TIME_ERR);
+    };
+
+    let reply = match db.get_mut(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let array = fields
+                .iter()
+                .map(|field| expire_field(old_hash, field, deadline, condition, now))
+                .collect();
+            ReplyFrame::Array(array)
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Array(vec![ReplyFrame::I64(-2); fields.len()]),
+    };
+    remove_if_empty(db, key);
+    reply
+}
+
+fn expire_field(
+    hash: &mut HashObject,
+    field: &str,
+    deadline: i64,
+    condition: Option<ExpireCondition>,
+    now: i64,
+) -> ReplyFrame {
+    if !hash.contains_key(field) {
+        return ReplyFrame::I64(-2);
+    }
+
+    // Field without a deadline is treated as never expire.
+    let old_deadline = hash.expire_at(field);
+    let matched = match condition {
+        None => true,
+        Some(ExpireCondition::NotExist) => old_deadline.is_none(),
+        Some(ExpireCondition::Exist) => old_deadline.is_some(),
+        Some(ExpireCondition::GreaterThan) => {
+            old_deadline.is_some_and(|old_deadline| deadline > old_deadline)
+        }
+        Some(ExpireCondition::LessThan) => {
+            old_deadline.map_or(true, |old_deadline| deadline < old_deadline)
+        }
+    };
+    if !matched {
+        return ReplyFrame::I64(0);
+    }
+
+    if deadline <= now {
+        hash.remove(field);
+        ReplyFrame::I64(2)
+    } else {
+        hash.set_expire_at(field, deadline);
+        ReplyFrame::I64(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hash::{ExpireCondition, ExpireTime};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::consts::INVALID_EXPIRE_TIME_ERR;
+    use crate::mem::hash::expire::expire;
+    use crate::mem::hash::set::set;
+    use crate::mem::hash::ttl::ttl;
+
+    #[test]
+    fn test_expire() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let now = 1_000_000;
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"hello".to_vec()),
+                ("field2".to_owned(), b"world".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let fields = [
+            "field1".to_owned(),
+            "field2".to_owned(),
+            "field3".to_owned(),
+        ];
+        let reply = expire(&mut db, &key, ExpireTime::Seconds(5), None, &fields, now);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(1),
+                ReplyFrame::I64(1),
+                ReplyFrame::I64(-2),
+            ])
+        );
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::Seconds(3),
+            Some(ExpireCondition::GreaterThan),
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(0)]));
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::Milliseconds(3000),
+            Some(ExpireCondition::LessThan),
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(1)]));
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::Seconds(10),
+            Some(ExpireCondition::NotExist),
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(0)]));
+        let reply = ttl(&db, &key, &fields, now);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(3),
+                ReplyFrame::I64(5),
+                ReplyFrame::I64(-2),
+            ])
+        );
+
+        let reply = expire(&mut db, &key, ExpireTime::Seconds(-1), None, &fields, now);
+        assert_eq!(reply, ReplyFrame::ConstError(INVALID_EXPIRE_TIME_ERR));
+
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::UnixSeconds(1),
+            None,
+            &fields,
+            now,
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(2),
+                ReplyFrame::I64(2),
+                ReplyFrame::I64(-2),
+            ])
+        );
+        assert!(db.is_empty());
+    }
+}
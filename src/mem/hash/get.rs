@@ -14,7 +14,7 @@ pub fn get(db: &Db, key: &str, field: &str) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::Hash(old_hash)) => old_hash
             .get(field)
-            .cloned()
+            .map(<[u8]>::to_vec)
             .map_or_else(ReplyFrame::null, ReplyFrame::bulk),
         Some(_) => ReplyFrame::wrong_type_err(),
         None => ReplyFrame::Null,
@@ -0,0 +1,75 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::remove_if_empty;
+
+/// Get and delete the value of one or more fields of a given hash key.
+///
+/// When the last field is deleted, the key will also be deleted.
+///
+/// Reply:
+/// - Array reply: a list of deleted fields and their values or nil for fields that do not exist.
+pub fn get_del(db: &mut Db, key: &str, fields: &[String]) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let array = fields
+                .iter()
+                .map(|field| {
+                    old_hash
+                        .remove(field)
+                        .map_or_else(ReplyFrame::null, ReplyFrame::bulk)
+                })
+                .collect();
+            remove_if_empty(db, key);
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::Null; fields.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::get_del::get_del;
+    use crate::mem::hash::len::len;
+    use crate::mem::hash::set::set;
+
+    #[test]
+    fn test_get_del() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"foo".to_vec()),
+                ("field2".to_owned(), b"bar".to_vec()),
+                ("field3".to_owned(), b"baz".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+
+        let reply = get_del(&mut db, &key, &["field2".to_owned(), "nofield".to_owned()]);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(b"bar".to_vec()), ReplyFrame::Null])
+        );
+        let reply = len(&db, &key);
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let reply = get_del(&mut db, &key, &["field3".to_owned(), "field1".to_owned()]);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"baz".to_vec()),
+                ReplyFrame::Bulk(b"foo".to_vec()),
+            ])
+        );
+        assert!(!db.contains_key(&key));
+    }
+}
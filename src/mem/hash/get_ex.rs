@@ -0,0 +1,154 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::hash::GetExOption;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::consts::INVALID_EXPIRE_TIME_ERR;
+use crate::mem::hash::remove_if_empty;
+
+/// Get the value of one or more fields of a given hash key, and optionally
+/// set their expiration time or time-to-live (TTL).
+///
+/// Options:
+/// - EX seconds: Set the specified expiration time, in seconds.
+/// - PX milliseconds: Set the specified expiration time, in milliseconds.
+/// - EXAT unix-time-seconds: Set the specified Unix time in seconds at which the fields will expire.
+/// - PXAT unix-time-milliseconds: Set the specified Unix time in milliseconds
+///   at which the fields will expire.
+/// - PERSIST: Remove the TTL associated with the fields.
+///
+/// Fields with a deadline in the past are deleted after their values are returned.
+///
+/// Reply, one of the following:
+/// - Array reply: a list of values associated with the given fields,
+///   in the same order as they are requested.
+/// - Simple error reply: if parsed time is negative or overflows.
+pub fn get_ex(
+    db: &mut Db,
+    key: &str,
+    option: Option<GetExOption>,
+    fields: &[String],
+    now: i64,
+) -> ReplyFrame {
+    let deadline = match option {
+        Some(GetExOption::Expire(expire_time)) => {
+            let Some(deadline) = expire_time.deadline(now) else {
+                return ReplyFrame::ConstError(INVALID_EXPIRE_TIME_ERR);
+            };
+            Some(deadline)
+        }
+        _ => None,
+    };
+
+    match db.get_mut(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let mut array = Vec::with_capacity(fields.len());
+            for field in fields {
+                let Some(value) = old_hash.get(field) else {
+                    array.push(ReplyFrame::Null);
+                    continue;
+                };
+                array.push(ReplyFrame::Bulk(value.to_vec()));
+                match (option, deadline) {
+                    (Some(GetExOption::Persist), _) => {
+                        old_hash.persist(field);
+                    }
+                    (_, Some(deadline)) if deadline <= now => {
+                        old_hash.remove(field);
+                    }
+                    (_, Some(deadline)) => {
+                        old_hash.set_expire_at(field, deadline);
+                    }
+                    _ => (),
+                }
+            }
+            remove_if_empty(db, key);
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::Null; fields.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hash::{ExpireTime, GetExOption};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::get_ex::get_ex;
+    use crate::mem::hash::set::set;
+    use crate::mem::hash::ttl::ttl;
+
+    #[test]
+    fn test_get_ex() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let now = 1_000_000;
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"foo".to_vec()),
+                ("field2".to_owned(), b"bar".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let fields = [
+            "field1".to_owned(),
+            "field2".to_owned(),
+            "field3".to_owned(),
+        ];
+
+        let reply = get_ex(
+            &mut db,
+            &key,
+            Some(GetExOption::Expire(ExpireTime::Seconds(60))),
+            &fields,
+            now,
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"foo".to_vec()),
+                ReplyFrame::Bulk(b"bar".to_vec()),
+                ReplyFrame::Null,
+            ])
+        );
+        let reply = ttl(&db, &key, &fields, now);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(60),
+                ReplyFrame::I64(60),
+                ReplyFrame::I64(-2),
+            ])
+        );
+
+        let reply = get_ex(&mut db, &key, Some(GetExOption::Persist), &fields[..1], now);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(b"foo".to_vec())])
+        );
+        let reply = ttl(&db, &key, &fields[..1], now);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(-1)]));
+
+        let reply = get_ex(
+            &mut db,
+            &key,
+            Some(GetExOption::Expire(ExpireTime::UnixMilliseconds(now))),
+            &fields,
+            now,
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"foo".to_vec()),
+                ReplyFrame::Bulk(b"bar".to_vec()),
+                ReplyFrame::Null,
+            ])
+        );
+        assert!(db.is_empty());
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Hash object with optional time to live of each field.
+//!
+//! Small hashes are stored in a `ListPack`, with fields and values stored alternately.
+//! Once a hash has too many fields or a large field, it is promoted to a `HashMap`,
+//! and is never converted back.
+
+use std::collections::{hash_map, BTreeSet, HashMap};
+
+use crate::mem::list_pack::{self, ListPack};
+
+/// Max number of fields in a listpack encoded hash, same as `hash-max-listpack-entries` in redis.
+pub const HASH_MAX_LISTPACK_ENTRIES: usize = 128;
+
+/// Max length of field or value in a listpack encoded hash, same as `hash-max-listpack-value` in redis.
+pub const HASH_MAX_LISTPACK_VALUE: usize = 64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Encoding {
+    ListPack(ListPack),
+    HashTable(HashMap<String, Vec<u8>>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HashObject {
+    encoding: Encoding,
+
+    /// Deadline of fields, as unix time in milliseconds.
+    expires: HashMap<String, i64>,
+
+    /// Fields ordered by deadline, so that expired fields are found without
+    /// walking through all of the fields with time to live.
+    deadlines: BTreeSet<(i64, String)>,
+}
+
+impl Default for HashObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashObject {
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            encoding: Encoding::ListPack(ListPack::new()),
+            expires: HashMap::new(),
+            deadlines: BTreeSet::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.encoding {
+            Encoding::ListPack(list_pack) => list_pack.len() / 2,
+            Encoding::HashTable(map) => map.len(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns name of current encoding, used by `OBJECT ENCODING`.
+    #[must_use]
+    pub const fn encoding(&self) -> &'static str {
+        match self.encoding {
+            Encoding::ListPack(_) => "listpack",
+            Encoding::HashTable(_) => "hashtable",
+        }
+    }
+
+    #[must_use]
+    pub fn contains_key(&self, field: &str) -> bool {
+        match &self.encoding {
+            Encoding::ListPack(list_pack) => find_field(list_pack, field).is_some(),
+            Encoding::HashTable(map) => map.contains_key(field),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, field: &str) -> Option<&[u8]> {
+        match &self.encoding {
+            Encoding::ListPack(list_pack) => {
+                let index = find_field(list_pack, field)?;
+                list_pack.get(index + 1)
+            }
+            Encoding::HashTable(map) => map.get(field).map(Vec::as_slice),
+        }
+    }
+
+    /// Insert a field, time to live of old field is discarded.
+    pub fn insert(&mut self, field: String, value: Vec<u8>) -> Option<Vec<u8>> {
+        self.remove_expire(&field);
+        self.insert_keep_ttl(field, value)
+    }
+
+    /// Update value of a field, and keep its time to live.
+    pub fn insert_keep_ttl(&mut self, field: String, value: Vec<u8>) -> Option<Vec<u8>> {
+        if let Encoding::ListPack(list_pack) = &mut self.encoding {
+            let fits =
+                field.len() <= HASH_MAX_LISTPACK_VALUE && value.len() <= HASH_MAX_LISTPACK_VALUE;
+            if fits {
+                if let Some(index) = find_field(list_pack, &field) {
+                    let old_value = list_pack.get(index + 1).map(<[u8]>::to_vec);
+                    list_pack.replace(index + 1, &value);
+                    return old_value;
+                }
+                if list_pack.len() / 2 < HASH_MAX_LISTPACK_ENTRIES {
+                    list_pack.push_back(field.as_bytes());
+                    list_pack.push_back(&value);
+                    return None;
+                }
+            }
+            self.convert_to_hash_table();
+        }
+
+        match &mut self.encoding {
+            Encoding::HashTable(map) => map.insert(field, value),
+            Encoding::ListPack(_) => unreachable!(),
+        }
+    }
+
+    pub fn remove(&mut self, field: &str) -> Option<Vec<u8>> {
+        self.remove_expire(field);
+        match &mut self.encoding {
+            Encoding::ListPack(list_pack) => {
+                let index = find_field(list_pack, field)?;
+                list_pack.remove(index);
+                list_pack.remove(index)
+            }
+            Encoding::HashTable(map) => map.remove(field),
+        }
+    }
+
+    /// Returns an iterator of `(field, value)` pairs, in arbitrary order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_> {
+        match &self.encoding {
+            Encoding::ListPack(list_pack) => Iter::ListPack(list_pack.iter()),
+            Encoding::HashTable(map) => Iter::HashTable(map.iter()),
+        }
+    }
+
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.iter().map(|(field, _value)| field)
+    }
+
+    /// Returns deadline of field in unix milliseconds.
+    #[must_use]
+    #[inline]
+    pub fn expire_at(&self, field: &str) -> Option<i64> {
+        self.expires.get(field).copied()
+    }
+
+    /// Set deadline of an existing field, returns false if field not found.
+    pub fn set_expire_at(&mut self, field: &str, deadline: i64) -> bool {
+        if self.contains_key(field) {
+            self.remove_expire(field);
+            self.expires.insert(field.to_owned(), deadline);
+            self.deadlines.insert((deadline, field.to_owned()));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove time to live of field, returns false if field has no deadline.
+    pub fn persist(&mut self, field: &str) -> bool {
+        self.remove_expire(field).is_some()
+    }
+
+    fn remove_expire(&mut self, field: &str) -> Option<i64> {
+        let deadline = self.expires.remove(field)?;
+        self.deadlines.remove(&(deadline, field.to_owned()));
+        Some(deadline)
+    }
+
+    /// Returns true if any field has a time to live.
+    #[must_use]
+    #[inline]
+    pub fn has_expires(&self) -> bool {
+        !self.expires.is_empty()
+    }
+
+    /// Remove fields whose deadline is reached, returns number of fields removed.
+    pub fn remove_expired(&mut self, now: i64) -> usize {
+        let mut count = 0;
+        while let Some((deadline, field)) = self.deadlines.pop_first() {
+            if deadline > now {
+                self.deadlines.insert((deadline, field));
+                break;
+            }
+            self.remove(&field);
+            count += 1;
+        }
+        count
+    }
+
+    fn convert_to_hash_table(&mut self) {
+        if let Encoding::ListPack(list_pack) = &self.encoding {
+            let mut map = HashMap::with_capacity(list_pack.len() / 2 + 1);
+            let mut iter = list_pack.iter();
+            while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+                map.insert(String::from_utf8_lossy(field).into_owned(), value.to_vec());
+            }
+            self.encoding = Encoding::HashTable(map);
+        }
+    }
+}
+
+/// Returns entry index of field in listpack.
+fn find_field(list_pack: &ListPack, field: &str) -> Option<usize> {
+    list_pack
+        .iter()
+        .step_by(2)
+        .position(|entry| entry == field.as_bytes())
+        .map(|pair_index| pair_index * 2)
+}
+
+pub enum Iter<'a> {
+    ListPack(list_pack::Iter<'a>),
+    HashTable(hash_map::Iter<'a, String, Vec<u8>>),
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::ListPack(iter) => {
+                let field = iter.next()?;
+                let value = iter.next()?;
+                Some((field, value))
+            }
+            Self::HashTable(iter) => iter
+                .next()
+                .map(|(field, value)| (field.as_bytes(), value.as_slice())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HashObject, HASH_MAX_LISTPACK_ENTRIES, HASH_MAX_LISTPACK_VALUE};
+
+    #[test]
+    fn test_expires() {
+        let mut hash = HashObject::new();
+        hash.insert("token".to_owned(), b"abc".to_vec());
+        hash.insert("name".to_owned(), b"Alice".to_vec());
+        assert!(!hash.has_expires());
+        assert!(hash.set_expire_at("token", 1000));
+        assert!(!hash.set_expire_at("nofield", 1000));
+        assert_eq!(hash.expire_at("token"), Some(1000));
+
+        hash.insert_keep_ttl("token".to_owned(), b"def".to_vec());
+        assert_eq!(hash.expire_at("token"), Some(1000));
+        assert_eq!(hash.remove_expired(999), 0);
+        assert_eq!(hash.remove_expired(1000), 1);
+        assert!(!hash.contains_key("token"));
+        assert!(!hash.has_expires());
+
+        assert!(hash.set_expire_at("name", 2000));
+        hash.insert("name".to_owned(), b"Bob".to_vec());
+        assert_eq!(hash.expire_at("name"), None);
+        assert!(!hash.persist("name"));
+        assert_eq!(hash.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_expired_in_order() {
+        let mut hash = HashObject::new();
+        for i in 0..10 {
+            let field = format!("field{i}");
+            hash.insert(field.clone(), b"value".to_vec());
+            assert!(hash.set_expire_at(&field, 1000 + i));
+        }
+        assert!(hash.set_expire_at("field0", 5000));
+        assert!(hash.persist("field1"));
+        assert_eq!(hash.remove_expired(1004), 3);
+        assert!(hash.contains_key("field0"));
+        assert!(hash.contains_key("field1"));
+        assert!(!hash.contains_key("field4"));
+        assert!(hash.contains_key("field5"));
+
+        assert_eq!(hash.remove_expired(5000), 6);
+        assert_eq!(hash.len(), 1);
+        assert!(hash.contains_key("field1"));
+        assert!(!hash.has_expires());
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mut hash = HashObject::new();
+        for i in 0..HASH_MAX_LISTPACK_ENTRIES {
+            assert_eq!(hash.insert(format!("field{i}"), b"value".to_vec()), None);
+        }
+        assert_eq!(hash.encoding(), "listpack");
+        assert_eq!(
+            hash.insert("field3".to_owned(), b"new-value".to_vec()),
+            Some(b"value".to_vec())
+        );
+        assert_eq!(hash.remove("field5"), Some(b"value".to_vec()));
+        assert_eq!(hash.get("field3"), Some(&b"new-value"[..]));
+        assert_eq!(hash.get("field6"), Some(&b"value"[..]));
+        assert_eq!(hash.len(), HASH_MAX_LISTPACK_ENTRIES - 1);
+
+        hash.insert("field5".to_owned(), b"value".to_vec());
+        assert_eq!(hash.encoding(), "listpack");
+        hash.insert("one-more".to_owned(), b"value".to_vec());
+        assert_eq!(hash.encoding(), "hashtable");
+        assert_eq!(hash.len(), HASH_MAX_LISTPACK_ENTRIES + 1);
+        assert_eq!(hash.get("field3"), Some(&b"new-value"[..]));
+
+        let mut hash = HashObject::new();
+        hash.insert("small".to_owned(), b"value".to_vec());
+        hash.insert("large".to_owned(), vec![b'x'; HASH_MAX_LISTPACK_VALUE + 1]);
+        assert_eq!(hash.encoding(), "hashtable");
+        assert_eq!(hash.iter().count(), 2);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::{ReplyFrame, OVERFLOW_ERR};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::consts::HASH_VALUE_NOT_INTEGER_ERR;
+use crate::mem::hash::HashObject;
+
+/// Increments the number stored at field in the hash stored at key by increment.
+///
+/// If key does not exist, a new key holding a hash is created.
+/// If field does not exist the value is set to 0 before the operation is performed.
+///
+/// The range of values supported by HINCRBY is limited to 64 bit signed integers.
+///
+/// Reply:
+/// - Integer reply: the value of the field after the increment operation.
+pub fn incr_by(db: &mut Db, key: String, field: String, increment: i64) -> ReplyFrame {
+    match db.entry(key) {
+        Entry::Occupied(mut occupied) => match occupied.get_mut() {
+            MemObject::Hash(old_hash) => {
+                let old_value = match old_hash.get(&field) {
+                    Some(value) => {
+                        let Some(old_value) = std::str::from_utf8(value)
+                            .ok()
+                            .and_then(|s| s.parse::<i64>().ok())
+                        else {
+                            return ReplyFrame::ConstError(HASH_VALUE_NOT_INTEGER_ERR);
+                        };
+                        old_value
+                    }
+                    None => 0,
+                };
+                let Some(new_value) = old_value.checked_add(increment) else {
+                    return ReplyFrame::ConstError(OVERFLOW_ERR);
+                };
+                old_hash.insert_keep_ttl(field, new_value.to_string().into_bytes());
+                ReplyFrame::I64(new_value)
+            }
+            _ => ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(vacant) => {
+            let mut new_hash = HashObject::new();
+            new_hash.insert(field, increment.to_string().into_bytes());
+            vacant.insert(MemObject::Hash(new_hash));
+            ReplyFrame::I64(increment)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::{ReplyFrame, OVERFLOW_ERR};
+    use crate::mem::db::Db;
+    use crate::mem::hash::consts::HASH_VALUE_NOT_INTEGER_ERR;
+    use crate::mem::hash::get::get;
+    use crate::mem::hash::incr_by::incr_by;
+    use crate::mem::hash::set::set;
+
+    #[test]
+    fn test_incr_by() {
+        let mut db = Db::new();
+        let key = "myhash".to_owned();
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field".to_owned(), b"5".to_vec()),
+                ("name".to_owned(), b"Alice".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let reply = incr_by(&mut db, key.clone(), "field".to_owned(), 1);
+        assert_eq!(reply, ReplyFrame::I64(6));
+        let reply = incr_by(&mut db, key.clone(), "field".to_owned(), -1);
+        assert_eq!(reply, ReplyFrame::I64(5));
+        let reply = incr_by(&mut db, key.clone(), "field".to_owned(), -10);
+        assert_eq!(reply, ReplyFrame::I64(-5));
+        let reply = get(&db, &key, "field");
+        assert_eq!(reply, ReplyFrame::Bulk(b"-5".to_vec()));
+
+        let reply = incr_by(&mut db, key.clone(), "new-field".to_owned(), 3);
+        assert_eq!(reply, ReplyFrame::I64(3));
+        let reply = incr_by(&mut db, key.clone(), "name".to_owned(), 1);
+        assert_eq!(reply, ReplyFrame::ConstError(HASH_VALUE_NOT_INTEGER_ERR));
+        let reply = incr_by(&mut db, key.clone(), "new-field".to_owned(), i64::MAX);
+        assert_eq!(reply, ReplyFrame::ConstError(OVERFLOW_ERR));
+        let reply = get(&db, &key, "new-field");
+        assert_eq!(reply, ReplyFrame::Bulk(b"3".to_vec()));
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::{ReplyFrame, NAN_OR_INFINITY_ERR, NOT_FLOAT_ERR};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::consts::HASH_VALUE_NOT_FLOAT_ERR;
+use crate::mem::hash::HashObject;
+use crate::mem::util::format_float;
+
+/// Increment the specified field of a hash stored at key, and representing a floating point number,
+/// by the specified increment.
+///
+/// If the increment value is negative, the result is to have the hash field value decremented
+/// instead of incremented. If the field does not exist, it is set to 0 before performing the operation.
+///
+/// An error is returned if one of the following conditions occur:
+/// - The key contains a value of the wrong type (not a hash).
+/// - The current field content or the specified increment are not parsable
+///   as a double precision floating point number.
+///
+/// Reply:
+/// - Bulk string reply: the value of the field after the increment operation.
+pub fn incr_by_float(db: &mut Db, key: String, field: String, increment: f64) -> ReplyFrame {
+    if !increment.is_finite() {
+        return ReplyFrame::ConstError(NOT_FLOAT_ERR);
+    }
+
+    match db.entry(key) {
+        Entry::Occupied(mut occupied) => match occupied.get_mut() {
+            MemObject::Hash(old_hash) => {
+                let old_value = match old_hash.get(&field) {
+                    Some(value) => {
+                        let Some(old_value) = parse_float(value) else {
+                            return ReplyFrame::ConstError(HASH_VALUE_NOT_FLOAT_ERR);
+                        };
+                        old_value
+                    }
+                    None => 0.0,
+                };
+                let new_value = old_value + increment;
+                if !new_value.is_finite() {
+                    return ReplyFrame::ConstError(NAN_OR_INFINITY_ERR);
+                }
+                let new_value = format_float(new_value).into_bytes();
+                old_hash.insert_keep_ttl(field, new_value.clone());
+                ReplyFrame::Bulk(new_value)
+            }
+            _ => ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(vacant) => {
+            let new_value = format_float(increment).into_bytes();
+            let mut new_hash = HashObject::new();
+            new_hash.insert(field, new_value.clone());
+            vacant.insert(MemObject::Hash(new_hash));
+            ReplyFrame::Bulk(new_value)
+        }
+    }
+}
+
+/// Values like "nan" and "inf" are not valid floats in hash field.
+fn parse_float(value: &[u8]) -> Option<f64> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|num| num.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::{ReplyFrame, NAN_OR_INFINITY_ERR};
+    use crate::mem::db::Db;
+    use crate::mem::hash::consts::HASH_VALUE_NOT_FLOAT_ERR;
+    use crate::mem::hash::incr_by_float::incr_by_float;
+    use crate::mem::hash::set::set;
+
+    #[test]
+    fn test_incr_by_float() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field".to_owned(), b"10.50".to_vec()),
+                ("exp".to_owned(), b"5.0e3".to_vec()),
+                ("name".to_owned(), b"Alice".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+
+        let reply = incr_by_float(&mut db, key.clone(), "field".to_owned(), 0.1);
+        assert_eq!(reply, ReplyFrame::Bulk(b"10.6".to_vec()));
+        let reply = incr_by_float(&mut db, key.clone(), "field".to_owned(), -5.0);
+        assert_eq!(reply, ReplyFrame::Bulk(b"5.6".to_vec()));
+        let reply = incr_by_float(&mut db, key.clone(), "exp".to_owned(), 2.0e2);
+        assert_eq!(reply, ReplyFrame::Bulk(b"5200".to_vec()));
+        let reply = incr_by_float(&mut db, key.clone(), "new".to_owned(), 1.5);
+        assert_eq!(reply, ReplyFrame::Bulk(b"1.5".to_vec()));
+        let reply = incr_by_float(&mut db, key.clone(), "big".to_owned(), 1e21);
+        assert_eq!(reply, ReplyFrame::Bulk(b"1e+21".to_vec()));
+
+        let reply = incr_by_float(&mut db, key.clone(), "name".to_owned(), 1.0);
+        assert_eq!(reply, ReplyFrame::ConstError(HASH_VALUE_NOT_FLOAT_ERR));
+        let reply = incr_by_float(&mut db, key.clone(), "new".to_owned(), f64::MAX);
+        assert_eq!(reply, ReplyFrame::Bulk(b"1.7976931348623157e+308".to_vec()));
+        let reply = incr_by_float(&mut db, key, "new".to_owned(), f64::MAX);
+        assert_eq!(reply, ReplyFrame::ConstError(NAN_OR_INFINITY_ERR));
+    }
+}
@@ -12,11 +12,11 @@ use crate::mem::db::{Db, MemObject};
 pub fn keys(db: &Db, key: &str) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::Hash(old_hash)) => {
-            let mut keys: Vec<&String> = old_hash.keys().collect();
+            let mut keys: Vec<&[u8]> = old_hash.keys().collect();
             keys.sort_unstable();
             let array: Vec<ReplyFrame> = keys
                 .into_iter()
-                .map(|key| ReplyFrame::Bulk(key.as_bytes().to_vec()))
+                .map(|key| ReplyFrame::Bulk(key.to_vec()))
                 .collect();
             ReplyFrame::Array(array)
         }
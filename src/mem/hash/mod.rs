@@ -2,40 +2,94 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use std::collections::HashMap;
-
 use crate::cmd::hash::HashCommand;
 use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::util::now_millis;
 use crate::mem::Mem;
 
+mod consts;
 pub mod delete;
 pub mod exists;
+pub mod expire;
 pub mod get;
 pub mod get_all;
+pub mod get_del;
+pub mod get_ex;
+mod hash_object;
+pub mod incr_by;
+pub mod incr_by_float;
 pub mod keys;
 pub mod len;
+pub mod multi_get;
+pub mod multi_set;
+pub mod persist;
+pub mod random_field;
+mod reclaim;
 pub mod set;
+pub mod set_not_exist;
 pub mod str_len;
+pub mod ttl;
 pub mod values;
 
-pub type HashObject = HashMap<String, Vec<u8>>;
+pub use hash_object::HashObject;
+pub use reclaim::ExpireKeys;
 
 impl Mem {
     pub fn handle_hash_command(&mut self, command: HashCommand) -> ReplyFrame {
+        let now = now_millis();
+        self.remove_expired_fields(command.key(), now);
+
         match command {
             HashCommand::Del(key, fields) => delete::delete(&mut self.db, &key, &fields),
             HashCommand::Exists(key, field) => exists::exists(&self.db, &key, &field),
+            HashCommand::Expire(key, expire_time, condition, fields) => {
+                let reply =
+                    expire::expire(&mut self.db, &key, expire_time, condition, &fields, now);
+                self.track_hash_expires(key);
+                reply
+            }
             HashCommand::Get(key, field) => get::get(&self.db, &key, &field),
             HashCommand::GetAll(key) => get_all::get_all(&self.db, &key),
+            HashCommand::GetDel(key, fields) => get_del::get_del(&mut self.db, &key, &fields),
+            HashCommand::GetEx(key, option, fields) => {
+                let reply = get_ex::get_ex(&mut self.db, &key, option, &fields, now);
+                self.track_hash_expires(key);
+                reply
+            }
+            HashCommand::IncrBy(key, field, increment) => {
+                incr_by::incr_by(&mut self.db, key, field, increment)
+            }
+            HashCommand::IncrByFloat(key, field, increment) => {
+                incr_by_float::incr_by_float(&mut self.db, key, field, increment)
+            }
             HashCommand::Keys(key) => keys::keys(&self.db, &key),
             HashCommand::Len(key) => len::len(&self.db, &key),
+            HashCommand::MultiGet(key, fields) => multi_get::multi_get(&self.db, &key, &fields),
+            HashCommand::MultiSet(key, pairs) => multi_set::multi_set(&mut self.db, key, pairs),
+            HashCommand::Persist(key, fields) => persist::persist(&mut self.db, &key, &fields),
+            HashCommand::PTtl(key, fields) => ttl::pttl(&self.db, &key, &fields, now),
+            HashCommand::RandomField(key, count) => {
+                random_field::random_field(&self.db, &key, count)
+            }
             HashCommand::Set(key, pairs) => set::set(&mut self.db, key, pairs),
+            HashCommand::SetNotExist(key, field, value) => {
+                set_not_exist::set_not_exist(&mut self.db, key, field, value)
+            }
             HashCommand::StrLen(key, field) => str_len::str_len(&self.db, &key, &field),
+            HashCommand::Ttl(key, fields) => ttl::ttl(&self.db, &key, &fields, now),
             HashCommand::Values(key) => values::values(&self.db, &key),
         }
     }
 }
 
+/// Delete the hash stored at key if it has no fields left.
+pub fn remove_if_empty(db: &mut Db, key: &str) {
+    if matches!(db.get(key), Some(MemObject::Hash(old_hash)) if old_hash.is_empty()) {
+        db.remove(key);
+    }
+}
+
 pub fn to_reply_frame(_hash_object: &HashObject) -> ReplyFrame {
     todo!()
 }
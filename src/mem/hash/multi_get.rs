@@ -0,0 +1,73 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the values associated with the specified fields in the hash stored at key.
+///
+/// For every field that does not exist in the hash, a nil value is returned.
+/// Because non-existing keys are treated as empty hashes, running HMGET against
+/// a non-existing key will return a list of nil values.
+///
+/// RESP2 Reply:
+/// - Array reply: a list of values associated with the given fields, in the same order as they are requested.
+pub fn multi_get(db: &Db, key: &str, fields: &[String]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let array = fields
+                .iter()
+                .map(|field| {
+                    old_hash
+                        .get(field)
+                        .map(<[u8]>::to_vec)
+                        .map_or_else(ReplyFrame::null, ReplyFrame::bulk)
+                })
+                .collect();
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::Null; fields.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::multi_get::multi_get;
+    use crate::mem::hash::set::set;
+
+    #[test]
+    fn test_multi_get() {
+        let mut db = Db::new();
+        let key = "myhash".to_owned();
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"Hello".to_vec()),
+                ("field2".to_owned(), b"World".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+
+        let fields = [
+            "field1".to_owned(),
+            "field2".to_owned(),
+            "nofield".to_owned(),
+        ];
+        let reply = multi_get(&db, &key, &fields);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"Hello".to_vec()),
+                ReplyFrame::Bulk(b"World".to_vec()),
+                ReplyFrame::Null,
+            ])
+        );
+        let reply = multi_get(&db, "nokey", &fields);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::Null; 3]));
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::hash::set::set;
+
+/// Sets the specified fields to their respective values in the hash stored at key.
+///
+/// This command overwrites any specified fields already existing in the hash.
+/// If key does not exist, a new key holding a hash is created.
+///
+/// As of Redis version 4.0.0, this command is regarded as deprecated, use HSET instead.
+///
+/// Reply:
+/// - Simple string reply: OK.
+pub fn multi_set(db: &mut Db, key: String, pairs: Vec<(String, Vec<u8>)>) -> ReplyFrame {
+    match set(db, key, pairs) {
+        ReplyFrame::Usize(_) => ReplyFrame::ok(),
+        reply => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::get::get;
+    use crate::mem::hash::multi_set::multi_set;
+
+    #[test]
+    fn test_multi_set() {
+        let mut db = Db::new();
+        let key = "myhash".to_owned();
+        let reply = multi_set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"Hello".to_vec()),
+                ("field2".to_owned(), b"World".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::ok());
+        let reply = get(&db, &key, "field1");
+        assert_eq!(reply, ReplyFrame::Bulk(b"Hello".to_vec()));
+        let reply = get(&db, &key, "field2");
+        assert_eq!(reply, ReplyFrame::Bulk(b"World".to_vec()));
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Remove the existing expiration on a hash key's field(s), turning the field(s)
+/// from volatile (a field with expiration set) to persistent (a field that will
+/// never expire as no TTL (time to live) is associated).
+///
+/// Reply, one of the following:
+/// - Array reply: for each field
+///   - Integer reply: -2 if no such field exists in the provided hash key,
+///     or the provided key does not exist.
+///   - Integer reply: -1 if the field exists but has no associated expiration set.
+///   - Integer reply: 1 the expiration was removed.
+pub fn persist(db: &mut Db, key: &str, fields: &[String]) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let array = fields
+                .iter()
+                .map(|field| {
+                    if !old_hash.contains_key(field) {
+                        ReplyFrame::I64(-2)
+                    } else if old_hash.persist(field) {
+                        ReplyFrame::I64(1)
+                    } else {
+                        ReplyFrame::I64(-1)
+                    }
+                })
+                .collect();
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::I64(-2); fields.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hash::ExpireTime;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::expire::expire;
+    use crate::mem::hash::persist::persist;
+    use crate::mem::hash::set::set;
+    use crate::mem::hash::ttl::ttl;
+
+    #[test]
+    fn test_persist() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let now = 1_000_000;
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"hello".to_vec()),
+                ("field2".to_owned(), b"world".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let fields = [
+            "field1".to_owned(),
+            "field2".to_owned(),
+            "field3".to_owned(),
+        ];
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::Seconds(10),
+            None,
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(1)]));
+
+        let reply = persist(&mut db, &key, &fields);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(1),
+                ReplyFrame::I64(-1),
+                ReplyFrame::I64(-2),
+            ])
+        );
+        let reply = ttl(&db, &key, &fields[..1], now);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(-1)]));
+    }
+}
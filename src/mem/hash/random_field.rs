@@ -0,0 +1,148 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use rand::seq::{IteratorRandom, SliceRandom};
+
+use crate::cmd::reply_frame::{ReplyFrame, OOM_ERR, VALUE_OUT_OF_RANGE_ERR};
+use crate::mem::db::{Db, MemObject};
+
+/// When called with just the key argument, return a random field from the hash value stored at key.
+///
+/// If the provided count argument is positive, return an array of distinct fields.
+/// The array's length is either count or the hash's number of fields (HLEN), whichever is lower.
+///
+/// If called with a negative count, the behavior changes and the command is allowed
+/// to return the same field multiple times. In this case, the number of returned fields
+/// is the absolute value of the specified count.
+///
+/// The optional WITHVALUES modifier changes the reply so it includes the respective values
+/// of the randomly selected hash fields.
+///
+/// RESP2 Reply, any of the following:
+/// - Null reply: if the key doesn't exist
+/// - Bulk string reply: a single, randomly selected field when the count option is not used
+/// - Array reply: a list containing count fields when the count option is used,
+///   or an empty array if the key does not exists.
+/// - Array reply: a list of fields and their values when count and WITHVALUES were both used.
+pub fn random_field(db: &Db, key: &str, count: Option<(isize, bool)>) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let mut rng = rand::thread_rng();
+            let Some((count, with_values)) = count else {
+                return old_hash
+                    .keys()
+                    .choose(&mut rng)
+                    .map_or_else(ReplyFrame::null, |field| ReplyFrame::Bulk(field.to_vec()));
+            };
+
+            // Same limit as redis, to avoid overflow when computing reply length.
+            if count < -(isize::MAX / 2) {
+                return ReplyFrame::ConstError(VALUE_OUT_OF_RANGE_ERR);
+            }
+            let entries: Vec<_> = old_hash.iter().collect();
+            let num_picked = if count >= 0 {
+                count.unsigned_abs().min(entries.len())
+            } else if entries.is_empty() {
+                0
+            } else {
+                count.unsigned_abs()
+            };
+            let mut array = Vec::new();
+            let reply_len = if with_values {
+                num_picked * 2
+            } else {
+                num_picked
+            };
+            if array.try_reserve_exact(reply_len).is_err() {
+                return ReplyFrame::ConstError(OOM_ERR);
+            }
+
+            let mut push_entry = |(field, value): &(&[u8], &[u8])| {
+                array.push(ReplyFrame::Bulk(field.to_vec()));
+                if with_values {
+                    array.push(ReplyFrame::Bulk(value.to_vec()));
+                }
+            };
+            if count >= 0 {
+                entries
+                    .choose_multiple(&mut rng, num_picked)
+                    .for_each(&mut push_entry);
+            } else {
+                for _i in 0..num_picked {
+                    if let Some(entry) = entries.choose(&mut rng) {
+                        push_entry(entry);
+                    }
+                }
+            }
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => {
+            if count.is_some() {
+                ReplyFrame::Array(Vec::new())
+            } else {
+                ReplyFrame::Null
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::{ReplyFrame, VALUE_OUT_OF_RANGE_ERR};
+    use crate::mem::db::Db;
+    use crate::mem::hash::random_field::random_field;
+    use crate::mem::hash::set::set;
+
+    #[test]
+    fn test_random_field() {
+        let mut db = Db::new();
+        let key = "coin".to_owned();
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("heads".to_owned(), b"obverse".to_vec()),
+                ("tails".to_owned(), b"reverse".to_vec()),
+                ("edge".to_owned(), b"null".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let fields = [
+            ReplyFrame::Bulk(b"heads".to_vec()),
+            ReplyFrame::Bulk(b"tails".to_vec()),
+            ReplyFrame::Bulk(b"edge".to_vec()),
+        ];
+
+        let reply = random_field(&db, &key, None);
+        assert!(fields.contains(&reply));
+
+        let reply = random_field(&db, &key, Some((5, false)));
+        let ReplyFrame::Array(array) = reply else {
+            panic!("Expected array reply");
+        };
+        assert_eq!(array.len(), 3);
+        for field in &fields {
+            assert!(array.contains(field));
+        }
+
+        let reply = random_field(&db, &key, Some((-5, true)));
+        let ReplyFrame::Array(array) = reply else {
+            panic!("Expected array reply");
+        };
+        assert_eq!(array.len(), 10);
+        for pair in array.chunks(2) {
+            assert!(fields.contains(&pair[0]));
+        }
+
+        let reply = random_field(&db, &key, Some((0, false)));
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+        let reply = random_field(&db, &key, Some((-isize::MAX, true)));
+        assert_eq!(reply, ReplyFrame::ConstError(VALUE_OUT_OF_RANGE_ERR));
+        let reply = random_field(&db, "nokey", None);
+        assert_eq!(reply, ReplyFrame::Null);
+        let reply = random_field(&db, "nokey", Some((2, true)));
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Reclaim hash fields whose time to live is reached.
+//!
+//! Expired fields are removed lazily before a hash is accessed by hash commands,
+//! and a background cycle samples hashes with field deadlines periodically,
+//! so that fields which are never accessed again do not waste memory.
+
+use std::collections::HashMap;
+
+use rand::seq::index;
+use rand::Rng;
+
+use crate::mem::db::MemObject;
+use crate::mem::hash::remove_if_empty;
+use crate::mem::Mem;
+
+/// Max number of hashes sampled in each active expire cycle.
+const ACTIVE_EXPIRE_KEYS_PER_CYCLE: usize = 20;
+
+/// Keys of hashes which have fields with time to live.
+///
+/// Keys are stored in a vector with a position map, so that both removing a key
+/// and sampling random keys do not walk through all of the keys.
+#[derive(Debug, Default, Clone)]
+pub struct ExpireKeys {
+    keys: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl ExpireKeys {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns false if key is tracked already.
+    pub fn insert(&mut self, key: String) -> bool {
+        if self.positions.contains_key(&key) {
+            return false;
+        }
+        self.positions.insert(key.clone(), self.keys.len());
+        self.keys.push(key);
+        true
+    }
+
+    /// Returns false if key is not tracked.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let Some(pos) = self.positions.remove(key) else {
+            return false;
+        };
+        self.keys.swap_remove(pos);
+        if let Some(moved) = self.keys.get(pos) {
+            self.positions.insert(moved.clone(), pos);
+        }
+        true
+    }
+
+    /// Returns at most `amount` distinct keys chosen randomly.
+    pub fn sample<R: Rng>(&self, rng: &mut R, amount: usize) -> Vec<String> {
+        index::sample(rng, self.keys.len(), amount.min(self.keys.len()))
+            .into_iter()
+            .map(|pos| self.keys[pos].clone())
+            .collect()
+    }
+}
+
+impl Mem {
+    /// Remove expired fields of hash stored at key.
+    ///
+    /// The hash is deleted if no fields left.
+    pub(super) fn remove_expired_fields(&mut self, key: &str, now: i64) {
+        if let Some(MemObject::Hash(old_hash)) = self.db.get_mut(key) {
+            if old_hash.has_expires() && old_hash.remove_expired(now) > 0 {
+                remove_if_empty(&mut self.db, key);
+            }
+        }
+    }
+
+    /// Track the hash stored at key if any of its fields has a time to live.
+    pub(crate) fn track_hash_expires(&mut self, key: String) {
+        if matches!(self.db.get(&key), Some(MemObject::Hash(old_hash)) if old_hash.has_expires()) {
+            self.hash_expire_keys.insert(key);
+        }
+    }
+
+    /// Sample tracked hashes and remove their expired fields.
+    ///
+    /// Keys which are deleted, overwritten or have no field deadlines any more
+    /// are not tracked any longer.
+    pub fn active_expire_hash_fields(&mut self, now: i64) {
+        if self.hash_expire_keys.is_empty() {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        let keys = self
+            .hash_expire_keys
+            .sample(&mut rng, ACTIVE_EXPIRE_KEYS_PER_CYCLE);
+        for key in keys {
+            self.remove_expired_fields(&key, now);
+            let still_expires = matches!(
+                self.db.get(&key),
+                Some(MemObject::Hash(old_hash)) if old_hash.has_expires()
+            );
+            if !still_expires {
+                self.hash_expire_keys.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use tokio::sync::mpsc;
+
+    use crate::cmd::hash::ExpireTime;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::hash::expire::expire;
+    use crate::mem::hash::reclaim::ExpireKeys;
+    use crate::mem::hash::set::set;
+    use crate::mem::Mem;
+
+    #[test]
+    fn test_active_expire() {
+        let (dispatcher_sender, _receiver) = mpsc::channel(1);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        let key = "session".to_owned();
+        let now = 1_000_000;
+        let reply = set(
+            &mut mem.db,
+            key.clone(),
+            vec![
+                ("token".to_owned(), b"abc".to_vec()),
+                ("user".to_owned(), b"alice".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let fields = ["token".to_owned(), "user".to_owned()];
+        let reply = expire(
+            &mut mem.db,
+            &key,
+            ExpireTime::Seconds(1),
+            None,
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(1)]));
+        mem.track_hash_expires(key.clone());
+        assert_eq!(mem.hash_expire_keys.len(), 1);
+
+        mem.active_expire_hash_fields(now + 500);
+        assert_eq!(mem.hash_expire_keys.len(), 1);
+        mem.active_expire_hash_fields(now + 1000);
+        assert!(mem.hash_expire_keys.is_empty());
+
+        let reply = expire(
+            &mut mem.db,
+            &key,
+            ExpireTime::Seconds(1),
+            None,
+            &fields,
+            now,
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::I64(-2), ReplyFrame::I64(1)])
+        );
+        mem.track_hash_expires(key);
+        mem.active_expire_hash_fields(now + 1000);
+        assert!(mem.db.is_empty());
+    }
+
+    #[test]
+    fn test_expire_keys() {
+        let mut keys = ExpireKeys::new();
+        for i in 0..100 {
+            assert!(keys.insert(format!("key-{i}")));
+        }
+        assert!(!keys.insert("key-0".to_owned()));
+        assert!(keys.remove("key-0"));
+        assert!(keys.remove("key-50"));
+        assert!(!keys.remove("key-50"));
+        assert_eq!(keys.len(), 98);
+
+        let mut rng = rand::thread_rng();
+        let sampled: HashSet<String> = keys.sample(&mut rng, 20).into_iter().collect();
+        assert_eq!(sampled.len(), 20);
+        assert!(!sampled.contains("key-0"));
+        let all = keys.sample(&mut rng, 200);
+        assert_eq!(all.len(), 98);
+        for key in &all {
+            assert!(keys.remove(key));
+        }
+        assert!(keys.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::hash::HashObject;
+
+/// Sets field in the hash stored at key to value, only if field does not yet exist.
+///
+/// If key does not exist, a new key holding a hash is created.
+/// If field already exists, this operation has no effect.
+///
+/// Reply:
+/// - Integer reply: 0 if the field already exists in the hash and no operation was performed.
+/// - Integer reply: 1 if the field is a new field in the hash and the value was set.
+pub fn set_not_exist(db: &mut Db, key: String, field: String, value: Vec<u8>) -> ReplyFrame {
+    match db.entry(key) {
+        Entry::Occupied(mut occupied) => match occupied.get_mut() {
+            MemObject::Hash(old_hash) => {
+                if old_hash.contains_key(&field) {
+                    ReplyFrame::zero()
+                } else {
+                    old_hash.insert(field, value);
+                    ReplyFrame::one()
+                }
+            }
+            _ => ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(vacant) => {
+            let mut new_hash = HashObject::new();
+            new_hash.insert(field, value);
+            vacant.insert(MemObject::Hash(new_hash));
+            ReplyFrame::one()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::get::get;
+    use crate::mem::hash::set_not_exist::set_not_exist;
+
+    #[test]
+    fn test_set_not_exist() {
+        let mut db = Db::new();
+        let key = "myhash".to_owned();
+        let reply = set_not_exist(&mut db, key.clone(), "field".to_owned(), b"Hello".to_vec());
+        assert_eq!(reply, ReplyFrame::one());
+        let reply = set_not_exist(&mut db, key.clone(), "field".to_owned(), b"World".to_vec());
+        assert_eq!(reply, ReplyFrame::zero());
+        let reply = get(&db, &key, "field");
+        assert_eq!(reply, ReplyFrame::Bulk(b"Hello".to_vec()));
+    }
+}
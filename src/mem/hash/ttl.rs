@@ -0,0 +1,112 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the remaining TTL (time to live) of a hash key's field(s)
+/// that have a set expiration, in seconds.
+///
+/// Reply, one of the following:
+/// - Array reply: for each field
+///   - Integer reply: -2 if no such field exists in the provided hash key,
+///     or the provided key does not exist.
+///   - Integer reply: -1 if the field exists but has no associated expiration set.
+///   - Integer reply: the TTL in seconds.
+pub fn ttl(db: &Db, key: &str, fields: &[String], now: i64) -> ReplyFrame {
+    // Round up to seconds, same as redis.
+    generic_ttl(db, key, fields, |deadline| (deadline - now + 999) / 1000)
+}
+
+/// Like HTTL, this command returns the remaining TTL (time to live) of a field
+/// that has an expiration set, but in milliseconds instead of seconds.
+///
+/// Reply, one of the following:
+/// - Array reply: for each field
+///   - Integer reply: -2 if no such field exists in the provided hash key,
+///     or the provided key does not exist.
+///   - Integer reply: -1 if the field exists but has no associated expiration set.
+///   - Integer reply: the TTL in milliseconds.
+pub fn pttl(db: &Db, key: &str, fields: &[String], now: i64) -> ReplyFrame {
+    generic_ttl(db, key, fields, |deadline| deadline - now)
+}
+
+fn generic_ttl<F>(db: &Db, key: &str, fields: &[String], remaining: F) -> ReplyFrame
+where
+    F: Fn(i64) -> i64,
+{
+    match db.get(key) {
+        Some(MemObject::Hash(old_hash)) => {
+            let array = fields
+                .iter()
+                .map(|field| {
+                    if !old_hash.contains_key(field) {
+                        ReplyFrame::I64(-2)
+                    } else if let Some(deadline) = old_hash.expire_at(field) {
+                        ReplyFrame::I64(remaining(deadline).max(0))
+                    } else {
+                        ReplyFrame::I64(-1)
+                    }
+                })
+                .collect();
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::I64(-2); fields.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hash::ExpireTime;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hash::expire::expire;
+    use crate::mem::hash::set::set;
+    use crate::mem::hash::ttl::{pttl, ttl};
+
+    #[test]
+    fn test_ttl() {
+        let mut db = Db::new();
+        let key = "mykey".to_owned();
+        let now = 1_000_000;
+        let reply = set(
+            &mut db,
+            key.clone(),
+            vec![
+                ("field1".to_owned(), b"hello".to_vec()),
+                ("field2".to_owned(), b"world".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let fields = [
+            "field1".to_owned(),
+            "field2".to_owned(),
+            "field3".to_owned(),
+        ];
+        let reply = expire(
+            &mut db,
+            &key,
+            ExpireTime::Milliseconds(1500),
+            None,
+            &fields[..1],
+            now,
+        );
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(1)]));
+
+        let reply = ttl(&db, &key, &fields, now);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(2),
+                ReplyFrame::I64(-1),
+                ReplyFrame::I64(-2),
+            ])
+        );
+        let reply = pttl(&db, &key, &fields[..1], now + 500);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(1000)]));
+        let reply = ttl(&db, "nokey", &fields[..1], now);
+        assert_eq!(reply, ReplyFrame::Array(vec![ReplyFrame::I64(-2)]));
+    }
+}
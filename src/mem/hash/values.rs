@@ -12,14 +12,12 @@ use crate::mem::db::{Db, MemObject};
 pub fn values(db: &Db, key: &str) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::Hash(old_hash)) => {
-            let mut keys: Vec<&String> = old_hash.keys().collect();
-            keys.sort_unstable();
-            let mut array = Vec::new();
-            for field in keys {
-                if let Some(value) = old_hash.get(field) {
-                    array.push(ReplyFrame::Bulk(value.clone()));
-                }
-            }
+            let mut pairs: Vec<_> = old_hash.iter().collect();
+            pairs.sort_unstable();
+            let array = pairs
+                .into_iter()
+                .map(|(_field, value)| ReplyFrame::Bulk(value.to_vec()))
+                .collect();
             ReplyFrame::Array(array)
         }
         Some(_) => ReplyFrame::wrong_type_err(),
@@ -4,12 +4,11 @@
 
 use std::collections::hash_map::Entry;
 
-use hyperloglogplus::HyperLogLog;
-use stdext::function_name;
-
 use crate::cmd::reply_frame::ReplyFrame;
-use crate::mem::db::{Db, MemObject};
-use crate::mem::hyper::new_hyper_object;
+use crate::mem::db::Db;
+use crate::mem::hyper::consts::CORRUPTED_HLL_ERR;
+use crate::mem::hyper::{add_elements, as_hyper_mut, new_hyper};
+use crate::mem::string::StrObject;
 
 /// Adds all the element arguments to the `HyperLogLog` data structure stored
 /// at the variable name specified as first argument.
@@ -31,36 +30,18 @@ use crate::mem::hyper::new_hyper_object;
 /// - Integer reply: 1 if at least one `HyperLogLog` internal register was altered.
 /// - Integer reply: 0 if no `HyperLogLog` internal registers were altered.
 pub fn add(db: &mut Db, key: String, elements: &[String]) -> ReplyFrame {
-    match db.entry(key) {
-        Entry::Occupied(mut occupied) => match occupied.get_mut() {
-            MemObject::Hyper(old_hyper) => {
-                let current_count = old_hyper.count().trunc();
-                for element in elements {
-                    old_hyper.insert(element);
-                }
-                let new_count = old_hyper.count().trunc();
-
-                ReplyFrame::I64(i64::from(new_count > current_count))
-            }
-            _ => ReplyFrame::wrong_type_err(),
-        },
-        Entry::Vacant(vacant) => match new_hyper_object() {
-            Ok(mut new_hyper) => {
-                for element in elements {
-                    new_hyper.insert(element);
-                }
-                vacant.insert(MemObject::Hyper(new_hyper));
-                ReplyFrame::I64(1)
-            }
-            Err(err) => {
-                log::warn!(
-                    "{}, Failed to create new hyper object, err: {err:?}",
-                    function_name!()
-                );
-                ReplyFrame::internal_err()
-            }
-        },
-    }
+    let (object, created) = match db.entry(key) {
+        Entry::Occupied(occupied) => (occupied.into_mut(), false),
+        Entry::Vacant(vacant) => (vacant.insert(StrObject::from_bytes(new_hyper())), true),
+    };
+    let bytes = match as_hyper_mut(object) {
+        Ok(bytes) => bytes,
+        Err(reply_frame) => return reply_frame,
+    };
+    add_elements(bytes, elements.iter().map(String::as_bytes))
+        .map_or(ReplyFrame::ConstError(CORRUPTED_HLL_ERR), |updated| {
+            ReplyFrame::I64(i64::from(updated || created))
+        })
 }
 
 #[cfg(test)]
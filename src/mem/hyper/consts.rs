@@ -0,0 +1,8 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const INVALID_HLL_ERR: &str = "WRONGTYPE Key is not a valid HyperLogLog string value.";
+pub const CORRUPTED_HLL_ERR: &str = "INVALIDOBJ Corrupted HLL object detected";
+pub const KEY_NOT_EXIST_ERR: &str = "ERR The specified key does not exist";
+pub const NOT_SPARSE_ERR: &str = "ERR HLL encoding is not sparse";
@@ -2,11 +2,13 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use hyperloglogplus::HyperLogLog;
-
 use crate::cmd::reply_frame::ReplyFrame;
-use crate::mem::db::{Db, MemObject};
-use crate::mem::hyper::merge::merge_hyper_objects;
+use crate::mem::db::Db;
+use crate::mem::hyper::consts::CORRUPTED_HLL_ERR;
+use crate::mem::hyper::merge::merge_registers;
+use crate::mem::hyper::{
+    as_hyper_mut, cached_cardinality, cardinality, registers, set_cached_cardinality, HLL_REGISTERS,
+};
 
 /// When called with a single key, returns the approximated cardinality
 /// computed by the `HyperLogLog` data structure stored at the specified variable,
@@ -33,26 +35,38 @@ use crate::mem::hyper::merge::merge_hyper_objects;
 ///
 /// Reply:
 /// - Integer reply: the approximated number of unique elements observed via `PFADD`.
-#[allow(clippy::cast_possible_truncation)]
 pub fn count(db: &mut Db, key: &str, extra_keys: &[String]) -> ReplyFrame {
-    match db.get_mut(key) {
-        Some(MemObject::Hyper(old_hyper)) => {
-            if extra_keys.is_empty() {
-                let count: i64 = old_hyper.count().trunc() as i64;
-                return ReplyFrame::I64(count);
-            }
-
-            let mut merged_hyper = old_hyper.clone();
-            if let Err(reply_frame) = merge_hyper_objects(db, &mut merged_hyper, extra_keys) {
-                return reply_frame;
-            }
-
-            let merged_count: i64 = merged_hyper.count().trunc() as i64;
-            ReplyFrame::I64(merged_count)
+    if extra_keys.is_empty() {
+        let Some(object) = db.get_mut(key) else {
+            return ReplyFrame::I64(0);
+        };
+        let bytes = match as_hyper_mut(object) {
+            Ok(bytes) => bytes,
+            Err(reply_frame) => return reply_frame,
+        };
+        if let Some(card) = cached_cardinality(bytes) {
+            return card_to_reply(card);
         }
-        Some(_) => ReplyFrame::wrong_type_err(),
-        None => ReplyFrame::I64(0),
+        let Some(registers) = registers(bytes) else {
+            return ReplyFrame::ConstError(CORRUPTED_HLL_ERR);
+        };
+        let card = cardinality(&registers);
+        set_cached_cardinality(bytes, card);
+        return card_to_reply(card);
     }
+
+    let mut registers = vec![0; HLL_REGISTERS];
+    let keys: Vec<&str> = std::iter::once(key)
+        .chain(extra_keys.iter().map(String::as_str))
+        .collect();
+    if let Err(reply_frame) = merge_registers(db, &keys, &mut registers) {
+        return reply_frame;
+    }
+    card_to_reply(cardinality(&registers))
+}
+
+fn card_to_reply(card: u64) -> ReplyFrame {
+    ReplyFrame::I64(i64::try_from(card).unwrap_or(i64::MAX))
 }
 
 #[cfg(test)]
@@ -97,8 +111,6 @@ mod tests {
         assert_eq!(reply, ReplyFrame::I64(1));
 
         let reply = count(&mut db, &key, &[other_key]);
-        // FIXME(Shaohua): the merged result is wrong
-        //assert_eq!(reply, ReplyFrame::I64(6));
-        assert_eq!(reply, ReplyFrame::I64(7));
+        assert_eq!(reply, ReplyFrame::I64(6));
     }
 }
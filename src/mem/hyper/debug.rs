@@ -0,0 +1,111 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::hyper::DebugSubcommand;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::hyper::consts::{CORRUPTED_HLL_ERR, KEY_NOT_EXIST_ERR, NOT_SPARSE_ERR};
+use crate::mem::hyper::{
+    as_hyper_mut, encoding, registers, sparse, store_registers, Encoding, HLL_HDR_SIZE,
+};
+
+/// Internal commands for debugging `HyperLogLog` values.
+///
+/// Subcommands:
+/// - GETREG: returns values of all registers, sparse encoding is converted to dense.
+/// - DECODE: returns opcodes of sparse encoding.
+/// - ENCODING: returns `sparse` or `dense`.
+/// - TODENSE: converts sparse encoding to dense.
+///
+/// Reply:
+/// - Array reply: values of registers for `GETREG`.
+/// - Simple string reply: for `DECODE` and `ENCODING`.
+/// - Integer reply: 1 if converted, or 0 if already dense, for `TODENSE`.
+/// - Error reply: if key does not exist, or `DECODE` on dense encoding.
+pub fn debug(db: &mut Db, subcommand: DebugSubcommand, key: &str) -> ReplyFrame {
+    let Some(object) = db.get_mut(key) else {
+        return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR);
+    };
+    let bytes = match as_hyper_mut(object) {
+        Ok(bytes) => bytes,
+        Err(reply_frame) => return reply_frame,
+    };
+    let is_sparse = encoding(bytes) == Encoding::Sparse;
+    match subcommand {
+        DebugSubcommand::GetReg | DebugSubcommand::ToDense => {
+            let Some(registers) = registers(bytes) else {
+                return ReplyFrame::ConstError(CORRUPTED_HLL_ERR);
+            };
+            if is_sparse {
+                store_registers(bytes, &registers, false);
+            }
+            if subcommand == DebugSubcommand::ToDense {
+                return ReplyFrame::from_bool(is_sparse);
+            }
+            ReplyFrame::Array(
+                registers
+                    .into_iter()
+                    .map(|register| ReplyFrame::Usize(usize::from(register)))
+                    .collect(),
+            )
+        }
+        DebugSubcommand::Decode => {
+            if !is_sparse {
+                return ReplyFrame::ConstError(NOT_SPARSE_ERR);
+            }
+            sparse::describe(&bytes[HLL_HDR_SIZE..]).map_or(
+                ReplyFrame::ConstError(CORRUPTED_HLL_ERR),
+                ReplyFrame::Simple,
+            )
+        }
+        DebugSubcommand::Encoding => {
+            ReplyFrame::ConstSimple(if is_sparse { "sparse" } else { "dense" })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::hyper::DebugSubcommand;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::hyper::add::add;
+    use crate::mem::hyper::consts::{KEY_NOT_EXIST_ERR, NOT_SPARSE_ERR};
+    use crate::mem::hyper::debug::debug;
+    use crate::mem::hyper::HLL_REGISTERS;
+
+    #[test]
+    fn test_debug() {
+        let mut db = Db::new();
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::Encoding, "hll"),
+            ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)
+        );
+        add(&mut db, "hll".to_owned(), &[]);
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::Decode, "hll"),
+            ReplyFrame::Simple("Z:16384".to_owned())
+        );
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::ToDense, "hll"),
+            ReplyFrame::one()
+        );
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::ToDense, "hll"),
+            ReplyFrame::zero()
+        );
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::Encoding, "hll"),
+            ReplyFrame::ConstSimple("dense")
+        );
+        assert_eq!(
+            debug(&mut db, DebugSubcommand::Decode, "hll"),
+            ReplyFrame::ConstError(NOT_SPARSE_ERR)
+        );
+        let ReplyFrame::Array(registers) = debug(&mut db, DebugSubcommand::GetReg, "hll") else {
+            panic!("expected array");
+        };
+        assert_eq!(registers.len(), HLL_REGISTERS);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Dense encoding, registers of 6 bits are packed from the least significant bit
+//! of each byte.
+
+use crate::mem::hyper::{HLL_BITS, HLL_REGISTERS, HLL_REGISTER_MAX};
+
+/// Number of bytes of all registers.
+pub(super) const DENSE_BYTES: usize = (HLL_REGISTERS * HLL_BITS).div_ceil(8);
+
+#[allow(clippy::cast_possible_truncation)]
+pub(super) fn get_register(data: &[u8], index: usize) -> u8 {
+    let bit = index * HLL_BITS;
+    let byte = bit / 8;
+    let fb = bit % 8;
+    let b0 = u16::from(data[byte]);
+    let b1 = u16::from(data.get(byte + 1).copied().unwrap_or(0));
+    (((b0 >> fb) | (b1 << (8 - fb))) & u16::from(HLL_REGISTER_MAX)) as u8
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub(super) fn set_register(data: &mut [u8], index: usize, value: u8) {
+    let bit = index * HLL_BITS;
+    let byte = bit / 8;
+    let fb = bit % 8;
+    let max = u16::from(HLL_REGISTER_MAX);
+    let value = u16::from(value) & max;
+    data[byte] &= !((max << fb) as u8);
+    data[byte] |= (value << fb) as u8;
+    if let Some(next) = data.get_mut(byte + 1) {
+        *next &= !((max >> (8 - fb)) as u8);
+        *next |= (value >> (8 - fb)) as u8;
+    }
+}
+
+/// Set register to count if it is larger, returns true if register is altered.
+pub(super) fn update_register(data: &mut [u8], index: usize, count: u8) -> bool {
+    if get_register(data, index) < count {
+        set_register(data, index, count);
+        true
+    } else {
+        false
+    }
+}
+
+pub(super) fn to_registers(data: &[u8]) -> Vec<u8> {
+    (0..HLL_REGISTERS)
+        .map(|index| get_register(data, index))
+        .collect()
+}
+
+pub(super) fn from_registers(registers: &[u8]) -> Vec<u8> {
+    let mut data = vec![0; DENSE_BYTES];
+    for (index, &value) in registers.iter().enumerate() {
+        if value != 0 {
+            set_register(&mut data, index, value);
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::hyper::dense::{from_registers, get_register, to_registers, DENSE_BYTES};
+    use crate::mem::hyper::{HLL_REGISTERS, HLL_REGISTER_MAX};
+
+    #[test]
+    fn test_registers() {
+        #[allow(clippy::cast_possible_truncation)]
+        let registers: Vec<u8> = (0..HLL_REGISTERS)
+            .map(|index| (index * 7 % 64) as u8)
+            .collect();
+        let data = from_registers(&registers);
+        assert_eq!(data.len(), DENSE_BYTES);
+        assert_eq!(to_registers(&data), registers);
+        assert_eq!(
+            get_register(&data, HLL_REGISTERS - 1),
+            registers[HLL_REGISTERS - 1]
+        );
+
+        // The first register is stored in the lowest 6 bits of the first byte.
+        let mut registers = vec![0; HLL_REGISTERS];
+        registers[0] = HLL_REGISTER_MAX;
+        registers[1] = 1;
+        let data = from_registers(&registers);
+        assert_eq!(data[..2], [0b0111_1111, 0]);
+    }
+}
@@ -2,34 +2,39 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use stdext::function_name;
+use std::collections::hash_map::Entry;
 
 use crate::cmd::reply_frame::ReplyFrame;
-use crate::mem::db::{Db, MemObject};
-use crate::mem::hyper::{new_hyper_object, HyperObject};
+use crate::mem::db::Db;
+use crate::mem::hyper::consts::CORRUPTED_HLL_ERR;
+use crate::mem::hyper::{
+    as_hyper, as_hyper_mut, encoding, new_hyper, registers, store_registers, Encoding,
+    HLL_REGISTERS,
+};
+use crate::mem::string::StrObject;
 
-pub(super) fn merge_hyper_objects(
+/// Merge registers of `HyperLogLog` values at keys into `max_registers`,
+/// missing keys are skipped.
+///
+/// Returns true if any of them is in dense encoding.
+pub(super) fn merge_registers(
     db: &Db,
-    dest_hyper: &mut HyperObject,
-    source_keys: &[String],
-) -> Result<(), ReplyFrame> {
-    // FIXME(Shaohua): merge() does not work as expected.
-    for source_key in source_keys {
-        match db.get(source_key) {
-            Some(MemObject::Hyper(extra_hyper)) => {
-                if let Err(err) = dest_hyper.merge(extra_hyper) {
-                    log::warn!(
-                        "{} Failed to merge hyper logs, err: {err:?}",
-                        function_name!()
-                    );
-                    return Err(ReplyFrame::internal_err());
-                }
-            }
-            Some(_) => return Err(ReplyFrame::wrong_type_err()),
-            None => continue,
+    keys: &[&str],
+    max_registers: &mut [u8],
+) -> Result<bool, ReplyFrame> {
+    let mut has_dense = false;
+    for &key in keys {
+        let Some(object) = db.get(key) else {
+            continue;
+        };
+        let bytes = as_hyper(object)?;
+        has_dense |= encoding(bytes) == Encoding::Dense;
+        let registers = registers(bytes).ok_or(ReplyFrame::ConstError(CORRUPTED_HLL_ERR))?;
+        for (max, register) in max_registers.iter_mut().zip(registers) {
+            *max = (*max).max(register);
         }
     }
-    Ok(())
+    Ok(has_dense)
 }
 
 /// Merge multiple `HyperLogLog` values into a unique value that will approximate
@@ -44,34 +49,26 @@ pub(super) fn merge_hyper_objects(
 /// Reply:
 /// - Simple string reply: OK.
 pub fn merge(db: &mut Db, dest_key: String, source_keys: &[String]) -> ReplyFrame {
-    match db.get(&dest_key) {
-        Some(MemObject::Hyper(old_hyper)) => {
-            let mut new_hyper = old_hyper.clone();
-            if let Err(reply_frame) = merge_hyper_objects(db, &mut new_hyper, source_keys) {
-                reply_frame
-            } else {
-                // FIXME(Shaohua): Do not clone hyper object.
-                db.insert(dest_key, MemObject::Hyper(new_hyper));
-                ReplyFrame::ok()
-            }
+    let mut registers = vec![0; HLL_REGISTERS];
+    let keys: Vec<&str> = std::iter::once(dest_key.as_str())
+        .chain(source_keys.iter().map(String::as_str))
+        .collect();
+    let has_dense = match merge_registers(db, &keys, &mut registers) {
+        Ok(has_dense) => has_dense,
+        Err(reply_frame) => return reply_frame,
+    };
+
+    let object = match db.entry(dest_key) {
+        Entry::Occupied(occupied) => occupied.into_mut(),
+        Entry::Vacant(vacant) => vacant.insert(StrObject::from_bytes(new_hyper())),
+    };
+    match as_hyper_mut(object) {
+        Ok(bytes) => {
+            // Keep sparse encoding only if all of the sources are sparse.
+            store_registers(bytes, &registers, !has_dense);
+            ReplyFrame::ok()
         }
-        Some(_) => ReplyFrame::wrong_type_err(),
-        None => match new_hyper_object() {
-            Ok(mut new_hyper) => {
-                if let Err(reply_frame) = merge_hyper_objects(db, &mut new_hyper, source_keys) {
-                    return reply_frame;
-                }
-                db.insert(dest_key, MemObject::Hyper(new_hyper));
-                ReplyFrame::ok()
-            }
-            Err(err) => {
-                log::warn!(
-                    "{}, Failed to create new hyper object, err: {err:?}",
-                    function_name!()
-                );
-                ReplyFrame::internal_err()
-            }
-        },
+        Err(reply_frame) => reply_frame,
     }
 }
 
@@ -117,8 +114,6 @@ mod tests {
         assert_eq!(reply, ReplyFrame::ok());
 
         let reply = count(&mut db, &key3, &[]);
-        // FIXME(Shaohua): Merge() failed.
-        //assert_eq!(reply, ReplyFrame::I64(6));
-        assert_eq!(reply, ReplyFrame::I64(8));
+        assert_eq!(reply, ReplyFrame::I64(6));
     }
 }
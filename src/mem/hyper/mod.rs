@@ -2,50 +2,297 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use std::hash::RandomState;
-
-use hyperloglogplus::{HyperLogLogError, HyperLogLogPlus};
+//! `HyperLogLog` stored in a string value, in the same format as redis.
+//!
+//! The string starts with a 16 bytes header:
+//! - 4 bytes magic `HYLL`
+//! - 1 byte encoding, `HLL_DENSE` or `HLL_SPARSE`
+//! - 3 bytes unused
+//! - 8 bytes cached cardinality in little endian, the most significant bit
+//!   set means the cache is invalid.
+//!
+//! Then follows 16384 registers of 6 bits, in dense or sparse encoding.
 
 use crate::cmd::hyper::HyperLogLogCommand;
 use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::MemObject;
+use crate::mem::util::murmur_hash64a;
 use crate::mem::Mem;
 
 pub mod add;
+mod consts;
 pub mod count;
+pub mod debug;
+mod dense;
 pub mod merge;
+pub mod self_test;
+mod sparse;
+
+/// Number of bits of hash used to select register.
+const HLL_P: u32 = 14;
+/// Number of bits of hash used to count leading zeros.
+const HLL_Q: usize = 64 - HLL_P as usize;
+pub const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_P_MASK: u64 = HLL_REGISTERS as u64 - 1;
+const HLL_BITS: usize = 6;
+const HLL_REGISTER_MAX: u8 = (1 << HLL_BITS) - 1;
+const HLL_HDR_SIZE: usize = 16;
+const HLL_DENSE_SIZE: usize = HLL_HDR_SIZE + (HLL_REGISTERS * HLL_BITS).div_ceil(8);
+const HLL_MAGIC: &[u8] = b"HYLL";
+const HLL_HASH_SEED: u64 = 0xadc8_3b19;
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+/// Sparse encoding is promoted to dense if it is larger than this size, in bytes.
+const HLL_SPARSE_MAX_BYTES: usize = 3000;
 
-pub type HyperObject = HyperLogLogPlus<String, RandomState>;
+const HLL_DENSE: u8 = 0;
+const HLL_SPARSE: u8 = 1;
 
-#[inline]
-fn new_hyper_object() -> Result<HyperObject, HyperLogLogError> {
-    HyperLogLogPlus::new(18, RandomState::new())
+/// Offset of encoding byte in header.
+const ENCODING_OFFSET: usize = 4;
+/// Offset of cached cardinality in header.
+const CARD_OFFSET: usize = 8;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Encoding {
+    Dense,
+    Sparse,
 }
 
 impl Mem {
-    #[allow(clippy::needless_pass_by_value)]
     pub fn handle_hyper_command(&mut self, command: HyperLogLogCommand) -> ReplyFrame {
         match command {
             HyperLogLogCommand::Add(key, elements) => add::add(&mut self.db, key, &elements),
             HyperLogLogCommand::Count(keys) => count::count(&mut self.db, &keys[0], &keys[1..]),
+            HyperLogLogCommand::Debug(subcommand, key) => {
+                debug::debug(&mut self.db, subcommand, &key)
+            }
             HyperLogLogCommand::Merge(dest_key, source_keys) => {
                 merge::merge(&mut self.db, dest_key, &source_keys)
             }
+            HyperLogLogCommand::SelfTest => self_test::self_test(),
+        }
+    }
+}
+
+/// Returns an empty `HyperLogLog` in sparse encoding.
+#[must_use]
+pub fn new_hyper() -> Vec<u8> {
+    let mut bytes = header(Encoding::Sparse);
+    bytes.extend_from_slice(&sparse::from_registers(&[0; HLL_REGISTERS]).unwrap_or_default());
+    bytes
+}
+
+fn header(encoding: Encoding) -> Vec<u8> {
+    let mut bytes = vec![0; HLL_HDR_SIZE];
+    bytes[..HLL_MAGIC.len()].copy_from_slice(HLL_MAGIC);
+    bytes[ENCODING_OFFSET] = match encoding {
+        Encoding::Dense => HLL_DENSE,
+        Encoding::Sparse => HLL_SPARSE,
+    };
+    bytes
+}
+
+/// Check header of string value.
+#[must_use]
+pub fn is_valid(bytes: &[u8]) -> bool {
+    bytes.len() >= HLL_HDR_SIZE
+        && bytes.starts_with(HLL_MAGIC)
+        && match bytes[ENCODING_OFFSET] {
+            HLL_DENSE => bytes.len() == HLL_DENSE_SIZE,
+            HLL_SPARSE => true,
+            _ => false,
+        }
+}
+
+/// Returns encoding of a valid `HyperLogLog`.
+#[must_use]
+pub fn encoding(bytes: &[u8]) -> Encoding {
+    if bytes[ENCODING_OFFSET] == HLL_DENSE {
+        Encoding::Dense
+    } else {
+        Encoding::Sparse
+    }
+}
+
+/// Returns string value at key, or an error reply if it is not a `HyperLogLog`.
+fn as_hyper(object: &MemObject) -> Result<&Vec<u8>, ReplyFrame> {
+    match object {
+        MemObject::Str(str_obj) if is_valid(&str_obj.vec) => Ok(&str_obj.vec),
+        MemObject::Str(_) => Err(ReplyFrame::ConstError(consts::INVALID_HLL_ERR)),
+        _ => Err(ReplyFrame::wrong_type_err()),
+    }
+}
+
+fn as_hyper_mut(object: &mut MemObject) -> Result<&mut Vec<u8>, ReplyFrame> {
+    match object {
+        MemObject::Str(str_obj) if is_valid(&str_obj.vec) => Ok(&mut str_obj.vec),
+        MemObject::Str(_) => Err(ReplyFrame::ConstError(consts::INVALID_HLL_ERR)),
+        _ => Err(ReplyFrame::wrong_type_err()),
+    }
+}
+
+/// Returns values of all registers, or None if sparse encoding is corrupted.
+#[must_use]
+pub fn registers(bytes: &[u8]) -> Option<Vec<u8>> {
+    match encoding(bytes) {
+        Encoding::Dense => Some(dense::to_registers(&bytes[HLL_HDR_SIZE..])),
+        Encoding::Sparse => sparse::to_registers(&bytes[HLL_HDR_SIZE..]),
+    }
+}
+
+/// Replace registers, in sparse encoding if `prefer_sparse` is true and
+/// registers fit in it, or else in dense encoding.
+pub fn store_registers(bytes: &mut Vec<u8>, registers: &[u8], prefer_sparse: bool) {
+    let (encoding, data) = prefer_sparse
+        .then(|| sparse::from_registers(registers))
+        .flatten()
+        .map_or_else(
+            || (Encoding::Dense, dense::from_registers(registers)),
+            |data| (Encoding::Sparse, data),
+        );
+    bytes.truncate(HLL_HDR_SIZE);
+    bytes[..HLL_HDR_SIZE].copy_from_slice(&header(encoding));
+    bytes.extend_from_slice(&data);
+    invalidate_cache(bytes);
+}
+
+/// Add elements, returns true if any register is altered, or None if sparse
+/// encoding is corrupted.
+pub fn add_elements<'a, I>(bytes: &mut Vec<u8>, elements: I) -> Option<bool>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut updated = false;
+    match encoding(bytes) {
+        Encoding::Dense => {
+            for element in elements {
+                let (index, count) = pattern(element);
+                updated |= dense::update_register(&mut bytes[HLL_HDR_SIZE..], index, count);
+            }
+        }
+        Encoding::Sparse => {
+            let mut registers = sparse::to_registers(&bytes[HLL_HDR_SIZE..])?;
+            for element in elements {
+                let (index, count) = pattern(element);
+                if registers[index] < count {
+                    registers[index] = count;
+                    updated = true;
+                }
+            }
+            if updated {
+                store_registers(bytes, &registers, true);
+            }
+        }
+    }
+    if updated {
+        invalidate_cache(bytes);
+    }
+    Some(updated)
+}
+
+#[must_use]
+pub fn cached_cardinality(bytes: &[u8]) -> Option<u64> {
+    let card = u64::from_le_bytes(bytes[CARD_OFFSET..HLL_HDR_SIZE].try_into().ok()?);
+    (card >> 63 == 0).then_some(card)
+}
+
+pub fn set_cached_cardinality(bytes: &mut [u8], card: u64) {
+    bytes[CARD_OFFSET..HLL_HDR_SIZE].copy_from_slice(&card.to_le_bytes());
+}
+
+fn invalidate_cache(bytes: &mut [u8]) {
+    bytes[HLL_HDR_SIZE - 1] |= 1 << 7;
+}
+
+/// Returns index of register and number of trailing zeros plus 1 of hash.
+#[allow(clippy::cast_possible_truncation)]
+fn pattern(element: &[u8]) -> (usize, u8) {
+    let hash = murmur_hash64a(element, HLL_HASH_SEED);
+    let index = (hash & HLL_P_MASK) as usize;
+    // Make sure the loop terminates and count is at most Q + 1.
+    let hash = (hash >> HLL_P) | (1 << HLL_Q);
+    (index, hash.trailing_zeros() as u8 + 1)
+}
+
+/// Estimate cardinality of registers, with the estimator of Otmar Ertl,
+/// same as redis.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+pub fn cardinality(registers: &[u8]) -> u64 {
+    let mut histogram = [0_usize; 64];
+    for &register in registers {
+        histogram[usize::from(register & HLL_REGISTER_MAX)] += 1;
+    }
+    let m = HLL_REGISTERS as f64;
+    let mut z = m * tau((m - histogram[HLL_Q + 1] as f64) / m);
+    for &count in histogram[1..=HLL_Q].iter().rev() {
+        z += count as f64;
+        z *= 0.5;
+    }
+    z += m * sigma(histogram[0] as f64 / m);
+    (HLL_ALPHA_INF * m * m / z).round() as u64
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if (x - 1.0).abs() < f64::EPSILON {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let z_prime = z;
+        z += x * y;
+        y += y;
+        if (z_prime - z).abs() <= 0.0 {
+            return z;
         }
     }
 }
 
-pub fn to_reply_frame(_hyper_object: &HyperObject) -> ReplyFrame {
-    todo!()
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || (x - 1.0).abs() < f64::EPSILON {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let z_prime = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if (z_prime - z).abs() <= 0.0 {
+            return z / 3.0;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::mem::size_of;
-
-    use crate::mem::hyper::HyperObject;
+    use crate::mem::hyper::{
+        add_elements, cardinality, encoding, is_valid, new_hyper, registers, Encoding,
+    };
 
     #[test]
-    fn test_hyper_log_log_object() {
-        assert_eq!(size_of::<HyperObject>(), 32);
+    fn test_hyper_log_log() {
+        let mut hyper = new_hyper();
+        assert!(is_valid(&hyper));
+        assert_eq!(hyper.len(), 18);
+        assert_eq!(encoding(&hyper), Encoding::Sparse);
+        assert_eq!(cardinality(&registers(&hyper).unwrap()), 0);
+
+        let elements: Vec<String> = (0..1000).map(|index| index.to_string()).collect();
+        assert_eq!(
+            add_elements(&mut hyper, elements.iter().map(String::as_bytes)),
+            Some(true)
+        );
+        assert_eq!(
+            add_elements(&mut hyper, elements.iter().map(String::as_bytes)),
+            Some(false)
+        );
+        let count = cardinality(&registers(&hyper).unwrap());
+        assert!((990..=1010).contains(&count));
     }
 }
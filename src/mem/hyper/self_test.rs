@@ -0,0 +1,106 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use rand::Rng;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::hyper::dense::{get_register, set_register, DENSE_BYTES};
+use crate::mem::hyper::{
+    add_elements, cardinality, new_hyper, registers, store_registers, HLL_REGISTERS,
+    HLL_REGISTER_MAX,
+};
+
+/// Number of elements added to `HyperLogLog` in self test.
+const NUM_ELEMENTS: usize = 100_000;
+
+/// Internal command for testing `HyperLogLog` implementation.
+///
+/// Tests:
+/// - Read and write registers in dense encoding.
+/// - Estimated cardinality of sparse and dense encodings are the same,
+///   and error of estimation is in expected range.
+///
+/// Reply:
+/// - Simple string reply: `OK` if all tests passed.
+/// - Error reply: description of the failed test.
+pub fn self_test() -> ReplyFrame {
+    match test_registers().and_then(|()| test_approximation()) {
+        Ok(()) => ReplyFrame::ok(),
+        Err(err) => ReplyFrame::Error(err),
+    }
+}
+
+fn test_registers() -> Result<(), String> {
+    let mut rng = rand::thread_rng();
+    let mut data = vec![0; DENSE_BYTES];
+    for _ in 0..10 {
+        let expected: Vec<u8> = (0..HLL_REGISTERS)
+            .map(|_| rng.gen_range(0..=HLL_REGISTER_MAX))
+            .collect();
+        for (index, &value) in expected.iter().enumerate() {
+            set_register(&mut data, index, value);
+        }
+        for (index, &value) in expected.iter().enumerate() {
+            let register = get_register(&data, index);
+            if register != value {
+                return Err(format!(
+                    "TESTFAILED Register error, register {index}, expected {value}, got {register}"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn test_approximation() -> Result<(), String> {
+    let mut sparse = new_hyper();
+    let mut dense = new_hyper();
+    store_registers(&mut dense, &vec![0; HLL_REGISTERS], false);
+
+    let relative_error = 1.04 / (HLL_REGISTERS as f64).sqrt();
+    let mut checkpoint = 1;
+    for index in 1..=NUM_ELEMENTS {
+        let element = index.to_string();
+        for bytes in [&mut sparse, &mut dense] {
+            add_elements(bytes, [element.as_bytes()])
+                .ok_or_else(|| "TESTFAILED Corrupted HLL object".to_owned())?;
+        }
+        if index != checkpoint {
+            continue;
+        }
+        let estimated = registers(&sparse).map(|registers| cardinality(&registers));
+        if estimated != registers(&dense).map(|registers| cardinality(&registers)) {
+            return Err(format!(
+                "TESTFAILED Sparse and dense encodings differ at {checkpoint}"
+            ));
+        }
+        let estimated = estimated.unwrap_or_default();
+        let max_error = match checkpoint {
+            1..=10 => 1,
+            100 => 2,
+            _ => (relative_error * 6.0 * checkpoint as f64).ceil() as u64,
+        };
+        if estimated.abs_diff(checkpoint as u64) > max_error {
+            return Err(format!(
+                "TESTFAILED Too big error, cardinality {checkpoint}, estimated {estimated}"
+            ));
+        }
+        checkpoint *= 10;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::hyper::self_test::self_test;
+
+    #[test]
+    fn test_self_test() {
+        assert_eq!(self_test(), ReplyFrame::ok());
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Sparse encoding, registers are run-length encoded with three opcodes:
+//! - `ZERO`, `00xxxxxx`: 1 to 64 registers of value 0.
+//! - `XZERO`, `01xxxxxx yyyyyyyy`: 1 to 16384 registers of value 0.
+//! - `VAL`, `1vvvvvxx`: 1 to 4 registers of value 1 to 32.
+
+use std::fmt::Write;
+
+use crate::mem::hyper::{HLL_HDR_SIZE, HLL_REGISTERS, HLL_SPARSE_MAX_BYTES};
+
+const ZERO_MAX_LEN: usize = 64;
+const XZERO_MAX_LEN: usize = HLL_REGISTERS;
+const VAL_MAX_VALUE: u8 = 32;
+const VAL_MAX_LEN: usize = 4;
+
+const XZERO_BIT: u8 = 0x40;
+const VAL_BIT: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum Opcode {
+    Zero(usize),
+    XZero(usize),
+    /// Value and number of registers.
+    Val(u8, usize),
+}
+
+/// Decode opcodes, returns None if an opcode is truncated.
+pub(super) fn decode(data: &[u8]) -> Option<Vec<Opcode>> {
+    let mut opcodes = Vec::new();
+    let mut iter = data.iter();
+    while let Some(&byte) = iter.next() {
+        let opcode = if byte & VAL_BIT != 0 {
+            Opcode::Val(((byte >> 2) & 0x1f) + 1, usize::from(byte & 0x03) + 1)
+        } else if byte & XZERO_BIT != 0 {
+            let low = *iter.next()?;
+            Opcode::XZero((usize::from(byte & 0x3f) << 8 | usize::from(low)) + 1)
+        } else {
+            Opcode::Zero(usize::from(byte & 0x3f) + 1)
+        };
+        opcodes.push(opcode);
+    }
+    Some(opcodes)
+}
+
+/// Returns opcodes in text, like `Z:16000 v:2,1 z:10`.
+pub(super) fn describe(data: &[u8]) -> Option<String> {
+    let mut text = String::new();
+    for opcode in decode(data)? {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        let _ = match opcode {
+            Opcode::Zero(len) => write!(text, "z:{len}"),
+            Opcode::XZero(len) => write!(text, "Z:{len}"),
+            Opcode::Val(value, len) => write!(text, "v:{value},{len}"),
+        };
+    }
+    Some(text)
+}
+
+/// Returns values of registers, or None if number of registers is invalid.
+pub(super) fn to_registers(data: &[u8]) -> Option<Vec<u8>> {
+    let mut registers = Vec::with_capacity(HLL_REGISTERS);
+    for opcode in decode(data)? {
+        let (value, len) = match opcode {
+            Opcode::Zero(len) | Opcode::XZero(len) => (0, len),
+            Opcode::Val(value, len) => (value, len),
+        };
+        if registers.len() + len > HLL_REGISTERS {
+            return None;
+        }
+        registers.resize(registers.len() + len, value);
+    }
+    (registers.len() == HLL_REGISTERS).then_some(registers)
+}
+
+/// Encode registers, returns None if any register is too large for sparse
+/// encoding, or encoded `HyperLogLog` is larger than `HLL_SPARSE_MAX_BYTES`.
+#[allow(clippy::cast_possible_truncation)]
+pub(super) fn from_registers(registers: &[u8]) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut start = 0;
+    while start < registers.len() {
+        let value = registers[start];
+        let run = registers[start..]
+            .iter()
+            .take_while(|&&register| register == value)
+            .count();
+        let mut remaining = run;
+        while remaining > 0 {
+            if value == 0 && remaining > ZERO_MAX_LEN {
+                let len = remaining.min(XZERO_MAX_LEN);
+                data.push(XZERO_BIT | ((len - 1) >> 8) as u8);
+                data.push((len - 1) as u8);
+                remaining -= len;
+            } else if value == 0 {
+                data.push((remaining - 1) as u8);
+                remaining = 0;
+            } else if value <= VAL_MAX_VALUE {
+                let len = remaining.min(VAL_MAX_LEN);
+                data.push(VAL_BIT | ((value - 1) << 2) | (len - 1) as u8);
+                remaining -= len;
+            } else {
+                return None;
+            }
+        }
+        if HLL_HDR_SIZE + data.len() > HLL_SPARSE_MAX_BYTES {
+            return None;
+        }
+        start += run;
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::hyper::sparse::{describe, from_registers, to_registers};
+    use crate::mem::hyper::HLL_REGISTERS;
+
+    #[test]
+    fn test_sparse() {
+        let mut registers = vec![0; HLL_REGISTERS];
+        assert_eq!(from_registers(&registers), Some(vec![0x7f, 0xff]));
+
+        registers[1000] = 3;
+        registers[1001] = 3;
+        registers[1010] = 32;
+        let data = from_registers(&registers).unwrap();
+        assert_eq!(describe(&data).unwrap(), "Z:1000 v:3,2 z:8 v:32,1 Z:15373");
+        assert_eq!(to_registers(&data).unwrap(), registers);
+
+        registers[0] = 33;
+        assert_eq!(from_registers(&registers), None);
+        assert_eq!(to_registers(&[0x7f]), None);
+        assert_eq!(to_registers(&[0x00]), None);
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! A sorted array of integers, similar to redis intset.
+//!
+//! All integers are stored in a contiguous buffer with the same width,
+//! which is 2, 4 or 8 bytes in little endian. The buffer is upgraded to a larger
+//! width when a new integer does not fit, and is never downgraded.
+
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IntSet {
+    width: usize,
+    buf: Vec<u8>,
+}
+
+impl Default for IntSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntSet {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            width: 2,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Returns number of integers in set.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len() / self.width
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns number of bytes used by integers.
+    #[must_use]
+    #[inline]
+    pub fn byte_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<i64> {
+        let start = index.checked_mul(self.width)?;
+        let bytes = self.buf.get(start..start + self.width)?;
+        let value = match self.width {
+            2 => i64::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+            4 => i64::from(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+            _ => i64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]),
+        };
+        Some(value)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, value: i64) -> bool {
+        self.search(value).is_ok()
+    }
+
+    /// Insert an integer, returns false if it is already in set.
+    pub fn insert(&mut self, value: i64) -> bool {
+        let width = Self::width_of(value);
+        if width > self.width {
+            self.upgrade(width);
+        }
+        match self.search(value) {
+            Ok(_) => false,
+            Err(index) => {
+                let start = index * self.width;
+                let bytes = Self::encode(value, self.width);
+                self.buf.splice(start..start, bytes);
+                true
+            }
+        }
+    }
+
+    /// Remove an integer, returns false if it is not in set.
+    pub fn remove(&mut self, value: i64) -> bool {
+        match self.search(value) {
+            Ok(index) => {
+                let start = index * self.width;
+                self.buf.drain(start..start + self.width);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns an iterator of integers in ascending order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = i64> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Binary search for `value`, same as `slice::binary_search()`.
+    fn search(&self, value: i64) -> Result<usize, usize> {
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid).map(|mid_value| mid_value.cmp(&value)) {
+                Some(Ordering::Less) => low = mid + 1,
+                Some(Ordering::Greater) => high = mid,
+                _ => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    fn width_of(value: i64) -> usize {
+        if i16::try_from(value).is_ok() {
+            2
+        } else if i32::try_from(value).is_ok() {
+            4
+        } else {
+            8
+        }
+    }
+
+    fn encode(value: i64, width: usize) -> Vec<u8> {
+        value.to_le_bytes()[..width].to_vec()
+    }
+
+    fn upgrade(&mut self, width: usize) {
+        let mut buf = Vec::with_capacity(self.len() * width + width);
+        for value in self.iter() {
+            buf.extend_from_slice(&Self::encode(value, width));
+        }
+        self.width = width;
+        self.buf = buf;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntSet;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = IntSet::new();
+        assert!(set.insert(5));
+        assert!(set.insert(-3));
+        assert!(set.insert(100));
+        assert!(!set.insert(5));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.byte_len(), 6);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![-3, 5, 100]);
+
+        assert!(set.insert(i64::from(i32::MAX)));
+        assert_eq!(set.byte_len(), 16);
+        assert!(set.insert(i64::MIN));
+        assert_eq!(set.byte_len(), 40);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![i64::MIN, -3, 5, 100, i64::from(i32::MAX)]
+        );
+
+        assert!(set.contains(100));
+        assert!(set.remove(100));
+        assert!(!set.remove(100));
+        assert!(!set.contains(100));
+        assert_eq!(set.get(3), Some(i64::from(i32::MAX)));
+        assert_eq!(set.get(4), None);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{parse_values, update_each};
+
+/// Append the JSON values into the array at path after its last element.
+///
+/// Reply:
+/// - Array reply: the new length of each array matched by `JSONPath`, or nil
+///   if the matched value is not an array.
+/// - Integer reply: the new length of array, with legacy path.
+/// - Error reply: if key does not exist, or legacy path does not match an array.
+pub fn arr_append(db: &mut Db, key: &str, path: &str, values: &[String]) -> ReplyFrame {
+    let values = match parse_values(values) {
+        Ok(values) => values,
+        Err(err) => return err,
+    };
+    let results = update_each(db, key, path, "array", |value| match value {
+        Value::Array(array) => {
+            array.extend(values.iter().cloned());
+            Some(array.len())
+        }
+        _ => None,
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_append::arr_append;
+
+    #[test]
+    fn test_arr_append() {
+        let mut db = Db::new();
+        let values = vec!["1".to_owned(), r#""a""#.to_owned()];
+        assert!(matches!(
+            arr_append(&mut db, "doc", "$", &values),
+            ReplyFrame::ConstError(_)
+        ));
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [], "b": {"a": 1}})),
+        );
+        assert_eq!(
+            arr_append(&mut db, "doc", "$..a", &values),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(2), ReplyFrame::Null])
+        );
+        assert_eq!(
+            arr_append(&mut db, "doc", ".a", &values),
+            ReplyFrame::Usize(4)
+        );
+        assert!(matches!(
+            arr_append(&mut db, "doc", ".b", &values),
+            ReplyFrame::Error(_)
+        ));
+    }
+}
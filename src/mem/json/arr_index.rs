@@ -0,0 +1,82 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{clamp_index, parse_value, read_each};
+
+/// Search for the first occurrence of a JSON value in the array at path.
+///
+/// The search range is `[start, stop)`, negative index counts from the end
+/// of array, and `stop` of 0 means the end of array.
+///
+/// Reply:
+/// - Array reply: position of value in each array matched by `JSONPath`,
+///   -1 if not found, or nil if the matched value is not an array.
+/// - Integer reply: position of value, with legacy path.
+/// - Nil reply: if key does not exist.
+pub fn arr_index(
+    db: &Db,
+    key: &str,
+    path: &str,
+    value: &str,
+    start: isize,
+    stop: isize,
+) -> ReplyFrame {
+    let value = match parse_value(value) {
+        Ok(value) => value,
+        Err(err) => return err,
+    };
+    let results = read_each(db, key, path, "array", |item| match item {
+        Value::Array(array) => Some(index_of(array, &value, start, stop)),
+        _ => None,
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::I64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn index_of(array: &[Value], value: &Value, start: isize, stop: isize) -> i64 {
+    let len = array.len();
+    let start = clamp_index(start, len);
+    let stop = if stop == 0 {
+        len
+    } else {
+        clamp_index(stop, len)
+    };
+    if start >= stop {
+        return -1;
+    }
+    array[start..stop]
+        .iter()
+        .position(|item| item == value)
+        .map_or(-1, |pos| (start + pos) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_index::arr_index;
+
+    #[test]
+    fn test_arr_index() {
+        let mut db = Db::new();
+        assert_eq!(arr_index(&db, "doc", "$", "1", 0, 0), ReplyFrame::Null);
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [1, 2, 3, 2], "b": "s"})),
+        );
+        assert_eq!(arr_index(&db, "doc", ".a", "2", 0, 0), ReplyFrame::I64(1));
+        assert_eq!(arr_index(&db, "doc", ".a", "2", 2, 0), ReplyFrame::I64(3));
+        assert_eq!(arr_index(&db, "doc", ".a", "2", 2, -1), ReplyFrame::I64(-1));
+        assert_eq!(
+            arr_index(&db, "doc", "$.*", "3", 0, 0),
+            ReplyFrame::Array(vec![ReplyFrame::I64(2), ReplyFrame::Null])
+        );
+    }
+}
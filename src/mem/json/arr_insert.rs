@@ -0,0 +1,89 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::consts::INDEX_OUT_OF_BOUNDS_ERR;
+use crate::mem::json::{parse_values, update_each};
+
+/// Insert the JSON values into the array at path before the index.
+///
+/// Negative index counts from the end of array.
+///
+/// Reply:
+/// - Array reply: the new length of each array matched by `JSONPath`, or nil
+///   if the matched value is not an array.
+/// - Integer reply: the new length of array, with legacy path.
+/// - Error reply: if key does not exist, or index is out of range.
+pub fn arr_insert(
+    db: &mut Db,
+    key: &str,
+    path: &str,
+    index: isize,
+    values: &[String],
+) -> ReplyFrame {
+    let values = match parse_values(values) {
+        Ok(values) => values,
+        Err(err) => return err,
+    };
+    let results = update_each(db, key, path, "array", |value| match value {
+        Value::Array(array) => Some(insert_values(array, index, &values)),
+        _ => None,
+    });
+    results.map_or_else(
+        |err| err,
+        |results| results.into_reply(|result| result.unwrap_or_else(|err| err)),
+    )
+}
+
+#[allow(clippy::cast_possible_wrap)]
+#[allow(clippy::cast_sign_loss)]
+fn insert_values(
+    array: &mut Vec<Value>,
+    index: isize,
+    values: &[Value],
+) -> Result<ReplyFrame, ReplyFrame> {
+    let len = array.len() as isize;
+    let pos = if index < 0 { index + len } else { index };
+    if pos < 0 || pos > len {
+        return Err(ReplyFrame::ConstError(INDEX_OUT_OF_BOUNDS_ERR));
+    }
+    let pos = pos as usize;
+    array.splice(pos..pos, values.iter().cloned());
+    Ok(ReplyFrame::Usize(array.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_insert::arr_insert;
+
+    #[test]
+    fn test_arr_insert() {
+        let mut db = Db::new();
+        db.insert("doc".to_owned(), MemObject::Json(json!({"a": [1, 4]})));
+        let values = vec!["2".to_owned(), "3".to_owned()];
+        assert_eq!(
+            arr_insert(&mut db, "doc", "$.a", -1, &values),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(4)])
+        );
+        assert!(matches!(
+            arr_insert(&mut db, "doc", ".a", 5, &values),
+            ReplyFrame::ConstError(_)
+        ));
+        assert_eq!(
+            arr_insert(&mut db, "doc", ".a", 4, &["5".to_owned()]),
+            ReplyFrame::Usize(5)
+        );
+        let Some(MemObject::Json(root)) = db.get("doc") else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"a": [1, 2, 3, 4, 5]}));
+    }
+}
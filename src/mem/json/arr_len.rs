@@ -0,0 +1,46 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{read_each, DEFAULT_PATH};
+
+/// Report the length of the array at path.
+///
+/// Reply:
+/// - Array reply: length of each array matched by `JSONPath`, or nil if the
+///   matched value is not an array.
+/// - Integer reply: length of array, with legacy path.
+/// - Nil reply: if key does not exist.
+pub fn arr_len(db: &Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let results = read_each(db, key, path.unwrap_or(DEFAULT_PATH), "array", |value| {
+        value.as_array().map(Vec::len)
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_len::arr_len;
+
+    #[test]
+    fn test_arr_len() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [1, 2], "b": {"a": [3]}, "c": 1})),
+        );
+        assert_eq!(
+            arr_len(&db, "doc", Some("$..a")),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(2), ReplyFrame::Usize(1)])
+        );
+        assert_eq!(arr_len(&db, "doc", Some("b.a")), ReplyFrame::Usize(1));
+        assert!(matches!(arr_len(&db, "doc", None), ReplyFrame::Error(_)));
+        assert_eq!(arr_len(&db, "none", None), ReplyFrame::Null);
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::json::FormatOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{clamp_index, to_json_string, update_each, DEFAULT_PATH};
+
+/// Remove and return an element from the index in the array at path.
+///
+/// Index defaults to -1, the last element. Negative index counts from the end
+/// of array, and out of range index is rounded to the start or the end of array.
+///
+/// Reply:
+/// - Array reply: the popped JSON value of each array matched by `JSONPath`,
+///   or nil if the matched value is not an array or is empty.
+/// - Bulk string reply: the popped JSON value, or nil if array is empty, with legacy path.
+/// - Error reply: if key does not exist.
+pub fn arr_pop(db: &mut Db, key: &str, path: Option<&str>, index: isize) -> ReplyFrame {
+    let results = update_each(db, key, path.unwrap_or(DEFAULT_PATH), "array", |value| {
+        value.as_array_mut().map(|array| pop(array, index))
+    });
+    let options = FormatOptions::default();
+    results.map_or_else(
+        |err| err,
+        |results| {
+            results.into_reply(|popped| {
+                popped.map_or(ReplyFrame::Null, |value| {
+                    ReplyFrame::bulk(to_json_string(&value, &options).into_bytes())
+                })
+            })
+        },
+    )
+}
+
+fn pop(array: &mut Vec<Value>, index: isize) -> Option<Value> {
+    if array.is_empty() {
+        return None;
+    }
+    let pos = clamp_index(index, array.len()).min(array.len() - 1);
+    Some(array.remove(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_pop::arr_pop;
+
+    #[test]
+    fn test_arr_pop() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [1, [2], 3], "b": []})),
+        );
+        assert_eq!(
+            arr_pop(&mut db, "doc", Some(".a"), -1),
+            ReplyFrame::bulk(b"3".to_vec())
+        );
+        assert_eq!(
+            arr_pop(&mut db, "doc", Some("$.*"), 10),
+            ReplyFrame::Array(vec![ReplyFrame::bulk(b"[2]".to_vec()), ReplyFrame::Null])
+        );
+        assert_eq!(
+            arr_pop(&mut db, "doc", Some(".a"), -10),
+            ReplyFrame::bulk(b"1".to_vec())
+        );
+        assert_eq!(arr_pop(&mut db, "doc", Some(".a"), 0), ReplyFrame::Null);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::update_each;
+
+/// Trim the array at path so that it contains only the specified inclusive
+/// range of elements.
+///
+/// Negative index counts from the end of array. If `start` is larger than
+/// the end of array or `stop`, array is emptied.
+///
+/// Reply:
+/// - Array reply: the new length of each array matched by `JSONPath`, or nil
+///   if the matched value is not an array.
+/// - Integer reply: the new length of array, with legacy path.
+/// - Error reply: if key does not exist.
+pub fn arr_trim(db: &mut Db, key: &str, path: &str, start: isize, stop: isize) -> ReplyFrame {
+    let results = update_each(db, key, path, "array", |value| {
+        value.as_array_mut().map(|array| trim(array, start, stop))
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+#[allow(clippy::cast_sign_loss)]
+fn trim(array: &mut Vec<Value>, start: isize, stop: isize) -> usize {
+    let len = array.len() as isize;
+    let start = if start < 0 { start + len } else { start }.max(0);
+    let stop = if stop < 0 { stop + len } else { stop }.min(len - 1);
+    if start >= len || start > stop {
+        array.clear();
+    } else {
+        array.truncate(stop as usize + 1);
+        array.drain(..start as usize);
+    }
+    array.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::arr_trim::arr_trim;
+
+    #[test]
+    fn test_arr_trim() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [0, 1, 2, 3, 4], "b": [1]})),
+        );
+        assert_eq!(arr_trim(&mut db, "doc", ".a", 1, -2), ReplyFrame::Usize(3));
+        assert_eq!(
+            arr_trim(&mut db, "doc", "$.*", 1, 100),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(2), ReplyFrame::Usize(0)])
+        );
+        let Some(MemObject::Json(root)) = db.get("doc") else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"a": [2, 3], "b": []}));
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::json::parse_path;
+use crate::mem::json::path::get_mut;
+
+/// Clear container values (arrays and objects) and set numeric values to 0.
+///
+/// Values of other types matched by path are left untouched.
+///
+/// Reply:
+/// - Integer reply: the number of values cleared.
+/// - Error reply: if key does not exist, or is not a JSON document.
+pub fn clear(db: &mut Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let path = match parse_path(path.unwrap_or("$")) {
+        Ok(path) => path,
+        Err(err) => return err,
+    };
+    let root = match db.get_mut(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    };
+    let count = path
+        .find_paths(root)
+        .iter()
+        .filter(|location| get_mut(root, location).is_some_and(clear_value))
+        .count();
+    ReplyFrame::Usize(count)
+}
+
+fn clear_value(value: &mut Value) -> bool {
+    match value {
+        Value::Array(array) => array.clear(),
+        Value::Object(map) => map.clear(),
+        Value::Number(_) => *value = Value::from(0),
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::clear::clear;
+
+    #[test]
+    fn test_clear() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [1, 2], "b": {"c": 1}, "d": 1.5, "e": "s"})),
+        );
+        assert_eq!(clear(&mut db, "doc", Some("$.*")), ReplyFrame::Usize(3));
+        let Some(MemObject::Json(root)) = db.get("doc") else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"a": [], "b": {}, "d": 0, "e": "s"}));
+        assert_eq!(clear(&mut db, "doc", None), ReplyFrame::Usize(1));
+        assert!(matches!(
+            clear(&mut db, "none", None),
+            ReplyFrame::ConstError(_)
+        ));
+    }
+}
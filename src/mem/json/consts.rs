@@ -0,0 +1,12 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const NEW_AT_ROOT_ERR: &str = "ERR new objects must be created at the root";
+pub const WRONG_STATIC_PATH_ERR: &str = "ERR wrong static path";
+pub const KEY_NOT_EXIST_ERR: &str =
+    "ERR could not perform this operation on a key that doesn't exist";
+pub const INDEX_OUT_OF_BOUNDS_ERR: &str = "ERR index out of bounds";
+pub const NOT_NUMBER_ERR: &str = "ERR expected a JSON number";
+pub const NOT_STRING_ERR: &str = "ERR expected a JSON string";
+pub const NUMBER_OVERFLOW_ERR: &str = "ERR result of number operation overflows";
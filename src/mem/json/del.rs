@@ -0,0 +1,69 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::parse_path;
+use crate::mem::json::path::remove;
+
+/// Delete values matched by path.
+///
+/// If path is omitted or is the root, the key is deleted.
+///
+/// Reply:
+/// - Integer reply: the number of paths deleted, 0 if key does not exist.
+/// - Error reply: if key is not a JSON document, or path is invalid.
+pub fn del(db: &mut Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let path = match parse_path(path.unwrap_or("$")) {
+        Ok(path) => path,
+        Err(err) => return err,
+    };
+    let root = match db.get_mut(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::zero(),
+    };
+    if path.is_root() {
+        db.remove(key);
+        return ReplyFrame::one();
+    }
+
+    let mut paths = path.find_paths(root);
+    // Remove from the last one, so that indexes of array are still valid.
+    paths.sort_unstable();
+    paths.dedup();
+    let count = paths
+        .iter()
+        .rev()
+        .filter(|location| remove(root, location))
+        .count();
+    ReplyFrame::Usize(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::del::del;
+
+    #[test]
+    fn test_del() {
+        let mut db = Db::new();
+        assert_eq!(del(&mut db, "doc", None), ReplyFrame::zero());
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": [1, 2, 3], "b": {"a": 1}})),
+        );
+        assert_eq!(del(&mut db, "doc", Some("$..a[0,2]")), ReplyFrame::Usize(2));
+        assert_eq!(del(&mut db, "doc", Some("$..a")), ReplyFrame::Usize(2));
+        let Some(MemObject::Json(root)) = db.get("doc") else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"b": {}}));
+        assert_eq!(del(&mut db, "doc", Some(".")), ReplyFrame::one());
+        assert!(db.is_empty());
+    }
+}
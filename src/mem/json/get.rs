@@ -0,0 +1,108 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::{Map, Value};
+
+use crate::cmd::json::FormatOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::path::JsonPath;
+use crate::mem::json::{eval_path, parse_path, to_json_string, DEFAULT_PATH};
+
+/// Return the value at path in JSON serialized form.
+///
+/// If no path is given, the root of document is returned.
+/// With a single `JSONPath`, an array of matched values is returned.
+/// With multiple paths, a JSON object with paths as keys is returned.
+/// If any of the paths is `JSONPath`, all paths are treated as `JSONPath`.
+///
+/// Reply:
+/// - Bulk string reply: the JSON serialized value.
+/// - Nil reply: if key does not exist.
+/// - Error reply: if legacy path does not exist, or key is not a JSON document.
+pub fn get(db: &Db, key: &str, options: &FormatOptions, paths: &[String]) -> ReplyFrame {
+    let root = match db.get(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Null,
+    };
+    let path_strs: Vec<&str> = if paths.is_empty() {
+        vec![DEFAULT_PATH]
+    } else {
+        paths.iter().map(String::as_str).collect()
+    };
+    let parsed_paths = match path_strs
+        .iter()
+        .map(|path| parse_path(path))
+        .collect::<Result<Vec<JsonPath>, ReplyFrame>>()
+    {
+        Ok(paths) => paths,
+        Err(err) => return err,
+    };
+
+    let value = if parsed_paths.len() == 1 {
+        eval_path(root, path_strs[0], &parsed_paths[0])
+    } else {
+        let is_legacy = parsed_paths.iter().all(JsonPath::is_legacy);
+        let mut map = Map::new();
+        path_strs
+            .iter()
+            .zip(&parsed_paths)
+            .try_for_each(|(path_str, path)| {
+                let value = if is_legacy {
+                    eval_path(root, path_str, path)?
+                } else {
+                    Value::Array(path.find_values(root).into_iter().cloned().collect())
+                };
+                map.insert((*path_str).to_owned(), value);
+                Ok(())
+            })
+            .map(|()| Value::Object(map))
+    };
+    match value {
+        Ok(value) => ReplyFrame::bulk(to_json_string(&value, options).into_bytes()),
+        Err(err) => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::json::FormatOptions;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::get::get;
+
+    fn get_str(db: &Db, paths: &[&str]) -> ReplyFrame {
+        let paths: Vec<String> = paths.iter().map(|&path| path.to_owned()).collect();
+        get(db, "doc", &FormatOptions::default(), &paths)
+    }
+
+    #[test]
+    fn test_get() {
+        let mut db = Db::new();
+        assert_eq!(get_str(&db, &[]), ReplyFrame::Null);
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": 1, "b": {"a": 2}})),
+        );
+        assert_eq!(
+            get_str(&db, &[]),
+            ReplyFrame::bulk(br#"{"a":1,"b":{"a":2}}"#.to_vec())
+        );
+        assert_eq!(get_str(&db, &["$..a"]), ReplyFrame::bulk(b"[1,2]".to_vec()));
+        assert_eq!(get_str(&db, &[".b.a"]), ReplyFrame::bulk(b"2".to_vec()));
+        assert_eq!(
+            get_str(&db, &["a", "b.a"]),
+            ReplyFrame::bulk(br#"{"a":1,"b.a":2}"#.to_vec())
+        );
+        assert_eq!(
+            get_str(&db, &["a", "$.c"]),
+            ReplyFrame::bulk(br#"{"a":[1],"$.c":[]}"#.to_vec())
+        );
+        assert!(matches!(get_str(&db, &[".c"]), ReplyFrame::Error(_)));
+        assert!(matches!(get_str(&db, &["$["]), ReplyFrame::Error(_)));
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::{parse_path, type_name, DEFAULT_PATH};
+
+/// Report the type of JSON value at path.
+///
+/// The types are `object`, `array`, `string`, `integer`, `number`, `boolean` and `null`.
+///
+/// Reply:
+/// - Array reply: types of every matched value, with `JSONPath`.
+/// - Simple string reply: type of the first matched value, with legacy path.
+/// - Nil reply: if key or legacy path does not exist.
+pub fn get_type(db: &Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let path = match parse_path(path.unwrap_or(DEFAULT_PATH)) {
+        Ok(path) => path,
+        Err(err) => return err,
+    };
+    let root = match db.get(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Null,
+    };
+    let values = path.find_values(root);
+    if path.is_legacy() {
+        values.first().map_or(ReplyFrame::Null, |value| {
+            ReplyFrame::ConstSimple(type_name(value))
+        })
+    } else {
+        ReplyFrame::Array(
+            values
+                .into_iter()
+                .map(|value| ReplyFrame::bulk(type_name(value).as_bytes().to_vec()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::get_type::get_type;
+
+    #[test]
+    fn test_get_type() {
+        let mut db = Db::new();
+        assert_eq!(get_type(&db, "doc", None), ReplyFrame::Null);
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": 1, "b": 1.5, "c": [null, true, "s"]})),
+        );
+        assert_eq!(
+            get_type(&db, "doc", None),
+            ReplyFrame::ConstSimple("object")
+        );
+        assert_eq!(
+            get_type(&db, "doc", Some(".b")),
+            ReplyFrame::ConstSimple("number")
+        );
+        assert_eq!(get_type(&db, "doc", Some(".d")), ReplyFrame::Null);
+        assert_eq!(
+            get_type(&db, "doc", Some("$.c[*]")),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"null".to_vec()),
+                ReplyFrame::bulk(b"boolean".to_vec()),
+                ReplyFrame::bulk(b"string".to_vec()),
+            ])
+        );
+    }
+}
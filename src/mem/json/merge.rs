@@ -0,0 +1,138 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::{Map, Value};
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::consts::NEW_AT_ROOT_ERR;
+use crate::mem::json::path::{get_mut, remove, JsonPath};
+use crate::mem::json::{parse_path, parse_value};
+
+/// Merge a JSON value into the values at path, following RFC 7396.
+///
+/// Members of patch object with null value are deleted from target, others
+/// are merged recursively. A patch which is not an object replaces target.
+/// If path does not exist but its parent object does, a new member is added.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key does not exist and path is not the root, if key is
+///   not a JSON document, or path or value is invalid.
+pub fn merge(db: &mut Db, key: String, path: &str, value: &str) -> ReplyFrame {
+    let json_path = match parse_path(path) {
+        Ok(json_path) => json_path,
+        Err(err) => return err,
+    };
+    let merge_value = match parse_value(value) {
+        Ok(merge_value) => merge_value,
+        Err(err) => return err,
+    };
+    match db.get_mut(&key) {
+        Some(MemObject::Json(root)) => {
+            merge_at(root, &json_path, &merge_value);
+            ReplyFrame::ok()
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => {
+            if !json_path.is_root() {
+                return ReplyFrame::ConstError(NEW_AT_ROOT_ERR);
+            }
+            let mut value = Value::Null;
+            merge_patch(&mut value, &merge_value);
+            db.insert(key, MemObject::Json(value));
+            ReplyFrame::ok()
+        }
+    }
+}
+
+fn merge_at(root: &mut Value, json_path: &JsonPath, patch: &Value) {
+    let mut locations = json_path.find_paths(root);
+    if locations.is_empty() {
+        if patch.is_null() {
+            return;
+        }
+        if let Some((parent, key)) = json_path.split_last_key() {
+            for location in parent.find_paths(root) {
+                if let Some(Value::Object(map)) = get_mut(root, &location) {
+                    let mut value = Value::Null;
+                    merge_patch(&mut value, patch);
+                    map.insert(key.to_owned(), value);
+                }
+            }
+        }
+    } else if patch.is_null() {
+        // Null patch deletes the target.
+        locations.sort_unstable();
+        locations.dedup();
+        for location in locations.iter().rev() {
+            remove(root, location);
+        }
+    } else {
+        for location in &locations {
+            if let Some(target) = get_mut(root, location) {
+                merge_patch(target, patch);
+            }
+        }
+    }
+}
+
+/// Apply merge patch to target, as defined in RFC 7396.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_map) = patch else {
+        target.clone_from(patch);
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = target {
+        for (key, value) in patch_map {
+            if value.is_null() {
+                map.shift_remove(key);
+            } else {
+                merge_patch(map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::merge::{merge, merge_patch};
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({"a": "b", "c": {"d": "e", "f": "g"}});
+        merge_patch(&mut target, &json!({"a": "z", "c": {"f": null}}));
+        assert_eq!(target, json!({"a": "z", "c": {"d": "e"}}));
+        let mut target = json!([1]);
+        merge_patch(&mut target, &json!({"a": {"b": null, "c": 1}}));
+        assert_eq!(target, json!({"a": {"c": 1}}));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut db = Db::new();
+        let key = "doc".to_owned();
+        assert!(matches!(
+            merge(&mut db, key.clone(), "$.a", "1"),
+            ReplyFrame::ConstError(_)
+        ));
+        assert_eq!(
+            merge(&mut db, key.clone(), "$", r#"{"a": 1, "b": null}"#),
+            ReplyFrame::ok()
+        );
+        assert_eq!(merge(&mut db, key.clone(), "$.b", "[2]"), ReplyFrame::ok());
+        assert_eq!(merge(&mut db, key.clone(), "$.a", "null"), ReplyFrame::ok());
+        let Some(MemObject::Json(root)) = db.get(&key) else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"b": [2]}));
+    }
+}
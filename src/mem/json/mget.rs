@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::json::FormatOptions;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::{eval_path, parse_path, to_json_string};
+
+/// Return the values at path from multiple keys.
+///
+/// Reply:
+/// - Array reply: JSON serialized value at path of each key, or nil if key
+///   does not exist, is not a JSON document or legacy path does not exist.
+pub fn mget(db: &Db, keys: &[String], path_str: &str) -> ReplyFrame {
+    let path = match parse_path(path_str) {
+        Ok(path) => path,
+        Err(err) => return err,
+    };
+    let options = FormatOptions::default();
+    let values = keys
+        .iter()
+        .map(|key| match db.get(key) {
+            Some(MemObject::Json(root)) => eval_path(root, path_str, &path)
+                .map_or(ReplyFrame::Null, |value| {
+                    ReplyFrame::bulk(to_json_string(&value, &options).into_bytes())
+                }),
+            _ => ReplyFrame::Null,
+        })
+        .collect();
+    ReplyFrame::Array(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::mget::mget;
+
+    #[test]
+    fn test_mget() {
+        let mut db = Db::new();
+        db.insert("a".to_owned(), MemObject::Json(json!({"x": 1})));
+        db.insert("b".to_owned(), MemObject::Json(json!({"y": 2})));
+        let keys = vec!["a".to_owned(), "b".to_owned(), "c".to_owned()];
+        assert_eq!(
+            mget(&db, &keys, "$.x"),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"[1]".to_vec()),
+                ReplyFrame::bulk(b"[]".to_vec()),
+                ReplyFrame::Null,
+            ])
+        );
+        assert_eq!(
+            mget(&db, &keys, ".x"),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"1".to_vec()),
+                ReplyFrame::Null,
+                ReplyFrame::Null,
+            ])
+        );
+    }
+}
@@ -1,3 +1,311 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::json::{FormatOptions, JsonCommand};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::json::path::{get_mut, JsonPath};
+use crate::mem::Mem;
+
+pub mod arr_append;
+pub mod arr_index;
+pub mod arr_insert;
+pub mod arr_len;
+pub mod arr_pop;
+pub mod arr_trim;
+pub mod clear;
+mod consts;
+pub mod del;
+pub mod get;
+pub mod get_type;
+pub mod merge;
+pub mod mget;
+pub mod num_incr_by;
+pub mod num_mult_by;
+pub mod obj_keys;
+pub mod obj_len;
+pub mod path;
+pub mod set;
+pub mod str_append;
+pub mod str_len;
+pub mod toggle;
+
+/// JSON document.
+pub type JsonObject = Value;
+
+/// Path used when path argument is omitted.
+const DEFAULT_PATH: &str = ".";
+
+/// Results of values matched by path.
+enum PathResults<T> {
+    /// Result of the first matched value of legacy path.
+    Legacy(T),
+    /// Result of each matched value of `JSONPath`, None if type of value mismatch.
+    Each(Vec<Option<T>>),
+}
+
+impl<T> PathResults<T> {
+    /// Convert to reply, one reply per matched value for `JSONPath`, nil if type mismatch.
+    fn into_reply<F: Fn(T) -> ReplyFrame>(self, to_reply: F) -> ReplyFrame {
+        match self {
+            Self::Legacy(result) => to_reply(result),
+            Self::Each(results) => ReplyFrame::Array(
+                results
+                    .into_iter()
+                    .map(|result| result.map_or(ReplyFrame::Null, &to_reply))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Mem {
+    pub fn handle_json_command(&mut self, command: JsonCommand) -> ReplyFrame {
+        match command {
+            JsonCommand::ArrAppend(key, path, values) => {
+                arr_append::arr_append(&mut self.db, &key, &path, &values)
+            }
+            JsonCommand::ArrIndex(key, path, value, start, stop) => {
+                arr_index::arr_index(&self.db, &key, &path, &value, start, stop)
+            }
+            JsonCommand::ArrInsert(key, path, index, values) => {
+                arr_insert::arr_insert(&mut self.db, &key, &path, index, &values)
+            }
+            JsonCommand::ArrLen(key, path) => arr_len::arr_len(&self.db, &key, path.as_deref()),
+            JsonCommand::ArrPop(key, path, index) => {
+                arr_pop::arr_pop(&mut self.db, &key, path.as_deref(), index)
+            }
+            JsonCommand::ArrTrim(key, path, start, stop) => {
+                arr_trim::arr_trim(&mut self.db, &key, &path, start, stop)
+            }
+            JsonCommand::Clear(key, path) => clear::clear(&mut self.db, &key, path.as_deref()),
+            JsonCommand::Del(key, path) => del::del(&mut self.db, &key, path.as_deref()),
+            JsonCommand::Get(key, options, paths) => get::get(&self.db, &key, &options, &paths),
+            JsonCommand::Merge(key, path, value) => merge::merge(&mut self.db, key, &path, &value),
+            JsonCommand::MGet(keys, path) => mget::mget(&self.db, &keys, &path),
+            JsonCommand::NumIncrBy(key, path, value) => {
+                num_incr_by::num_incr_by(&mut self.db, &key, &path, &value)
+            }
+            JsonCommand::NumMultBy(key, path, value) => {
+                num_mult_by::num_mult_by(&mut self.db, &key, &path, &value)
+            }
+            JsonCommand::ObjKeys(key, path) => obj_keys::obj_keys(&self.db, &key, path.as_deref()),
+            JsonCommand::ObjLen(key, path) => obj_len::obj_len(&self.db, &key, path.as_deref()),
+            JsonCommand::Set(options) => set::set(&mut self.db, *options),
+            JsonCommand::StrAppend(key, path, value) => {
+                str_append::str_append(&mut self.db, &key, path.as_deref(), &value)
+            }
+            JsonCommand::StrLen(key, path) => str_len::str_len(&self.db, &key, path.as_deref()),
+            JsonCommand::Toggle(key, path) => toggle::toggle(&mut self.db, &key, &path),
+            JsonCommand::Type(key, path) => get_type::get_type(&self.db, &key, path.as_deref()),
+        }
+    }
+}
+
+/// Parse `JSONPath`, or returns error reply if path is invalid.
+fn parse_path(path: &str) -> Result<JsonPath, ReplyFrame> {
+    JsonPath::parse(path).ok_or_else(|| ReplyFrame::Error(format!("ERR invalid JSONPath '{path}'")))
+}
+
+fn path_not_exist_err(path: &str) -> ReplyFrame {
+    ReplyFrame::Error(format!("ERR Path '{path}' does not exist"))
+}
+
+/// Returns values matched by path, as a JSON array for `JSONPath`, or the first
+/// matched value for legacy path.
+fn eval_path(root: &Value, path_str: &str, path: &JsonPath) -> Result<Value, ReplyFrame> {
+    let values = path.find_values(root);
+    if path.is_legacy() {
+        values
+            .first()
+            .map(|&value| value.clone())
+            .ok_or_else(|| path_not_exist_err(path_str))
+    } else {
+        Ok(Value::Array(values.into_iter().cloned().collect()))
+    }
+}
+
+fn wrong_path_type_err(expected: &str, found: &str) -> ReplyFrame {
+    ReplyFrame::Error(format!(
+        "ERR wrong type of path value - expected {expected} but found {found}"
+    ))
+}
+
+/// Parse value in JSON format, or returns error reply if value is invalid.
+fn parse_value(value: &str) -> Result<Value, ReplyFrame> {
+    serde_json::from_str(value).map_err(|err| ReplyFrame::Error(format!("ERR {err}")))
+}
+
+/// Parse values in JSON format, or returns error reply if any value is invalid.
+fn parse_values(values: &[String]) -> Result<Vec<Value>, ReplyFrame> {
+    values.iter().map(|value| parse_value(value)).collect()
+}
+
+/// Convert index which may be negative to offset from the start, clamped to `[0, len]`.
+#[allow(clippy::cast_possible_wrap)]
+#[allow(clippy::cast_sign_loss)]
+fn clamp_index(index: isize, len: usize) -> usize {
+    let len = len as isize;
+    let index = if index < 0 { index + len } else { index };
+    index.clamp(0, len) as usize
+}
+
+/// Returns type name of JSON value.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Apply `f` to values matched by path.
+///
+/// `f` returns None if type of value is not the `expected` one.
+/// Returns error reply if key does not exist, or legacy path does not match
+/// a value of `expected` type.
+fn update_each<T, F>(
+    db: &mut Db,
+    key: &str,
+    path_str: &str,
+    expected: &str,
+    mut f: F,
+) -> Result<PathResults<T>, ReplyFrame>
+where
+    F: FnMut(&mut Value) -> Option<T>,
+{
+    let path = parse_path(path_str)?;
+    let root = match db.get_mut(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return Err(ReplyFrame::wrong_type_err()),
+        None => return Err(ReplyFrame::ConstError(KEY_NOT_EXIST_ERR)),
+    };
+    let locations = path.find_paths(root);
+    if path.is_legacy() {
+        let value = locations
+            .first()
+            .and_then(|location| get_mut(root, location))
+            .ok_or_else(|| path_not_exist_err(path_str))?;
+        let found = type_name(value);
+        return f(value)
+            .map(PathResults::Legacy)
+            .ok_or_else(|| wrong_path_type_err(expected, found));
+    }
+    Ok(PathResults::Each(
+        locations
+            .iter()
+            .map(|location| get_mut(root, location).and_then(&mut f))
+            .collect(),
+    ))
+}
+
+/// Apply `f` to values matched by path, without modifying them.
+///
+/// Returns nil reply as error if key does not exist.
+fn read_each<T, F>(
+    db: &Db,
+    key: &str,
+    path_str: &str,
+    expected: &str,
+    f: F,
+) -> Result<PathResults<T>, ReplyFrame>
+where
+    F: Fn(&Value) -> Option<T>,
+{
+    let path = parse_path(path_str)?;
+    let root = match db.get(key) {
+        Some(MemObject::Json(root)) => root,
+        Some(_) => return Err(ReplyFrame::wrong_type_err()),
+        None => return Err(ReplyFrame::Null),
+    };
+    let values = path.find_values(root);
+    if path.is_legacy() {
+        let value = values.first().ok_or_else(|| path_not_exist_err(path_str))?;
+        return f(value)
+            .map(PathResults::Legacy)
+            .ok_or_else(|| wrong_path_type_err(expected, type_name(value)));
+    }
+    Ok(PathResults::Each(values.into_iter().map(f).collect()))
+}
+
+/// Serialize value to string with indentation, newline and space between key and value.
+#[must_use]
+pub fn to_json_string(value: &Value, options: &FormatOptions) -> String {
+    let mut out = String::new();
+    write_value(value, options, 0, &mut out);
+    out
+}
+
+fn write_value(value: &Value, options: &FormatOptions, level: usize, out: &mut String) {
+    match value {
+        Value::Array(array) if !array.is_empty() => {
+            out.push('[');
+            for (index, item) in array.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_line_start(options, level + 1, out);
+                write_value(item, options, level + 1, out);
+            }
+            write_line_start(options, level, out);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push('{');
+            for (index, (key, item)) in map.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_line_start(options, level + 1, out);
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                out.push_str(&options.space);
+                write_value(item, options, level + 1, out);
+            }
+            write_line_start(options, level, out);
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+fn write_line_start(options: &FormatOptions, level: usize, out: &mut String) {
+    out.push_str(&options.newline);
+    for _ in 0..level {
+        out.push_str(&options.indent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::json::FormatOptions;
+    use crate::mem::json::to_json_string;
+
+    #[test]
+    fn test_to_json_string() {
+        let value = json!({"a": [1, 2], "b": {}, "c": "x"});
+        assert_eq!(
+            to_json_string(&value, &FormatOptions::default()),
+            r#"{"a":[1,2],"b":{},"c":"x"}"#
+        );
+        let options = FormatOptions {
+            indent: "  ".to_owned(),
+            newline: "\n".to_owned(),
+            space: " ".to_owned(),
+        };
+        assert_eq!(
+            to_json_string(&value, &options),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {},\n  \"c\": \"x\"\n}"
+        );
+    }
+}
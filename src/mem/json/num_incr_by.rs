@@ -0,0 +1,133 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::{Number, Value};
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::consts::{NOT_NUMBER_ERR, NUMBER_OVERFLOW_ERR};
+use crate::mem::json::{parse_value, read_each, update_each, PathResults};
+
+/// Increment the number values at path by the given number.
+///
+/// Reply:
+/// - Bulk string reply: JSON array of new value of each number matched by
+///   `JSONPath`, with null if the matched value is not a number.
+/// - Bulk string reply: the new value, with legacy path.
+/// - Error reply: if key does not exist, value is not a number, or result overflows.
+pub fn num_incr_by(db: &mut Db, key: &str, path: &str, value: &str) -> ReplyFrame {
+    update_number(db, key, path, value, i64::checked_add, |a, b| a + b)
+}
+
+/// Update number values at path with `int_op`, or `float_op` if any operand
+/// is float or the integer operation overflows.
+pub(super) fn update_number(
+    db: &mut Db,
+    key: &str,
+    path: &str,
+    value: &str,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> ReplyFrame {
+    let Ok(Value::Number(operand)) = parse_value(value) else {
+        return ReplyFrame::ConstError(NOT_NUMBER_ERR);
+    };
+    // Check results before any value is updated, so that no value is changed if any overflows.
+    let overflows = read_each(db, key, path, "number", |value| match value {
+        Value::Number(number) => Some(apply_number(number, &operand, int_op, float_op).is_none()),
+        _ => None,
+    });
+    let overflows = match overflows {
+        Ok(PathResults::Legacy(overflow)) => overflow,
+        Ok(PathResults::Each(overflows)) => overflows.contains(&Some(true)),
+        Err(_) => false,
+    };
+    if overflows {
+        return ReplyFrame::ConstError(NUMBER_OVERFLOW_ERR);
+    }
+    let results = update_each(db, key, path, "number", |value| {
+        let Value::Number(number) = value else {
+            return None;
+        };
+        let result = apply_number(number, &operand, int_op, float_op)?;
+        number.clone_from(&result);
+        Some(result)
+    });
+    match results {
+        Ok(PathResults::Legacy(number)) => ReplyFrame::bulk(number.to_string().into_bytes()),
+        Ok(PathResults::Each(numbers)) => {
+            let numbers = numbers
+                .into_iter()
+                .map(|number| number.map_or(Value::Null, Value::Number))
+                .collect();
+            ReplyFrame::bulk(Value::Array(numbers).to_string().into_bytes())
+        }
+        Err(err) => err,
+    }
+}
+
+/// Returns None if result is not a finite number.
+fn apply_number(
+    number: &Number,
+    operand: &Number,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> Option<Number> {
+    if let Some(result) = number
+        .as_i64()
+        .zip(operand.as_i64())
+        .and_then(|(a, b)| int_op(a, b))
+    {
+        return Some(result.into());
+    }
+    Number::from_f64(float_op(number.as_f64()?, operand.as_f64()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::consts::NUMBER_OVERFLOW_ERR;
+    use crate::mem::json::num_incr_by::num_incr_by;
+
+    #[test]
+    fn test_num_incr_by() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": 1, "b": {"a": 1.5}, "c": {"a": "s"}})),
+        );
+        assert_eq!(
+            num_incr_by(&mut db, "doc", "$..a", "2"),
+            ReplyFrame::bulk(b"[3,3.5,null]".to_vec())
+        );
+        assert_eq!(
+            num_incr_by(&mut db, "doc", ".a", "-1"),
+            ReplyFrame::bulk(b"2".to_vec())
+        );
+        assert!(matches!(
+            num_incr_by(&mut db, "doc", ".c.a", "1"),
+            ReplyFrame::Error(_)
+        ));
+        assert!(matches!(
+            num_incr_by(&mut db, "doc", ".a", "x"),
+            ReplyFrame::ConstError(_)
+        ));
+
+        db.insert(
+            "big".to_owned(),
+            MemObject::Json(json!({"a": 1, "b": 1.7e308})),
+        );
+        assert_eq!(
+            num_incr_by(&mut db, "big", "$.*", "1.7e308"),
+            ReplyFrame::ConstError(NUMBER_OVERFLOW_ERR)
+        );
+        assert_eq!(
+            num_incr_by(&mut db, "big", ".a", "0"),
+            ReplyFrame::bulk(b"1".to_vec())
+        );
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::num_incr_by::update_number;
+
+/// Multiply the number values at path by the given number.
+///
+/// Reply:
+/// - Bulk string reply: JSON array of new value of each number matched by
+///   `JSONPath`, with null if the matched value is not a number.
+/// - Bulk string reply: the new value, with legacy path.
+/// - Error reply: if key does not exist, or value is not a number.
+pub fn num_mult_by(db: &mut Db, key: &str, path: &str, value: &str) -> ReplyFrame {
+    update_number(db, key, path, value, i64::checked_mul, |a, b| a * b)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::num_mult_by::num_mult_by;
+
+    #[test]
+    fn test_num_mult_by() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": 2, "b": i64::MAX})),
+        );
+        assert_eq!(
+            num_mult_by(&mut db, "doc", "$.a", "3"),
+            ReplyFrame::bulk(b"[6]".to_vec())
+        );
+        assert_eq!(
+            num_mult_by(&mut db, "doc", ".a", "0.5"),
+            ReplyFrame::bulk(b"3.0".to_vec())
+        );
+        assert_eq!(
+            num_mult_by(&mut db, "doc", ".b", "2"),
+            ReplyFrame::bulk(b"1.8446744073709552e19".to_vec())
+        );
+    }
+}
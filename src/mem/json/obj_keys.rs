@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{read_each, DEFAULT_PATH};
+
+/// Return the keys in the object at path.
+///
+/// Reply:
+/// - Array reply: keys of each object matched by `JSONPath`, or nil if the
+///   matched value is not an object.
+/// - Array reply: keys of object, with legacy path.
+/// - Nil reply: if key does not exist.
+pub fn obj_keys(db: &Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let results = read_each(db, key, path.unwrap_or(DEFAULT_PATH), "object", |value| {
+        value.as_object().map(|map| {
+            map.keys()
+                .map(|key| ReplyFrame::bulk(key.as_bytes().to_vec()))
+                .collect()
+        })
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Array))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::obj_keys::obj_keys;
+
+    #[test]
+    fn test_obj_keys() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"b": {"y": 1, "x": 2}, "a": 1})),
+        );
+        assert_eq!(
+            obj_keys(&db, "doc", None),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"b".to_vec()),
+                ReplyFrame::bulk(b"a".to_vec()),
+            ])
+        );
+        assert_eq!(
+            obj_keys(&db, "doc", Some("$.*")),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"y".to_vec()),
+                    ReplyFrame::bulk(b"x".to_vec()),
+                ]),
+                ReplyFrame::Null,
+            ])
+        );
+    }
+}
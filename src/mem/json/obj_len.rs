@@ -0,0 +1,48 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{read_each, DEFAULT_PATH};
+
+/// Report the number of keys in the object at path.
+///
+/// Reply:
+/// - Array reply: length of each object matched by `JSONPath`, or nil if the
+///   matched value is not an object.
+/// - Integer reply: length of object, with legacy path.
+/// - Nil reply: if key does not exist.
+pub fn obj_len(db: &Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let results = read_each(db, key, path.unwrap_or(DEFAULT_PATH), "object", |value| {
+        value.as_object().map(serde_json::Map::len)
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::obj_len::obj_len;
+
+    #[test]
+    fn test_obj_len() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": {"b": 1, "c": 2}})),
+        );
+        assert_eq!(obj_len(&db, "doc", None), ReplyFrame::Usize(1));
+        assert_eq!(
+            obj_len(&db, "doc", Some("$..*")),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Usize(2),
+                ReplyFrame::Null,
+                ReplyFrame::Null,
+            ])
+        );
+    }
+}
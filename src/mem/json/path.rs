@@ -0,0 +1,726 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! `JSONPath` to select values in JSON documents.
+//!
+//! Two syntaxes are supported:
+//! - `JSONPath` starting with `$`, like `$.store.book[*].author`, which may match
+//!   multiple values.
+//! - Legacy path, like `.store.book[0]` or `store.book`, where `.` is the root.
+//!   Commands reply with the first matched value of a legacy path.
+//!
+//! Supported selectors are `.name`, `['name']`, `['a','b']`, `.*`, `[*]`, `[0]`,
+//! `[0,-1]`, `[start:end:step]`, recursive descent `..` and filters like
+//! `[?(@.price < 10 && @.tag)]`.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+/// Maximum nesting depth of filters in a path, and of values visited by
+/// recursive descent.
+const MAX_DEPTH: usize = 128;
+
+/// Concrete location of a value in JSON document.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// `.name`, `['name']` or `['a','b']`.
+    Keys(Vec<String>),
+    /// `[0]` or `[0,-1]`.
+    Indexes(Vec<i64>),
+    /// `.*` or `[*]`.
+    Wildcard,
+    /// `[start:end:step]`.
+    Slice(Option<i64>, Option<i64>, usize),
+    /// `..`, the value itself and all of its descendants.
+    Descendants,
+    /// `[?(expr)]`, children which match the expression.
+    Filter(Box<Filter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Or(Vec<Self>),
+    And(Vec<Self>),
+    Exists(Vec<Token>),
+    Compare(Operand, CompareOp, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    /// Relative path starting with `@`.
+    Current(Vec<Token>),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    legacy: bool,
+    tokens: Vec<Token>,
+}
+
+impl JsonPath {
+    /// Parse `JSONPath` or legacy path, returns None if path is invalid.
+    #[must_use]
+    pub fn parse(path: &str) -> Option<Self> {
+        let (legacy, path) = path.strip_prefix('$').map_or_else(
+            || {
+                // The leading dot of legacy path is optional.
+                let rest = path.strip_prefix('.').unwrap_or(path);
+                if rest.is_empty() || rest.starts_with('[') {
+                    (true, rest.to_owned())
+                } else {
+                    (true, format!(".{rest}"))
+                }
+            },
+            |rest| (false, rest.to_owned()),
+        );
+        let mut tokenizer = Tokenizer::new(&path);
+        let tokens = tokenizer.parse_tokens()?;
+        tokenizer.is_end().then_some(Self { legacy, tokens })
+    }
+
+    /// Returns true if this is a legacy path.
+    #[must_use]
+    #[inline]
+    pub const fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+
+    /// Returns true if this path selects the root value only.
+    #[must_use]
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Returns locations and values matched by this path.
+    #[must_use]
+    pub fn find<'a>(&self, root: &'a Value) -> Vec<(Vec<Segment>, &'a Value)> {
+        evaluate(&self.tokens, root)
+    }
+
+    /// Returns values matched by this path.
+    #[must_use]
+    pub fn find_values<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        self.find(root)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Returns locations of values matched by this path.
+    #[must_use]
+    pub fn find_paths(&self, root: &Value) -> Vec<Vec<Segment>> {
+        self.find(root).into_iter().map(|(path, _)| path).collect()
+    }
+
+    /// Split path into parent path and the last key, if the last selector is
+    /// a single key like `.name`.
+    #[must_use]
+    pub fn split_last_key(&self) -> Option<(Self, &str)> {
+        let (last, parent) = self.tokens.split_last()?;
+        match last {
+            Token::Keys(keys) if keys.len() == 1 => Some((
+                Self {
+                    legacy: self.legacy,
+                    tokens: parent.to_vec(),
+                },
+                &keys[0],
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Returns mutable reference to value at location.
+pub fn get_mut<'a>(root: &'a mut Value, path: &[Segment]) -> Option<&'a mut Value> {
+    path.iter()
+        .try_fold(root, |value, segment| match (value, segment) {
+            (Value::Object(map), Segment::Key(key)) => map.get_mut(key),
+            (Value::Array(array), Segment::Index(index)) => array.get_mut(*index),
+            _ => None,
+        })
+}
+
+/// Remove value at location, returns true if value is removed.
+pub fn remove(root: &mut Value, path: &[Segment]) -> bool {
+    let Some((last, parent)) = path.split_last() else {
+        return false;
+    };
+    match (get_mut(root, parent), last) {
+        (Some(Value::Object(map)), Segment::Key(key)) => map.shift_remove(key).is_some(),
+        (Some(Value::Array(array)), Segment::Index(index)) if *index < array.len() => {
+            array.remove(*index);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn evaluate<'a>(tokens: &[Token], root: &'a Value) -> Vec<(Vec<Segment>, &'a Value)> {
+    let mut nodes = vec![(Vec::new(), root)];
+    for token in tokens {
+        let mut next = Vec::new();
+        for (path, value) in nodes {
+            apply_token(token, path, value, &mut next);
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+fn apply_token<'a>(
+    token: &Token,
+    path: Vec<Segment>,
+    value: &'a Value,
+    result: &mut Vec<(Vec<Segment>, &'a Value)>,
+) {
+    match (token, value) {
+        (Token::Keys(keys), Value::Object(map)) => {
+            for key in keys {
+                if let Some(child) = map.get(key) {
+                    result.push((child_path(&path, Segment::Key(key.clone())), child));
+                }
+            }
+        }
+        (Token::Indexes(indexes), Value::Array(array)) => {
+            for &index in indexes {
+                if let Some(index) = normalize_index(index, array.len()) {
+                    result.push((child_path(&path, Segment::Index(index)), &array[index]));
+                }
+            }
+        }
+        (Token::Slice(start, end, step), Value::Array(array)) => {
+            let len = array.len();
+            let start = start.map_or(0, |start| clamp_index(start, len));
+            let end = end.map_or(len, |end| clamp_index(end, len));
+            for index in (start..end).step_by(*step) {
+                result.push((child_path(&path, Segment::Index(index)), &array[index]));
+            }
+        }
+        (Token::Wildcard, _) => result.extend(children(&path, value)),
+        (Token::Descendants, _) => descendants(path, value, 0, result),
+        (Token::Filter(filter), _) => result.extend(
+            children(&path, value)
+                .into_iter()
+                .filter(|(_, child)| filter.matches(child)),
+        ),
+        _ => (),
+    }
+}
+
+fn child_path(path: &[Segment], segment: Segment) -> Vec<Segment> {
+    let mut child = Vec::with_capacity(path.len() + 1);
+    child.extend_from_slice(path);
+    child.push(segment);
+    child
+}
+
+fn children<'a>(path: &[Segment], value: &'a Value) -> Vec<(Vec<Segment>, &'a Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (child_path(path, Segment::Key(key.clone())), child))
+            .collect(),
+        Value::Array(array) => array
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (child_path(path, Segment::Index(index)), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Push value and its descendants to result, values nested deeper than
+/// `MAX_DEPTH` are skipped.
+fn descendants<'a>(
+    path: Vec<Segment>,
+    value: &'a Value,
+    depth: usize,
+    result: &mut Vec<(Vec<Segment>, &'a Value)>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let children = children(&path, value);
+    result.push((path, value));
+    for (child_path, child) in children {
+        descendants(child_path, child, depth + 1, result);
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_truncation)]
+const fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { index + len as i64 } else { index };
+    if index >= 0 && (index as usize) < len {
+        Some(index as usize)
+    } else {
+        None
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_truncation)]
+fn clamp_index(index: i64, len: usize) -> usize {
+    let index = if index < 0 { index + len as i64 } else { index };
+    index.clamp(0, len as i64) as usize
+}
+
+impl Filter {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::Or(filters) => filters.iter().any(|filter| filter.matches(value)),
+            Self::And(filters) => filters.iter().all(|filter| filter.matches(value)),
+            Self::Exists(tokens) => !evaluate(tokens, value).is_empty(),
+            Self::Compare(left, op, right) => match (left.resolve(value), right.resolve(value)) {
+                (Some(left), Some(right)) => compare(left, *op, right),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, value: &'a Value) -> Option<&'a Value> {
+        match self {
+            Self::Current(tokens) => evaluate(tokens, value)
+                .into_iter()
+                .next()
+                .map(|(_, value)| value),
+            Self::Literal(literal) => Some(literal),
+        }
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left
+            .as_f64()
+            .zip(right.as_f64())
+            .and_then(|(left, right)| left.partial_cmp(&right)),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => (left == right).then_some(Ordering::Equal),
+    };
+    match op {
+        CompareOp::Eq => ordering == Some(Ordering::Equal),
+        CompareOp::Ne => ordering != Some(Ordering::Equal),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+struct Tokenizer {
+    chars: Vec<char>,
+    pos: usize,
+    /// Nesting depth of filters being parsed.
+    depth: usize,
+}
+
+impl Tokenizer {
+    fn new(path: &str) -> Self {
+        Self {
+            chars: path.chars().collect(),
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos == self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        let matched = self
+            .chars
+            .get(self.pos..self.pos + len)
+            .is_some_and(|chars| chars.iter().copied().eq(s.chars()));
+        if matched {
+            self.pos += len;
+        }
+        matched
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse selectors until end of path, or a character which can not start a selector.
+    fn parse_tokens(&mut self) -> Option<Vec<Token>> {
+        let mut tokens = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') => {
+                    self.pos += 1;
+                    if self.eat('.') {
+                        tokens.push(Token::Descendants);
+                        if self.peek() == Some('[') {
+                            continue;
+                        }
+                    }
+                    tokens.push(self.parse_name()?);
+                }
+                Some('[') => {
+                    self.pos += 1;
+                    tokens.push(self.parse_bracket()?);
+                }
+                _ => return Some(tokens),
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Option<Token> {
+        if self.eat('*') {
+            return Some(Token::Wildcard);
+        }
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '.' || c == '[' || c.is_whitespace() || "=!<>&|)".contains(c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| Token::Keys(vec![self.chars[start..self.pos].iter().collect()]))
+    }
+
+    /// Parse selector in brackets, after the `[`.
+    fn parse_bracket(&mut self) -> Option<Token> {
+        self.skip_spaces();
+        let token = match self.peek()? {
+            '*' => {
+                self.pos += 1;
+                Token::Wildcard
+            }
+            '?' => {
+                self.pos += 1;
+                self.skip_spaces();
+                if !self.eat('(') {
+                    return None;
+                }
+                let filter = self.parse_or()?;
+                self.skip_spaces();
+                if !self.eat(')') {
+                    return None;
+                }
+                Token::Filter(Box::new(filter))
+            }
+            '\'' | '"' => {
+                let mut keys = vec![self.parse_quoted()?];
+                self.skip_spaces();
+                while self.eat(',') {
+                    self.skip_spaces();
+                    keys.push(self.parse_quoted()?);
+                    self.skip_spaces();
+                }
+                Token::Keys(keys)
+            }
+            _ => self.parse_indexes()?,
+        };
+        self.skip_spaces();
+        self.eat(']').then_some(token)
+    }
+
+    fn parse_quoted(&mut self) -> Option<String> {
+        let quote = self.peek().filter(|&c| c == '\'' || c == '"')?;
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let c = self.peek()?;
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    s.push(self.peek()?);
+                    self.pos += 1;
+                }
+                c if c == quote => return Some(s),
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn parse_indexes(&mut self) -> Option<Token> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != ']') {
+            self.pos += 1;
+        }
+        let content: String = self.chars[start..self.pos].iter().collect();
+        if !content.contains(':') {
+            return content
+                .split(',')
+                .map(|index| index.trim().parse::<i64>().ok())
+                .collect::<Option<Vec<_>>>()
+                .map(Token::Indexes);
+        }
+
+        let parts: Vec<&str> = content.split(':').map(str::trim).collect();
+        if parts.len() > 3 {
+            return None;
+        }
+        let parse_bound = |s: &str| {
+            if s.is_empty() {
+                Some(None)
+            } else {
+                s.parse::<i64>().ok().map(Some)
+            }
+        };
+        let start = parse_bound(parts[0])?;
+        let end = parse_bound(parts[1])?;
+        let step = match parts.get(2) {
+            Some(step) if !step.is_empty() => {
+                step.parse::<usize>().ok().filter(|&step| step > 0)?
+            }
+            _ => 1,
+        };
+        Some(Token::Slice(start, end, step))
+    }
+
+    /// Parse a filter expression, returns None if it is nested deeper than `MAX_DEPTH`.
+    fn parse_or(&mut self) -> Option<Filter> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let filter = self.parse_or_operands();
+        self.depth -= 1;
+        filter
+    }
+
+    fn parse_or_operands(&mut self) -> Option<Filter> {
+        let mut filters = vec![self.parse_and()?];
+        self.skip_spaces();
+        while self.eat_str("||") {
+            filters.push(self.parse_and()?);
+            self.skip_spaces();
+        }
+        if filters.len() == 1 {
+            filters.pop()
+        } else {
+            Some(Filter::Or(filters))
+        }
+    }
+
+    fn parse_and(&mut self) -> Option<Filter> {
+        let mut filters = vec![self.parse_comparison()?];
+        self.skip_spaces();
+        while self.eat_str("&&") {
+            filters.push(self.parse_comparison()?);
+            self.skip_spaces();
+        }
+        if filters.len() == 1 {
+            filters.pop()
+        } else {
+            Some(Filter::And(filters))
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Option<Filter> {
+        self.skip_spaces();
+        if self.eat('(') {
+            let filter = self.parse_or()?;
+            self.skip_spaces();
+            return self.eat(')').then_some(filter);
+        }
+        let left = self.parse_operand()?;
+        self.skip_spaces();
+        let Some(op) = self.parse_compare_op() else {
+            return match left {
+                Operand::Current(tokens) => Some(Filter::Exists(tokens)),
+                Operand::Literal(_) => None,
+            };
+        };
+        self.skip_spaces();
+        let right = self.parse_operand()?;
+        Some(Filter::Compare(left, op, right))
+    }
+
+    fn parse_compare_op(&mut self) -> Option<CompareOp> {
+        let ops = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        ops.into_iter()
+            .find(|(s, _op)| self.eat_str(s))
+            .map(|(_s, op)| op)
+    }
+
+    fn parse_operand(&mut self) -> Option<Operand> {
+        match self.peek()? {
+            '@' => {
+                self.pos += 1;
+                Some(Operand::Current(self.parse_tokens()?))
+            }
+            '\'' | '"' => Some(Operand::Literal(Value::String(self.parse_quoted()?))),
+            _ => {
+                // Number, true, false or null.
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || "-+.".contains(c))
+                {
+                    self.pos += 1;
+                }
+                let literal: String = self.chars[start..self.pos].iter().collect();
+                serde_json::from_str::<Value>(&literal)
+                    .ok()
+                    .filter(|value| !value.is_object() && !value.is_array())
+                    .map(Operand::Literal)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::mem::json::path::{get_mut, remove, JsonPath, Segment, MAX_DEPTH};
+
+    fn find(path: &str, root: &Value) -> Vec<Value> {
+        JsonPath::parse(path)
+            .unwrap()
+            .find_values(root)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(JsonPath::parse("$").unwrap().is_root());
+        assert!(JsonPath::parse(".").unwrap().is_root());
+        assert!(JsonPath::parse(".").unwrap().is_legacy());
+        assert!(!JsonPath::parse("$.a").unwrap().is_legacy());
+        assert_eq!(JsonPath::parse("a.b"), JsonPath::parse(".a.b"));
+        assert_eq!(JsonPath::parse("a['b']"), JsonPath::parse(".a.b"));
+        assert!(JsonPath::parse("$.").is_none());
+        assert!(JsonPath::parse("$[").is_none());
+        assert!(JsonPath::parse("$[1:2:3:4]").is_none());
+        assert!(JsonPath::parse("$[?(@.a <)]").is_none());
+        let nested = |depth: usize| format!("$[?({}@.a{})]", "(".repeat(depth), ")".repeat(depth));
+        assert!(JsonPath::parse(&nested(100)).is_some());
+        assert!(JsonPath::parse(&nested(200_000)).is_none());
+        let nested = |depth: usize| {
+            format!(
+                "$[?({}@.a == 1{})]",
+                "@[?(".repeat(depth),
+                ")]".repeat(depth)
+            )
+        };
+        assert!(JsonPath::parse(&nested(100)).is_some());
+        assert!(JsonPath::parse(&nested(200_000)).is_none());
+        let path = JsonPath::parse("$.a.b").unwrap();
+        let (parent, key) = path.split_last_key().unwrap();
+        assert_eq!(key, "b");
+        assert_eq!(parent, JsonPath::parse("$.a").unwrap());
+    }
+
+    #[test]
+    fn test_find() {
+        let root = json!({
+            "name": "alice",
+            "tags": ["a", "b", "c", "d"],
+            "address": {"city": "Paris", "zip": 75000},
+            "friends": [
+                {"name": "bob", "age": 20},
+                {"name": "carol", "age": 30, "vip": true},
+            ],
+        });
+        assert_eq!(find("$.name", &root), vec![json!("alice")]);
+        assert_eq!(find("name", &root), vec![json!("alice")]);
+        assert_eq!(find("$.none", &root), Vec::<Value>::new());
+        assert_eq!(find("$.tags[-1]", &root), vec![json!("d")]);
+        assert_eq!(find("$.tags[0,2]", &root), vec![json!("a"), json!("c")]);
+        assert_eq!(find("$.tags[1:]", &root).len(), 3);
+        assert_eq!(find("$.tags[::2]", &root), vec![json!("a"), json!("c")]);
+        assert_eq!(find("$.tags[-2:]", &root), vec![json!("c"), json!("d")]);
+        assert_eq!(
+            find("$.address.*", &root),
+            vec![json!("Paris"), json!(75000)]
+        );
+        assert_eq!(
+            find("$['address']['city','zip']", &root),
+            vec![json!("Paris"), json!(75000)]
+        );
+        assert_eq!(
+            find("$..name", &root),
+            vec![json!("alice"), json!("bob"), json!("carol")]
+        );
+        assert_eq!(find("$.friends[*].age", &root), vec![json!(20), json!(30)]);
+        assert_eq!(
+            find("$.friends[?(@.age > 25)].name", &root),
+            vec![json!("carol")]
+        );
+        assert_eq!(
+            find("$.friends[?(@.vip)].name", &root),
+            vec![json!("carol")]
+        );
+        assert_eq!(
+            find("$.friends[?(@.age < 25 || @.name == 'carol')].name", &root),
+            vec![json!("bob"), json!("carol")]
+        );
+        assert_eq!(
+            find("$.friends[?(@.age >= 20 && @.name != \"bob\")].age", &root),
+            vec![json!(30)]
+        );
+
+        let mut deep = json!(1);
+        for _ in 0..200 {
+            deep = json!({ "a": deep });
+        }
+        assert_eq!(find("$..a", &deep).len(), MAX_DEPTH + 1);
+    }
+
+    #[test]
+    fn test_mutate() {
+        let mut root = json!({"a": [1, 2, 3], "b": {"c": 1}});
+        let path = vec![Segment::Key("a".to_owned()), Segment::Index(1)];
+        *get_mut(&mut root, &path).unwrap() = json!(5);
+        assert_eq!(root, json!({"a": [1, 5, 3], "b": {"c": 1}}));
+        assert!(remove(&mut root, &path));
+        assert!(remove(&mut root, &[Segment::Key("b".to_owned())]));
+        assert!(!remove(&mut root, &[Segment::Key("b".to_owned())]));
+        assert_eq!(root, json!({"a": [1, 3]}));
+    }
+}
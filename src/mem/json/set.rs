@@ -0,0 +1,143 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::json::{SetCondition, SetOptions};
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::json::consts::{NEW_AT_ROOT_ERR, WRONG_STATIC_PATH_ERR};
+use crate::mem::json::path::{get_mut, JsonPath};
+use crate::mem::json::{parse_path, parse_value};
+
+/// Set the JSON value at path in key.
+///
+/// A new key must be created at the root path.
+/// For an existing key, all values matched by path are replaced, or a new
+/// member is added to the parent object if the last element of path is a key.
+///
+/// Options:
+/// - NX: sets the key only if it does not already exist.
+/// - XX: sets the key only if it already exists.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Nil reply: if NX or XX conditions were not met, or the parent of `JSONPath`
+///   does not exist.
+/// - Error reply: if key exists but is not a JSON document, if path or value is invalid.
+pub fn set(db: &mut Db, options: SetOptions) -> ReplyFrame {
+    let path = match parse_path(&options.path) {
+        Ok(path) => path,
+        Err(err) => return err,
+    };
+    let value = match parse_value(&options.value) {
+        Ok(value) => value,
+        Err(err) => return err,
+    };
+
+    match db.get_mut(&options.key) {
+        Some(MemObject::Json(root)) => set_value(root, &path, &value, options.condition),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => {
+            if !path.is_root() {
+                return ReplyFrame::ConstError(NEW_AT_ROOT_ERR);
+            }
+            if options.condition == Some(SetCondition::Exist) {
+                return ReplyFrame::Null;
+            }
+            db.insert(options.key, MemObject::Json(value));
+            ReplyFrame::ok()
+        }
+    }
+}
+
+fn set_value(
+    root: &mut Value,
+    path: &JsonPath,
+    value: &Value,
+    condition: Option<SetCondition>,
+) -> ReplyFrame {
+    let paths = path.find_paths(root);
+    if !paths.is_empty() {
+        if condition == Some(SetCondition::NotExist) {
+            return ReplyFrame::Null;
+        }
+        for location in &paths {
+            if let Some(target) = get_mut(root, location) {
+                *target = value.clone();
+            }
+        }
+        return ReplyFrame::ok();
+    }
+
+    if condition == Some(SetCondition::Exist) {
+        return ReplyFrame::Null;
+    }
+    let mut created = false;
+    if let Some((parent, key)) = path.split_last_key() {
+        for location in parent.find_paths(root) {
+            if let Some(Value::Object(map)) = get_mut(root, &location) {
+                map.insert(key.to_owned(), value.clone());
+                created = true;
+            }
+        }
+    }
+    if created {
+        ReplyFrame::ok()
+    } else if path.is_legacy() {
+        ReplyFrame::ConstError(WRONG_STATIC_PATH_ERR)
+    } else {
+        ReplyFrame::Null
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::json::{SetCondition, SetOptions};
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::set::set;
+
+    fn options(path: &str, value: &str, condition: Option<SetCondition>) -> SetOptions {
+        SetOptions {
+            key: "doc".to_owned(),
+            path: path.to_owned(),
+            value: value.to_owned(),
+            condition,
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let mut db = Db::new();
+        let reply = set(&mut db, options("$.a", "1", None));
+        assert!(matches!(reply, ReplyFrame::ConstError(_)));
+        let reply = set(&mut db, options("$", "{}", Some(SetCondition::Exist)));
+        assert_eq!(reply, ReplyFrame::Null);
+        let reply = set(&mut db, options("$", r#"{"a":[1,2],"b":{"c":1}}"#, None));
+        assert_eq!(reply, ReplyFrame::ok());
+        let reply = set(&mut db, options("$.a[*]", "0", None));
+        assert_eq!(reply, ReplyFrame::ok());
+        let reply = set(&mut db, options("$.b.c", "2", Some(SetCondition::NotExist)));
+        assert_eq!(reply, ReplyFrame::Null);
+        let reply = set(
+            &mut db,
+            options("b.d", "true", Some(SetCondition::NotExist)),
+        );
+        assert_eq!(reply, ReplyFrame::ok());
+        let reply = set(&mut db, options("$.x.y", "1", None));
+        assert_eq!(reply, ReplyFrame::Null);
+        let reply = set(&mut db, options(".x.y", "1", None));
+        assert!(matches!(reply, ReplyFrame::ConstError(_)));
+        let reply = set(&mut db, options("$", "{", None));
+        assert!(matches!(reply, ReplyFrame::Error(_)));
+
+        let Some(MemObject::Json(root)) = db.get("doc") else {
+            panic!("expected JSON object");
+        };
+        assert_eq!(root, &json!({"a": [0, 0], "b": {"c": 1, "d": true}}));
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::consts::NOT_STRING_ERR;
+use crate::mem::json::{parse_value, update_each, DEFAULT_PATH};
+
+/// Append a JSON string to the string values at path.
+///
+/// Reply:
+/// - Array reply: the new length of each string matched by `JSONPath`, or nil
+///   if the matched value is not a string.
+/// - Integer reply: the new length of string, with legacy path.
+/// - Error reply: if key does not exist, or value is not a JSON string.
+pub fn str_append(db: &mut Db, key: &str, path: Option<&str>, value: &str) -> ReplyFrame {
+    let Ok(Value::String(suffix)) = parse_value(value) else {
+        return ReplyFrame::ConstError(NOT_STRING_ERR);
+    };
+    let results = update_each(db, key, path.unwrap_or(DEFAULT_PATH), "string", |value| {
+        let Value::String(s) = value else {
+            return None;
+        };
+        s.push_str(&suffix);
+        Some(s.len())
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::str_append::str_append;
+
+    #[test]
+    fn test_str_append() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": "foo", "b": {"a": 1}})),
+        );
+        assert_eq!(
+            str_append(&mut db, "doc", Some("$..a"), r#""bar""#),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(6), ReplyFrame::Null])
+        );
+        assert_eq!(
+            str_append(&mut db, "doc", Some("a"), r#""!""#),
+            ReplyFrame::Usize(7)
+        );
+        assert!(matches!(
+            str_append(&mut db, "doc", None, r#""!""#),
+            ReplyFrame::Error(_)
+        ));
+        assert!(matches!(
+            str_append(&mut db, "doc", None, "bar"),
+            ReplyFrame::ConstError(_)
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{read_each, DEFAULT_PATH};
+
+/// Report the length of the string values at path.
+///
+/// Reply:
+/// - Array reply: length of each string matched by `JSONPath`, or nil if the
+///   matched value is not a string.
+/// - Integer reply: length of string, with legacy path.
+/// - Nil reply: if key does not exist.
+pub fn str_len(db: &Db, key: &str, path: Option<&str>) -> ReplyFrame {
+    let results = read_each(db, key, path.unwrap_or(DEFAULT_PATH), "string", |value| {
+        value.as_str().map(str::len)
+    });
+    results.map_or_else(|err| err, |results| results.into_reply(ReplyFrame::Usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::str_len::str_len;
+
+    #[test]
+    fn test_str_len() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": "foo", "b": ["hello", 1]})),
+        );
+        assert_eq!(str_len(&db, "doc", Some(".a")), ReplyFrame::Usize(3));
+        assert_eq!(
+            str_len(&db, "doc", Some("$.b[*]")),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(5), ReplyFrame::Null])
+        );
+        assert_eq!(str_len(&db, "none", None), ReplyFrame::Null);
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use serde_json::Value;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::json::{update_each, PathResults};
+
+/// Toggle the boolean values at path.
+///
+/// Reply:
+/// - Array reply: 1 if the new value is true, 0 if false, for each boolean
+///   matched by `JSONPath`, or nil if the matched value is not a boolean.
+/// - Bulk string reply: `true` or `false`, the new value, with legacy path.
+/// - Error reply: if key does not exist.
+pub fn toggle(db: &mut Db, key: &str, path: &str) -> ReplyFrame {
+    let results = update_each(db, key, path, "boolean", |value| {
+        let Value::Bool(flag) = value else {
+            return None;
+        };
+        *flag = !*flag;
+        Some(*flag)
+    });
+    match results {
+        Ok(PathResults::Legacy(flag)) => ReplyFrame::bulk(flag.to_string().into_bytes()),
+        Ok(results) => results.into_reply(ReplyFrame::from_bool),
+        Err(err) => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::json::toggle::toggle;
+
+    #[test]
+    fn test_toggle() {
+        let mut db = Db::new();
+        db.insert(
+            "doc".to_owned(),
+            MemObject::Json(json!({"a": true, "b": {"a": 1}})),
+        );
+        assert_eq!(
+            toggle(&mut db, "doc", "$..a"),
+            ReplyFrame::Array(vec![ReplyFrame::zero(), ReplyFrame::Null])
+        );
+        assert_eq!(
+            toggle(&mut db, "doc", ".a"),
+            ReplyFrame::bulk(b"true".to_vec())
+        );
+    }
+}
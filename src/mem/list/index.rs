@@ -20,13 +20,8 @@ use crate::mem::util::prune_index;
 pub fn index(db: &Db, key: &str, index: isize) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::List(list)) => {
-            if let Some(real_index) = prune_index(list.len(), index) {
-                // TODO(Shaohua): Use iter().skip().take()
-                for (index, item) in list.iter().enumerate() {
-                    if index == real_index {
-                        return ReplyFrame::Bulk(item.clone());
-                    }
-                }
+            if let Some(item) = prune_index(list.len(), index).and_then(|index| list.get(index)) {
+                return ReplyFrame::Bulk(item.to_vec());
             }
         }
         Some(_other) => return ReplyFrame::wrong_type_err(),
@@ -47,9 +42,9 @@ mod tests {
     fn test_index() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_front(&mut db, key.clone(), vec![b"World".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
-        let reply = push_front(&mut db, key.clone(), vec![b"Hello".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
 
         let reply = index(&db, &key, 0);
@@ -21,23 +21,19 @@ pub fn insert(
     key: &str,
     position: RelativePosition,
     pivot: &[u8],
-    element: Vec<u8>,
+    element: &[u8],
 ) -> ReplyFrame {
     match db.get_mut(key) {
         Some(MemObject::List(old_list)) => {
             old_list
-                .iter()
-                .position(|value| value == pivot)
+                .position(pivot)
                 .map_or_else(ReplyFrame::minus_one, |index| {
-                    // TODO(Shaohua): Simplify
                     let index = if position == RelativePosition::Before {
                         index
                     } else {
                         index + 1
                     };
-                    let mut tail_list = old_list.split_off(index);
-                    old_list.push_back(element);
-                    old_list.append(&mut tail_list);
+                    old_list.insert(index, element);
                     ReplyFrame::Usize(old_list.len())
                 })
         }
@@ -59,17 +55,11 @@ mod tests {
     fn test_insert() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_back(&mut db, key.clone(), vec![b"Hello".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
-        let reply = push_back(&mut db, key.clone(), vec![b"World".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
-        let reply = insert(
-            &mut db,
-            &key,
-            RelativePosition::Before,
-            b"World",
-            b"Three".to_vec(),
-        );
+        let reply = insert(&mut db, &key, RelativePosition::Before, b"World", b"Three");
         assert_eq!(reply, ReplyFrame::Usize(3));
 
         let reply = range(&db, &key, 0, -1);
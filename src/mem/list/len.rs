@@ -31,9 +31,9 @@ mod tests {
     fn test_len() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_front(&mut db, key.clone(), vec![b"World".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
-        let reply = push_front(&mut db, key.clone(), vec![b"Hello".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
         let reply = len(&db, &key);
         assert_eq!(reply, ReplyFrame::Usize(2));
@@ -55,7 +55,7 @@ mod tests {
         let reply = push_back(
             &mut db,
             key.clone(),
-            vec![
+            &[
                 b"one".to_vec(),
                 b"two".to_vec(),
                 b"three".to_vec(),
@@ -12,7 +12,7 @@ use crate::mem::db::{Db, MemObject};
 ///
 /// Reply:
 /// - Integer reply: the length of the list after the push operation.
-pub fn push_back_exist(db: &mut Db, key: &str, values: Vec<Vec<u8>>) -> ReplyFrame {
+pub fn push_back_exist(db: &mut Db, key: &str, values: &[Vec<u8>]) -> ReplyFrame {
     match db.get_mut(key) {
         Some(MemObject::List(old_list)) => {
             for value in values {
@@ -37,12 +37,12 @@ mod tests {
     fn test_push_back_exist() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_back(&mut db, key.clone(), vec![b"Hello".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
 
-        let reply = push_back_exist(&mut db, &key, vec![b"World".to_vec()]);
+        let reply = push_back_exist(&mut db, &key, &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
-        let reply = push_back_exist(&mut db, "myotherlist", vec![b"World".to_vec()]);
+        let reply = push_back_exist(&mut db, "myotherlist", &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(0));
 
         let reply = range(&db, &key, 0, -1);
@@ -22,7 +22,7 @@ use crate::mem::list::ListObject;
 ///
 /// Reply:
 /// - Integer reply: the length of the list after the push operation.
-pub fn push_front(db: &mut Db, key: String, values: Vec<Vec<u8>>) -> ReplyFrame {
+pub fn push_front(db: &mut Db, key: String, values: &[Vec<u8>]) -> ReplyFrame {
     match db.entry(key) {
         Entry::Occupied(mut occupied) => match occupied.get_mut() {
             MemObject::List(old_list) => {
@@ -57,10 +57,10 @@ mod tests {
     fn test_push_front() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_front(&mut db, key.clone(), vec![b"world".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"world".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
 
-        let reply = push_front(&mut db, key.clone(), vec![b"hello".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
         let reply = range(&db, &key, 0, -1);
         assert_eq!(
@@ -12,7 +12,7 @@ use crate::mem::db::{Db, MemObject};
 ///
 /// Reply:
 // - Integer reply: the length of the list after the push operation.
-pub fn push_front_exist(db: &mut Db, key: &str, values: Vec<Vec<u8>>) -> ReplyFrame {
+pub fn push_front_exist(db: &mut Db, key: &str, values: &[Vec<u8>]) -> ReplyFrame {
     match db.get_mut(key) {
         Some(MemObject::List(old_list)) => {
             for value in values {
@@ -37,12 +37,12 @@ mod tests {
     fn test_push_front_exist() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_front(&mut db, key.clone(), vec![b"World".to_vec()]);
+        let reply = push_front(&mut db, key.clone(), &[b"World".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
 
-        let reply = push_front_exist(&mut db, &key, vec![b"Hello".to_vec()]);
+        let reply = push_front_exist(&mut db, &key, &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
-        let reply = push_front_exist(&mut db, "myotherlist", vec![b"Hello".to_vec()]);
+        let reply = push_front_exist(&mut db, "myotherlist", &[b"Hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(0));
 
         let reply = range(&db, &key, 0, -1);
@@ -0,0 +1,422 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! A deque of compact chunks, similar to redis quicklist.
+//!
+//! Elements are stored in a sequence of `ListPack` chunks, so that each element does
+//! not pay for a separated heap allocation, and index based operations only need to
+//! walk through the chunks instead of every element.
+
+use std::collections::VecDeque;
+
+use crate::mem::list_pack::ListPack;
+
+/// Max number of entries in one chunk.
+pub const CHUNK_MAX_ENTRIES: usize = 128;
+
+/// Max number of bytes in one chunk, same as `list-max-listpack-size -2` in redis.
+///
+/// An element larger than this limit is stored in a chunk of its own.
+pub const CHUNK_MAX_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct QuickList {
+    chunks: VecDeque<ListPack>,
+    len: usize,
+}
+
+impl QuickList {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns number of elements in list.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns number of chunks in list.
+    #[must_use]
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns number of bytes used by elements, not including chunk headers.
+    #[must_use]
+    pub fn byte_len(&self) -> usize {
+        self.chunks.iter().map(ListPack::byte_len).sum()
+    }
+
+    pub fn push_back(&mut self, value: &[u8]) {
+        match self.chunks.back_mut() {
+            Some(chunk) if Self::can_insert(chunk, value) => chunk.push_back(value),
+            _ => {
+                let mut chunk = ListPack::new();
+                chunk.push_back(value);
+                self.chunks.push_back(chunk);
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: &[u8]) {
+        match self.chunks.front_mut() {
+            Some(chunk) if Self::can_insert(chunk, value) => chunk.push_front(value),
+            _ => {
+                let mut chunk = ListPack::new();
+                chunk.push_back(value);
+                self.chunks.push_front(chunk);
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        let chunk = self.chunks.front_mut()?;
+        let value = chunk.pop_front();
+        if chunk.is_empty() {
+            self.chunks.pop_front();
+        }
+        if value.is_some() {
+            self.len -= 1;
+        }
+        value
+    }
+
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        let chunk = self.chunks.back_mut()?;
+        let value = chunk.pop_back();
+        if chunk.is_empty() {
+            self.chunks.pop_back();
+        }
+        if value.is_some() {
+            self.len -= 1;
+        }
+        value
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        let (chunk_index, offset) = self.locate(index)?;
+        self.chunks[chunk_index].get(offset)
+    }
+
+    /// Replace element at `index`, returns false if index is out of range.
+    pub fn set(&mut self, index: usize, value: &[u8]) -> bool {
+        let Some((chunk_index, offset)) = self.locate(index) else {
+            return false;
+        };
+        let ok = self.chunks[chunk_index].replace(offset, value);
+        self.split_chunk_if_needed(chunk_index);
+        ok
+    }
+
+    /// Insert `value` at `index`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: &[u8]) {
+        assert!(index <= self.len, "insertion index out of bounds");
+        if index == self.len {
+            self.push_back(value);
+            return;
+        }
+        if index == 0 {
+            self.push_front(value);
+            return;
+        }
+        if let Some((chunk_index, offset)) = self.locate(index) {
+            self.chunks[chunk_index].insert(offset, value);
+            self.len += 1;
+            self.split_chunk_if_needed(chunk_index);
+        }
+    }
+
+    /// Removes and returns element at `index`.
+    pub fn remove(&mut self, index: usize) -> Option<Vec<u8>> {
+        let (chunk_index, offset) = self.locate(index)?;
+        let value = self.chunks[chunk_index].remove(offset)?;
+        self.len -= 1;
+        if self.chunks[chunk_index].is_empty() {
+            self.chunks.remove(chunk_index);
+            if chunk_index > 0 {
+                self.merge_chunks_if_possible(chunk_index - 1);
+            }
+        } else {
+            self.merge_chunks_if_possible(chunk_index);
+        }
+        Some(value)
+    }
+
+    /// Removes up to `limit` elements equal to `value`, moving from tail to head
+    /// if `from_back` is true.
+    ///
+    /// Elements are removed in place inside each chunk, and chunks which become
+    /// empty are dropped.
+    ///
+    /// Returns number of removed elements.
+    pub fn remove_matches(&mut self, value: &[u8], limit: usize, from_back: bool) -> usize {
+        let num_chunks = self.chunks.len();
+        let mut removed = 0;
+        for i in 0..num_chunks {
+            if removed == limit {
+                break;
+            }
+            let chunk_index = if from_back { num_chunks - 1 - i } else { i };
+            let chunk = &mut self.chunks[chunk_index];
+            let remaining = limit - removed;
+            // Keep matches at the head of chunk if only the last ones are removed.
+            let skip = if from_back {
+                let matches = chunk.iter().filter(|item| *item == value).count();
+                matches.saturating_sub(remaining)
+            } else {
+                0
+            };
+            removed += chunk.remove_matches(value, skip, remaining);
+        }
+        if removed > 0 {
+            self.chunks.retain(|chunk| !chunk.is_empty());
+            self.len -= removed;
+        }
+        removed
+    }
+
+    /// Returns index of the first element which equals to `value`.
+    #[must_use]
+    pub fn position(&self, value: &[u8]) -> Option<usize> {
+        self.iter().position(|item| item == value)
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &[u8]> + '_ {
+        self.chunks.iter().flat_map(ListPack::iter)
+    }
+
+    /// Returns an iterator starting from element at `start`.
+    ///
+    /// Chunks before `start` are skipped as a whole.
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = &[u8]> + '_ {
+        let (chunk_index, offset) = self.locate(start).unwrap_or((self.chunks.len(), 0));
+        self.chunks
+            .iter()
+            .skip(chunk_index)
+            .enumerate()
+            .flat_map(move |(index, chunk)| {
+                let skip = if index == 0 { offset } else { 0 };
+                chunk.iter().skip(skip)
+            })
+    }
+
+    /// Returns `(chunk_index, offset_in_chunk)` of element at `index`.
+    ///
+    /// Chunks are scanned from the nearest end of the list.
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.len {
+            return None;
+        }
+        if index <= self.len / 2 {
+            let mut index = index;
+            for (chunk_index, chunk) in self.chunks.iter().enumerate() {
+                if index < chunk.len() {
+                    return Some((chunk_index, index));
+                }
+                index -= chunk.len();
+            }
+        } else {
+            let mut index_from_back = self.len - index;
+            for (chunk_index, chunk) in self.chunks.iter().enumerate().rev() {
+                if index_from_back <= chunk.len() {
+                    return Some((chunk_index, chunk.len() - index_from_back));
+                }
+                index_from_back -= chunk.len();
+            }
+        }
+        None
+    }
+
+    fn can_insert(chunk: &ListPack, value: &[u8]) -> bool {
+        chunk.len() < CHUNK_MAX_ENTRIES
+            && chunk.byte_len() + ListPack::entry_size(value) <= CHUNK_MAX_BYTES
+    }
+
+    fn is_oversized(chunk: &ListPack) -> bool {
+        chunk.len() > 1 && (chunk.len() > CHUNK_MAX_ENTRIES || chunk.byte_len() > CHUNK_MAX_BYTES)
+    }
+
+    fn split_chunk_if_needed(&mut self, chunk_index: usize) {
+        let chunk = &mut self.chunks[chunk_index];
+        if Self::is_oversized(chunk) {
+            let mut tail = chunk.split_off(chunk.len() / 2);
+            chunk.shrink_to_fit();
+            tail.shrink_to_fit();
+            self.chunks.insert(chunk_index + 1, tail);
+        }
+    }
+
+    /// Merge chunk at `chunk_index` with its neighbours if the merged chunk still fits.
+    fn merge_chunks_if_possible(&mut self, chunk_index: usize) {
+        if chunk_index + 1 < self.chunks.len() && self.can_merge(chunk_index, chunk_index + 1) {
+            if let Some(mut next) = self.chunks.remove(chunk_index + 1) {
+                self.chunks[chunk_index].append(&mut next);
+            }
+        }
+        if chunk_index > 0 && self.can_merge(chunk_index - 1, chunk_index) {
+            if let Some(mut current) = self.chunks.remove(chunk_index) {
+                self.chunks[chunk_index - 1].append(&mut current);
+            }
+        }
+    }
+
+    fn can_merge(&self, left: usize, right: usize) -> bool {
+        let left = &self.chunks[left];
+        let right = &self.chunks[right];
+        // Only merge small chunks, to avoid splitting them again soon.
+        left.len() + right.len() <= CHUNK_MAX_ENTRIES / 2
+            && left.byte_len() + right.byte_len() <= CHUNK_MAX_BYTES / 2
+    }
+}
+
+impl<T: AsRef<[u8]>> FromIterator<T> for QuickList {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for value in iter {
+            list.push_back(value.as_ref());
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuickList, CHUNK_MAX_ENTRIES};
+
+    fn new_list(len: usize) -> QuickList {
+        (0..len).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut list = QuickList::new();
+        for i in 0..1000 {
+            list.push_back(i.to_string().as_bytes());
+            list.push_front(i.to_string().as_bytes());
+        }
+        assert_eq!(list.len(), 2000);
+        // Both ends are full, except the first chunk.
+        assert_eq!(
+            list.chunk_count(),
+            2000_usize.div_ceil(CHUNK_MAX_ENTRIES) + 1
+        );
+        assert_eq!(list.pop_front(), Some(b"999".to_vec()));
+        assert_eq!(list.pop_back(), Some(b"999".to_vec()));
+        for _i in 0..1998 {
+            assert!(list.pop_back().is_some());
+        }
+        assert!(list.is_empty());
+        assert_eq!(list.chunk_count(), 0);
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut list = new_list(1000);
+        assert_eq!(list.get(0), Some(&b"0"[..]));
+        assert_eq!(list.get(500), Some(&b"500"[..]));
+        assert_eq!(list.get(999), Some(&b"999"[..]));
+        assert_eq!(list.get(1000), None);
+        assert!(list.set(700, b"seven hundred"));
+        assert!(!list.set(1000, b"none"));
+        assert_eq!(list.get(700), Some(&b"seven hundred"[..]));
+        assert_eq!(list.get(701), Some(&b"701"[..]));
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list = new_list(10);
+        for _i in 0..CHUNK_MAX_ENTRIES * 2 {
+            list.insert(5, b"x");
+        }
+        assert_eq!(list.len(), 10 + CHUNK_MAX_ENTRIES * 2);
+        assert!(list.chunk_count() > 1);
+        assert_eq!(list.get(4), Some(&b"4"[..]));
+        assert_eq!(list.get(5), Some(&b"x"[..]));
+        assert_eq!(list.get(list.len() - 5), Some(&b"5"[..]));
+
+        while list.len() > 10 {
+            assert_eq!(list.remove(5), Some(b"x".to_vec()));
+        }
+        assert_eq!(list.chunk_count(), 1);
+        let values: Vec<&[u8]> = list.iter().collect();
+        let expected = new_list(10);
+        assert_eq!(values, expected.iter().collect::<Vec<_>>());
+        assert_eq!(list.remove(10), None);
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut list: QuickList = (0..CHUNK_MAX_ENTRIES * 3)
+            .map(|i| if i % CHUNK_MAX_ENTRIES == 0 { "x" } else { "y" })
+            .collect();
+        list.push_back(b"x");
+        assert_eq!(list.remove_matches(b"x", 2, true), 2);
+        assert_eq!(list.len(), CHUNK_MAX_ENTRIES * 3 - 1);
+        assert_eq!(list.get(CHUNK_MAX_ENTRIES), Some(&b"x"[..]));
+        assert_eq!(list.position(b"x"), Some(0));
+
+        assert_eq!(
+            list.remove_matches(b"y", usize::MAX, false),
+            CHUNK_MAX_ENTRIES * 3 - 3
+        );
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.chunk_count(), 2);
+        assert_eq!(list.remove_matches(b"x", 1, false), 1);
+        assert_eq!(list.chunk_count(), 1);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&b"x"[..]]);
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let list = new_list(1000);
+        let values: Vec<&[u8]> = list.iter_from(998).collect();
+        assert_eq!(values, vec![&b"998"[..], b"999"]);
+        assert_eq!(list.iter_from(300).next(), Some(&b"300"[..]));
+        assert_eq!(list.iter_from(1000).next(), None);
+        assert_eq!(list.iter().rev().next(), Some(&b"999"[..]));
+        assert_eq!(list.position(b"512"), Some(512));
+    }
+
+    #[test]
+    fn test_large_elements() {
+        let large = vec![b'x'; 10_000];
+        let mut list = QuickList::new();
+        list.push_back(b"a");
+        list.push_back(&large);
+        list.push_back(b"b");
+        assert_eq!(list.chunk_count(), 3);
+        list.insert(1, &large);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.get(1), Some(&large[..]));
+        assert_eq!(list.get(3), Some(&b"b"[..]));
+    }
+}
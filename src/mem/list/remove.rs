@@ -2,9 +2,6 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
-use std::cmp::Ordering;
-use std::collections::LinkedList;
-
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
 
@@ -22,49 +19,15 @@ use crate::mem::db::{Db, MemObject};
 ///
 /// Reply:
 /// - Integer reply: the number of removed elements.
-#[allow(clippy::cast_sign_loss)]
 pub fn remove(db: &mut Db, key: &str, count: isize, element: &[u8]) -> ReplyFrame {
     match db.get_mut(key) {
-        Some(MemObject::List(old_list)) => {
-            // TODO(Shaohua): Simplify operation.
-            let mut new_list = LinkedList::new();
-            let mut num_removed = 0;
-            match count.cmp(&0) {
-                Ordering::Equal => {
-                    while let Some(value) = old_list.pop_front() {
-                        if value == element {
-                            num_removed += 1;
-                        } else {
-                            new_list.push_back(value);
-                        }
-                    }
-                }
-                Ordering::Greater => {
-                    let mut count = count as usize;
-                    while let Some(value) = old_list.pop_front() {
-                        if value == element && count > 0 {
-                            num_removed += 1;
-                            count -= 1;
-                        } else {
-                            new_list.push_back(value);
-                        }
-                    }
-                }
-
-                Ordering::Less => {
-                    let mut count = (-count) as usize;
-                    while let Some(value) = old_list.pop_back() {
-                        if value == element && count > 0 {
-                            count -= 1;
-                            num_removed += 1;
-                        } else {
-                            new_list.push_front(value);
-                        }
-                    }
-                }
-            }
-
-            *old_list = new_list;
+        Some(MemObject::List(list)) => {
+            let limit = if count == 0 {
+                usize::MAX
+            } else {
+                count.unsigned_abs()
+            };
+            let num_removed = list.remove_matches(element, limit, count < 0);
             ReplyFrame::Usize(num_removed)
         }
         Some(_) => ReplyFrame::wrong_type_err(),
@@ -84,13 +47,13 @@ mod tests {
     fn test_remove() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_back(&mut db, key.clone(), vec![b"hello".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
-        let reply = push_back(&mut db, key.clone(), vec![b"hello".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
-        let reply = push_back(&mut db, key.clone(), vec![b"foo".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"foo".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(3));
-        let reply = push_back(&mut db, key.clone(), vec![b"hello".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"hello".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(4));
 
         let reply = remove(&mut db, &key, -2, b"hello");
@@ -18,18 +18,11 @@ use crate::mem::util::prune_index;
 ///
 /// Reply:
 /// - Simple string reply: OK.
-pub fn set(db: &mut Db, key: &str, index: isize, value: Vec<u8>) -> ReplyFrame {
+pub fn set(db: &mut Db, key: &str, index: isize, value: &[u8]) -> ReplyFrame {
     match db.get_mut(key) {
         Some(MemObject::List(old_list)) => {
             prune_index(old_list.len(), index).map_or_else(ReplyFrame::out_of_range_err, |index| {
-                // TODO(Shaohua): Simplify
-                for (i, old_value) in old_list.iter_mut().enumerate() {
-                    if i == index {
-                        // TODO(Shaohua): Replace with mem::swap()
-                        *old_value = value;
-                        break;
-                    }
-                }
+                old_list.set(index, value);
                 ReplyFrame::ok()
             })
         }
@@ -50,15 +43,15 @@ mod tests {
     fn test_set() {
         let mut db = Db::new();
         let key = "mylist".to_owned();
-        let reply = push_back(&mut db, key.clone(), vec![b"one".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"one".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(1));
-        let reply = push_back(&mut db, key.clone(), vec![b"two".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"two".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(2));
-        let reply = push_back(&mut db, key.clone(), vec![b"three".to_vec()]);
+        let reply = push_back(&mut db, key.clone(), &[b"three".to_vec()]);
         assert_eq!(reply, ReplyFrame::Usize(3));
-        let reply = set(&mut db, &key, 0, b"four".to_vec());
+        let reply = set(&mut db, &key, 0, b"four");
         assert_eq!(reply, ReplyFrame::ok());
-        let reply = set(&mut db, &key, -2, b"five".to_vec());
+        let reply = set(&mut db, &key, -2, b"five");
         assert_eq!(reply, ReplyFrame::ok());
         let reply = range(&db, &key, 0, -1);
         assert_eq!(
@@ -0,0 +1,488 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! A compact, flat sequence of byte strings, similar to redis listpack.
+//!
+//! All entries are stored in one contiguous buffer, each entry is encoded as:
+//! ```txt
+//! <header-len> <data> <back-len>
+//! ```
+//!
+//! - `header-len` is the length of data, in LEB128 varint format.
+//! - `back-len` is the length of `header-len` + `data`, stored in reversed varint format,
+//!   so that the buffer can be traversed from tail to head too.
+
+use std::iter::FusedIterator;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct ListPack {
+    buf: Vec<u8>,
+    len: usize,
+}
+
+impl ListPack {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns number of entries.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns number of bytes used by entries.
+    #[must_use]
+    #[inline]
+    pub fn byte_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns number of bytes required to store `value` as an entry.
+    #[must_use]
+    #[inline]
+    pub const fn entry_size(value: &[u8]) -> usize {
+        let header_len = varint_len(value.len());
+        let back_len = varint_len(header_len + value.len());
+        header_len + value.len() + back_len
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.len {
+            return None;
+        }
+        let offset = self.offset_of(index);
+        let (data_start, data_len) = read_header(&self.buf, offset);
+        Some(&self.buf[data_start..data_start + data_len])
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn first(&self) -> Option<&[u8]> {
+        self.iter().next()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn last(&self) -> Option<&[u8]> {
+        self.iter().next_back()
+    }
+
+    pub fn push_back(&mut self, value: &[u8]) {
+        write_entry(&mut self.buf, value);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: &[u8]) {
+        let mut entry = Vec::with_capacity(Self::entry_size(value));
+        write_entry(&mut entry, value);
+        self.buf.splice(0..0, entry);
+        self.len += 1;
+    }
+
+    /// Insert `value` at `index`, shifting all entries after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: &[u8]) {
+        assert!(index <= self.len, "insertion index out of bounds");
+        if index == self.len {
+            self.push_back(value);
+            return;
+        }
+        let offset = self.offset_of(index);
+        let mut entry = Vec::with_capacity(Self::entry_size(value));
+        write_entry(&mut entry, value);
+        self.buf.splice(offset..offset, entry);
+        self.len += 1;
+    }
+
+    /// Replace entry at `index` with `value`, returns false if index is out of range.
+    pub fn replace(&mut self, index: usize, value: &[u8]) -> bool {
+        if index >= self.len {
+            return false;
+        }
+        let offset = self.offset_of(index);
+        let end = next_offset(&self.buf, offset);
+        let mut entry = Vec::with_capacity(Self::entry_size(value));
+        write_entry(&mut entry, value);
+        self.buf.splice(offset..end, entry);
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Vec<u8>> {
+        if index >= self.len {
+            return None;
+        }
+        let offset = self.offset_of(index);
+        let (data_start, data_len) = read_header(&self.buf, offset);
+        let value = self.buf[data_start..data_start + data_len].to_vec();
+        let end = next_offset(&self.buf, offset);
+        self.buf.drain(offset..end);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes up to `limit` entries equal to `value` in one pass, after the first
+    /// `skip` matched entries are kept.
+    ///
+    /// Remaining entries are moved forward inside the buffer, no reallocation is done.
+    ///
+    /// Returns number of removed entries.
+    pub fn remove_matches(&mut self, value: &[u8], mut skip: usize, limit: usize) -> usize {
+        let buf_len = self.buf.len();
+        let mut read = 0;
+        let mut write = 0;
+        let mut removed = 0;
+        while read < buf_len && removed < limit {
+            let (data_start, data_len) = read_header(&self.buf, read);
+            let end = next_offset(&self.buf, read);
+            let matched = &self.buf[data_start..data_start + data_len] == value;
+            if matched && skip == 0 {
+                removed += 1;
+            } else {
+                if matched {
+                    skip -= 1;
+                }
+                if write != read {
+                    self.buf.copy_within(read..end, write);
+                }
+                write += end - read;
+            }
+            read = end;
+        }
+        if removed > 0 {
+            self.buf.copy_within(read..buf_len, write);
+            self.buf.truncate(write + buf_len - read);
+            self.len -= removed;
+        }
+        removed
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        self.remove(0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        if self.len == 0 {
+            return None;
+        }
+        let offset = prev_offset(&self.buf, self.buf.len());
+        let (data_start, data_len) = read_header(&self.buf, offset);
+        let value = self.buf[data_start..data_start + data_len].to_vec();
+        self.buf.truncate(offset);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Splits entries into two at the given index.
+    ///
+    /// Returns a newly allocated pack containing entries in range `[at, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split index out of bounds");
+        let offset = if at == self.len {
+            self.buf.len()
+        } else {
+            self.offset_of(at)
+        };
+        let buf = self.buf.split_off(offset);
+        let len = self.len - at;
+        self.len = at;
+        Self { buf, len }
+    }
+
+    /// Moves all entries of `other` to the end of self.
+    pub fn append(&mut self, other: &mut Self) {
+        self.buf.append(&mut other.buf);
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.len = 0;
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.buf.shrink_to_fit();
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            buf: &self.buf,
+            front: 0,
+            back: self.buf.len(),
+            remaining: self.len,
+        }
+    }
+
+    /// Returns byte offset of entry at `index`.
+    ///
+    /// Entries are scanned from the nearest end of the pack.
+    fn offset_of(&self, index: usize) -> usize {
+        debug_assert!(index < self.len);
+        if index <= self.len / 2 {
+            let mut offset = 0;
+            for _i in 0..index {
+                offset = next_offset(&self.buf, offset);
+            }
+            offset
+        } else {
+            let mut offset = self.buf.len();
+            for _i in index..self.len {
+                offset = prev_offset(&self.buf, offset);
+            }
+            offset
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ListPack {
+    type Item = &'a [u8];
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: AsRef<[u8]>> FromIterator<T> for ListPack {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut pack = Self::new();
+        for value in iter {
+            pack.push_back(value.as_ref());
+        }
+        pack
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    buf: &'a [u8],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (data_start, data_len) = read_header(self.buf, self.front);
+        self.front = next_offset(self.buf, self.front);
+        self.remaining -= 1;
+        Some(&self.buf[data_start..data_start + data_len])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back = prev_offset(self.buf, self.back);
+        let (data_start, data_len) = read_header(self.buf, self.back);
+        self.remaining -= 1;
+        Some(&self.buf[data_start..data_start + data_len])
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+const fn varint_len(mut value: usize) -> usize {
+    let mut len = 1;
+    value >>= 7;
+    while value > 0 {
+        len += 1;
+        value >>= 7;
+    }
+    len
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn write_entry(buf: &mut Vec<u8>, value: &[u8]) {
+    // Header, LEB128 varint.
+    let mut data_len = value.len();
+    loop {
+        let byte = (data_len & 0x7f) as u8;
+        data_len >>= 7;
+        if data_len == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+
+    buf.extend_from_slice(value);
+
+    // Back-len, the lowest 7 bits are stored in the last byte.
+    let back_len = varint_len(value.len()) + value.len();
+    let size = varint_len(back_len);
+    for i in (0..size).rev() {
+        let byte = ((back_len >> (7 * i)) & 0x7f) as u8;
+        if i == size - 1 {
+            buf.push(byte);
+        } else {
+            buf.push(byte | 0x80);
+        }
+    }
+}
+
+/// Returns `(data_start, data_len)` of entry at `offset`.
+fn read_header(buf: &[u8], offset: usize) -> (usize, usize) {
+    let mut data_len = 0;
+    let mut shift = 0;
+    let mut pos = offset;
+    loop {
+        let byte = buf[pos];
+        data_len |= usize::from(byte & 0x7f) << shift;
+        pos += 1;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    (pos, data_len)
+}
+
+/// Returns offset of next entry.
+fn next_offset(buf: &[u8], offset: usize) -> usize {
+    let (data_start, data_len) = read_header(buf, offset);
+    let end = data_start + data_len;
+    end + varint_len(end - offset)
+}
+
+/// Returns offset of the entry which ends at `end`.
+fn prev_offset(buf: &[u8], end: usize) -> usize {
+    let mut back_len = 0;
+    let mut shift = 0;
+    let mut pos = end;
+    loop {
+        pos -= 1;
+        let byte = buf[pos];
+        back_len |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    pos - back_len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListPack;
+
+    #[test]
+    fn test_push_pop() {
+        let mut pack = ListPack::new();
+        pack.push_back(b"two");
+        pack.push_front(b"one");
+        pack.push_back(b"three");
+        assert_eq!(pack.len(), 3);
+        assert_eq!(pack.get(0), Some(&b"one"[..]));
+        assert_eq!(pack.get(2), Some(&b"three"[..]));
+        assert_eq!(pack.get(3), None);
+        assert_eq!(pack.pop_back(), Some(b"three".to_vec()));
+        assert_eq!(pack.pop_front(), Some(b"one".to_vec()));
+        assert_eq!(pack.pop_front(), Some(b"two".to_vec()));
+        assert_eq!(pack.pop_front(), None);
+        assert!(pack.is_empty());
+        assert_eq!(pack.byte_len(), 0);
+    }
+
+    #[test]
+    fn test_large_entry() {
+        let large = vec![b'x'; 20_000];
+        let mut pack = ListPack::new();
+        pack.push_back(b"a");
+        pack.push_back(&large);
+        pack.push_back(b"b");
+        assert_eq!(pack.byte_len(), 3 + 3 + 20_000 + 3 + 3);
+        let values: Vec<&[u8]> = pack.iter().rev().collect();
+        assert_eq!(values, vec![&b"b"[..], &large[..], &b"a"[..]]);
+        assert_eq!(pack.pop_back(), Some(b"b".to_vec()));
+        assert_eq!(pack.pop_back(), Some(large));
+    }
+
+    #[test]
+    fn test_insert_replace_remove() {
+        let mut pack: ListPack = ["a", "b", "d"].iter().collect();
+        pack.insert(2, b"c");
+        pack.insert(4, b"e");
+        assert_eq!(
+            pack.iter().collect::<Vec<_>>(),
+            vec![&b"a"[..], b"b", b"c", b"d", b"e"]
+        );
+        assert!(pack.replace(1, b"bbbb"));
+        assert!(!pack.replace(5, b"f"));
+        assert_eq!(pack.get(1), Some(&b"bbbb"[..]));
+        assert_eq!(pack.remove(3), Some(b"d".to_vec()));
+        assert_eq!(pack.remove(9), None);
+        assert_eq!(
+            pack.iter().collect::<Vec<_>>(),
+            vec![&b"a"[..], b"bbbb", b"c", b"e"]
+        );
+    }
+
+    #[test]
+    fn test_split_append() {
+        let mut pack: ListPack = ["a", "b", "c", "d"].iter().collect();
+        let mut tail = pack.split_off(1);
+        assert_eq!(pack.len(), 1);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.first(), Some(&b"b"[..]));
+        assert_eq!(tail.last(), Some(&b"d"[..]));
+        pack.append(&mut tail);
+        assert!(tail.is_empty());
+        assert_eq!(pack.len(), 4);
+        assert_eq!(pack.get(3), Some(&b"d"[..]));
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut pack: ListPack = ["a", "x", "b", "x", "c", "x"].iter().collect();
+        assert_eq!(pack.remove_matches(b"x", 1, 1), 1);
+        assert_eq!(
+            pack.iter().collect::<Vec<_>>(),
+            vec![&b"a"[..], b"x", b"b", b"c", b"x"]
+        );
+        assert_eq!(pack.remove_matches(b"x", 0, usize::MAX), 2);
+        assert_eq!(pack.remove_matches(b"y", 0, usize::MAX), 0);
+        assert_eq!(pack.len(), 3);
+        assert_eq!(pack.byte_len(), 9);
+        assert_eq!(pack.last(), Some(&b"c"[..]));
+    }
+}
@@ -7,21 +7,30 @@ use std::collections::HashMap;
 use tokio::sync::mpsc::{Receiver, Sender};
 
 use crate::commands::{DispatcherToMemCmd, MemToDispatcherCmd};
+use crate::mem::blocking::BlockedClients;
 use crate::mem::db::Db;
+use crate::mem::hash::ExpireKeys;
+pub use crate::mem::list::quick_list::QuickList;
+use crate::mem::pub_sub::PubSub;
+use crate::mem::time_series::index::LabelIndex;
 
 mod auto_suggest;
 mod bitmap;
+mod blocking;
 mod bloom_filter;
 mod count_min_sketch;
 mod cuckoo_filter;
 mod db;
 mod dispatcher;
+mod dump;
 mod generic;
 mod geo;
 mod hash;
 mod hyper;
+mod int_set;
 mod json;
 mod list;
+mod list_pack;
 mod pub_sub;
 mod run;
 mod set;
@@ -36,6 +45,18 @@ mod zset;
 pub struct Mem {
     db: Db,
 
+    /// Keys of hashes which have fields with time to live.
+    hash_expire_keys: ExpireKeys,
+
+    /// Clients blocked by commands like `BZPOPMIN`, indexed by keys they watch.
+    blocked_clients: BlockedClients,
+
+    /// Channels and patterns subscribed by clients.
+    pub_sub: PubSub,
+
+    /// Keys of time series indexed by labels.
+    time_series_index: LabelIndex,
+
     dispatcher_sender: Sender<MemToDispatcherCmd>,
     dispatcher_receiver: Receiver<DispatcherToMemCmd>,
 }
@@ -49,6 +70,10 @@ impl Mem {
     ) -> Self {
         Self {
             db: HashMap::new(),
+            hash_expire_keys: ExpireKeys::new(),
+            blocked_clients: BlockedClients::new(),
+            pub_sub: PubSub::new(),
+            time_series_index: LabelIndex::new(),
 
             dispatcher_sender,
             dispatcher_receiver,
@@ -0,0 +1,180 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::pub_sub::PubSub;
+use crate::mem::util::glob_match;
+
+/// Lists the currently active channels.
+///
+/// An active channel is a channel with one or more subscribers, not including
+/// clients subscribed to patterns.
+/// If no pattern is specified, all the channels are listed, otherwise only channels
+/// matching the specified glob-style pattern are listed.
+///
+/// Reply:
+/// - Array reply: a list of active channels, optionally matching the specified pattern.
+pub fn channels(pub_sub: &PubSub, pattern: Option<&str>) -> ReplyFrame {
+    let mut channels: Vec<&String> = pub_sub
+        .channels
+        .keys()
+        .filter(|channel| {
+            pattern.map_or(true, |pattern| {
+                glob_match(pattern.as_bytes(), channel.as_bytes())
+            })
+        })
+        .collect();
+    channels.sort_unstable();
+    ReplyFrame::Array(
+        channels
+            .into_iter()
+            .map(|channel| ReplyFrame::Bulk(channel.as_bytes().to_vec()))
+            .collect(),
+    )
+}
+
+/// Returns the number of unique patterns that are subscribed to by clients.
+///
+/// Reply:
+/// - Integer reply: the number of patterns all the clients are subscribed to.
+pub fn num_pat(pub_sub: &PubSub) -> ReplyFrame {
+    ReplyFrame::Usize(pub_sub.patterns.len())
+}
+
+/// Returns the number of subscribers, not counting clients subscribed to patterns,
+/// for the specified channels.
+///
+/// Reply:
+/// - Array reply: the channel name followed by the number of its subscribers,
+///   for each channel in the same order as specified.
+pub fn num_sub(pub_sub: &PubSub, channels: &[String]) -> ReplyFrame {
+    let mut list = Vec::with_capacity(channels.len() * 2);
+    for channel in channels {
+        let count = pub_sub.channels.get(channel).map_or(0, BTreeSet::len);
+        list.push(ReplyFrame::Bulk(channel.as_bytes().to_vec()));
+        list.push(ReplyFrame::Usize(count));
+    }
+    ReplyFrame::Array(list)
+}
+
+/// Lists the currently active shard channels.
+///
+/// An active shard channel is a shard channel with one or more subscribers.
+/// If no pattern is specified, all the shard channels are listed, otherwise only
+/// shard channels matching the specified glob-style pattern are listed.
+///
+/// Reply:
+/// - Array reply: a list of active shard channels, optionally matching the specified pattern.
+pub fn shard_channels(pub_sub: &PubSub, pattern: Option<&str>) -> ReplyFrame {
+    let mut channels: Vec<&String> = pub_sub
+        .shard_channels
+        .values()
+        .flat_map(HashMap::keys)
+        .filter(|channel| {
+            pattern.map_or(true, |pattern| {
+                glob_match(pattern.as_bytes(), channel.as_bytes())
+            })
+        })
+        .collect();
+    channels.sort_unstable();
+    ReplyFrame::Array(
+        channels
+            .into_iter()
+            .map(|channel| ReplyFrame::Bulk(channel.as_bytes().to_vec()))
+            .collect(),
+    )
+}
+
+/// Returns the number of subscribers for the specified shard channels.
+///
+/// Reply:
+/// - Array reply: the shard channel name followed by the number of its subscribers,
+///   for each shard channel in the same order as specified.
+pub fn shard_num_sub(pub_sub: &PubSub, channels: &[String]) -> ReplyFrame {
+    let mut list = Vec::with_capacity(channels.len() * 2);
+    for channel in channels {
+        let count = pub_sub.shard_subscribers(channel).map_or(0, BTreeSet::len);
+        list.push(ReplyFrame::Bulk(channel.as_bytes().to_vec()));
+        list.push(ReplyFrame::Usize(count));
+    }
+    ReplyFrame::Array(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::pub_sub::introspect::{
+        channels, num_pat, num_sub, shard_channels, shard_num_sub,
+    };
+    use crate::mem::pub_sub::PubSub;
+
+    #[test]
+    fn test_introspect() {
+        let mut pub_sub = PubSub::new();
+        let alice = SessionGroup::new(1, 1);
+        let bob = SessionGroup::new(1, 2);
+        pub_sub.subscribe_channel(alice, "news.tech");
+        pub_sub.subscribe_channel(bob, "news.tech");
+        pub_sub.subscribe_channel(bob, "sports");
+        pub_sub.subscribe_pattern(alice, "news.*");
+        pub_sub.subscribe_pattern(bob, "news.*");
+
+        assert_eq!(
+            channels(&pub_sub, None),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"news.tech".to_vec()),
+                ReplyFrame::Bulk(b"sports".to_vec()),
+            ])
+        );
+        assert_eq!(
+            channels(&pub_sub, Some("s*")),
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(b"sports".to_vec())])
+        );
+        assert_eq!(num_pat(&pub_sub), ReplyFrame::Usize(1));
+        assert_eq!(
+            num_sub(&pub_sub, &["news.tech".to_owned(), "none".to_owned()]),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"news.tech".to_vec()),
+                ReplyFrame::Usize(2),
+                ReplyFrame::Bulk(b"none".to_vec()),
+                ReplyFrame::Usize(0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_shard_introspect() {
+        let mut pub_sub = PubSub::new();
+        let alice = SessionGroup::new(1, 1);
+        let bob = SessionGroup::new(1, 2);
+        pub_sub.subscribe_shard_channel(alice, "orders");
+        pub_sub.subscribe_shard_channel(bob, "orders");
+        pub_sub.subscribe_shard_channel(bob, "users");
+        pub_sub.subscribe_channel(bob, "news");
+
+        assert_eq!(
+            shard_channels(&pub_sub, None),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"orders".to_vec()),
+                ReplyFrame::Bulk(b"users".to_vec()),
+            ])
+        );
+        assert_eq!(
+            shard_channels(&pub_sub, Some("u*")),
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(b"users".to_vec())])
+        );
+        assert_eq!(
+            shard_num_sub(&pub_sub, &["orders".to_owned(), "news".to_owned()]),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"orders".to_vec()),
+                ReplyFrame::Usize(2),
+                ReplyFrame::Bulk(b"news".to_vec()),
+                ReplyFrame::Usize(0),
+            ])
+        );
+    }
+}
@@ -1,3 +1,385 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Channel, pattern and shard channel subscriptions.
+//!
+//! Confirmations of subscription commands and published messages are not replies
+//! to requests, they are queued as push frames and sent to subscribers after
+//! each request.
+//!
+//! Shard channels are grouped by hash slot, same as keys, so that a shard channel
+//! is owned by the partition which owns a key of the same name.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use stdext::function_name;
+
+use crate::cluster::slot::key_slot;
+use crate::cmd::pub_sub::PubSubCommand;
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::commands::MemToDispatcherCmd;
+use crate::error::Error;
+use crate::listener::types::SessionGroup;
+use crate::mem::Mem;
+
+mod consts;
+pub mod introspect;
+pub mod publish;
+pub mod subscribe;
+pub mod unsubscribe;
+
+#[derive(Debug, Default)]
+pub struct PubSub {
+    /// Subscribers of each channel.
+    channels: HashMap<String, BTreeSet<SessionGroup>>,
+    /// Subscribers of each glob-style pattern.
+    patterns: HashMap<String, BTreeSet<SessionGroup>>,
+    /// Subscribers of each shard channel, grouped by hash slot of channel.
+    shard_channels: BTreeMap<u16, HashMap<String, BTreeSet<SessionGroup>>>,
+    /// Channels, patterns and shard channels subscribed by each client.
+    clients: HashMap<SessionGroup, Subscriptions>,
+    /// Frames to be pushed to clients.
+    push_frames: Vec<(SessionGroup, ReplyFrame)>,
+}
+
+#[derive(Debug, Default)]
+struct Subscriptions {
+    channels: BTreeSet<String>,
+    patterns: BTreeSet<String>,
+    shard_channels: BTreeSet<String>,
+}
+
+impl Subscriptions {
+    fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.patterns.is_empty() && self.shard_channels.is_empty()
+    }
+}
+
+impl PubSub {
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if client subscribed to any channel, pattern or shard channel.
+    #[must_use]
+    pub fn is_subscribed(&self, client: SessionGroup) -> bool {
+        self.clients.contains_key(&client)
+    }
+
+    /// Returns number of channels and patterns subscribed by client.
+    #[must_use]
+    pub fn subscription_count(&self, client: SessionGroup) -> usize {
+        self.clients
+            .get(&client)
+            .map_or(0, |subs| subs.channels.len() + subs.patterns.len())
+    }
+
+    /// Returns number of shard channels subscribed by client.
+    #[must_use]
+    pub fn shard_subscription_count(&self, client: SessionGroup) -> usize {
+        self.clients
+            .get(&client)
+            .map_or(0, |subs| subs.shard_channels.len())
+    }
+
+    /// Returns true if client was not subscribed to the channel.
+    pub fn subscribe_channel(&mut self, client: SessionGroup, channel: &str) -> bool {
+        let subs = self.clients.entry(client).or_default();
+        if !subs.channels.insert(channel.to_owned()) {
+            return false;
+        }
+        add_subscriber(&mut self.channels, channel, client);
+        true
+    }
+
+    /// Returns true if client was not subscribed to the pattern.
+    pub fn subscribe_pattern(&mut self, client: SessionGroup, pattern: &str) -> bool {
+        let subs = self.clients.entry(client).or_default();
+        if !subs.patterns.insert(pattern.to_owned()) {
+            return false;
+        }
+        add_subscriber(&mut self.patterns, pattern, client);
+        true
+    }
+
+    /// Returns true if client was subscribed to the channel.
+    pub fn unsubscribe_channel(&mut self, client: SessionGroup, channel: &str) -> bool {
+        let Some(subs) = self.clients.get_mut(&client) else {
+            return false;
+        };
+        if !subs.channels.remove(channel) {
+            return false;
+        }
+        if subs.is_empty() {
+            self.clients.remove(&client);
+        }
+        remove_subscriber(&mut self.channels, channel, client);
+        true
+    }
+
+    /// Returns true if client was subscribed to the pattern.
+    pub fn unsubscribe_pattern(&mut self, client: SessionGroup, pattern: &str) -> bool {
+        let Some(subs) = self.clients.get_mut(&client) else {
+            return false;
+        };
+        if !subs.patterns.remove(pattern) {
+            return false;
+        }
+        if subs.is_empty() {
+            self.clients.remove(&client);
+        }
+        remove_subscriber(&mut self.patterns, pattern, client);
+        true
+    }
+
+    /// Returns true if client was not subscribed to the shard channel.
+    pub fn subscribe_shard_channel(&mut self, client: SessionGroup, channel: &str) -> bool {
+        let subs = self.clients.entry(client).or_default();
+        if !subs.shard_channels.insert(channel.to_owned()) {
+            return false;
+        }
+        let slot = key_slot(channel.as_bytes());
+        add_subscriber(
+            self.shard_channels.entry(slot).or_default(),
+            channel,
+            client,
+        );
+        true
+    }
+
+    /// Returns true if client was subscribed to the shard channel.
+    pub fn unsubscribe_shard_channel(&mut self, client: SessionGroup, channel: &str) -> bool {
+        let Some(subs) = self.clients.get_mut(&client) else {
+            return false;
+        };
+        if !subs.shard_channels.remove(channel) {
+            return false;
+        }
+        if subs.is_empty() {
+            self.clients.remove(&client);
+        }
+        self.remove_shard_subscriber(channel, client);
+        true
+    }
+
+    fn remove_shard_subscriber(&mut self, channel: &str, client: SessionGroup) {
+        let slot = key_slot(channel.as_bytes());
+        if let Some(channels) = self.shard_channels.get_mut(&slot) {
+            remove_subscriber(channels, channel, client);
+            if channels.is_empty() {
+                self.shard_channels.remove(&slot);
+            }
+        }
+    }
+
+    /// Returns subscribers of the shard channel.
+    #[must_use]
+    pub fn shard_subscribers(&self, channel: &str) -> Option<&BTreeSet<SessionGroup>> {
+        self.shard_channels
+            .get(&key_slot(channel.as_bytes()))?
+            .get(channel)
+    }
+
+    /// Returns channels subscribed by client.
+    #[must_use]
+    pub fn client_channels(&self, client: SessionGroup) -> Vec<String> {
+        self.clients
+            .get(&client)
+            .map(|subs| subs.channels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns patterns subscribed by client.
+    #[must_use]
+    pub fn client_patterns(&self, client: SessionGroup) -> Vec<String> {
+        self.clients
+            .get(&client)
+            .map(|subs| subs.patterns.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns shard channels subscribed by client.
+    #[must_use]
+    pub fn client_shard_channels(&self, client: SessionGroup) -> Vec<String> {
+        self.clients
+            .get(&client)
+            .map(|subs| subs.shard_channels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove all subscriptions of a disconnected client, without notifying it.
+    pub fn remove_client(&mut self, client: SessionGroup) {
+        let Some(subs) = self.clients.remove(&client) else {
+            return;
+        };
+        for channel in &subs.channels {
+            remove_subscriber(&mut self.channels, channel, client);
+        }
+        for pattern in &subs.patterns {
+            remove_subscriber(&mut self.patterns, pattern, client);
+        }
+        for channel in &subs.shard_channels {
+            self.remove_shard_subscriber(channel, client);
+        }
+    }
+
+    /// Queue a frame to be pushed to client.
+    pub fn push(&mut self, client: SessionGroup, frame: ReplyFrame) {
+        self.push_frames.push((client, frame));
+    }
+
+    /// Take all of the queued push frames.
+    pub fn take_push_frames(&mut self) -> Vec<(SessionGroup, ReplyFrame)> {
+        std::mem::take(&mut self.push_frames)
+    }
+}
+
+fn add_subscriber(
+    subscribers: &mut HashMap<String, BTreeSet<SessionGroup>>,
+    name: &str,
+    client: SessionGroup,
+) {
+    subscribers
+        .entry(name.to_owned())
+        .or_default()
+        .insert(client);
+}
+
+fn remove_subscriber(
+    subscribers: &mut HashMap<String, BTreeSet<SessionGroup>>,
+    name: &str,
+    client: SessionGroup,
+) {
+    if let Some(clients) = subscribers.get_mut(name) {
+        clients.remove(&client);
+        if clients.is_empty() {
+            subscribers.remove(name);
+        }
+    }
+}
+
+/// Returns true if all of the shard channels hash to the same slot.
+fn is_same_slot(channels: &[String]) -> bool {
+    let mut slots = channels.iter().map(|channel| key_slot(channel.as_bytes()));
+    slots
+        .next()
+        .map_or(true, |first| slots.all(|slot| slot == first))
+}
+
+/// Frame pushed to client, like `[subscribe, channel, count]`.
+fn push_reply(kind: &'static [u8], name: Option<&str>, count: usize) -> ReplyFrame {
+    ReplyFrame::Array(vec![
+        ReplyFrame::Bulk(kind.to_vec()),
+        name.map_or(ReplyFrame::Null, |name| {
+            ReplyFrame::Bulk(name.as_bytes().to_vec())
+        }),
+        ReplyFrame::Usize(count),
+    ])
+}
+
+impl Mem {
+    /// Handle pub/sub command of client.
+    ///
+    /// Returns None for subscription commands, which are replied with push frames.
+    pub fn handle_pub_sub_command(
+        &mut self,
+        client: SessionGroup,
+        command: PubSubCommand,
+    ) -> Option<ReplyFrame> {
+        let pub_sub = &mut self.pub_sub;
+        match command {
+            PubSubCommand::Channels(pattern) => {
+                return Some(introspect::channels(pub_sub, pattern.as_deref()));
+            }
+            PubSubCommand::NumPat => return Some(introspect::num_pat(pub_sub)),
+            PubSubCommand::NumSub(channels) => {
+                return Some(introspect::num_sub(pub_sub, &channels));
+            }
+            PubSubCommand::PSubscribe(patterns) => {
+                subscribe::psubscribe(pub_sub, client, &patterns);
+            }
+            PubSubCommand::Publish(channel, message) => {
+                return Some(publish::publish(pub_sub, &channel, &message));
+            }
+            PubSubCommand::ShardChannels(pattern) => {
+                return Some(introspect::shard_channels(pub_sub, pattern.as_deref()));
+            }
+            PubSubCommand::ShardNumSub(channels) => {
+                return Some(introspect::shard_num_sub(pub_sub, &channels));
+            }
+            PubSubCommand::SPublish(channel, message) => {
+                return Some(publish::spublish(pub_sub, &channel, &message));
+            }
+            PubSubCommand::SSubscribe(channels) => {
+                subscribe::ssubscribe(pub_sub, client, &channels);
+            }
+            PubSubCommand::SUnsubscribe(channels) => {
+                unsubscribe::sunsubscribe(pub_sub, client, channels);
+            }
+            PubSubCommand::PUnsubscribe(patterns) => {
+                unsubscribe::punsubscribe(pub_sub, client, patterns);
+            }
+            PubSubCommand::Subscribe(channels) => {
+                subscribe::subscribe(pub_sub, client, &channels);
+            }
+            PubSubCommand::Unsubscribe(channels) => {
+                unsubscribe::unsubscribe(pub_sub, client, channels);
+            }
+        }
+        None
+    }
+
+    /// Send queued push frames to clients.
+    ///
+    /// Consecutive frames of the same client are merged into one command.
+    pub(super) async fn send_push_frames(&mut self) -> Result<(), Error> {
+        let mut cmds: Vec<MemToDispatcherCmd> = Vec::new();
+        for (session_group, frame) in self.pub_sub.take_push_frames() {
+            match cmds.last_mut() {
+                Some(cmd) if cmd.session_group == session_group => cmd.reply_frames.push(frame),
+                _ => cmds.push(MemToDispatcherCmd {
+                    session_group,
+                    reply_frames: vec![frame],
+                    push: true,
+                }),
+            }
+        }
+        for cmd in cmds {
+            log::debug!(
+                "{} send push cmd to dispatcher, cmd: {cmd:?}",
+                function_name!()
+            );
+            self.dispatcher_sender.send(cmd).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::listener::types::SessionGroup;
+    use crate::mem::pub_sub::PubSub;
+
+    #[test]
+    fn test_subscriptions() {
+        let mut pub_sub = PubSub::new();
+        let client = SessionGroup::new(1, 1);
+        assert!(!pub_sub.is_subscribed(client));
+        assert!(pub_sub.subscribe_channel(client, "news"));
+        assert!(!pub_sub.subscribe_channel(client, "news"));
+        assert!(pub_sub.subscribe_pattern(client, "news.*"));
+        assert_eq!(pub_sub.subscription_count(client), 2);
+
+        assert!(pub_sub.unsubscribe_channel(client, "news"));
+        assert!(!pub_sub.unsubscribe_channel(client, "news"));
+        assert!(pub_sub.channels.is_empty());
+        assert!(pub_sub.is_subscribed(client));
+
+        pub_sub.remove_client(client);
+        assert!(!pub_sub.is_subscribed(client));
+        assert!(pub_sub.patterns.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::listener::types::SessionGroup;
+use crate::mem::pub_sub::PubSub;
+use crate::mem::util::glob_match;
+
+/// Posts a message to the given channel.
+///
+/// The message is pushed to clients subscribed to the channel as
+/// `[message, channel, message]`, and to clients subscribed to patterns
+/// matching the channel as `[pmessage, pattern, channel, message]`.
+///
+/// Reply:
+/// - Integer reply: the number of clients that received the message.
+pub fn publish(pub_sub: &mut PubSub, channel: &str, message: &[u8]) -> ReplyFrame {
+    let mut frames = Vec::new();
+    if let Some(clients) = pub_sub.channels.get(channel) {
+        for client in clients {
+            let frame = ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"message".to_vec()),
+                ReplyFrame::Bulk(channel.as_bytes().to_vec()),
+                ReplyFrame::Bulk(message.to_vec()),
+            ]);
+            frames.push((*client, frame));
+        }
+    }
+    for (pattern, clients) in &pub_sub.patterns {
+        if !glob_match(pattern.as_bytes(), channel.as_bytes()) {
+            continue;
+        }
+        for client in clients {
+            let frame = ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"pmessage".to_vec()),
+                ReplyFrame::Bulk(pattern.as_bytes().to_vec()),
+                ReplyFrame::Bulk(channel.as_bytes().to_vec()),
+                ReplyFrame::Bulk(message.to_vec()),
+            ]);
+            frames.push((*client, frame));
+        }
+    }
+
+    let receivers = frames.len();
+    for (client, frame) in frames {
+        pub_sub.push(client, frame);
+    }
+    ReplyFrame::Usize(receivers)
+}
+
+/// Posts a message to the given shard channel.
+///
+/// The message is pushed to clients subscribed to the shard channel as
+/// `[smessage, shard channel, message]`. Patterns are not matched against
+/// shard channels.
+///
+/// Reply:
+/// - Integer reply: the number of clients that received the message.
+pub fn spublish(pub_sub: &mut PubSub, channel: &str, message: &[u8]) -> ReplyFrame {
+    let clients: Vec<SessionGroup> = pub_sub
+        .shard_subscribers(channel)
+        .map(|clients| clients.iter().copied().collect())
+        .unwrap_or_default();
+    for &client in &clients {
+        let frame = ReplyFrame::Array(vec![
+            ReplyFrame::Bulk(b"smessage".to_vec()),
+            ReplyFrame::Bulk(channel.as_bytes().to_vec()),
+            ReplyFrame::Bulk(message.to_vec()),
+        ]);
+        pub_sub.push(client, frame);
+    }
+    ReplyFrame::Usize(clients.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::pub_sub::publish::{publish, spublish};
+    use crate::mem::pub_sub::PubSub;
+
+    #[test]
+    fn test_publish() {
+        let mut pub_sub = PubSub::new();
+        let alice = SessionGroup::new(1, 1);
+        let bob = SessionGroup::new(1, 2);
+        assert_eq!(publish(&mut pub_sub, "news", b"hi"), ReplyFrame::zero());
+
+        pub_sub.subscribe_channel(alice, "news");
+        pub_sub.subscribe_pattern(alice, "n*");
+        pub_sub.subscribe_pattern(bob, "n?ws");
+        pub_sub.subscribe_pattern(bob, "sports.*");
+        assert_eq!(publish(&mut pub_sub, "news", b"hi"), ReplyFrame::Usize(3));
+        let frames = pub_sub.take_push_frames();
+        assert_eq!(
+            frames[0],
+            (
+                alice,
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"message".to_vec()),
+                    ReplyFrame::Bulk(b"news".to_vec()),
+                    ReplyFrame::Bulk(b"hi".to_vec()),
+                ])
+            )
+        );
+        assert!(frames.contains(&(
+            bob,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"pmessage".to_vec()),
+                ReplyFrame::Bulk(b"n?ws".to_vec()),
+                ReplyFrame::Bulk(b"news".to_vec()),
+                ReplyFrame::Bulk(b"hi".to_vec()),
+            ])
+        )));
+    }
+
+    #[test]
+    fn test_spublish() {
+        let mut pub_sub = PubSub::new();
+        let alice = SessionGroup::new(1, 1);
+        pub_sub.subscribe_shard_channel(alice, "orders");
+        pub_sub.subscribe_channel(alice, "orders");
+        pub_sub.subscribe_pattern(alice, "*");
+        assert_eq!(spublish(&mut pub_sub, "orders", b"hi"), ReplyFrame::one());
+        assert_eq!(
+            pub_sub.take_push_frames(),
+            vec![(
+                alice,
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"smessage".to_vec()),
+                    ReplyFrame::Bulk(b"orders".to_vec()),
+                    ReplyFrame::Bulk(b"hi".to_vec()),
+                ])
+            )]
+        );
+        assert_eq!(spublish(&mut pub_sub, "other", b"hi"), ReplyFrame::zero());
+    }
+}
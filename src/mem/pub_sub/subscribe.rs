@@ -0,0 +1,128 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::listener::types::SessionGroup;
+use crate::mem::pub_sub::consts::CROSS_SLOT_ERR;
+use crate::mem::pub_sub::{is_same_slot, push_reply, PubSub};
+
+/// Subscribes the client to the specified channels.
+///
+/// Once the client enters the subscribed state it is not supposed to issue any
+/// other commands, except for additional `SUBSCRIBE`, `PSUBSCRIBE`, `UNSUBSCRIBE`
+/// and `PUNSUBSCRIBE` commands.
+///
+/// Push, for each channel:
+/// - Array reply: `subscribe`, the channel name, and the number of channels
+///   and patterns the client is subscribed to.
+pub fn subscribe(pub_sub: &mut PubSub, client: SessionGroup, channels: &[String]) {
+    for channel in channels {
+        pub_sub.subscribe_channel(client, channel);
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"subscribe", Some(channel), count));
+    }
+}
+
+/// Subscribes the client to the given glob-style patterns.
+///
+/// Push, for each pattern:
+/// - Array reply: `psubscribe`, the pattern, and the number of channels
+///   and patterns the client is subscribed to.
+pub fn psubscribe(pub_sub: &mut PubSub, client: SessionGroup, patterns: &[String]) {
+    for pattern in patterns {
+        pub_sub.subscribe_pattern(client, pattern);
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"psubscribe", Some(pattern), count));
+    }
+}
+
+/// Subscribes the client to the specified shard channels.
+///
+/// A shard channel is assigned to a slot in the same way as keys, all of the
+/// shard channels must hash to the same slot.
+///
+/// Push, for each shard channel:
+/// - Array reply: `ssubscribe`, the shard channel name, and the number of
+///   shard channels the client is subscribed to.
+/// - Error reply: if shard channels do not hash to the same slot.
+pub fn ssubscribe(pub_sub: &mut PubSub, client: SessionGroup, channels: &[String]) {
+    if !is_same_slot(channels) {
+        pub_sub.push(client, ReplyFrame::ConstError(CROSS_SLOT_ERR));
+        return;
+    }
+    for channel in channels {
+        pub_sub.subscribe_shard_channel(client, channel);
+        let count = pub_sub.shard_subscription_count(client);
+        pub_sub.push(client, push_reply(b"ssubscribe", Some(channel), count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::pub_sub::consts::CROSS_SLOT_ERR;
+    use crate::mem::pub_sub::subscribe::{psubscribe, ssubscribe, subscribe};
+    use crate::mem::pub_sub::PubSub;
+
+    #[test]
+    fn test_subscribe() {
+        let mut pub_sub = PubSub::new();
+        let client = SessionGroup::new(1, 1);
+        subscribe(&mut pub_sub, client, &["a".to_owned(), "a".to_owned()]);
+        psubscribe(&mut pub_sub, client, &["b*".to_owned()]);
+        let frames = pub_sub.take_push_frames();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(
+            frames[1],
+            (
+                client,
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"subscribe".to_vec()),
+                    ReplyFrame::Bulk(b"a".to_vec()),
+                    ReplyFrame::Usize(1),
+                ])
+            )
+        );
+        assert_eq!(
+            frames[2],
+            (
+                client,
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"psubscribe".to_vec()),
+                    ReplyFrame::Bulk(b"b*".to_vec()),
+                    ReplyFrame::Usize(2),
+                ])
+            )
+        );
+    }
+
+    #[test]
+    fn test_ssubscribe() {
+        let mut pub_sub = PubSub::new();
+        let client = SessionGroup::new(1, 1);
+        subscribe(&mut pub_sub, client, &["a".to_owned()]);
+        ssubscribe(
+            &mut pub_sub,
+            client,
+            &["{user}.a".to_owned(), "{user}.b".to_owned()],
+        );
+        let frames = pub_sub.take_push_frames();
+        assert_eq!(
+            frames[2].1,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"ssubscribe".to_vec()),
+                ReplyFrame::Bulk(b"{user}.b".to_vec()),
+                ReplyFrame::Usize(2),
+            ])
+        );
+
+        ssubscribe(&mut pub_sub, client, &["foo".to_owned(), "bar".to_owned()]);
+        assert_eq!(
+            pub_sub.take_push_frames(),
+            vec![(client, ReplyFrame::ConstError(CROSS_SLOT_ERR))]
+        );
+        assert_eq!(pub_sub.shard_subscription_count(client), 2);
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::listener::types::SessionGroup;
+use crate::mem::pub_sub::consts::CROSS_SLOT_ERR;
+use crate::mem::pub_sub::{is_same_slot, push_reply, PubSub};
+
+/// Unsubscribes the client from the given channels, or from all of them if none is given.
+///
+/// When no channels are specified and the client is not subscribed to any channel,
+/// a single push with nil channel is sent.
+///
+/// Push, for each channel:
+/// - Array reply: `unsubscribe`, the channel name, and the number of channels
+///   and patterns the client is still subscribed to.
+pub fn unsubscribe(pub_sub: &mut PubSub, client: SessionGroup, mut channels: Vec<String>) {
+    if channels.is_empty() {
+        channels = pub_sub.client_channels(client);
+    }
+    if channels.is_empty() {
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"unsubscribe", None, count));
+    }
+    for channel in &channels {
+        pub_sub.unsubscribe_channel(client, channel);
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"unsubscribe", Some(channel), count));
+    }
+}
+
+/// Unsubscribes the client from the given patterns, or from all of them if none is given.
+///
+/// Push, for each pattern:
+/// - Array reply: `punsubscribe`, the pattern, and the number of channels
+///   and patterns the client is still subscribed to.
+pub fn punsubscribe(pub_sub: &mut PubSub, client: SessionGroup, mut patterns: Vec<String>) {
+    if patterns.is_empty() {
+        patterns = pub_sub.client_patterns(client);
+    }
+    if patterns.is_empty() {
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"punsubscribe", None, count));
+    }
+    for pattern in &patterns {
+        pub_sub.unsubscribe_pattern(client, pattern);
+        let count = pub_sub.subscription_count(client);
+        pub_sub.push(client, push_reply(b"punsubscribe", Some(pattern), count));
+    }
+}
+
+/// Unsubscribes the client from the given shard channels, or from all of them
+/// if none is given.
+///
+/// Push, for each shard channel:
+/// - Array reply: `sunsubscribe`, the shard channel name, and the number of
+///   shard channels the client is still subscribed to.
+/// - Error reply: if shard channels do not hash to the same slot.
+pub fn sunsubscribe(pub_sub: &mut PubSub, client: SessionGroup, mut channels: Vec<String>) {
+    if !is_same_slot(&channels) {
+        pub_sub.push(client, ReplyFrame::ConstError(CROSS_SLOT_ERR));
+        return;
+    }
+    if channels.is_empty() {
+        channels = pub_sub.client_shard_channels(client);
+    }
+    if channels.is_empty() {
+        let count = pub_sub.shard_subscription_count(client);
+        pub_sub.push(client, push_reply(b"sunsubscribe", None, count));
+    }
+    for channel in &channels {
+        pub_sub.unsubscribe_shard_channel(client, channel);
+        let count = pub_sub.shard_subscription_count(client);
+        pub_sub.push(client, push_reply(b"sunsubscribe", Some(channel), count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::listener::types::SessionGroup;
+    use crate::mem::pub_sub::subscribe::{psubscribe, ssubscribe, subscribe};
+    use crate::mem::pub_sub::unsubscribe::{punsubscribe, sunsubscribe, unsubscribe};
+    use crate::mem::pub_sub::PubSub;
+
+    #[test]
+    fn test_unsubscribe() {
+        let mut pub_sub = PubSub::new();
+        let client = SessionGroup::new(1, 1);
+        unsubscribe(&mut pub_sub, client, Vec::new());
+        assert_eq!(
+            pub_sub.take_push_frames(),
+            vec![(
+                client,
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"unsubscribe".to_vec()),
+                    ReplyFrame::Null,
+                    ReplyFrame::Usize(0),
+                ])
+            )]
+        );
+
+        subscribe(&mut pub_sub, client, &["a".to_owned(), "b".to_owned()]);
+        psubscribe(&mut pub_sub, client, &["c*".to_owned()]);
+        pub_sub.take_push_frames();
+        unsubscribe(&mut pub_sub, client, Vec::new());
+        let frames = pub_sub.take_push_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[1].1,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"unsubscribe".to_vec()),
+                ReplyFrame::Bulk(b"b".to_vec()),
+                ReplyFrame::Usize(1),
+            ])
+        );
+        punsubscribe(&mut pub_sub, client, vec!["c*".to_owned()]);
+        assert!(!pub_sub.is_subscribed(client));
+    }
+
+    #[test]
+    fn test_sunsubscribe() {
+        let mut pub_sub = PubSub::new();
+        let client = SessionGroup::new(1, 1);
+        ssubscribe(
+            &mut pub_sub,
+            client,
+            &["{a}1".to_owned(), "{a}2".to_owned()],
+        );
+        pub_sub.take_push_frames();
+        sunsubscribe(&mut pub_sub, client, Vec::new());
+        let frames = pub_sub.take_push_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[1].1,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"sunsubscribe".to_vec()),
+                ReplyFrame::Bulk(b"{a}2".to_vec()),
+                ReplyFrame::Usize(0),
+            ])
+        );
+        assert!(!pub_sub.is_subscribed(client));
+        assert!(pub_sub.shard_channels.is_empty());
+    }
+}
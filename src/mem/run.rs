@@ -2,14 +2,30 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::time::Duration;
+
+use crate::mem::util::now_millis;
 use crate::mem::Mem;
 
+/// Interval of active expire cycle, same as `hz 10` in redis.
+const ACTIVE_EXPIRE_INTERVAL: Duration = Duration::from_millis(100);
+
 impl Mem {
     pub async fn run_loop(&mut self) -> ! {
+        let mut active_expire_interval = tokio::time::interval(ACTIVE_EXPIRE_INTERVAL);
         loop {
-            if let Some(cmd) = self.dispatcher_receiver.recv().await {
-                if let Err(err) = self.handle_dispatcher_cmd(cmd).await {
-                    log::warn!("Failed to handle dispatcher cmd, err: {err:?}");
+            tokio::select! {
+                Some(cmd) = self.dispatcher_receiver.recv() => {
+                    if let Err(err) = self.handle_dispatcher_cmd(cmd).await {
+                        log::warn!("Failed to handle dispatcher cmd, err: {err:?}");
+                    }
+                }
+                _ = active_expire_interval.tick() => {
+                    let now = now_millis();
+                    self.active_expire_hash_fields(now);
+                    if let Err(err) = self.expire_blocked_clients(now).await {
+                        log::warn!("Failed to reply blocked clients, err: {err:?}");
+                    }
                 }
             }
         }
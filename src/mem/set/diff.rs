@@ -2,32 +2,51 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
 
 /// Returns the members of the set resulting from the difference between the first set
 /// and all the successive sets.
 ///
+/// Keys that do not exist are considered to be empty sets.
+///
 /// Reply:
 /// - Array reply: a list with members of the resulting set.
 pub fn diff(db: &Db, keys: &[String]) -> ReplyFrame {
-    let mut new_set = match db.get(&keys[0]) {
-        Some(MemObject::Set(old_set)) => old_set.clone(),
-        Some(_) => return ReplyFrame::wrong_type_err(),
-        None => return ReplyFrame::EmptyArray,
+    match diff_members(db, keys) {
+        Ok(new_set) => {
+            let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
+            ReplyFrame::Array(vec)
+        }
+        Err(reply) => reply,
+    }
+}
+
+/// Returns difference between the first set and all the successive sets,
+/// or error reply if any of them is not a set.
+pub fn diff_members(db: &Db, keys: &[String]) -> Result<BTreeSet<Vec<u8>>, ReplyFrame> {
+    let Some((first_key, other_keys)) = keys.split_first() else {
+        return Ok(BTreeSet::new());
+    };
+    let mut new_set: BTreeSet<Vec<u8>> = match db.get(first_key) {
+        Some(MemObject::Set(old_set)) => old_set.iter().map(Cow::into_owned).collect(),
+        Some(_) => return Err(ReplyFrame::wrong_type_err()),
+        None => BTreeSet::new(),
     };
 
-    for key in &keys[1..] {
+    for key in other_keys {
         match db.get(key) {
             Some(MemObject::Set(old_set)) => {
-                new_set = new_set.difference(old_set).cloned().collect();
+                new_set.retain(|member| !old_set.contains(member));
             }
-            Some(_) => return ReplyFrame::wrong_type_err(),
-            None => continue,
+            Some(_) => return Err(ReplyFrame::wrong_type_err()),
+            None => (),
         }
     }
-    let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
-    ReplyFrame::Array(vec)
+    Ok(new_set)
 }
 
 #[cfg(test)]
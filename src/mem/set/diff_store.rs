@@ -0,0 +1,60 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::set::diff::diff_members;
+use crate::mem::set::store;
+
+/// This command is equal to `SDIFF`, but instead of returning the resulting set, it is stored in destination.
+///
+/// If destination already exists, it is overwritten.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting set.
+pub fn diff_store(db: &mut Db, destination: String, keys: &[String]) -> ReplyFrame {
+    match diff_members(db, keys) {
+        Ok(new_set) => store(db, destination, new_set),
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::diff_store::diff_store;
+    use crate::mem::set::members::members;
+
+    #[test]
+    fn test_diff_store() {
+        let mut db = Db::new();
+        let key1 = "key1".to_owned();
+        let key2 = "key2".to_owned();
+        let reply = add(
+            &mut db,
+            key1.clone(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = add(
+            &mut db,
+            key2.clone(),
+            vec![b"c".to_vec(), b"d".to_vec(), b"e".to_vec()],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+
+        let reply = diff_store(&mut db, "key".to_owned(), &[key1, key2]);
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = members(&db, "key");
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"a".to_vec()),
+                ReplyFrame::Bulk(b"b".to_vec()),
+            ])
+        );
+    }
+}
@@ -2,30 +2,48 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
 
 /// Returns the members of the set resulting from the intersection of all the given sets.
+///
+/// Keys that do not exist are considered to be empty sets.
+/// With one of the keys being an empty set, the resulting set is also empty
+/// (since set intersection with an empty set always results in an empty set).
+///
 /// Reply:
 /// - Array reply: an array with the members of the resulting set.
 pub fn intersect(db: &Db, keys: &[String]) -> ReplyFrame {
-    let mut new_set = match db.get(&keys[0]) {
-        Some(MemObject::Set(old_set)) => old_set.clone(),
-        Some(_) => return ReplyFrame::wrong_type_err(),
-        None => return ReplyFrame::EmptyArray,
-    };
+    match intersect_members(db, keys) {
+        Ok(new_set) => {
+            let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
+            ReplyFrame::Array(vec)
+        }
+        Err(reply) => reply,
+    }
+}
 
-    for key in &keys[1..] {
+/// Returns intersection of sets stored at `keys`, or error reply if any of them is not a set.
+pub fn intersect_members(db: &Db, keys: &[String]) -> Result<BTreeSet<Vec<u8>>, ReplyFrame> {
+    let mut new_set: Option<BTreeSet<Vec<u8>>> = None;
+    let mut has_empty = false;
+    for key in keys {
         match db.get(key) {
-            Some(MemObject::Set(old_set)) => {
-                new_set = new_set.intersection(old_set).cloned().collect();
-            }
-            Some(_) => return ReplyFrame::wrong_type_err(),
-            None => continue,
+            Some(MemObject::Set(old_set)) => match new_set.as_mut() {
+                Some(new_set) => new_set.retain(|member| old_set.contains(member)),
+                None => new_set = Some(old_set.iter().map(Cow::into_owned).collect()),
+            },
+            Some(_) => return Err(ReplyFrame::wrong_type_err()),
+            None => has_empty = true,
         }
     }
-    let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
-    ReplyFrame::Array(vec)
+    if has_empty {
+        return Ok(BTreeSet::new());
+    }
+    Ok(new_set.unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::set::intersect::intersect_members;
+
+/// This command is similar to `SINTER`, but instead of returning the result set,
+/// it returns just the cardinality of the result.
+///
+/// Keys that do not exist are considered to be empty sets.
+///
+/// By default, the command calculates the cardinality of the intersection of all given sets.
+/// When provided with the optional LIMIT argument (which defaults to 0 and means unlimited),
+/// if the intersection cardinality reaches limit partway through the computation,
+/// the algorithm will exit and yield limit as the cardinality.
+///
+/// Reply:
+/// - Integer reply: the number of the elements in the resulting intersection.
+pub fn intersect_card(db: &Db, keys: &[String], limit: usize) -> ReplyFrame {
+    match intersect_members(db, keys) {
+        Ok(new_set) => {
+            let len = if limit == 0 {
+                new_set.len()
+            } else {
+                new_set.len().min(limit)
+            };
+            ReplyFrame::Usize(len)
+        }
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::intersect_card::intersect_card;
+
+    #[test]
+    fn test_intersect_card() {
+        let mut db = Db::new();
+        let key1 = "key1".to_owned();
+        let key2 = "key2".to_owned();
+        let reply = add(
+            &mut db,
+            key1.clone(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(4));
+        let reply = add(
+            &mut db,
+            key2.clone(),
+            vec![b"c".to_vec(), b"d".to_vec(), b"e".to_vec()],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+
+        let keys = [key1, key2];
+        assert_eq!(intersect_card(&db, &keys, 0), ReplyFrame::Usize(2));
+        assert_eq!(intersect_card(&db, &keys, 1), ReplyFrame::one());
+        assert_eq!(intersect_card(&db, &keys[..1], 10), ReplyFrame::Usize(4));
+    }
+}
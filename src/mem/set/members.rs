@@ -2,6 +2,8 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::borrow::Cow;
+
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
 
@@ -15,7 +17,7 @@ pub fn members(db: &Db, key: &str) -> ReplyFrame {
     match db.get(key) {
         Some(MemObject::Set(old_set)) => {
             // NOTE(Shaohua): Sort members.
-            let mut vec: Vec<Vec<u8>> = old_set.iter().cloned().collect();
+            let mut vec: Vec<Vec<u8>> = old_set.iter().map(Cow::into_owned).collect();
             vec.sort_unstable();
             let vec: Vec<_> = vec.into_iter().map(ReplyFrame::bulk).collect();
             ReplyFrame::Array(vec)
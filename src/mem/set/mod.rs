@@ -6,19 +6,28 @@ use std::collections::BTreeSet;
 
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::cmd::set::SetCommand;
+use crate::mem::db::{Db, MemObject};
 use crate::mem::Mem;
 
 pub mod add;
 pub mod diff;
+pub mod diff_store;
 pub mod intersect;
+pub mod intersect_card;
+pub mod intersect_store;
 pub mod is_member;
 pub mod len;
 pub mod members;
+pub mod move_member;
+pub mod multi_is_member;
+pub mod pop;
 pub mod random_member;
 pub mod remove;
+mod set_object;
 pub mod union;
+pub mod union_store;
 
-pub type SetObject = BTreeSet<Vec<u8>>;
+pub use set_object::SetObject;
 
 impl Mem {
     pub fn handle_set_command(&mut self, command: SetCommand) -> ReplyFrame {
@@ -27,13 +36,53 @@ impl Mem {
             SetCommand::Len(key) => len::len(&self.db, &key),
             SetCommand::Members(key) => members::members(&self.db, &key),
             SetCommand::IsMember(key, member) => is_member::is_member(&self.db, &key, &member),
+            SetCommand::MultiIsMember(key, members) => {
+                multi_is_member::multi_is_member(&self.db, &key, &members)
+            }
+            SetCommand::Move(source, destination, member) => {
+                move_member::move_member(&mut self.db, &source, destination, member)
+            }
+            SetCommand::Pop(key, count) => pop::pop(&mut self.db, &key, count),
             SetCommand::Remove(key, members) => remove::remove(&mut self.db, &key, &members),
             SetCommand::RandomMember(key, count) => {
                 random_member::random_member(&self.db, &key, count)
             }
             SetCommand::Intersect(keys) => intersect::intersect(&self.db, &keys),
+            SetCommand::IntersectCard(keys, limit) => {
+                intersect_card::intersect_card(&self.db, &keys, limit)
+            }
+            SetCommand::IntersectStore(destination, keys) => {
+                intersect_store::intersect_store(&mut self.db, destination, &keys)
+            }
             SetCommand::Union(keys) => union::union(&self.db, &keys),
+            SetCommand::UnionStore(destination, keys) => {
+                union_store::union_store(&mut self.db, destination, &keys)
+            }
             SetCommand::Diff(keys) => diff::diff(&self.db, &keys),
+            SetCommand::DiffStore(destination, keys) => {
+                diff_store::diff_store(&mut self.db, destination, &keys)
+            }
         }
     }
 }
+
+/// Store members as a set at `destination`, overwriting any existing value.
+///
+/// The destination key is deleted if there are no members.
+pub fn store(db: &mut Db, destination: String, members: BTreeSet<Vec<u8>>) -> ReplyFrame {
+    if members.is_empty() {
+        db.remove(&destination);
+        return ReplyFrame::zero();
+    }
+    let new_set: SetObject = members.into_iter().collect();
+    let len = new_set.len();
+    db.insert(destination, MemObject::Set(new_set));
+    ReplyFrame::Usize(len)
+}
+
+/// Delete the set stored at key if it has no members left.
+pub fn remove_if_empty(db: &mut Db, key: &str) {
+    if matches!(db.get(key), Some(MemObject::Set(old_set)) if old_set.is_empty()) {
+        db.remove(key);
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::set::{remove_if_empty, SetObject};
+
+/// Move member from the set at source to the set at destination.
+///
+/// This operation is atomic. In every given moment the element will appear
+/// to be a member of source or destination for other clients.
+///
+/// If the source set does not exist or does not contain the specified element,
+/// no operation is performed and 0 is returned. Otherwise, the element is removed
+/// from the source set and added to the destination set. When the specified element
+/// already exists in the destination set, it is only removed from the source set.
+///
+/// An error is returned if source or destination does not hold a set value.
+///
+/// Reply, one of the following:
+/// - Integer reply: 1 if the element is moved.
+/// - Integer reply: 0 if the element is not a member of source and no operation was performed.
+pub fn move_member(db: &mut Db, source: &str, destination: String, member: Vec<u8>) -> ReplyFrame {
+    match db.get(destination.as_str()) {
+        Some(MemObject::Set(_)) | None => (),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+    }
+    match db.get_mut(source) {
+        Some(MemObject::Set(old_set)) => {
+            if source == destination {
+                return ReplyFrame::from_bool(old_set.contains(&member));
+            }
+            if !old_set.remove(&member) {
+                return ReplyFrame::zero();
+            }
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::zero(),
+    }
+    remove_if_empty(db, source);
+
+    match db
+        .entry(destination)
+        .or_insert_with(|| MemObject::Set(SetObject::new()))
+    {
+        MemObject::Set(new_set) => {
+            new_set.insert(member);
+            ReplyFrame::one()
+        }
+        _ => ReplyFrame::internal_err(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::members::members;
+    use crate::mem::set::move_member::move_member;
+    use crate::mem::string::set::set;
+
+    #[test]
+    fn test_move_member() {
+        let mut db = Db::new();
+        let reply = add(
+            &mut db,
+            "myset".to_owned(),
+            vec![b"one".to_vec(), b"two".to_vec()],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = add(&mut db, "myotherset".to_owned(), vec![b"three".to_vec()]);
+        assert_eq!(reply, ReplyFrame::one());
+
+        let reply = move_member(&mut db, "myset", "myotherset".to_owned(), b"two".to_vec());
+        assert_eq!(reply, ReplyFrame::one());
+        let reply = move_member(&mut db, "myset", "myotherset".to_owned(), b"four".to_vec());
+        assert_eq!(reply, ReplyFrame::zero());
+        let reply = members(&db, "myset");
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(b"one".to_vec())])
+        );
+        let reply = members(&db, "myotherset");
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"three".to_vec()),
+                ReplyFrame::Bulk(b"two".to_vec()),
+            ])
+        );
+
+        set(&mut db, "str".to_owned(), b"value".to_vec());
+        let reply = move_member(&mut db, "myset", "str".to_owned(), b"one".to_vec());
+        assert_eq!(reply, ReplyFrame::wrong_type_err());
+        let reply = move_member(&mut db, "myset", "newset".to_owned(), b"one".to_vec());
+        assert_eq!(reply, ReplyFrame::one());
+        assert!(!db.contains_key("myset"));
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns whether each member is a member of the set stored at key.
+///
+/// For every member, 1 is returned if the value is a member of the set,
+/// or 0 if the element is not a member of the set or if key does not exist.
+///
+/// Reply:
+/// - Array reply: a list representing the membership of the given elements,
+///   in the same order as they are requested.
+pub fn multi_is_member(db: &Db, key: &str, members: &[Vec<u8>]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::Set(old_set)) => {
+            let array = members
+                .iter()
+                .map(|member| ReplyFrame::from_bool(old_set.contains(member)))
+                .collect();
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::zero(); members.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::multi_is_member::multi_is_member;
+
+    #[test]
+    fn test_multi_is_member() {
+        let mut db = Db::new();
+        let key = "myset".to_owned();
+        let reply = add(&mut db, key.clone(), vec![b"one".to_vec()]);
+        assert_eq!(reply, ReplyFrame::one());
+        let members = [b"one".to_vec(), b"notamember".to_vec()];
+        let reply = multi_is_member(&db, &key, &members);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::one(), ReplyFrame::zero()])
+        );
+        let reply = multi_is_member(&db, "nokey", &members);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![ReplyFrame::zero(), ReplyFrame::zero()])
+        );
+    }
+}
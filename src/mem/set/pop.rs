@@ -0,0 +1,107 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::borrow::Cow;
+
+use rand::seq::IteratorRandom;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::set::remove_if_empty;
+
+/// Removes and returns one or more random members from the set value store at key.
+///
+/// By default, the command pops a single member from the set. When provided with
+/// the optional count argument, the reply will consist of up to count members,
+/// depending on the set's cardinality.
+///
+/// Reply, one of the following:
+/// - Null reply: if the key does not exist.
+/// - Bulk string reply: when called without the count argument, the removed member.
+/// - Array reply: when called with the count argument, a list of the removed members.
+pub fn pop(db: &mut Db, key: &str, count: Option<usize>) -> ReplyFrame {
+    let reply = match db.get_mut(key) {
+        Some(MemObject::Set(old_set)) => {
+            let mut rng = rand::thread_rng();
+            let picked: Vec<Vec<u8>> = old_set
+                .iter()
+                .choose_multiple(&mut rng, count.unwrap_or(1))
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect();
+            for member in &picked {
+                old_set.remove(member);
+            }
+            if count.is_some() {
+                ReplyFrame::Array(picked.into_iter().map(ReplyFrame::bulk).collect())
+            } else {
+                picked
+                    .into_iter()
+                    .next()
+                    .map_or_else(ReplyFrame::null, ReplyFrame::bulk)
+            }
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => {
+            return if count.is_some() {
+                ReplyFrame::Array(Vec::new())
+            } else {
+                ReplyFrame::Null
+            };
+        }
+    };
+    remove_if_empty(db, key);
+    reply
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::len::len;
+    use crate::mem::set::pop::pop;
+
+    #[test]
+    fn test_pop() {
+        let mut db = Db::new();
+        let key = "myset".to_owned();
+        let members = [
+            ReplyFrame::Bulk(b"one".to_vec()),
+            ReplyFrame::Bulk(b"two".to_vec()),
+            ReplyFrame::Bulk(b"three".to_vec()),
+            ReplyFrame::Bulk(b"four".to_vec()),
+        ];
+        let reply = add(
+            &mut db,
+            key.clone(),
+            vec![
+                b"one".to_vec(),
+                b"two".to_vec(),
+                b"three".to_vec(),
+                b"four".to_vec(),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(4));
+
+        let reply = pop(&mut db, &key, None);
+        assert!(members.contains(&reply));
+        let reply = pop(&mut db, &key, Some(2));
+        let ReplyFrame::Array(array) = reply else {
+            panic!("Expected array reply");
+        };
+        assert_eq!(array.len(), 2);
+        assert!(array.iter().all(|member| members.contains(member)));
+        assert_eq!(len(&db, &key), ReplyFrame::one());
+
+        let reply = pop(&mut db, &key, Some(5));
+        let ReplyFrame::Array(array) = reply else {
+            panic!("Expected array reply");
+        };
+        assert_eq!(array.len(), 1);
+        assert!(!db.contains_key(&key));
+        assert_eq!(pop(&mut db, &key, None), ReplyFrame::Null);
+        assert_eq!(pop(&mut db, &key, Some(1)), ReplyFrame::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Set object with compact encodings for small sets.
+//!
+//! - A set containing only integers is stored in an `IntSet`.
+//! - Other small sets are stored in a `ListPack`.
+//! - Once a set grows beyond thresholds, it is promoted to a `HashSet`,
+//!   and is never converted back.
+
+use std::borrow::Cow;
+use std::collections::{hash_set, HashSet};
+use std::ops::Range;
+
+use crate::mem::int_set::IntSet;
+use crate::mem::list_pack::{self, ListPack};
+
+/// Max number of members in an intset encoded set, same as `set-max-intset-entries` in redis.
+pub const SET_MAX_INTSET_ENTRIES: usize = 512;
+
+/// Max number of members in a listpack encoded set, same as `set-max-listpack-entries` in redis.
+pub const SET_MAX_LISTPACK_ENTRIES: usize = 128;
+
+/// Max length of member in a listpack encoded set, same as `set-max-listpack-value` in redis.
+pub const SET_MAX_LISTPACK_VALUE: usize = 64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Encoding {
+    IntSet(IntSet),
+    ListPack(ListPack),
+    HashTable(HashSet<Vec<u8>>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SetObject {
+    encoding: Encoding,
+}
+
+impl Default for SetObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetObject {
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            encoding: Encoding::IntSet(IntSet::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match &self.encoding {
+            Encoding::IntSet(int_set) => int_set.len(),
+            Encoding::ListPack(list_pack) => list_pack.len(),
+            Encoding::HashTable(set) => set.len(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns name of current encoding, used by `OBJECT ENCODING`.
+    #[must_use]
+    pub const fn encoding(&self) -> &'static str {
+        match self.encoding {
+            Encoding::IntSet(_) => "intset",
+            Encoding::ListPack(_) => "listpack",
+            Encoding::HashTable(_) => "hashtable",
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, member: &[u8]) -> bool {
+        match &self.encoding {
+            Encoding::IntSet(int_set) => {
+                parse_int(member).is_some_and(|value| int_set.contains(value))
+            }
+            Encoding::ListPack(list_pack) => list_pack.iter().any(|entry| entry == member),
+            Encoding::HashTable(set) => set.contains(member),
+        }
+    }
+
+    /// Add a member, returns false if it is already in set.
+    pub fn insert(&mut self, member: Vec<u8>) -> bool {
+        if let Encoding::IntSet(int_set) = &mut self.encoding {
+            if let Some(value) = parse_int(&member) {
+                if int_set.len() < SET_MAX_INTSET_ENTRIES || int_set.contains(value) {
+                    return int_set.insert(value);
+                }
+                self.convert_to_hash_table();
+            } else if int_set.len() < SET_MAX_LISTPACK_ENTRIES
+                && member.len() <= SET_MAX_LISTPACK_VALUE
+            {
+                self.convert_to_list_pack();
+            } else {
+                self.convert_to_hash_table();
+            }
+        }
+
+        if let Encoding::ListPack(list_pack) = &mut self.encoding {
+            if list_pack.iter().any(|entry| entry == member) {
+                return false;
+            }
+            if list_pack.len() < SET_MAX_LISTPACK_ENTRIES && member.len() <= SET_MAX_LISTPACK_VALUE
+            {
+                list_pack.push_back(&member);
+                return true;
+            }
+            self.convert_to_hash_table();
+        }
+
+        match &mut self.encoding {
+            Encoding::HashTable(set) => set.insert(member),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Remove a member, returns false if it is not in set.
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match &mut self.encoding {
+            Encoding::IntSet(int_set) => {
+                parse_int(member).is_some_and(|value| int_set.remove(value))
+            }
+            Encoding::ListPack(list_pack) => list_pack
+                .iter()
+                .position(|entry| entry == member)
+                .is_some_and(|index| list_pack.remove(index).is_some()),
+            Encoding::HashTable(set) => set.remove(member),
+        }
+    }
+
+    /// Returns an iterator of members, in arbitrary order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_> {
+        match &self.encoding {
+            Encoding::IntSet(int_set) => Iter::IntSet(int_set, 0..int_set.len()),
+            Encoding::ListPack(list_pack) => Iter::ListPack(list_pack.iter()),
+            Encoding::HashTable(set) => Iter::HashTable(set.iter()),
+        }
+    }
+
+    fn convert_to_list_pack(&mut self) {
+        let list_pack = self.iter().collect();
+        self.encoding = Encoding::ListPack(list_pack);
+    }
+
+    fn convert_to_hash_table(&mut self) {
+        let set = self.iter().map(Cow::into_owned).collect();
+        self.encoding = Encoding::HashTable(set);
+    }
+}
+
+impl FromIterator<Vec<u8>> for SetObject {
+    fn from_iter<I: IntoIterator<Item = Vec<u8>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for member in iter {
+            set.insert(member);
+        }
+        set
+    }
+}
+
+/// Parse member as integer, only if it is in canonical decimal format,
+/// so that it can be converted back to the same bytes.
+fn parse_int(member: &[u8]) -> Option<i64> {
+    // i64::MIN has 20 bytes.
+    if member.is_empty() || member.len() > 20 {
+        return None;
+    }
+    let value = std::str::from_utf8(member).ok()?.parse::<i64>().ok()?;
+    if value.to_string().as_bytes() == member {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+pub enum Iter<'a> {
+    IntSet(&'a IntSet, Range<usize>),
+    ListPack(list_pack::Iter<'a>),
+    HashTable(hash_set::Iter<'a, Vec<u8>>),
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Cow<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::IntSet(int_set, range) => {
+                let value = int_set.get(range.next()?)?;
+                Some(Cow::Owned(value.to_string().into_bytes()))
+            }
+            Self::ListPack(iter) => iter.next().map(Cow::Borrowed),
+            Self::HashTable(iter) => iter.next().map(|member| Cow::Borrowed(member.as_slice())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_int, SetObject, SET_MAX_INTSET_ENTRIES, SET_MAX_LISTPACK_ENTRIES,
+        SET_MAX_LISTPACK_VALUE,
+    };
+
+    #[test]
+    fn test_parse_int() {
+        assert_eq!(parse_int(b"42"), Some(42));
+        assert_eq!(parse_int(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse_int(b"+42"), None);
+        assert_eq!(parse_int(b"042"), None);
+        assert_eq!(parse_int(b"-0"), None);
+        assert_eq!(parse_int(b"4.2"), None);
+        assert_eq!(parse_int(b""), None);
+    }
+
+    #[test]
+    fn test_int_set_encoding() {
+        let mut set = SetObject::new();
+        for i in 0..SET_MAX_INTSET_ENTRIES {
+            assert!(set.insert(i.to_string().into_bytes()));
+        }
+        assert_eq!(set.encoding(), "intset");
+        assert!(!set.insert(b"0".to_vec()));
+        assert!(set.contains(b"511"));
+        assert!(!set.contains(b"0511"));
+        assert!(set.remove(b"1"));
+        assert!(!set.remove(b"foo"));
+        assert!(set.insert(b"1".to_vec()));
+        assert!(set.insert(b"512".to_vec()));
+        assert_eq!(set.encoding(), "hashtable");
+        assert_eq!(set.len(), SET_MAX_INTSET_ENTRIES + 1);
+        assert!(set.contains(b"511"));
+    }
+
+    #[test]
+    fn test_list_pack_encoding() {
+        let mut set: SetObject = [b"1".to_vec(), b"2".to_vec()].into_iter().collect();
+        assert_eq!(set.encoding(), "intset");
+        assert!(set.insert(b"apple".to_vec()));
+        assert_eq!(set.encoding(), "listpack");
+        assert!(set.contains(b"1"));
+        assert!(set.remove(b"2"));
+        assert!(!set.insert(b"apple".to_vec()));
+        for i in set.len()..SET_MAX_LISTPACK_ENTRIES {
+            assert!(set.insert(format!("member{i}").into_bytes()));
+        }
+        assert_eq!(set.encoding(), "listpack");
+        assert!(set.insert(b"one-more".to_vec()));
+        assert_eq!(set.encoding(), "hashtable");
+        assert_eq!(set.iter().count(), SET_MAX_LISTPACK_ENTRIES + 1);
+
+        let mut set = SetObject::new();
+        assert!(set.insert(vec![b'x'; SET_MAX_LISTPACK_VALUE + 1]));
+        assert_eq!(set.encoding(), "hashtable");
+    }
+}
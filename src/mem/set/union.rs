@@ -2,27 +2,41 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
 use crate::cmd::reply_frame::ReplyFrame;
 use crate::mem::db::{Db, MemObject};
-use crate::mem::set::SetObject;
 
 /// Returns the members of the set resulting from the union of all the given sets.
 ///
+/// Keys that do not exist are considered to be empty sets.
+///
 /// Reply:
 /// - Array reply: a list with members of the resulting set.
 pub fn union(db: &Db, keys: &[String]) -> ReplyFrame {
-    let mut new_set = SetObject::new();
+    match union_members(db, keys) {
+        Ok(new_set) => {
+            let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
+            ReplyFrame::Array(vec)
+        }
+        Err(reply) => reply,
+    }
+}
+
+/// Returns union of sets stored at `keys`, or error reply if any of them is not a set.
+pub fn union_members(db: &Db, keys: &[String]) -> Result<BTreeSet<Vec<u8>>, ReplyFrame> {
+    let mut new_set = BTreeSet::new();
     for key in keys {
         match db.get(key) {
             Some(MemObject::Set(old_set)) => {
-                new_set = new_set.union(old_set).cloned().collect();
+                new_set.extend(old_set.iter().map(Cow::into_owned));
             }
-            Some(_) => return ReplyFrame::wrong_type_err(),
-            None => continue,
+            Some(_) => return Err(ReplyFrame::wrong_type_err()),
+            None => (),
         }
     }
-    let vec = new_set.into_iter().map(ReplyFrame::bulk).collect();
-    ReplyFrame::Array(vec)
+    Ok(new_set)
 }
 
 #[cfg(test)]
@@ -0,0 +1,55 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::set::store;
+use crate::mem::set::union::union_members;
+
+/// This command is equal to `SUNION`, but instead of returning the resulting set, it is stored in destination.
+///
+/// If destination already exists, it is overwritten.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting set.
+pub fn union_store(db: &mut Db, destination: String, keys: &[String]) -> ReplyFrame {
+    match union_members(db, keys) {
+        Ok(new_set) => store(db, destination, new_set),
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::set::add::add;
+    use crate::mem::set::members::members;
+    use crate::mem::set::union_store::union_store;
+    use crate::mem::string::set::set;
+
+    #[test]
+    fn test_union_store() {
+        let mut db = Db::new();
+        let key1 = "key1".to_owned();
+        let key2 = "key2".to_owned();
+        let reply = add(&mut db, key1.clone(), vec![b"a".to_vec(), b"b".to_vec()]);
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = add(&mut db, key2.clone(), vec![b"b".to_vec(), b"c".to_vec()]);
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        set(&mut db, "key".to_owned(), b"string".to_vec());
+
+        let reply = union_store(&mut db, "key".to_owned(), &[key1, key2]);
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = members(&db, "key");
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"a".to_vec()),
+                ReplyFrame::Bulk(b"b".to_vec()),
+                ReplyFrame::Bulk(b"c".to_vec()),
+            ])
+        );
+    }
+}
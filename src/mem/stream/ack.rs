@@ -0,0 +1,30 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::StreamId;
+use crate::mem::db::{Db, MemObject};
+
+/// Removes one or multiple entries from the pending entries list (PEL) of a stream
+/// consumer group.
+///
+/// Once a consumer successfully processes an entry, it should call `XACK`
+/// so that such entry does not get processed again.
+///
+/// Reply:
+/// - Integer reply: The command returns the number of entries successfully acknowledged.
+///   Certain entry IDs may no longer be part of the PEL (for example because
+///   they have already been acknowledged), and `XACK` will not count them as
+///   successfully acknowledged.
+pub fn ack(db: &mut Db, key: &str, group: &str, ids: &[StreamId]) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream
+            .group_mut(group)
+            .map_or_else(ReplyFrame::zero, |group| {
+                ReplyFrame::Usize(ids.iter().filter(|id| group.ack(**id)).count())
+            }),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
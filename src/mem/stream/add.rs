@@ -0,0 +1,109 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::AddOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::StreamObject;
+use crate::mem::util::now_millis;
+
+/// Appends the specified stream entry to the stream at the specified key.
+///
+/// If the key does not exist, as a side effect of running this command the key is
+/// created with a stream value, unless NOMKSTREAM option is specified.
+///
+/// An entry is composed of a list of field-value pairs. The field-value pairs are
+/// stored in the same order they are given by the user.
+///
+/// The ID of entry is auto-generated with `*`, or explicitly specified.
+/// The ID must be greater than any other ID in the stream.
+///
+/// Optionally, the stream is trimmed with MAXLEN or MINID after adding the entry.
+///
+/// Reply, one of the following:
+/// - Bulk string reply: The ID of the added entry.
+/// - Nil reply: if the NOMKSTREAM option is given and the key doesn't exist.
+pub fn add(
+    db: &mut Db,
+    key: String,
+    options: &AddOptions,
+    fields: Vec<(Vec<u8>, Vec<u8>)>,
+) -> ReplyFrame {
+    let now = u64::try_from(now_millis()).unwrap_or_default();
+    let stream = match db.entry(key) {
+        Entry::Occupied(occupied) => match occupied.into_mut() {
+            MemObject::Stream(stream) => stream,
+            _ => return ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(_) if options.no_mkstream => return ReplyFrame::Null,
+        Entry::Vacant(vacant) => {
+            // Do not create an empty stream if ID is invalid.
+            let stream = StreamObject::new();
+            if let Err(err) = stream.next_id(options.id, now) {
+                return ReplyFrame::ConstError(err);
+            }
+            match vacant.insert(MemObject::Stream(stream)) {
+                MemObject::Stream(stream) => stream,
+                _ => unreachable!(),
+            }
+        }
+    };
+
+    let id = match stream.next_id(options.id, now) {
+        Ok(id) => id,
+        Err(err) => return ReplyFrame::ConstError(err),
+    };
+    stream.append(id, fields);
+    if let Some(trim) = &options.trim {
+        stream.trim(trim);
+    }
+    ReplyFrame::Bulk(id.to_string().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddId, AddOptions, StreamId, TrimOptions, TrimStrategy};
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::consts::ID_TOO_SMALL_ERR;
+    use crate::mem::stream::len::len;
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        let fields = vec![(b"name".to_vec(), b"Sara".to_vec())];
+        let options = AddOptions {
+            no_mkstream: true,
+            ..AddOptions::default()
+        };
+        let reply = add(&mut db, key.clone(), &options, fields.clone());
+        assert_eq!(reply, ReplyFrame::Null);
+
+        let options = AddOptions {
+            id: AddId::Explicit(StreamId::new(1, 1)),
+            ..AddOptions::default()
+        };
+        let reply = add(&mut db, key.clone(), &options, fields.clone());
+        assert_eq!(reply, ReplyFrame::Bulk(b"1-1".to_vec()));
+        let reply = add(&mut db, key.clone(), &options, fields.clone());
+        assert_eq!(reply, ReplyFrame::ConstError(ID_TOO_SMALL_ERR));
+
+        let options = AddOptions {
+            id: AddId::AutoSeq(1),
+            trim: Some(TrimOptions {
+                strategy: TrimStrategy::MaxLen(1),
+                approx: false,
+                limit: None,
+            }),
+            ..AddOptions::default()
+        };
+        let reply = add(&mut db, key.clone(), &options, fields);
+        assert_eq!(reply, ReplyFrame::Bulk(b"1-2".to_vec()));
+        assert_eq!(len(&db, &key), ReplyFrame::Usize(1));
+    }
+}
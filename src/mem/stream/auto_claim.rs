@@ -0,0 +1,157 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{AutoClaimOptions, StreamId};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::{entry_reply, id_reply, no_group_err};
+use crate::mem::util::now_millis;
+
+/// Transfers ownership of pending entries idle for at least `min-idle-time` milliseconds,
+/// with IDs equal or greater than `start`, to the consumer specified in options.
+///
+/// It behaves like `XPENDING` followed by `XCLAIM`. At most count entries are
+/// claimed (defaults to 100), and at most ten times count pending entries are scanned.
+/// Entries deleted from the stream are removed from the pending entries list.
+///
+/// With JUSTID option, only IDs of claimed entries are returned, and their delivery
+/// counts are not increased.
+///
+/// Reply:
+/// - Array reply, an array with three elements:
+///   1. A stream ID to be used as the start argument for the next call to `XAUTOCLAIM`.
+///   2. An array containing all the successfully claimed entries.
+///   3. An array containing entry IDs that no longer exist in the stream.
+pub fn auto_claim(db: &mut Db, key: &str, group: &str, options: &AutoClaimOptions) -> ReplyFrame {
+    let stream = match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return no_group_err(key, group),
+    };
+    let Some(group_obj) = stream.group(group) else {
+        return no_group_err(key, group);
+    };
+
+    let now = now_millis();
+    let mut attempts = options.count * 10;
+    let mut count = options.count;
+    // One more entry is taken to find out start of next call.
+    let mut candidates = group_obj
+        .pending()
+        .range(options.start..)
+        .take(attempts + 1)
+        .map(|(id, entry)| (*id, entry.delivery_time))
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let mut claimed = Vec::new();
+    let mut deleted = Vec::new();
+    while attempts > 0 && count > 0 {
+        let Some((id, delivery_time)) = candidates.next() else {
+            break;
+        };
+        attempts -= 1;
+        if options.min_idle > 0 && now - delivery_time < options.min_idle {
+            continue;
+        }
+        count -= 1;
+        let entry = stream.get(id).map(|entry| {
+            if options.just_id {
+                id_reply(id)
+            } else {
+                entry_reply(&entry)
+            }
+        });
+        let Some(group_obj) = stream.group_mut(group) else {
+            break;
+        };
+        if let Some(entry) = entry {
+            let pending = group_obj.claim(id, &options.consumer, now, now);
+            if !options.just_id {
+                pending.delivery_count += 1;
+            }
+            claimed.push(entry);
+        } else {
+            // Entry was deleted from stream.
+            group_obj.ack(id);
+            deleted.push(id_reply(id));
+        }
+    }
+    let next_start = candidates.next().map_or(StreamId::MIN, |(id, _)| id);
+    ReplyFrame::Array(vec![
+        id_reply(next_start),
+        ReplyFrame::Array(claimed),
+        ReplyFrame::Array(deleted),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{
+        AddId, AddOptions, AutoClaimOptions, GroupId, GroupOptions, ReadGroupId, ReadGroupOptions,
+        StreamId,
+    };
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::auto_claim::auto_claim;
+    use crate::mem::stream::delete::delete;
+    use crate::mem::stream::group::create;
+    use crate::mem::stream::read_group::read_group;
+
+    #[test]
+    fn test_auto_claim() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        for ms in 1..=3 {
+            let options = AddOptions {
+                id: AddId::Explicit(StreamId::new(ms, 0)),
+                ..AddOptions::default()
+            };
+            add(
+                &mut db,
+                key.clone(),
+                &options,
+                vec![(b"a".to_vec(), b"1".to_vec())],
+            );
+        }
+        let options = GroupOptions {
+            group: "mygroup".to_owned(),
+            id: GroupId::Explicit(StreamId::MIN),
+            mkstream: false,
+            entries_read: None,
+        };
+        create(&mut db, key.clone(), &options);
+        let options = ReadGroupOptions {
+            group: "mygroup".to_owned(),
+            consumer: "alice".to_owned(),
+            count: None,
+            block: None,
+            no_ack: false,
+            streams: vec![(key.clone(), ReadGroupId::New)],
+        };
+        read_group(&mut db, &options);
+        delete(&mut db, &key, &[StreamId::new(1, 0)]);
+
+        let options = AutoClaimOptions {
+            consumer: "bob".to_owned(),
+            min_idle: 0,
+            start: StreamId::MIN,
+            count: 2,
+            just_id: true,
+        };
+        assert_eq!(
+            auto_claim(&mut db, &key, "mygroup", &options),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"3-0".to_vec()),
+                ReplyFrame::Array(vec![ReplyFrame::Bulk(b"2-0".to_vec())]),
+                ReplyFrame::Array(vec![ReplyFrame::Bulk(b"1-0".to_vec())]),
+            ])
+        );
+        assert!(matches!(
+            auto_claim(&mut db, &key, "nogroup", &options),
+            ReplyFrame::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{ClaimOptions, StreamId};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::{entry_reply, id_reply, no_group_err};
+use crate::mem::util::now_millis;
+
+/// Changes the ownership of pending entries of a consumer group, so that the new owner
+/// is the consumer specified in options.
+///
+/// Only entries idle for at least `min-idle-time` milliseconds are claimed.
+/// Entries deleted from the stream are removed from the pending entries list.
+///
+/// Options:
+/// - IDLE: set the idle time of the entries, defaults to 0.
+/// - TIME: set the delivery time of the entries to a unix time in milliseconds.
+/// - RETRYCOUNT: set the delivery count of the entries, otherwise it is increased.
+/// - FORCE: create the pending entries even if not exist, as long as they are in stream.
+/// - JUSTID: return just IDs of claimed entries, and do not increase delivery count.
+/// - LASTID: update last delivered ID of the consumer group.
+///
+/// Reply:
+/// - Array reply: entries successfully claimed, or their IDs with JUSTID option.
+pub fn claim(db: &mut Db, key: &str, group: &str, options: &ClaimOptions) -> ReplyFrame {
+    let stream = match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return no_group_err(key, group),
+    };
+    let now = now_millis();
+    let delivery_time = options
+        .time
+        .or_else(|| options.idle.map(|idle| now.saturating_sub(idle)))
+        .filter(|time| (0..=now).contains(time))
+        .unwrap_or(now);
+
+    let entries: Vec<(StreamId, Option<ReplyFrame>)> = options
+        .ids
+        .iter()
+        .map(|&id| {
+            let entry = stream.get(id).map(|entry| {
+                if options.just_id {
+                    id_reply(id)
+                } else {
+                    entry_reply(&entry)
+                }
+            });
+            (id, entry)
+        })
+        .collect();
+    let Some(group_obj) = stream.group_mut(group) else {
+        return no_group_err(key, group);
+    };
+
+    let mut claimed = Vec::new();
+    for (id, entry) in entries {
+        let Some(entry) = entry else {
+            // Entry was deleted from stream.
+            group_obj.ack(id);
+            continue;
+        };
+        match group_obj.pending().get(&id) {
+            None if !options.force => continue,
+            Some(pending) if now - pending.delivery_time < options.min_idle => continue,
+            _ => (),
+        }
+        let pending = group_obj.claim(id, &options.consumer, delivery_time, now);
+        if let Some(retry_count) = options.retry_count {
+            pending.delivery_count = retry_count;
+        } else if !options.just_id {
+            pending.delivery_count += 1;
+        }
+        claimed.push(entry);
+    }
+    if let Some(last_id) = options.last_id {
+        group_obj.advance_last_id(last_id);
+    }
+    ReplyFrame::Array(claimed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddOptions, ClaimOptions, GroupId, GroupOptions, StreamId};
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::claim::claim;
+    use crate::mem::stream::group::create;
+
+    #[test]
+    fn test_claim() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        add(
+            &mut db,
+            key.clone(),
+            &AddOptions::default(),
+            vec![(b"a".to_vec(), b"1".to_vec())],
+        );
+        let options = GroupOptions {
+            group: "mygroup".to_owned(),
+            id: GroupId::Explicit(StreamId::MIN),
+            mkstream: false,
+            entries_read: None,
+        };
+        create(&mut db, key.clone(), &options);
+        let Some(MemObject::Stream(stream)) = db.get(&key) else {
+            panic!("expected stream");
+        };
+        let id = stream.last_id();
+
+        let mut options = ClaimOptions {
+            consumer: "bob".to_owned(),
+            min_idle: 0,
+            ids: vec![id],
+            idle: None,
+            time: None,
+            retry_count: None,
+            force: false,
+            just_id: true,
+            last_id: None,
+        };
+        // Not in pending entries list.
+        assert_eq!(
+            claim(&mut db, &key, "mygroup", &options),
+            ReplyFrame::Array(Vec::new())
+        );
+        options.force = true;
+        options.retry_count = Some(5);
+        assert_eq!(
+            claim(&mut db, &key, "mygroup", &options),
+            ReplyFrame::Array(vec![ReplyFrame::Bulk(id.to_string().into_bytes())])
+        );
+        let Some(MemObject::Stream(stream)) = db.get(&key) else {
+            panic!("expected stream");
+        };
+        let group = stream.group("mygroup").unwrap();
+        assert_eq!(group.pending()[&id].consumer, "bob");
+        assert_eq!(group.pending()[&id].delivery_count, 5);
+
+        // Not idle for long enough.
+        options.min_idle = 3_600_000;
+        assert_eq!(
+            claim(&mut db, &key, "mygroup", &options),
+            ReplyFrame::Array(Vec::new())
+        );
+    }
+}
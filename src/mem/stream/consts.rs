@@ -0,0 +1,12 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const ID_TOO_SMALL_ERR: &str =
+    "ERR The ID specified in XADD is equal or smaller than the target stream top item";
+pub const ID_ZERO_ERR: &str = "ERR The ID specified in XADD must be greater than 0-0";
+pub const ID_EXHAUSTED_ERR: &str =
+    "ERR The stream has exhausted the last possible ID, unable to add more items";
+pub const KEY_REQUIRED_ERR: &str = "ERR The XGROUP subcommand requires the key to exist. \
+Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.";
+pub const BUSY_GROUP_ERR: &str = "BUSYGROUP Consumer Group name already exists";
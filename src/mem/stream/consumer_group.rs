@@ -0,0 +1,345 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Consumer groups of stream.
+//!
+//! Each group tracks the last delivered ID and a pending entries list (PEL),
+//! which holds entries delivered to consumers but not yet acknowledged.
+//! Each consumer holds IDs of its own pending entries as well.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::cmd::stream::StreamId;
+
+/// An entry delivered to a consumer but not yet acknowledged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingEntry {
+    /// Name of consumer that owns this entry.
+    pub consumer: String,
+    /// Last time this entry was delivered, unix time in milliseconds.
+    pub delivery_time: i64,
+    /// Number of times this entry was delivered.
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Consumer {
+    /// Last time this consumer interacted with the group, unix time in milliseconds.
+    pub seen_time: i64,
+    /// Last time this consumer read or claimed an entry successfully.
+    pub active_time: Option<i64>,
+    /// IDs of pending entries owned by this consumer.
+    pub pending: BTreeSet<StreamId>,
+}
+
+impl Consumer {
+    #[must_use]
+    #[inline]
+    pub const fn new(now: i64) -> Self {
+        Self {
+            seen_time: now,
+            active_time: None,
+            pending: BTreeSet::new(),
+        }
+    }
+}
+
+/// Basic statistics of stream, used to track number of entries read by a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub len: usize,
+    pub first_id: StreamId,
+    pub last_id: StreamId,
+    pub max_deleted_id: StreamId,
+    pub entries_added: u64,
+}
+
+impl StreamStats {
+    /// Returns true if some entries with IDs not less than `start` were deleted.
+    #[must_use]
+    pub fn has_tombstones(&self, start: StreamId) -> bool {
+        if self.len == 0 || self.max_deleted_id == StreamId::MIN {
+            return false;
+        }
+        // The latest tombstone is before the first entry.
+        if self.first_id > self.max_deleted_id {
+            return false;
+        }
+        start <= self.max_deleted_id
+    }
+
+    /// Estimate the logical position of `id` since the first ever entry of stream.
+    ///
+    /// Returns None if it can not be computed because of deleted entries.
+    #[must_use]
+    pub fn estimate_entries_read(&self, id: StreamId) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+        if self.len == 0 && id <= self.last_id {
+            return Some(self.entries_added);
+        }
+        if id == self.last_id {
+            return Some(self.entries_added);
+        }
+        if id > self.last_id {
+            return None;
+        }
+        if self.max_deleted_id == StreamId::MIN || self.max_deleted_id < self.first_id {
+            // There is no fragmentation ahead.
+            let len = self.len as u64;
+            if id < self.first_id {
+                return Some(self.entries_added - len);
+            }
+            if id == self.first_id {
+                return Some(self.entries_added - len + 1);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerGroup {
+    /// ID of the last entry delivered to consumers of this group.
+    last_id: StreamId,
+    /// Logical number of entries read by this group, None if unknown.
+    entries_read: Option<u64>,
+    pending: BTreeMap<StreamId, PendingEntry>,
+    consumers: BTreeMap<String, Consumer>,
+}
+
+impl ConsumerGroup {
+    #[must_use]
+    #[inline]
+    pub const fn new(last_id: StreamId, entries_read: Option<u64>) -> Self {
+        Self {
+            last_id,
+            entries_read,
+            pending: BTreeMap::new(),
+            consumers: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn entries_read(&self) -> Option<u64> {
+        self.entries_read
+    }
+
+    /// Number of entries in the stream that are still waiting to be delivered
+    /// to consumers of this group, None if it can not be computed.
+    #[must_use]
+    pub fn lag(&self, stats: &StreamStats) -> Option<u64> {
+        if stats.entries_added == 0 {
+            return Some(0);
+        }
+        let entries_read = match self.entries_read {
+            // No fragmentation ahead means the counter is valid.
+            Some(entries_read) if !stats.has_tombstones(self.last_id) => Some(entries_read),
+            _ => stats.estimate_entries_read(self.last_id),
+        };
+        entries_read.map(|entries_read| stats.entries_added.saturating_sub(entries_read))
+    }
+
+    /// Update last delivered ID and number of entries read.
+    pub fn set_last_id(&mut self, last_id: StreamId, entries_read: Option<u64>) {
+        self.last_id = last_id;
+        self.entries_read = entries_read;
+    }
+
+    /// Update last delivered ID if `id` is greater than it.
+    pub fn advance_last_id(&mut self, id: StreamId) {
+        self.last_id = self.last_id.max(id);
+    }
+
+    /// Pending entries list of this group.
+    #[must_use]
+    #[inline]
+    pub const fn pending(&self) -> &BTreeMap<StreamId, PendingEntry> {
+        &self.pending
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn consumers(&self) -> &BTreeMap<String, Consumer> {
+        &self.consumers
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn consumer(&self, name: &str) -> Option<&Consumer> {
+        self.consumers.get(name)
+    }
+
+    /// Create a new consumer, returns false if it already exists.
+    pub fn create_consumer(&mut self, name: &str, now: i64) -> bool {
+        if self.consumers.contains_key(name) {
+            return false;
+        }
+        self.consumers.insert(name.to_owned(), Consumer::new(now));
+        true
+    }
+
+    /// Delete a consumer and its pending entries, returns number of pending entries
+    /// it had.
+    pub fn delete_consumer(&mut self, name: &str) -> Option<usize> {
+        let consumer = self.consumers.remove(name)?;
+        for id in &consumer.pending {
+            self.pending.remove(id);
+        }
+        Some(consumer.pending.len())
+    }
+
+    /// Get a consumer, create it if not exists, and update its seen time.
+    pub fn touch_consumer(&mut self, name: &str, now: i64) -> &mut Consumer {
+        let consumer = self
+            .consumers
+            .entry(name.to_owned())
+            .or_insert_with(|| Consumer::new(now));
+        consumer.seen_time = now;
+        consumer
+    }
+
+    /// Mark an entry as delivered to group, by updating last delivered ID and
+    /// number of entries read.
+    pub fn mark_read(&mut self, id: StreamId, stats: &StreamStats) {
+        if id <= self.last_id {
+            return;
+        }
+        match self.entries_read {
+            // A valid counter and no future tombstones mean we can increment it.
+            Some(entries_read) if !stats.has_tombstones(id) => {
+                self.entries_read = Some(entries_read + 1);
+            }
+            _ if stats.entries_added > 0 => {
+                self.entries_read = stats.estimate_entries_read(id);
+            }
+            _ => (),
+        }
+        self.last_id = id;
+    }
+
+    /// Add an entry to pending entries list of `consumer`, or move it from another
+    /// consumer, and reset its delivery count.
+    pub fn deliver(&mut self, id: StreamId, consumer: &str, now: i64) {
+        let entry = PendingEntry {
+            consumer: consumer.to_owned(),
+            delivery_time: now,
+            delivery_count: 1,
+        };
+        if let Some(old) = self.pending.insert(id, entry) {
+            if let Some(old_consumer) = self.consumers.get_mut(&old.consumer) {
+                old_consumer.pending.remove(&id);
+            }
+        }
+        self.touch_consumer(consumer, now).pending.insert(id);
+    }
+
+    /// Transfer ownership of a pending entry to `consumer` and update its delivery time,
+    /// the entry is created if not exists.
+    pub fn claim(
+        &mut self,
+        id: StreamId,
+        consumer: &str,
+        delivery_time: i64,
+        now: i64,
+    ) -> &mut PendingEntry {
+        let entry = self.pending.entry(id).or_insert_with(|| PendingEntry {
+            consumer: String::new(),
+            delivery_time,
+            delivery_count: 1,
+        });
+        entry.delivery_time = delivery_time;
+        if entry.consumer != consumer {
+            let old = std::mem::replace(&mut entry.consumer, consumer.to_owned());
+            if let Some(old_consumer) = self.consumers.get_mut(&old) {
+                old_consumer.pending.remove(&id);
+            }
+        }
+        let owner = self
+            .consumers
+            .entry(consumer.to_owned())
+            .or_insert_with(|| Consumer::new(now));
+        owner.seen_time = now;
+        owner.active_time = Some(now);
+        owner.pending.insert(id);
+        entry
+    }
+
+    /// Increase delivery count of a pending entry, and update its delivery time.
+    pub fn redeliver(&mut self, id: StreamId, now: i64) {
+        if let Some(entry) = self.pending.get_mut(&id) {
+            entry.delivery_time = now;
+            entry.delivery_count += 1;
+        }
+    }
+
+    /// Remove an entry from pending entries list, returns false if not found.
+    pub fn ack(&mut self, id: StreamId) -> bool {
+        let Some(entry) = self.pending.remove(&id) else {
+            return false;
+        };
+        if let Some(consumer) = self.consumers.get_mut(&entry.consumer) {
+            consumer.pending.remove(&id);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::stream::StreamId;
+    use crate::mem::stream::consumer_group::{ConsumerGroup, StreamStats};
+
+    #[test]
+    fn test_pending() {
+        let mut group = ConsumerGroup::new(StreamId::MIN, Some(0));
+        let id = StreamId::new(1, 0);
+        group.deliver(id, "alice", 10);
+        assert_eq!(group.pending()[&id].consumer, "alice");
+        assert!(group.consumer("alice").unwrap().pending.contains(&id));
+
+        group.claim(id, "bob", 20, 20).delivery_count += 1;
+        assert_eq!(group.pending()[&id].delivery_count, 2);
+        assert!(group.consumer("alice").unwrap().pending.is_empty());
+        assert!(group.consumer("bob").unwrap().pending.contains(&id));
+
+        assert!(group.ack(id));
+        assert!(!group.ack(id));
+        assert!(group.consumer("bob").unwrap().pending.is_empty());
+
+        group.deliver(id, "bob", 30);
+        assert_eq!(group.delete_consumer("bob"), Some(1));
+        assert!(group.pending().is_empty());
+    }
+
+    #[test]
+    fn test_entries_read() {
+        let stats = StreamStats {
+            len: 3,
+            first_id: StreamId::new(1, 0),
+            last_id: StreamId::new(3, 0),
+            max_deleted_id: StreamId::MIN,
+            entries_added: 3,
+        };
+        assert_eq!(stats.estimate_entries_read(StreamId::new(3, 0)), Some(3));
+        assert_eq!(stats.estimate_entries_read(StreamId::new(1, 0)), Some(1));
+        assert_eq!(stats.estimate_entries_read(StreamId::MIN), Some(0));
+        assert_eq!(stats.estimate_entries_read(StreamId::new(2, 0)), None);
+        assert_eq!(stats.estimate_entries_read(StreamId::new(4, 0)), None);
+
+        let mut group = ConsumerGroup::new(StreamId::MIN, Some(0));
+        group.mark_read(StreamId::new(1, 0), &stats);
+        group.mark_read(StreamId::new(2, 0), &stats);
+        assert_eq!(group.entries_read(), Some(2));
+        assert_eq!(group.last_id(), StreamId::new(2, 0));
+    }
+}
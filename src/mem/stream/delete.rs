@@ -0,0 +1,62 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::StreamId;
+use crate::mem::db::{Db, MemObject};
+
+/// Removes the specified entries from a stream, and returns the number of entries deleted.
+///
+/// This number may be less than the number of IDs passed to the command in the case
+/// where some of the specified IDs do not exist in the stream.
+///
+/// Reply:
+/// - Integer reply: the number of entries that were deleted.
+pub fn delete(db: &mut Db, key: &str, ids: &[StreamId]) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => {
+            let count = ids.iter().filter(|id| stream.delete(**id)).count();
+            ReplyFrame::Usize(count)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddId, AddOptions, StreamId};
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::delete::delete;
+
+    #[test]
+    fn test_delete() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        for seq in 1..=3 {
+            let options = AddOptions {
+                id: AddId::Explicit(StreamId::new(1_538_561_700_640, seq)),
+                ..AddOptions::default()
+            };
+            let fields = vec![(b"a".to_vec(), seq.to_string().into_bytes())];
+            add(&mut db, key.clone(), &options, fields);
+        }
+        let reply = delete(
+            &mut db,
+            &key,
+            &[
+                StreamId::new(1_538_561_700_640, 2),
+                StreamId::new(1_538_561_700_640, 2),
+                StreamId::new(1_538_561_700_640, 9),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(1));
+        assert_eq!(
+            delete(&mut db, "nokey", &[StreamId::MIN]),
+            ReplyFrame::zero()
+        );
+    }
+}
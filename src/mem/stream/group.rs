@@ -0,0 +1,180 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{GroupId, GroupOptions};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::consts::{BUSY_GROUP_ERR, KEY_REQUIRED_ERR};
+use crate::mem::stream::{ConsumerGroup, StreamObject};
+use crate::mem::util::now_millis;
+
+/// Creates a new consumer group uniquely identified by `group` for the stream stored at key.
+///
+/// The ID specifies the last delivered entry in the stream from the new group's
+/// perspective. The special ID `$` is the ID of the last entry in the stream.
+///
+/// By default, the command expects the stream exists. With MKSTREAM option,
+/// an empty stream is created if not exists.
+///
+/// With ENTRIESREAD option, the number of entries read by the group is set,
+/// to enable consumer group lag tracking.
+///
+/// Reply:
+/// - Simple string reply: OK.
+pub fn create(db: &mut Db, key: String, options: &GroupOptions) -> ReplyFrame {
+    let stream = match db.entry(key) {
+        Entry::Occupied(occupied) => match occupied.into_mut() {
+            MemObject::Stream(stream) => stream,
+            _ => return ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(_) if !options.mkstream => return ReplyFrame::ConstError(KEY_REQUIRED_ERR),
+        Entry::Vacant(vacant) => match vacant.insert(MemObject::Stream(StreamObject::new())) {
+            MemObject::Stream(stream) => stream,
+            _ => unreachable!(),
+        },
+    };
+
+    let last_id = match options.id {
+        GroupId::Last => stream.last_id(),
+        GroupId::Explicit(id) => id,
+    };
+    let group = ConsumerGroup::new(last_id, options.entries_read);
+    if stream.create_group(&options.group, group) {
+        ReplyFrame::ok()
+    } else {
+        ReplyFrame::ConstError(BUSY_GROUP_ERR)
+    }
+}
+
+/// Set the last delivered ID for a consumer group.
+///
+/// Reply:
+/// - Simple string reply: OK.
+pub fn set_id(db: &mut Db, key: &str, options: &GroupOptions) -> ReplyFrame {
+    let stream = match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_REQUIRED_ERR),
+    };
+    let last_id = match options.id {
+        GroupId::Last => stream.last_id(),
+        GroupId::Explicit(id) => id,
+    };
+    stream.group_mut(&options.group).map_or_else(
+        || no_group_err(key, &options.group),
+        |group| {
+            group.set_last_id(last_id, options.entries_read);
+            ReplyFrame::ok()
+        },
+    )
+}
+
+/// Completely destroys a consumer group, with all its consumers and pending entries.
+///
+/// Reply:
+/// - Integer reply: the number of destroyed consumer groups, 0 or 1.
+pub fn destroy(db: &mut Db, key: &str, group: &str) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => ReplyFrame::from_bool(stream.destroy_group(group)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_REQUIRED_ERR),
+    }
+}
+
+/// Create a consumer named `consumer` in the consumer group `group`.
+///
+/// Consumers are also created automatically whenever an operation, such as `XREADGROUP`,
+/// references a consumer that doesn't exist.
+///
+/// Reply:
+/// - Integer reply: the number of created consumers, 0 or 1.
+pub fn create_consumer(db: &mut Db, key: &str, group: &str, consumer: &str) -> ReplyFrame {
+    let stream = match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_REQUIRED_ERR),
+    };
+    stream.group_mut(group).map_or_else(
+        || no_group_err(key, group),
+        |group_obj| ReplyFrame::from_bool(group_obj.create_consumer(consumer, now_millis())),
+    )
+}
+
+/// Delete a consumer from the consumer group.
+///
+/// Pending entries owned by the consumer are deleted as well.
+///
+/// Reply:
+/// - Integer reply: the number of pending messages the consumer had before it was deleted.
+pub fn delete_consumer(db: &mut Db, key: &str, group: &str, consumer: &str) -> ReplyFrame {
+    let stream = match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_REQUIRED_ERR),
+    };
+    stream.group_mut(group).map_or_else(
+        || no_group_err(key, group),
+        |group_obj| ReplyFrame::Usize(group_obj.delete_consumer(consumer).unwrap_or_default()),
+    )
+}
+
+fn no_group_err(key: &str, group: &str) -> ReplyFrame {
+    ReplyFrame::Error(format!(
+        "NOGROUP No such consumer group '{group}' for key name '{key}'"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{GroupId, GroupOptions, StreamId};
+    use crate::mem::db::Db;
+    use crate::mem::stream::consts::{BUSY_GROUP_ERR, KEY_REQUIRED_ERR};
+    use crate::mem::stream::group::{create, create_consumer, delete_consumer, destroy, set_id};
+
+    #[test]
+    fn test_group() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        let mut options = GroupOptions {
+            group: "mygroup".to_owned(),
+            id: GroupId::Last,
+            mkstream: false,
+            entries_read: None,
+        };
+        assert_eq!(
+            create(&mut db, key.clone(), &options),
+            ReplyFrame::ConstError(KEY_REQUIRED_ERR)
+        );
+        options.mkstream = true;
+        assert_eq!(create(&mut db, key.clone(), &options), ReplyFrame::ok());
+        assert_eq!(
+            create(&mut db, key.clone(), &options),
+            ReplyFrame::ConstError(BUSY_GROUP_ERR)
+        );
+
+        options.id = GroupId::Explicit(StreamId::new(1, 0));
+        assert_eq!(set_id(&mut db, &key, &options), ReplyFrame::ok());
+        assert_eq!(
+            create_consumer(&mut db, &key, "mygroup", "alice"),
+            ReplyFrame::one()
+        );
+        assert_eq!(
+            create_consumer(&mut db, &key, "mygroup", "alice"),
+            ReplyFrame::zero()
+        );
+        assert_eq!(
+            delete_consumer(&mut db, &key, "mygroup", "alice"),
+            ReplyFrame::zero()
+        );
+        assert!(matches!(
+            create_consumer(&mut db, &key, "nogroup", "alice"),
+            ReplyFrame::Error(_)
+        ));
+        assert_eq!(destroy(&mut db, &key, "mygroup"), ReplyFrame::one());
+        assert_eq!(destroy(&mut db, &key, "mygroup"), ReplyFrame::zero());
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::StreamId;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::{entry_reply, id_reply, u64_reply, ConsumerGroup, StreamStats};
+use crate::mem::util::now_millis;
+
+/// Returns information about the stream stored at key.
+///
+/// The informative details provided by this command are:
+/// - length: the number of entries in the stream
+/// - radix-tree-keys: the number of keys in the underlying radix data structure
+/// - radix-tree-nodes: the number of nodes in the underlying radix data structure
+/// - groups: the number of consumer groups defined for the stream
+/// - last-generated-id: the ID of the least-recently entry that was added to the stream
+/// - max-deleted-entry-id: the maximal entry ID that was deleted from the stream
+/// - entries-added: the count of all entries added to the stream during its lifetime
+/// - first-entry: the ID and field-value tuples of the first entry in the stream
+/// - last-entry: the ID and field-value tuples of the last entry in the stream
+///
+/// The optional FULL modifier provides a more verbose reply, with at most `count`
+/// entries of the stream in ascending order instead of first and last entries.
+/// Default count is 10, and 0 means all entries are returned.
+///
+/// Reply:
+/// - Array reply: a list of informational bits
+pub fn info_stream(db: &Db, key: &str, full: Option<usize>) -> ReplyFrame {
+    let stream = match db.get(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::no_such_key(),
+    };
+
+    let first_id = stream.first_entry().map_or(StreamId::MIN, |entry| entry.id);
+    let mut arr = vec![
+        field("length"),
+        ReplyFrame::Usize(stream.len()),
+        field("radix-tree-keys"),
+        ReplyFrame::Usize(stream.node_count()),
+        field("radix-tree-nodes"),
+        ReplyFrame::Usize(stream.node_count()),
+        field("last-generated-id"),
+        id_reply(stream.last_id()),
+        field("max-deleted-entry-id"),
+        id_reply(stream.max_deleted_id()),
+        field("entries-added"),
+        u64_reply(stream.entries_added()),
+        field("recorded-first-entry-id"),
+        id_reply(first_id),
+    ];
+
+    if let Some(count) = full {
+        let count = if count == 0 { usize::MAX } else { count };
+        let entries = stream
+            .range(StreamId::MIN, StreamId::MAX, false)
+            .take(count)
+            .map(|entry| entry_reply(&entry))
+            .collect();
+        let stats = stream.stats();
+        let groups = stream
+            .groups()
+            .iter()
+            .map(|(name, group)| group_full_reply(name, group, &stats, count))
+            .collect();
+        arr.extend([
+            field("entries"),
+            ReplyFrame::Array(entries),
+            field("groups"),
+            ReplyFrame::Array(groups),
+        ]);
+    } else {
+        let first_entry = stream
+            .first_entry()
+            .map_or(ReplyFrame::Null, |entry| entry_reply(&entry));
+        let last_entry = stream
+            .last_entry()
+            .map_or(ReplyFrame::Null, |entry| entry_reply(&entry));
+        arr.extend([
+            field("groups"),
+            ReplyFrame::Usize(stream.groups().len()),
+            field("first-entry"),
+            first_entry,
+            field("last-entry"),
+            last_entry,
+        ]);
+    }
+    ReplyFrame::Array(arr)
+}
+
+/// Returns the list of all consumer groups of the stream stored at key.
+///
+/// The informative details provided for each group are:
+/// - name: the consumer group's name
+/// - consumers: the number of consumers in the group
+/// - pending: the length of the group's pending entries list (PEL)
+/// - last-delivered-id: the ID of the last entry delivered to the group's consumers
+/// - entries-read: the logical "read counter" of the last entry delivered to group's consumers
+/// - lag: the number of entries in the stream that are still waiting to be delivered
+///   to the group's consumers, or nil when that number can't be determined
+///
+/// Reply:
+/// - Array reply: a list of consumer groups.
+pub fn info_groups(db: &Db, key: &str) -> ReplyFrame {
+    let stream = match db.get(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::no_such_key(),
+    };
+    let stats = stream.stats();
+    let groups = stream
+        .groups()
+        .iter()
+        .map(|(name, group)| {
+            ReplyFrame::Array(vec![
+                field("name"),
+                ReplyFrame::Bulk(name.as_bytes().to_vec()),
+                field("consumers"),
+                ReplyFrame::Usize(group.consumers().len()),
+                field("pending"),
+                ReplyFrame::Usize(group.pending().len()),
+                field("last-delivered-id"),
+                id_reply(group.last_id()),
+                field("entries-read"),
+                group.entries_read().map_or(ReplyFrame::Null, u64_reply),
+                field("lag"),
+                group.lag(&stats).map_or(ReplyFrame::Null, u64_reply),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(groups)
+}
+
+/// Returns the list of consumers that belong to the consumer group of the stream
+/// stored at key.
+///
+/// The informative details provided for each consumer are:
+/// - name: the consumer's name
+/// - pending: the number of entries in the PEL, pending messages for the consumer
+/// - idle: the number of milliseconds that have passed since the consumer's
+///   last attempted interaction
+/// - inactive: the number of milliseconds that have passed since the consumer's
+///   last successful interaction, or -1 if it never happened
+///
+/// Reply:
+/// - Array reply: a list of consumers.
+pub fn info_consumers(db: &Db, key: &str, group: &str) -> ReplyFrame {
+    let stream = match db.get(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::no_such_key(),
+    };
+    let Some(group_obj) = stream.group(group) else {
+        return ReplyFrame::Error(format!(
+            "NOGROUP No such consumer group '{group}' for key name '{key}'"
+        ));
+    };
+    let now = now_millis();
+    let consumers = group_obj
+        .consumers()
+        .iter()
+        .map(|(name, consumer)| {
+            ReplyFrame::Array(vec![
+                field("name"),
+                ReplyFrame::Bulk(name.as_bytes().to_vec()),
+                field("pending"),
+                ReplyFrame::Usize(consumer.pending.len()),
+                field("idle"),
+                ReplyFrame::I64(now - consumer.seen_time),
+                field("inactive"),
+                ReplyFrame::I64(consumer.active_time.map_or(-1, |time| now - time)),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(consumers)
+}
+
+/// Returns details of consumer group in `XINFO STREAM FULL`, with at most `count`
+/// pending entries of the group and of each consumer.
+fn group_full_reply(
+    name: &str,
+    group: &ConsumerGroup,
+    stats: &StreamStats,
+    count: usize,
+) -> ReplyFrame {
+    let pending = group
+        .pending()
+        .iter()
+        .take(count)
+        .map(|(id, entry)| {
+            ReplyFrame::Array(vec![
+                id_reply(*id),
+                ReplyFrame::Bulk(entry.consumer.as_bytes().to_vec()),
+                ReplyFrame::I64(entry.delivery_time),
+                u64_reply(entry.delivery_count),
+            ])
+        })
+        .collect();
+    let consumers = group
+        .consumers()
+        .iter()
+        .map(|(consumer_name, consumer)| {
+            let pending = consumer
+                .pending
+                .iter()
+                .take(count)
+                .filter_map(|id| {
+                    let entry = group.pending().get(id)?;
+                    Some(ReplyFrame::Array(vec![
+                        id_reply(*id),
+                        ReplyFrame::I64(entry.delivery_time),
+                        u64_reply(entry.delivery_count),
+                    ]))
+                })
+                .collect();
+            ReplyFrame::Array(vec![
+                field("name"),
+                ReplyFrame::Bulk(consumer_name.as_bytes().to_vec()),
+                field("seen-time"),
+                ReplyFrame::I64(consumer.seen_time),
+                field("active-time"),
+                ReplyFrame::I64(consumer.active_time.unwrap_or(-1)),
+                field("pel-count"),
+                ReplyFrame::Usize(consumer.pending.len()),
+                field("pending"),
+                ReplyFrame::Array(pending),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(vec![
+        field("name"),
+        ReplyFrame::Bulk(name.as_bytes().to_vec()),
+        field("last-delivered-id"),
+        id_reply(group.last_id()),
+        field("entries-read"),
+        group.entries_read().map_or(ReplyFrame::Null, u64_reply),
+        field("lag"),
+        group.lag(stats).map_or(ReplyFrame::Null, u64_reply),
+        field("pel-count"),
+        ReplyFrame::Usize(group.pending().len()),
+        field("pending"),
+        ReplyFrame::Array(pending),
+        field("consumers"),
+        ReplyFrame::Array(consumers),
+    ])
+}
+
+fn field(name: &str) -> ReplyFrame {
+    ReplyFrame::Bulk(name.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{
+        AddId, AddOptions, GroupId, GroupOptions, ReadGroupId, ReadGroupOptions, StreamId,
+    };
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::group::create;
+    use crate::mem::stream::info::{info_consumers, info_groups, info_stream};
+    use crate::mem::stream::read_group::read_group;
+
+    #[test]
+    fn test_info_stream() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        let options = AddOptions {
+            id: AddId::Explicit(StreamId::new(1, 0)),
+            ..AddOptions::default()
+        };
+        add(
+            &mut db,
+            key.clone(),
+            &options,
+            vec![(b"a".to_vec(), b"1".to_vec())],
+        );
+
+        let ReplyFrame::Array(arr) = info_stream(&db, &key, None) else {
+            panic!("expected array reply");
+        };
+        assert_eq!(arr.len(), 20);
+        assert_eq!(arr[1], ReplyFrame::Usize(1));
+        assert_eq!(arr[7], ReplyFrame::Bulk(b"1-0".to_vec()));
+        assert_eq!(arr[19], arr[17]);
+
+        let ReplyFrame::Array(arr) = info_stream(&db, &key, Some(10)) else {
+            panic!("expected array reply");
+        };
+        assert_eq!(arr[14], ReplyFrame::Bulk(b"entries".to_vec()));
+        assert_eq!(info_stream(&db, "nokey", None), ReplyFrame::no_such_key());
+    }
+
+    #[test]
+    fn test_info_groups() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        for ms in 1..=2 {
+            let options = AddOptions {
+                id: AddId::Explicit(StreamId::new(ms, 0)),
+                ..AddOptions::default()
+            };
+            add(
+                &mut db,
+                key.clone(),
+                &options,
+                vec![(b"a".to_vec(), b"1".to_vec())],
+            );
+        }
+        let options = GroupOptions {
+            group: "mygroup".to_owned(),
+            id: GroupId::Explicit(StreamId::MIN),
+            mkstream: false,
+            entries_read: None,
+        };
+        create(&mut db, key.clone(), &options);
+        let options = ReadGroupOptions {
+            group: "mygroup".to_owned(),
+            consumer: "alice".to_owned(),
+            count: Some(1),
+            block: None,
+            no_ack: false,
+            streams: vec![(key.clone(), ReadGroupId::New)],
+        };
+        read_group(&mut db, &options);
+
+        let ReplyFrame::Array(groups) = info_groups(&db, &key) else {
+            panic!("expected array reply");
+        };
+        let ReplyFrame::Array(group) = &groups[0] else {
+            panic!("expected array reply");
+        };
+        assert_eq!(group[3], ReplyFrame::Usize(1));
+        assert_eq!(group[5], ReplyFrame::Usize(1));
+        assert_eq!(group[7], ReplyFrame::Bulk(b"1-0".to_vec()));
+        assert_eq!(group[9], ReplyFrame::Usize(1));
+        assert_eq!(group[11], ReplyFrame::Usize(1));
+
+        let ReplyFrame::Array(consumers) = info_consumers(&db, &key, "mygroup") else {
+            panic!("expected array reply");
+        };
+        let ReplyFrame::Array(consumer) = &consumers[0] else {
+            panic!("expected array reply");
+        };
+        assert_eq!(consumer[1], ReplyFrame::Bulk(b"alice".to_vec()));
+        assert_eq!(consumer[3], ReplyFrame::Usize(1));
+        assert!(matches!(
+            info_consumers(&db, &key, "nogroup"),
+            ReplyFrame::Error(_)
+        ));
+
+        let ReplyFrame::Array(arr) = info_stream(&db, &key, None) else {
+            panic!("expected array reply");
+        };
+        assert_eq!(arr[15], ReplyFrame::Usize(1));
+    }
+}
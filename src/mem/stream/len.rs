@@ -0,0 +1,20 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the number of entries inside a stream.
+///
+/// If the specified key does not exist the command returns zero, as if the stream was empty.
+///
+/// Reply:
+/// - Integer reply: the number of entries of the stream at key.
+pub fn len(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::Stream(stream)) => ReplyFrame::Usize(stream.len()),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
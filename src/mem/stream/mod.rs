@@ -1,3 +1,109 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{StreamCommand, StreamId};
+use crate::mem::Mem;
+
+pub mod ack;
+pub mod add;
+pub mod auto_claim;
+pub mod claim;
+mod consts;
+mod consumer_group;
+pub mod delete;
+pub mod group;
+pub mod info;
+pub mod len;
+pub mod pending;
+pub mod range;
+pub mod read;
+pub mod read_group;
+mod stream_object;
+pub mod trim;
+
+pub use consumer_group::{ConsumerGroup, StreamStats};
+pub use stream_object::{StreamEntry, StreamObject};
+
+impl Mem {
+    pub fn handle_stream_command(&mut self, command: StreamCommand) -> ReplyFrame {
+        match command {
+            StreamCommand::Ack(key, group, ids) => ack::ack(&mut self.db, &key, &group, &ids),
+            StreamCommand::Add(key, options, fields) => {
+                add::add(&mut self.db, key, &options, fields)
+            }
+            StreamCommand::AutoClaim(key, group, options) => {
+                auto_claim::auto_claim(&mut self.db, &key, &group, &options)
+            }
+            StreamCommand::Claim(key, group, options) => {
+                claim::claim(&mut self.db, &key, &group, &options)
+            }
+            StreamCommand::Delete(key, ids) => delete::delete(&mut self.db, &key, &ids),
+            StreamCommand::GroupCreate(key, options) => group::create(&mut self.db, key, &options),
+            StreamCommand::GroupCreateConsumer(key, group, consumer) => {
+                group::create_consumer(&mut self.db, &key, &group, &consumer)
+            }
+            StreamCommand::GroupDelConsumer(key, group, consumer) => {
+                group::delete_consumer(&mut self.db, &key, &group, &consumer)
+            }
+            StreamCommand::GroupDestroy(key, group) => group::destroy(&mut self.db, &key, &group),
+            StreamCommand::GroupSetId(key, options) => group::set_id(&mut self.db, &key, &options),
+            StreamCommand::InfoConsumers(key, group) => {
+                info::info_consumers(&self.db, &key, &group)
+            }
+            StreamCommand::InfoGroups(key) => info::info_groups(&self.db, &key),
+            StreamCommand::InfoStream(key, full) => info::info_stream(&self.db, &key, full),
+            StreamCommand::Len(key) => len::len(&self.db, &key),
+            StreamCommand::Pending(key, group, options) => {
+                pending::pending(&self.db, &key, &group, options.as_deref())
+            }
+            StreamCommand::Range(key, start, end, count) => {
+                range::range(&self.db, &key, start, end, count, false)
+            }
+            StreamCommand::Read(options) => read::read(&self.db, &options),
+            StreamCommand::ReadGroup(options) => read_group::read_group(&mut self.db, &options),
+            StreamCommand::RevRange(key, start, end, count) => {
+                range::range(&self.db, &key, start, end, count, true)
+            }
+            StreamCommand::Trim(key, options) => trim::trim(&mut self.db, &key, &options),
+        }
+    }
+}
+
+/// Returns entry as an array of ID and field-value pairs.
+#[must_use]
+pub fn entry_reply(entry: &StreamEntry<'_>) -> ReplyFrame {
+    let fields = entry
+        .fields
+        .iter()
+        .flat_map(|(field, value)| {
+            [
+                ReplyFrame::Bulk(field.to_vec()),
+                ReplyFrame::Bulk(value.to_vec()),
+            ]
+        })
+        .collect();
+    ReplyFrame::Array(vec![
+        ReplyFrame::Bulk(entry.id.to_string().into_bytes()),
+        ReplyFrame::Array(fields),
+    ])
+}
+
+/// Returns stream ID as bulk string.
+#[must_use]
+pub fn id_reply(id: StreamId) -> ReplyFrame {
+    ReplyFrame::Bulk(id.to_string().into_bytes())
+}
+
+/// Returns unsigned integer as integer reply.
+fn u64_reply(value: u64) -> ReplyFrame {
+    ReplyFrame::Usize(usize::try_from(value).unwrap_or(usize::MAX))
+}
+
+/// Error of missing stream or consumer group.
+fn no_group_err(key: &str, group: &str) -> ReplyFrame {
+    ReplyFrame::Error(format!(
+        "NOGROUP No such key '{key}' or consumer group '{group}'"
+    ))
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::PendingOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::{id_reply, no_group_err, u64_reply, ConsumerGroup};
+use crate::mem::util::now_millis;
+
+/// Returns entries in the pending entries list (PEL) of a consumer group,
+/// which are delivered to consumers but not yet acknowledged.
+///
+/// Without extended options, a summary of pending entries is returned:
+/// the total number of pending entries, the smallest and greatest IDs
+/// among them, and every consumer with the number of its pending entries.
+///
+/// With extended options, at most count pending entries in the range of IDs
+/// are returned. Entries can be filtered by minimal idle time and consumer.
+///
+/// Reply:
+/// - Array reply: different data depending on the way `XPENDING` is called.
+pub fn pending(db: &Db, key: &str, group: &str, options: Option<&PendingOptions>) -> ReplyFrame {
+    let group_obj = match db.get(key) {
+        Some(MemObject::Stream(stream)) => match stream.group(group) {
+            Some(group) => group,
+            None => return no_group_err(key, group),
+        },
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return no_group_err(key, group),
+    };
+    options.map_or_else(
+        || summary(group_obj),
+        |options| extended(group_obj, options, now_millis()),
+    )
+}
+
+fn summary(group: &ConsumerGroup) -> ReplyFrame {
+    let pending = group.pending();
+    let (Some((first_id, _)), Some((last_id, _))) =
+        (pending.first_key_value(), pending.last_key_value())
+    else {
+        return ReplyFrame::Array(vec![
+            ReplyFrame::zero(),
+            ReplyFrame::Null,
+            ReplyFrame::Null,
+            ReplyFrame::Null,
+        ]);
+    };
+    let consumers = group
+        .consumers()
+        .iter()
+        .filter(|(_name, consumer)| !consumer.pending.is_empty())
+        .map(|(name, consumer)| {
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(name.as_bytes().to_vec()),
+                ReplyFrame::Bulk(consumer.pending.len().to_string().into_bytes()),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(vec![
+        ReplyFrame::Usize(pending.len()),
+        id_reply(*first_id),
+        id_reply(*last_id),
+        ReplyFrame::Array(consumers),
+    ])
+}
+
+fn extended(group: &ConsumerGroup, options: &PendingOptions, now: i64) -> ReplyFrame {
+    if options.start > options.end {
+        return ReplyFrame::Array(Vec::new());
+    }
+    let entries = group
+        .pending()
+        .range(options.start..=options.end)
+        .filter(|(_id, entry)| {
+            options
+                .consumer
+                .as_ref()
+                .map_or(true, |consumer| *consumer == entry.consumer)
+        })
+        .map(|(id, entry)| (id, entry, now - entry.delivery_time))
+        .filter(|(_id, _entry, idle)| options.min_idle.map_or(true, |min_idle| *idle >= min_idle))
+        .take(options.count)
+        .map(|(id, entry, idle)| {
+            ReplyFrame::Array(vec![
+                id_reply(*id),
+                ReplyFrame::Bulk(entry.consumer.as_bytes().to_vec()),
+                ReplyFrame::I64(idle),
+                u64_reply(entry.delivery_count),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{PendingOptions, StreamId};
+    use crate::mem::stream::consumer_group::ConsumerGroup;
+    use crate::mem::stream::pending::{extended, summary};
+
+    #[test]
+    fn test_pending() {
+        let mut group = ConsumerGroup::new(StreamId::MIN, None);
+        assert_eq!(
+            summary(&group),
+            ReplyFrame::Array(vec![
+                ReplyFrame::zero(),
+                ReplyFrame::Null,
+                ReplyFrame::Null,
+                ReplyFrame::Null
+            ])
+        );
+        group.deliver(StreamId::new(1, 0), "alice", 100);
+        group.deliver(StreamId::new(2, 0), "bob", 200);
+        group.deliver(StreamId::new(3, 0), "alice", 300);
+        assert_eq!(
+            summary(&group),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Usize(3),
+                ReplyFrame::Bulk(b"1-0".to_vec()),
+                ReplyFrame::Bulk(b"3-0".to_vec()),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(b"alice".to_vec()),
+                        ReplyFrame::Bulk(b"2".to_vec())
+                    ]),
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(b"bob".to_vec()),
+                        ReplyFrame::Bulk(b"1".to_vec())
+                    ]),
+                ]),
+            ])
+        );
+
+        let mut options = PendingOptions {
+            min_idle: Some(150),
+            start: StreamId::MIN,
+            end: StreamId::MAX,
+            count: 10,
+            consumer: Some("alice".to_owned()),
+        };
+        assert_eq!(
+            extended(&group, &options, 400),
+            ReplyFrame::Array(vec![ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"1-0".to_vec()),
+                ReplyFrame::Bulk(b"alice".to_vec()),
+                ReplyFrame::I64(300),
+                ReplyFrame::Usize(1),
+            ])])
+        );
+        options.min_idle = None;
+        options.consumer = None;
+        options.count = 2;
+        let ReplyFrame::Array(entries) = extended(&group, &options, 400) else {
+            panic!("expected array reply");
+        };
+        assert_eq!(entries.len(), 2);
+    }
+}
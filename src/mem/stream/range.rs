@@ -0,0 +1,87 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::StreamId;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::entry_reply;
+
+/// Returns the stream entries matching a given range of IDs.
+///
+/// The range is specified by a minimum and maximum ID. All the entries having an ID
+/// between the two specified or exactly one of the two IDs specified (closed interval)
+/// are returned. `-` and `+` are the minimum and maximum IDs, and IDs prefixed with `(`
+/// are exclusive.
+///
+/// With COUNT option, at most count entries are returned.
+///
+/// `XREVRANGE` returns the entries in reverse order.
+///
+/// Reply:
+/// - Array reply: a list of stream entries with IDs matching the specified range.
+pub fn range(
+    db: &Db,
+    key: &str,
+    start: StreamId,
+    end: StreamId,
+    count: Option<usize>,
+    reverse: bool,
+) -> ReplyFrame {
+    let stream = match db.get(key) {
+        Some(MemObject::Stream(stream)) => stream,
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Array(Vec::new()),
+    };
+    let entries = stream
+        .range(start, end, reverse)
+        .take(count.unwrap_or(usize::MAX))
+        .map(|entry| entry_reply(&entry))
+        .collect();
+    ReplyFrame::Array(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddId, AddOptions, StreamId};
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::range::range;
+
+    fn entry(id: &str, value: &str) -> ReplyFrame {
+        ReplyFrame::Array(vec![
+            ReplyFrame::Bulk(id.as_bytes().to_vec()),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"n".to_vec()),
+                ReplyFrame::Bulk(value.as_bytes().to_vec()),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_range() {
+        let mut db = Db::new();
+        let key = "writers".to_owned();
+        for ms in 1..=4 {
+            let options = AddOptions {
+                id: AddId::Explicit(StreamId::new(ms, 0)),
+                ..AddOptions::default()
+            };
+            let fields = vec![(b"n".to_vec(), ms.to_string().into_bytes())];
+            add(&mut db, key.clone(), &options, fields);
+        }
+        let reply = range(&db, &key, StreamId::MIN, StreamId::MAX, Some(2), false);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![entry("1-0", "1"), entry("2-0", "2")])
+        );
+        let reply = range(&db, &key, StreamId::new(2, 1), StreamId::MAX, None, true);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![entry("4-0", "4"), entry("3-0", "3")])
+        );
+        let reply = range(&db, &key, StreamId::MIN, StreamId::MAX, Some(0), false);
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+    }
+}
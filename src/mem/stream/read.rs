@@ -0,0 +1,148 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{ReadId, ReadOptions, StreamId};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::entry_reply;
+
+/// Read entries from one or multiple streams, only returning entries with an ID
+/// greater than the last received ID reported by the caller.
+///
+/// The special ID `$` is the ID of the last entry in the stream, so only new entries
+/// are returned, and `+` returns the last entry in the stream.
+///
+/// With BLOCK option, the client is blocked if there are no entries to return,
+/// until new entries are added to any of the streams or the timeout is reached.
+///
+/// Reply, one of the following:
+/// - Array reply: a list of streams, each element is composed of the key name and
+///   the entries reported for that key.
+/// - Nil reply: if there are no entries to return.
+pub fn read(db: &Db, options: &ReadOptions) -> ReplyFrame {
+    let count = options.count.unwrap_or(usize::MAX);
+    let mut streams = Vec::new();
+    for (key, id) in &options.streams {
+        let stream = match db.get(key) {
+            Some(MemObject::Stream(stream)) => stream,
+            Some(_) => return ReplyFrame::wrong_type_err(),
+            None => continue,
+        };
+        let entries: Vec<ReplyFrame> = match id {
+            ReadId::Last => continue,
+            ReadId::LastEntry => stream
+                .last_entry()
+                .map(|entry| entry_reply(&entry))
+                .into_iter()
+                .collect(),
+            ReadId::Explicit(id) => match id.next() {
+                Some(start) => stream
+                    .range(start, StreamId::MAX, false)
+                    .take(count)
+                    .map(|entry| entry_reply(&entry))
+                    .collect(),
+                None => continue,
+            },
+        };
+        if !entries.is_empty() {
+            streams.push(ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(key.as_bytes().to_vec()),
+                ReplyFrame::Array(entries),
+            ]));
+        }
+    }
+    if streams.is_empty() {
+        ReplyFrame::Null
+    } else {
+        ReplyFrame::Array(streams)
+    }
+}
+
+/// Replace `$` and `+` with ID of the last entry of each stream, so that a blocked
+/// client is served only with entries added after it was blocked.
+pub fn resolve_ids(db: &Db, options: &mut ReadOptions) {
+    for (key, id) in &mut options.streams {
+        if matches!(id, ReadId::Last | ReadId::LastEntry) {
+            let last_id = match db.get(key) {
+                Some(MemObject::Stream(stream)) => stream.last_id(),
+                _ => StreamId::MIN,
+            };
+            *id = ReadId::Explicit(last_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddId, AddOptions, ReadId, ReadOptions, StreamId};
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::read::{read, resolve_ids};
+
+    fn add_entry(db: &mut Db, key: &str, ms: u64) {
+        let options = AddOptions {
+            id: AddId::Explicit(StreamId::new(ms, 0)),
+            ..AddOptions::default()
+        };
+        let fields = vec![(b"n".to_vec(), ms.to_string().into_bytes())];
+        add(db, key.to_owned(), &options, fields);
+    }
+
+    fn entry(id: &str, value: &str) -> ReplyFrame {
+        ReplyFrame::Array(vec![
+            ReplyFrame::Bulk(id.as_bytes().to_vec()),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"n".to_vec()),
+                ReplyFrame::Bulk(value.as_bytes().to_vec()),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_read() {
+        let mut db = Db::new();
+        add_entry(&mut db, "s1", 1);
+        add_entry(&mut db, "s1", 2);
+        add_entry(&mut db, "s2", 3);
+
+        let mut options = ReadOptions {
+            count: Some(1),
+            block: None,
+            streams: vec![
+                ("s1".to_owned(), ReadId::Explicit(StreamId::MIN)),
+                ("s2".to_owned(), ReadId::LastEntry),
+                ("s3".to_owned(), ReadId::Explicit(StreamId::MIN)),
+            ],
+        };
+        assert_eq!(
+            read(&db, &options),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"s1".to_vec()),
+                    ReplyFrame::Array(vec![entry("1-0", "1")]),
+                ]),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Bulk(b"s2".to_vec()),
+                    ReplyFrame::Array(vec![entry("3-0", "3")]),
+                ]),
+            ])
+        );
+
+        options.streams = vec![
+            ("s1".to_owned(), ReadId::Last),
+            ("s3".to_owned(), ReadId::LastEntry),
+        ];
+        assert_eq!(read(&db, &options), ReplyFrame::Null);
+        resolve_ids(&db, &mut options);
+        add_entry(&mut db, "s3", 4);
+        assert_eq!(
+            read(&db, &options),
+            ReplyFrame::Array(vec![ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"s3".to_vec()),
+                ReplyFrame::Array(vec![entry("4-0", "4")]),
+            ])])
+        );
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::{ReadGroupId, ReadGroupOptions, StreamId};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::stream::{entry_reply, id_reply, StreamObject};
+use crate::mem::util::now_millis;
+
+/// Reads entries from streams on behalf of a consumer of a consumer group.
+///
+/// With the special ID `>`, entries never delivered to other consumers of the group
+/// are returned, and they are added to the pending entries list (PEL) of the consumer
+/// unless NOACK option is specified.
+///
+/// With any other ID, pending entries of the consumer with IDs greater than the one
+/// provided are returned, and their delivery counts are increased.
+/// Entries deleted from the stream are returned with nil fields.
+///
+/// Reply, one of the following:
+/// - Array reply: a list of streams, each element is composed of the key name and
+///   the entries reported for that key.
+/// - Nil reply: if there are no new entries to deliver.
+pub fn read_group(db: &mut Db, options: &ReadGroupOptions) -> ReplyFrame {
+    // Check that all the streams and groups exist before reading any of them.
+    for (key, _id) in &options.streams {
+        match db.get(key) {
+            Some(MemObject::Stream(stream)) if stream.group(&options.group).is_some() => (),
+            Some(MemObject::Stream(_)) | None => {
+                return ReplyFrame::Error(format!(
+                    "NOGROUP No such key '{key}' or consumer group '{}' in XREADGROUP \
+                     with GROUP option",
+                    options.group
+                ));
+            }
+            Some(_) => return ReplyFrame::wrong_type_err(),
+        }
+    }
+
+    let now = now_millis();
+    let count = options.count.unwrap_or(usize::MAX);
+    let mut streams = Vec::new();
+    for (key, id) in &options.streams {
+        let Some(MemObject::Stream(stream)) = db.get_mut(key) else {
+            continue;
+        };
+        let entries = match id {
+            ReadGroupId::New => read_new(stream, options, count, now),
+            ReadGroupId::Pending(id) => Some(read_pending(stream, options, *id, count, now)),
+        };
+        if let Some(entries) = entries {
+            streams.push(ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(key.as_bytes().to_vec()),
+                ReplyFrame::Array(entries),
+            ]));
+        }
+    }
+    if streams.is_empty() {
+        ReplyFrame::Null
+    } else {
+        ReplyFrame::Array(streams)
+    }
+}
+
+/// Deliver entries never delivered to the group, returns None if there are no new entries.
+fn read_new(
+    stream: &mut StreamObject,
+    options: &ReadGroupOptions,
+    count: usize,
+    now: i64,
+) -> Option<Vec<ReplyFrame>> {
+    let stats = stream.stats();
+    let group = stream.group_mut(&options.group)?;
+    group.touch_consumer(&options.consumer, now);
+    if stats.last_id <= group.last_id() {
+        return None;
+    }
+    let start = group.last_id().next()?;
+
+    let (ids, entries): (Vec<_>, Vec<_>) = stream
+        .range(start, StreamId::MAX, false)
+        .take(count)
+        .map(|entry| (entry.id, entry_reply(&entry)))
+        .unzip();
+    let group = stream.group_mut(&options.group)?;
+    for &id in &ids {
+        group.mark_read(id, &stats);
+        if !options.no_ack {
+            group.deliver(id, &options.consumer, now);
+        }
+    }
+    if !ids.is_empty() {
+        group.touch_consumer(&options.consumer, now).active_time = Some(now);
+    }
+    Some(entries)
+}
+
+/// Deliver pending entries of consumer with IDs greater than `last_id` again.
+fn read_pending(
+    stream: &mut StreamObject,
+    options: &ReadGroupOptions,
+    last_id: StreamId,
+    count: usize,
+    now: i64,
+) -> Vec<ReplyFrame> {
+    let Some(group) = stream.group_mut(&options.group) else {
+        return Vec::new();
+    };
+    let consumer = group.touch_consumer(&options.consumer, now);
+    let ids: Vec<StreamId> = match last_id.next() {
+        Some(start) => consumer
+            .pending
+            .range(start..)
+            .take(count)
+            .copied()
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in ids {
+        let Some(entry) = stream.get(id).map(|entry| entry_reply(&entry)) else {
+            // Entry was deleted from stream.
+            entries.push(ReplyFrame::Array(vec![id_reply(id), ReplyFrame::Null]));
+            continue;
+        };
+        if let Some(group) = stream.group_mut(&options.group) {
+            group.redeliver(id, now);
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{
+        AddId, AddOptions, GroupId, GroupOptions, ReadGroupId, ReadGroupOptions, StreamId,
+    };
+    use crate::mem::db::Db;
+    use crate::mem::stream::ack::ack;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::delete::delete;
+    use crate::mem::stream::group::create;
+    use crate::mem::stream::read_group::read_group;
+
+    fn read(db: &mut Db, id: ReadGroupId) -> ReplyFrame {
+        let options = ReadGroupOptions {
+            group: "mygroup".to_owned(),
+            consumer: "alice".to_owned(),
+            count: None,
+            block: None,
+            no_ack: false,
+            streams: vec![("mystream".to_owned(), id)],
+        };
+        read_group(db, &options)
+    }
+
+    fn reply_len(reply: &ReplyFrame) -> usize {
+        let ReplyFrame::Array(streams) = reply else {
+            panic!("expected array reply");
+        };
+        let ReplyFrame::Array(stream) = &streams[0] else {
+            panic!("expected array reply");
+        };
+        let ReplyFrame::Array(entries) = &stream[1] else {
+            panic!("expected array reply");
+        };
+        entries.len()
+    }
+
+    #[test]
+    fn test_read_group() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        let options = GroupOptions {
+            group: "mygroup".to_owned(),
+            id: GroupId::Last,
+            mkstream: true,
+            entries_read: None,
+        };
+        assert_eq!(create(&mut db, key.clone(), &options), ReplyFrame::ok());
+        assert_eq!(read(&mut db, ReadGroupId::New), ReplyFrame::Null);
+
+        for ms in 1..=3 {
+            let options = AddOptions {
+                id: AddId::Explicit(StreamId::new(ms, 0)),
+                ..AddOptions::default()
+            };
+            let fields = vec![(b"n".to_vec(), ms.to_string().into_bytes())];
+            add(&mut db, key.clone(), &options, fields);
+        }
+        assert_eq!(reply_len(&read(&mut db, ReadGroupId::New)), 3);
+        assert_eq!(read(&mut db, ReadGroupId::New), ReplyFrame::Null);
+
+        // Read history of pending entries.
+        assert_eq!(
+            ack(&mut db, &key, "mygroup", &[StreamId::new(1, 0)]),
+            ReplyFrame::one()
+        );
+        delete(&mut db, &key, &[StreamId::new(2, 0)]);
+        let reply = read(&mut db, ReadGroupId::Pending(StreamId::MIN));
+        assert_eq!(reply_len(&reply), 2);
+        let ReplyFrame::Array(streams) = reply else {
+            panic!("expected array reply");
+        };
+        assert_eq!(
+            streams[0],
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(key.as_bytes().to_vec()),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Array(vec![ReplyFrame::Bulk(b"2-0".to_vec()), ReplyFrame::Null]),
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(b"3-0".to_vec()),
+                        ReplyFrame::Array(vec![
+                            ReplyFrame::Bulk(b"n".to_vec()),
+                            ReplyFrame::Bulk(b"3".to_vec())
+                        ])
+                    ]),
+                ])
+            ])
+        );
+        assert_eq!(
+            reply_len(&read(&mut db, ReadGroupId::Pending(StreamId::new(3, 0)))),
+            0
+        );
+    }
+}
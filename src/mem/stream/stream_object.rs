@@ -0,0 +1,493 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Stream object, an append-only log of entries ordered by ID.
+//!
+//! Like redis, entries are stored in chunked nodes indexed by ID of their first entry.
+//! Field names of the first entry in a node are kept as master fields, and the following
+//! entries with the same fields only store their values.
+//! Deleted entries are only flagged until the whole node is removed.
+
+use std::collections::BTreeMap;
+
+use crate::cmd::stream::{AddId, StreamId, TrimOptions, TrimStrategy};
+use crate::mem::stream::consts::{ID_EXHAUSTED_ERR, ID_TOO_SMALL_ERR, ID_ZERO_ERR};
+use crate::mem::stream::consumer_group::{ConsumerGroup, StreamStats};
+
+/// Max number of entries in a node, like `stream-node-max-entries` in redis.
+pub const NODE_MAX_ENTRIES: usize = 100;
+
+/// Iterator of live entries.
+pub type Iter<'a> = Box<dyn Iterator<Item = StreamEntry<'a>> + 'a>;
+
+#[derive(Debug, Clone)]
+enum EntryFields {
+    /// Values of the master fields of node.
+    Same(Vec<Vec<u8>>),
+    Own(Vec<(Vec<u8>, Vec<u8>)>),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    id: StreamId,
+    fields: EntryFields,
+    deleted: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    master_fields: Vec<Vec<u8>>,
+    entries: Vec<Entry>,
+    /// Number of entries not deleted.
+    live: usize,
+}
+
+impl Node {
+    fn new(id: StreamId, fields: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        let (master_fields, values) = fields.into_iter().unzip();
+        Self {
+            master_fields,
+            entries: vec![Entry {
+                id,
+                fields: EntryFields::Same(values),
+                deleted: false,
+            }],
+            live: 1,
+        }
+    }
+
+    fn push(&mut self, id: StreamId, fields: Vec<(Vec<u8>, Vec<u8>)>) {
+        let same_fields = fields.len() == self.master_fields.len()
+            && fields
+                .iter()
+                .zip(&self.master_fields)
+                .all(|((field, _value), master)| field == master);
+        let fields = if same_fields {
+            EntryFields::Same(fields.into_iter().map(|(_field, value)| value).collect())
+        } else {
+            EntryFields::Own(fields)
+        };
+        self.entries.push(Entry {
+            id,
+            fields,
+            deleted: false,
+        });
+        self.live += 1;
+    }
+
+    fn to_stream_entry<'a>(&'a self, entry: &'a Entry) -> StreamEntry<'a> {
+        let fields = match &entry.fields {
+            EntryFields::Same(values) => self
+                .master_fields
+                .iter()
+                .zip(values)
+                .map(|(field, value)| (field.as_slice(), value.as_slice()))
+                .collect(),
+            EntryFields::Own(fields) => fields
+                .iter()
+                .map(|(field, value)| (field.as_slice(), value.as_slice()))
+                .collect(),
+        };
+        StreamEntry {
+            id: entry.id,
+            fields,
+        }
+    }
+
+    /// Flag entry as deleted, and release its fields.
+    fn delete(&mut self, index: usize) {
+        let entry = &mut self.entries[index];
+        entry.deleted = true;
+        entry.fields = EntryFields::Own(Vec::new());
+        self.live -= 1;
+    }
+
+    fn last_id(&self) -> StreamId {
+        self.entries.last().map_or(StreamId::MIN, |entry| entry.id)
+    }
+}
+
+/// A live entry of stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamEntry<'a> {
+    pub id: StreamId,
+    pub fields: Vec<(&'a [u8], &'a [u8])>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StreamObject {
+    /// Nodes indexed by ID of their first entry.
+    nodes: BTreeMap<StreamId, Node>,
+    len: usize,
+    last_id: StreamId,
+    max_deleted_id: StreamId,
+    entries_added: u64,
+    groups: BTreeMap<String, ConsumerGroup>,
+}
+
+impl StreamObject {
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns number of nodes.
+    #[must_use]
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// ID of the last added entry, even if it was deleted.
+    #[must_use]
+    #[inline]
+    pub const fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    /// The maximal ID that was deleted from the stream.
+    #[must_use]
+    #[inline]
+    pub const fn max_deleted_id(&self) -> StreamId {
+        self.max_deleted_id
+    }
+
+    /// Number of entries added to the stream during its lifetime.
+    #[must_use]
+    #[inline]
+    pub const fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    /// Returns statistics used to track entries read by consumer groups.
+    #[must_use]
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            len: self.len,
+            first_id: self.first_entry().map_or(StreamId::MIN, |entry| entry.id),
+            last_id: self.last_id,
+            max_deleted_id: self.max_deleted_id,
+            entries_added: self.entries_added,
+        }
+    }
+
+    /// Consumer groups indexed by name.
+    #[must_use]
+    #[inline]
+    pub const fn groups(&self) -> &BTreeMap<String, ConsumerGroup> {
+        &self.groups
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn group(&self, name: &str) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn group_mut(&mut self, name: &str) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    /// Create a consumer group, returns false if it already exists.
+    pub fn create_group(&mut self, name: &str, group: ConsumerGroup) -> bool {
+        if self.groups.contains_key(name) {
+            return false;
+        }
+        self.groups.insert(name.to_owned(), group);
+        true
+    }
+
+    /// Destroy a consumer group, returns false if not found.
+    pub fn destroy_group(&mut self, name: &str) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /// Returns the first live entry.
+    #[must_use]
+    pub fn first_entry(&self) -> Option<StreamEntry<'_>> {
+        self.range(StreamId::MIN, StreamId::MAX, false).next()
+    }
+
+    /// Returns the last live entry.
+    #[must_use]
+    pub fn last_entry(&self) -> Option<StreamEntry<'_>> {
+        self.range(StreamId::MIN, StreamId::MAX, true).next()
+    }
+
+    /// Returns live entry with `id`.
+    #[must_use]
+    pub fn get(&self, id: StreamId) -> Option<StreamEntry<'_>> {
+        let (_first_id, node) = self.nodes.range(..=id).next_back()?;
+        let index = node
+            .entries
+            .binary_search_by(|entry| entry.id.cmp(&id))
+            .ok()?;
+        let entry = &node.entries[index];
+        (!entry.deleted).then(|| node.to_stream_entry(entry))
+    }
+
+    /// Generate ID of a new entry, it shall be greater than the last ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error message if ID is not greater than the last ID.
+    pub fn next_id(&self, id: AddId, now: u64) -> Result<StreamId, &'static str> {
+        let last = self.last_id;
+        if last == StreamId::MAX {
+            return Err(ID_EXHAUSTED_ERR);
+        }
+        match id {
+            AddId::Auto if now > last.ms => Ok(StreamId::new(now, 0)),
+            AddId::Auto => last.next().ok_or(ID_EXHAUSTED_ERR),
+            AddId::AutoSeq(ms) if ms > last.ms => Ok(StreamId::new(ms, 0)),
+            AddId::AutoSeq(ms) if ms == last.ms && last.seq < u64::MAX => {
+                Ok(StreamId::new(ms, last.seq + 1))
+            }
+            AddId::Explicit(StreamId::MIN) => Err(ID_ZERO_ERR),
+            AddId::Explicit(id) if id > last => Ok(id),
+            AddId::AutoSeq(_) | AddId::Explicit(_) => Err(ID_TOO_SMALL_ERR),
+        }
+    }
+
+    /// Append a new entry, `id` shall be greater than the last ID.
+    pub fn append(&mut self, id: StreamId, fields: Vec<(Vec<u8>, Vec<u8>)>) {
+        debug_assert!(id > self.last_id);
+        match self.nodes.values_mut().next_back() {
+            Some(node) if node.entries.len() < NODE_MAX_ENTRIES => node.push(id, fields),
+            _ => {
+                self.nodes.insert(id, Node::new(id, fields));
+            }
+        }
+        self.len += 1;
+        self.last_id = id;
+        self.entries_added += 1;
+    }
+
+    /// Delete entry with `id`, returns false if not found.
+    pub fn delete(&mut self, id: StreamId) -> bool {
+        let Some((&first_id, node)) = self.nodes.range_mut(..=id).next_back() else {
+            return false;
+        };
+        let Ok(index) = node.entries.binary_search_by(|entry| entry.id.cmp(&id)) else {
+            return false;
+        };
+        if node.entries[index].deleted {
+            return false;
+        }
+        node.delete(index);
+        if node.live == 0 {
+            self.nodes.remove(&first_id);
+        }
+        self.len -= 1;
+        self.max_deleted_id = self.max_deleted_id.max(id);
+        true
+    }
+
+    /// Evict old entries, returns number of entries deleted.
+    ///
+    /// With approximate trimming, only whole nodes are removed.
+    pub fn trim(&mut self, options: &TrimOptions) -> usize {
+        let limit = match (options.approx, options.limit) {
+            (false, _) => 0,
+            (true, None) => 100 * NODE_MAX_ENTRIES,
+            (true, Some(limit)) => limit,
+        };
+
+        let mut deleted = 0;
+        while let Some(mut first) = self.nodes.first_entry() {
+            if let TrimStrategy::MaxLen(max_len) = options.strategy {
+                if self.len <= max_len {
+                    break;
+                }
+            }
+            let node = first.get_mut();
+            let live = node.live;
+            if limit != 0 && deleted + live > limit {
+                break;
+            }
+
+            let remove_node = match options.strategy {
+                TrimStrategy::MaxLen(max_len) => self.len - live >= max_len,
+                TrimStrategy::MinId(min_id) => node.last_id() < min_id,
+            };
+            if remove_node {
+                first.remove();
+                self.len -= live;
+                deleted += live;
+                continue;
+            }
+            if options.approx {
+                break;
+            }
+
+            // Delete entries of the first node one by one.
+            for index in 0..node.entries.len() {
+                let entry = &node.entries[index];
+                if entry.deleted {
+                    continue;
+                }
+                let stop = match options.strategy {
+                    TrimStrategy::MaxLen(max_len) => self.len <= max_len,
+                    TrimStrategy::MinId(min_id) => entry.id >= min_id,
+                };
+                if stop {
+                    break;
+                }
+                node.delete(index);
+                self.len -= 1;
+                deleted += 1;
+            }
+            break;
+        }
+        deleted
+    }
+
+    /// Returns live entries with ID between `start` and `end`, both inclusive.
+    #[must_use]
+    pub fn range(&self, start: StreamId, end: StreamId, reverse: bool) -> Iter<'_> {
+        if start > end {
+            return Box::new(std::iter::empty());
+        }
+        if reverse {
+            let iter = self
+                .nodes
+                .range(..=end)
+                .rev()
+                .flat_map(|(_first_id, node)| node.entries.iter().rev().map(move |e| (node, e)))
+                .skip_while(move |(_node, entry)| entry.id > end)
+                .take_while(move |(_node, entry)| entry.id >= start)
+                .filter(|(_node, entry)| !entry.deleted)
+                .map(|(node, entry)| node.to_stream_entry(entry));
+            Box::new(iter)
+        } else {
+            let first_id = self
+                .nodes
+                .range(..=start)
+                .next_back()
+                .map_or(start, |(first_id, _node)| *first_id);
+            let iter = self
+                .nodes
+                .range(first_id..=end)
+                .flat_map(|(_first_id, node)| node.entries.iter().map(move |e| (node, e)))
+                .skip_while(move |(_node, entry)| entry.id < start)
+                .take_while(move |(_node, entry)| entry.id <= end)
+                .filter(|(_node, entry)| !entry.deleted)
+                .map(|(node, entry)| node.to_stream_entry(entry));
+            Box::new(iter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::stream::{AddId, StreamId, TrimOptions, TrimStrategy};
+    use crate::mem::stream::consts::{ID_TOO_SMALL_ERR, ID_ZERO_ERR};
+    use crate::mem::stream::stream_object::{StreamObject, NODE_MAX_ENTRIES};
+
+    fn fields(value: u64) -> Vec<(Vec<u8>, Vec<u8>)> {
+        vec![(b"n".to_vec(), value.to_string().into_bytes())]
+    }
+
+    fn stream(len: u64) -> StreamObject {
+        let mut stream = StreamObject::new();
+        for i in 1..=len {
+            stream.append(StreamId::new(i, 0), fields(i));
+        }
+        stream
+    }
+
+    #[test]
+    fn test_next_id() {
+        let mut stream = StreamObject::new();
+        assert_eq!(stream.next_id(AddId::Auto, 5), Ok(StreamId::new(5, 0)));
+        assert_eq!(
+            stream.next_id(AddId::AutoSeq(0), 5),
+            Ok(StreamId::new(0, 1))
+        );
+        assert_eq!(
+            stream.next_id(AddId::Explicit(StreamId::MIN), 5),
+            Err(ID_ZERO_ERR)
+        );
+        stream.append(StreamId::new(10, 3), fields(1));
+        assert_eq!(stream.next_id(AddId::Auto, 5), Ok(StreamId::new(10, 4)));
+        assert_eq!(
+            stream.next_id(AddId::AutoSeq(10), 5),
+            Ok(StreamId::new(10, 4))
+        );
+        assert_eq!(stream.next_id(AddId::AutoSeq(9), 5), Err(ID_TOO_SMALL_ERR));
+        assert_eq!(
+            stream.next_id(AddId::Explicit(StreamId::new(10, 3)), 5),
+            Err(ID_TOO_SMALL_ERR)
+        );
+    }
+
+    #[test]
+    fn test_range_delete() {
+        let mut stream = stream(250);
+        assert_eq!(stream.node_count(), 3);
+        let ids: Vec<u64> = stream
+            .range(StreamId::new(99, 0), StreamId::new(102, 0), false)
+            .map(|entry| entry.id.ms)
+            .collect();
+        assert_eq!(ids, vec![99, 100, 101, 102]);
+        assert!(stream.delete(StreamId::new(100, 0)));
+        assert!(!stream.delete(StreamId::new(100, 0)));
+        assert!(!stream.delete(StreamId::new(100, 1)));
+        let ids: Vec<u64> = stream
+            .range(StreamId::new(99, 0), StreamId::new(102, 0), true)
+            .map(|entry| entry.id.ms)
+            .collect();
+        assert_eq!(ids, vec![102, 101, 99]);
+        assert_eq!(stream.len(), 249);
+        assert_eq!(stream.max_deleted_id(), StreamId::new(100, 0));
+        let entry = stream.get(StreamId::new(7, 0)).unwrap();
+        assert_eq!(entry.fields, vec![(&b"n"[..], &b"7"[..])]);
+        assert!(stream.get(StreamId::new(100, 0)).is_none());
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut stream = stream(250);
+        let options = TrimOptions {
+            strategy: TrimStrategy::MaxLen(120),
+            approx: true,
+            limit: None,
+        };
+        assert_eq!(stream.trim(&options), NODE_MAX_ENTRIES);
+        assert_eq!(stream.len(), 150);
+
+        let options = TrimOptions {
+            strategy: TrimStrategy::MaxLen(120),
+            approx: false,
+            limit: None,
+        };
+        assert_eq!(stream.trim(&options), 30);
+        assert_eq!(stream.first_entry().unwrap().id, StreamId::new(131, 0));
+
+        let options = TrimOptions {
+            strategy: TrimStrategy::MinId(StreamId::new(240, 0)),
+            approx: false,
+            limit: None,
+        };
+        assert_eq!(stream.trim(&options), 109);
+        assert_eq!(stream.len(), 11);
+        assert_eq!(stream.node_count(), 1);
+        assert_eq!(stream.last_entry().unwrap().id, StreamId::new(250, 0));
+    }
+}
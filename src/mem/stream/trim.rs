@@ -0,0 +1,61 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::stream::TrimOptions;
+use crate::mem::db::{Db, MemObject};
+
+/// Trims the stream by evicting older entries (entries with lower IDs) if needed.
+///
+/// Trimming the stream can be done using one of these strategies:
+/// - MAXLEN: Evicts entries as long as the stream's length exceeds the specified threshold.
+/// - MINID: Evicts entries with IDs lower than threshold.
+///
+/// With the `~` modifier, trimming is approximate and only whole nodes of entries
+/// are evicted, and LIMIT specifies the maximal count of entries that will be evicted.
+///
+/// Reply:
+/// - Integer reply: The number of entries deleted from the stream.
+pub fn trim(db: &mut Db, key: &str, options: &TrimOptions) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::Stream(stream)) => ReplyFrame::Usize(stream.trim(options)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::stream::{AddOptions, TrimOptions, TrimStrategy};
+    use crate::mem::db::Db;
+    use crate::mem::stream::add::add;
+    use crate::mem::stream::len::len;
+    use crate::mem::stream::trim::trim;
+
+    #[test]
+    fn test_trim() {
+        let mut db = Db::new();
+        let key = "mystream".to_owned();
+        for i in 0..5 {
+            let fields = vec![(b"field".to_vec(), i.to_string().into_bytes())];
+            add(&mut db, key.clone(), &AddOptions::default(), fields);
+        }
+        let options = TrimOptions {
+            strategy: TrimStrategy::MaxLen(2),
+            approx: false,
+            limit: None,
+        };
+        assert_eq!(trim(&mut db, &key, &options), ReplyFrame::Usize(3));
+        assert_eq!(len(&db, &key), ReplyFrame::Usize(2));
+
+        // Approximate trimming does not remove partial node.
+        let options = TrimOptions {
+            strategy: TrimStrategy::MaxLen(1),
+            approx: true,
+            limit: None,
+        };
+        assert_eq!(trim(&mut db, &key, &options), ReplyFrame::zero());
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::AddOptions;
+use crate::mem::db::Db;
+use crate::mem::time_series::rule::compact;
+use crate::mem::time_series::{get_or_create, Sample};
+use crate::mem::util::now_millis;
+
+/// Append a sample to a time series.
+///
+/// If key does not exist, a new time series is created with the series options.
+/// Timestamp `*` means the current server time.
+///
+/// Options:
+/// - `ON_DUPLICATE`: overwrite the duplicate policy for this sample.
+///
+/// Reply:
+/// - Integer reply: the timestamp of the upserted sample.
+/// - Error reply: if sample is older than retention period, or is blocked
+///   by duplicate policy.
+pub fn add(db: &mut Db, options: &AddOptions) -> ReplyFrame {
+    let series = match get_or_create(db, &options.key, &options.series) {
+        Ok(series) => series,
+        Err(err) => return err,
+    };
+    let timestamp = options.timestamp.unwrap_or_else(now_millis);
+    let sample = Sample {
+        timestamp,
+        value: options.value,
+    };
+    match series.add(sample, options.on_duplicate) {
+        Ok(()) => {
+            compact(db, &options.key, timestamp);
+            ReplyFrame::I64(timestamp)
+        }
+        Err(err) => ReplyFrame::ConstError(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{AddOptions, DuplicatePolicy, SeriesOptions};
+    use crate::mem::db::Db;
+    use crate::mem::time_series::add::add;
+
+    fn options(timestamp: i64, value: f64, on_duplicate: Option<DuplicatePolicy>) -> AddOptions {
+        AddOptions {
+            key: "temp".to_owned(),
+            timestamp: Some(timestamp),
+            value,
+            on_duplicate,
+            series: SeriesOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        assert_eq!(add(&mut db, &options(10, 1.0, None)), ReplyFrame::I64(10));
+        assert_eq!(add(&mut db, &options(5, 1.0, None)), ReplyFrame::I64(5));
+        assert!(matches!(
+            add(&mut db, &options(5, 2.0, None)),
+            ReplyFrame::ConstError(_)
+        ));
+        assert_eq!(
+            add(&mut db, &options(5, 2.0, Some(DuplicatePolicy::Last))),
+            ReplyFrame::I64(5)
+        );
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::time_series::{Aggregation, Aggregator, BucketTimestamp};
+use crate::mem::time_series::Sample;
+
+/// Aggregate samples in a bucket, samples are sorted by timestamp.
+///
+/// Returns 0 for `sum` and `count` of an empty bucket, and NaN for others.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn aggregate(aggregator: Aggregator, samples: &[Sample]) -> f64 {
+    let count = samples.len() as f64;
+    let values = || samples.iter().map(|sample| sample.value);
+    let sum = || values().sum::<f64>();
+    match aggregator {
+        Aggregator::Sum => sum(),
+        Aggregator::Count => count,
+        _ if samples.is_empty() => f64::NAN,
+        Aggregator::Avg => sum() / count,
+        Aggregator::Min => values().fold(f64::INFINITY, f64::min),
+        Aggregator::Max => values().fold(f64::NEG_INFINITY, f64::max),
+        Aggregator::Range => {
+            aggregate(Aggregator::Max, samples) - aggregate(Aggregator::Min, samples)
+        }
+        Aggregator::First => samples[0].value,
+        Aggregator::Last => samples[samples.len() - 1].value,
+        Aggregator::VarP | Aggregator::VarS | Aggregator::StdP | Aggregator::StdS => {
+            let mean = sum() / count;
+            let squares: f64 = values().map(|value| (value - mean).powi(2)).sum();
+            let variance = match aggregator {
+                Aggregator::VarP | Aggregator::StdP => squares / count,
+                _ if samples.len() < 2 => 0.0,
+                _ => squares / (count - 1.0),
+            };
+            if matches!(aggregator, Aggregator::StdP | Aggregator::StdS) {
+                variance.sqrt()
+            } else {
+                variance
+            }
+        }
+        Aggregator::Twa => time_weighted_average(samples),
+    }
+}
+
+/// Average of samples weighted by time, values between two samples are
+/// linearly interpolated.
+///
+/// Returns the plain average if all samples have the same timestamp.
+#[allow(clippy::cast_precision_loss)]
+fn time_weighted_average(samples: &[Sample]) -> f64 {
+    let duration = samples[samples.len() - 1].timestamp - samples[0].timestamp;
+    if duration == 0 {
+        return aggregate(Aggregator::Avg, samples);
+    }
+    let area: f64 = samples
+        .windows(2)
+        .map(|pair| {
+            (pair[0].value + pair[1].value) / 2.0 * (pair[1].timestamp - pair[0].timestamp) as f64
+        })
+        .sum();
+    area / duration as f64
+}
+
+/// Returns start timestamp of the bucket which contains `timestamp`.
+#[must_use]
+pub const fn bucket_start(timestamp: i64, bucket_duration: i64, align: i64) -> i64 {
+    timestamp - (timestamp - align).rem_euclid(bucket_duration)
+}
+
+/// Group samples sorted by timestamp into buckets, and aggregate each bucket.
+///
+/// Buckets start at `align` plus a multiple of bucket duration.
+#[must_use]
+pub fn aggregate_buckets(samples: &[Sample], aggregation: &Aggregation, align: i64) -> Vec<Sample> {
+    let duration = aggregation.bucket_duration;
+    let mut buckets = Vec::new();
+    let mut last_start: Option<i64> = None;
+    let mut index = 0;
+    while index < samples.len() {
+        let start = bucket_start(samples[index].timestamp, duration, align);
+        let end_index = samples[index..]
+            .iter()
+            .position(|sample| sample.timestamp >= start + duration)
+            .map_or(samples.len(), |pos| index + pos);
+
+        if aggregation.empty {
+            if let Some(last_start) = last_start {
+                let mut empty_start = last_start + duration;
+                while empty_start < start {
+                    buckets.push(Sample {
+                        timestamp: empty_start,
+                        value: aggregate(aggregation.aggregator, &[]),
+                    });
+                    empty_start += duration;
+                }
+            }
+        }
+        buckets.push(Sample {
+            timestamp: start,
+            value: aggregate(aggregation.aggregator, &samples[index..end_index]),
+        });
+        last_start = Some(start);
+        index = end_index;
+    }
+
+    let offset = match aggregation.bucket_timestamp {
+        BucketTimestamp::Start => 0,
+        BucketTimestamp::End => duration,
+        BucketTimestamp::Mid => duration / 2,
+    };
+    for bucket in &mut buckets {
+        bucket.timestamp += offset;
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::time_series::{Aggregation, Aggregator, BucketTimestamp};
+    use crate::mem::time_series::aggregation::{aggregate, aggregate_buckets, bucket_start};
+    use crate::mem::time_series::Sample;
+
+    fn to_samples(values: &[f64]) -> Vec<Sample> {
+        (0..)
+            .zip(values)
+            .map(|(timestamp, &value)| Sample { timestamp, value })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let values = to_samples(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(aggregate(Aggregator::Avg, &values), 5.0);
+        assert_eq!(aggregate(Aggregator::Range, &values), 7.0);
+        assert_eq!(aggregate(Aggregator::StdP, &values), 2.0);
+        assert_eq!(aggregate(Aggregator::VarS, &values), 32.0 / 7.0);
+        assert_eq!(aggregate(Aggregator::Last, &values), 9.0);
+        assert_eq!(aggregate(Aggregator::Count, &[]), 0.0);
+        assert!(aggregate(Aggregator::Max, &[]).is_nan());
+        assert_eq!(
+            aggregate(Aggregator::Twa, &to_samples(&[1.0, 3.0, 3.0])),
+            2.5
+        );
+    }
+
+    #[test]
+    fn test_aggregate_buckets() {
+        assert_eq!(bucket_start(25, 10, 0), 20);
+        assert_eq!(bucket_start(25, 10, 3), 23);
+        assert_eq!(bucket_start(2, 10, 3), -7);
+
+        let samples: Vec<Sample> = [(1, 1.0), (5, 2.0), (12, 3.0), (35, 4.0)]
+            .iter()
+            .map(|&(timestamp, value)| Sample { timestamp, value })
+            .collect();
+        let mut aggregation = Aggregation {
+            aggregator: Aggregator::Sum,
+            bucket_duration: 10,
+            bucket_timestamp: BucketTimestamp::Start,
+            empty: false,
+        };
+        let buckets = aggregate_buckets(&samples, &aggregation, 0);
+        assert_eq!(
+            buckets,
+            vec![
+                Sample {
+                    timestamp: 0,
+                    value: 3.0
+                },
+                Sample {
+                    timestamp: 10,
+                    value: 3.0
+                },
+                Sample {
+                    timestamp: 30,
+                    value: 4.0
+                },
+            ]
+        );
+        aggregation.empty = true;
+        aggregation.bucket_timestamp = BucketTimestamp::End;
+        let buckets = aggregate_buckets(&samples, &aggregation, 0);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(
+            buckets[2],
+            Sample {
+                timestamp: 30,
+                value: 0.0
+            }
+        );
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::SeriesOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::check_options;
+use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+
+/// Update the retention, chunk size, duplicate policy and labels of an existing
+/// time series.
+///
+/// Only specified options are changed, and labels are replaced as a whole.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key does not exist, or options are invalid.
+pub fn alter(db: &mut Db, key: &str, options: SeriesOptions) -> ReplyFrame {
+    if let Err(err) = check_options(&options) {
+        return err;
+    }
+    match db.get_mut(key) {
+        Some(MemObject::TimeSeries(series)) => {
+            series.alter(options);
+            ReplyFrame::ok()
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::SeriesOptions;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::time_series::alter::alter;
+    use crate::mem::time_series::create::create;
+
+    #[test]
+    fn test_alter() {
+        let mut db = Db::new();
+        let options = SeriesOptions {
+            labels: Some(vec![("room".to_owned(), "a".to_owned())]),
+            ..SeriesOptions::default()
+        };
+        assert!(matches!(
+            alter(&mut db, "temp", options.clone()),
+            ReplyFrame::ConstError(_)
+        ));
+        assert_eq!(
+            create(&mut db, "temp".to_owned(), &SeriesOptions::default()),
+            ReplyFrame::ok()
+        );
+        assert_eq!(alter(&mut db, "temp", options), ReplyFrame::ok());
+        let Some(MemObject::TimeSeries(series)) = db.get("temp") else {
+            panic!("expected time series");
+        };
+        assert_eq!(series.labels().len(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Compressed chunk of samples, as described in Facebook's Gorilla paper.
+//!
+//! The first sample is stored as raw 64-bit timestamp and value. For following
+//! samples, delta-of-delta of timestamps is stored with variable bit length,
+//! and value is stored as XOR with the previous value, with only the meaningful
+//! bits between leading and trailing zeros written.
+
+use crate::mem::time_series::Sample;
+
+/// Sentinel of no previous leading zeros, so that the first non-zero XOR
+/// always writes its own leading zeros and length.
+const NO_LEADING: u32 = u32::MAX;
+
+/// Bit stream writer, bits are written from the most significant bit of each byte.
+#[derive(Debug, Default, Clone)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_len: usize,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        let offset = self.bit_len % 8;
+        if offset == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            if let Some(last) = self.bytes.last_mut() {
+                *last |= 0x80 >> offset;
+            }
+        }
+        self.bit_len += 1;
+    }
+
+    /// Write the lowest `count` bits of value.
+    fn write_bits(&mut self, value: u64, count: u32) {
+        for index in (0..count).rev() {
+            self.write_bit((value >> index) & 1 == 1);
+        }
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.pos / 8)?;
+        let bit = byte & (0x80 >> (self.pos % 8)) != 0;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    fn read_bits(&mut self, count: u32) -> Option<u64> {
+        (0..count).try_fold(0_u64, |acc, _| {
+            Some((acc << 1) | u64::from(self.read_bit()?))
+        })
+    }
+
+    /// Read `count` bits as two's complement signed integer.
+    #[allow(clippy::cast_possible_wrap)]
+    fn read_signed(&mut self, count: u32) -> Option<i64> {
+        let bits = self.read_bits(count)?;
+        let shift = 64 - count;
+        Some(((bits << shift) as i64) >> shift)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    writer: BitWriter,
+    count: usize,
+    first_timestamp: i64,
+    last_timestamp: i64,
+    last_delta: i64,
+    last_value: u64,
+    leading: u32,
+    trailing: u32,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chunk {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            writer: BitWriter {
+                bytes: Vec::new(),
+                bit_len: 0,
+            },
+            count: 0,
+            first_timestamp: 0,
+            last_timestamp: 0,
+            last_delta: 0,
+            last_value: 0,
+            leading: NO_LEADING,
+            trailing: 0,
+        }
+    }
+
+    /// Create a chunk from samples sorted by timestamp.
+    #[must_use]
+    pub fn from_samples(samples: &[Sample]) -> Self {
+        let mut chunk = Self::new();
+        for sample in samples {
+            chunk.append(*sample);
+        }
+        chunk
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns number of bytes used by compressed samples.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.writer.bytes.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn first_timestamp(&self) -> i64 {
+        self.first_timestamp
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn last_timestamp(&self) -> i64 {
+        self.last_timestamp
+    }
+
+    #[must_use]
+    pub fn last_sample(&self) -> Option<Sample> {
+        (self.count > 0).then(|| Sample {
+            timestamp: self.last_timestamp,
+            value: f64::from_bits(self.last_value),
+        })
+    }
+
+    /// Append sample to the end of chunk.
+    ///
+    /// Timestamp of sample must be larger than the last one.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn append(&mut self, sample: Sample) {
+        debug_assert!(self.count == 0 || sample.timestamp > self.last_timestamp);
+        let bits = sample.value.to_bits();
+        if self.count == 0 {
+            self.writer.write_bits(sample.timestamp as u64, 64);
+            self.writer.write_bits(bits, 64);
+            self.first_timestamp = sample.timestamp;
+            self.last_delta = 0;
+        } else {
+            let delta = sample.timestamp - self.last_timestamp;
+            self.write_delta_of_delta(delta - self.last_delta);
+            self.write_value(bits);
+            self.last_delta = delta;
+        }
+        self.last_timestamp = sample.timestamp;
+        self.last_value = bits;
+        self.count += 1;
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn write_delta_of_delta(&mut self, dod: i64) {
+        let (prefix, prefix_len, value_len) = match dod {
+            0 => {
+                self.writer.write_bit(false);
+                return;
+            }
+            -64..=63 => (0b10, 2, 7),
+            -256..=255 => (0b110, 3, 9),
+            -2048..=2047 => (0b1110, 4, 12),
+            _ => (0b1111, 4, 64),
+        };
+        self.writer.write_bits(prefix, prefix_len);
+        self.writer.write_bits(dod as u64, value_len);
+    }
+
+    fn write_value(&mut self, bits: u64) {
+        let xor = bits ^ self.last_value;
+        if xor == 0 {
+            self.writer.write_bit(false);
+            return;
+        }
+        self.writer.write_bit(true);
+
+        // Leading zeros are stored in 5 bits.
+        let leading = xor.leading_zeros().min(31);
+        let trailing = xor.trailing_zeros();
+        if self.leading != NO_LEADING && leading >= self.leading && trailing >= self.trailing {
+            // Meaningful bits fall into the previous window.
+            self.writer.write_bit(false);
+            self.writer
+                .write_bits(xor >> self.trailing, 64 - self.leading - self.trailing);
+        } else {
+            let meaningful = 64 - leading - trailing;
+            self.writer.write_bit(true);
+            self.writer.write_bits(u64::from(leading), 5);
+            self.writer.write_bits(u64::from(meaningful - 1), 6);
+            self.writer.write_bits(xor >> trailing, meaningful);
+            self.leading = leading;
+            self.trailing = trailing;
+        }
+    }
+
+    /// Decompress all samples in chunk.
+    #[must_use]
+    pub fn samples(&self) -> Vec<Sample> {
+        let mut samples = Vec::with_capacity(self.count);
+        let decoded = self.decode(&mut samples);
+        debug_assert!(decoded.is_some());
+        samples
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[allow(clippy::cast_possible_truncation)]
+    fn decode(&self, samples: &mut Vec<Sample>) -> Option<()> {
+        let mut reader = BitReader::new(&self.writer.bytes);
+        let mut timestamp = 0;
+        let mut delta = 0;
+        let mut value = 0;
+        let mut leading = 0;
+        let mut trailing = 0;
+        for index in 0..self.count {
+            if index == 0 {
+                timestamp = reader.read_bits(64)? as i64;
+                value = reader.read_bits(64)?;
+            } else {
+                delta += read_delta_of_delta(&mut reader)?;
+                timestamp += delta;
+                if reader.read_bit()? {
+                    if reader.read_bit()? {
+                        leading = reader.read_bits(5)? as u32;
+                        let meaningful = reader.read_bits(6)? as u32 + 1;
+                        trailing = 64 - leading - meaningful;
+                    }
+                    value ^= reader.read_bits(64 - leading - trailing)? << trailing;
+                }
+            }
+            samples.push(Sample {
+                timestamp,
+                value: f64::from_bits(value),
+            });
+        }
+        Some(())
+    }
+}
+
+fn read_delta_of_delta(reader: &mut BitReader) -> Option<i64> {
+    if !reader.read_bit()? {
+        return Some(0);
+    }
+    if !reader.read_bit()? {
+        return reader.read_signed(7);
+    }
+    if !reader.read_bit()? {
+        return reader.read_signed(9);
+    }
+    if !reader.read_bit()? {
+        return reader.read_signed(12);
+    }
+    reader.read_signed(64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mem::time_series::chunk::Chunk;
+    use crate::mem::time_series::Sample;
+
+    #[test]
+    fn test_chunk() {
+        let timestamps = [
+            1_700_000_000_000,
+            1_700_000_001_000,
+            1_700_000_002_000,
+            1_700_000_002_001,
+            1_700_000_002_300,
+            1_700_000_004_000,
+            1_800_000_000_000,
+        ];
+        let values = [20.0, 20.0, 20.5, -3.25, 1e300, f64::MIN_POSITIVE, 0.0];
+        let samples: Vec<Sample> = timestamps
+            .iter()
+            .zip(values)
+            .map(|(&timestamp, value)| Sample { timestamp, value })
+            .collect();
+        let chunk = Chunk::from_samples(&samples);
+        assert_eq!(chunk.len(), samples.len());
+        assert_eq!(chunk.samples(), samples);
+        assert_eq!(chunk.first_timestamp(), timestamps[0]);
+        assert_eq!(chunk.last_sample(), samples.last().copied());
+        // Regular samples are compressed well.
+        let regular: Vec<Sample> = (0..1000)
+            .map(|index| Sample {
+                timestamp: index * 1000,
+                value: 42.0,
+            })
+            .collect();
+        let chunk = Chunk::from_samples(&regular);
+        assert!(chunk.size() < 300);
+        assert_eq!(chunk.samples(), regular);
+    }
+}
@@ -0,0 +1,18 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const KEY_NOT_EXIST_ERR: &str = "ERR TSDB: the key does not exist";
+pub const KEY_EXISTS_ERR: &str = "ERR TSDB: key already exists";
+pub const OLDER_THAN_RETENTION_ERR: &str = "ERR TSDB: Timestamp is older than retention";
+pub const DUPLICATE_BLOCKED_ERR: &str =
+    "ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode";
+pub const TIMESTAMP_TOO_OLD_ERR: &str =
+    "ERR TSDB: timestamp must be equal to or higher than the maximum existing timestamp";
+pub const INVALID_CHUNK_SIZE_ERR: &str =
+    "ERR TSDB: CHUNK_SIZE value must be a multiple of 8 in the range [48 .. 1048576]";
+pub const SAME_KEY_RULE_ERR: &str =
+    "ERR TSDB: the source key and destination key should be different";
+pub const DEST_HAS_SOURCE_ERR: &str = "ERR TSDB: the destination key already has a src rule";
+pub const DEST_HAS_RULES_ERR: &str = "ERR TSDB: the destination key already has a dst rule";
+pub const RULE_NOT_EXIST_ERR: &str = "ERR TSDB: compaction rule does not exist";
@@ -0,0 +1,58 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::SeriesOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::KEY_EXISTS_ERR;
+use crate::mem::time_series::{check_options, TimeSeriesObject};
+
+/// Create a new time series.
+///
+/// Options:
+/// - RETENTION: maximum age for samples compared to the highest reported
+///   timestamp, in milliseconds. Default is 0, samples never expire.
+/// - `CHUNK_SIZE`: memory size of each data chunk, in bytes, default is 4096.
+/// - `DUPLICATE_POLICY`: policy to handle multiple samples with identical timestamps,
+///   default is BLOCK.
+/// - LABELS: label-value pairs that represent metadata labels of the key.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key already exists, or options are invalid.
+pub fn create(db: &mut Db, key: String, options: &SeriesOptions) -> ReplyFrame {
+    if db.contains_key(&key) {
+        return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+    }
+    if let Err(err) = check_options(options) {
+        return err;
+    }
+    db.insert(key, MemObject::TimeSeries(TimeSeriesObject::new(options)));
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::SeriesOptions;
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+
+    #[test]
+    fn test_create() {
+        let mut db = Db::new();
+        let key = "temp".to_owned();
+        let mut options = SeriesOptions::default();
+        assert_eq!(create(&mut db, key.clone(), &options), ReplyFrame::ok());
+        assert!(matches!(
+            create(&mut db, key, &options),
+            ReplyFrame::ConstError(_)
+        ));
+        options.chunk_size = Some(50);
+        assert!(matches!(
+            create(&mut db, "other".to_owned(), &options),
+            ReplyFrame::ConstError(_)
+        ));
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::Aggregator;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::{
+    DEST_HAS_RULES_ERR, DEST_HAS_SOURCE_ERR, KEY_NOT_EXIST_ERR, SAME_KEY_RULE_ERR,
+};
+use crate::mem::time_series::rule::CompactionRule;
+
+/// Create a compaction rule.
+///
+/// Samples added to source series later are aggregated into buckets of
+/// `bucket_duration`, and each bucket is added to destination series
+/// once a sample of the next bucket is added.
+///
+/// Buckets start at `align` plus a multiple of bucket duration.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if source or destination key does not exist, they are the same key,
+///   or destination series is already used by another rule.
+pub fn create_rule(
+    db: &mut Db,
+    source: &str,
+    dest: &str,
+    aggregator: Aggregator,
+    bucket_duration: i64,
+    align: i64,
+) -> ReplyFrame {
+    if source == dest {
+        return ReplyFrame::ConstError(SAME_KEY_RULE_ERR);
+    }
+    match db.get(source) {
+        Some(MemObject::TimeSeries(_)) => {}
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+    match db.get_mut(dest) {
+        Some(MemObject::TimeSeries(dest_series)) => {
+            if dest_series.source.is_some() {
+                return ReplyFrame::ConstError(DEST_HAS_SOURCE_ERR);
+            }
+            if !dest_series.rules.is_empty() {
+                return ReplyFrame::ConstError(DEST_HAS_RULES_ERR);
+            }
+            dest_series.source = Some(source.to_owned());
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+    if let Some(MemObject::TimeSeries(series)) = db.get_mut(source) {
+        let rule = CompactionRule::new(dest.to_owned(), aggregator, bucket_duration, align);
+        series.rules.push(rule);
+    }
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{AddOptions, Aggregator, DuplicatePolicy, SeriesOptions};
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::time_series::add::add;
+    use crate::mem::time_series::consts::{DEST_HAS_SOURCE_ERR, SAME_KEY_RULE_ERR};
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::create_rule::create_rule;
+    use crate::mem::time_series::del::del;
+    use crate::mem::time_series::get::get;
+    use crate::mem::time_series::Sample;
+
+    fn add_sample(db: &mut Db, timestamp: i64, value: f64) {
+        let options = AddOptions {
+            key: "raw".to_owned(),
+            timestamp: Some(timestamp),
+            value,
+            on_duplicate: Some(DuplicatePolicy::Last),
+            series: SeriesOptions::default(),
+        };
+        assert_eq!(add(db, &options), ReplyFrame::I64(timestamp));
+    }
+
+    #[test]
+    fn test_create_rule() {
+        let mut db = Db::new();
+        for key in ["raw", "avg"] {
+            create(&mut db, key.to_owned(), &SeriesOptions::default());
+        }
+        assert_eq!(
+            create_rule(&mut db, "raw", "raw", Aggregator::Avg, 10, 0),
+            ReplyFrame::ConstError(SAME_KEY_RULE_ERR)
+        );
+        assert_eq!(
+            create_rule(&mut db, "raw", "avg", Aggregator::Avg, 10, 0),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            create_rule(&mut db, "raw", "avg", Aggregator::Max, 10, 0),
+            ReplyFrame::ConstError(DEST_HAS_SOURCE_ERR)
+        );
+
+        add_sample(&mut db, 1, 1.0);
+        add_sample(&mut db, 5, 3.0);
+        assert_eq!(get(&db, "avg"), ReplyFrame::EmptyArray);
+        add_sample(&mut db, 12, 10.0);
+        assert_eq!(
+            get(&db, "avg"),
+            ReplyFrame::Array(vec![ReplyFrame::I64(0), ReplyFrame::Double(2.0)])
+        );
+        add_sample(&mut db, 35, 10.0);
+        assert_eq!(
+            get(&db, "avg"),
+            ReplyFrame::Array(vec![ReplyFrame::I64(10), ReplyFrame::Double(10.0)])
+        );
+    }
+
+    #[test]
+    fn test_compact_changed_bucket() {
+        let mut db = Db::new();
+        for key in ["raw", "sum"] {
+            create(&mut db, key.to_owned(), &SeriesOptions::default());
+        }
+        create_rule(&mut db, "raw", "sum", Aggregator::Sum, 10, 0);
+        for (timestamp, value) in [(1, 1.0), (5, 2.0), (12, 3.0), (25, 4.0)] {
+            add_sample(&mut db, timestamp, value);
+        }
+        let compacted = |db: &Db| match db.get("sum") {
+            Some(MemObject::TimeSeries(series)) => series.range(i64::MIN, i64::MAX),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            compacted(&db),
+            vec![
+                Sample {
+                    timestamp: 0,
+                    value: 3.0
+                },
+                Sample {
+                    timestamp: 10,
+                    value: 3.0
+                }
+            ]
+        );
+
+        add_sample(&mut db, 5, 7.0);
+        add_sample(&mut db, 15, 1.0);
+        assert_eq!(
+            compacted(&db),
+            vec![
+                Sample {
+                    timestamp: 0,
+                    value: 8.0
+                },
+                Sample {
+                    timestamp: 10,
+                    value: 4.0
+                }
+            ]
+        );
+
+        assert_eq!(del(&mut db, "raw", Some(5), Some(12)), ReplyFrame::Usize(2));
+        assert_eq!(
+            compacted(&db),
+            vec![
+                Sample {
+                    timestamp: 0,
+                    value: 1.0
+                },
+                Sample {
+                    timestamp: 10,
+                    value: 1.0
+                }
+            ]
+        );
+        assert_eq!(
+            del(&mut db, "raw", Some(10), Some(20)),
+            ReplyFrame::Usize(1)
+        );
+        assert_eq!(
+            compacted(&db),
+            vec![Sample {
+                timestamp: 0,
+                value: 1.0
+            }]
+        );
+    }
+}
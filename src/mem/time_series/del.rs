@@ -0,0 +1,55 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::time_series::rule::compact_deleted;
+
+/// Delete all samples between two timestamps of a time series.
+///
+/// The range is inclusive, `-` means the earliest and `+` means the latest sample.
+/// Compacted samples of destination series are updated too.
+///
+/// Reply:
+/// - Integer reply: the number of samples deleted.
+/// - Error reply: if key does not exist.
+pub fn del(db: &mut Db, key: &str, from: Option<i64>, to: Option<i64>) -> ReplyFrame {
+    let from = from.unwrap_or(i64::MIN);
+    let to = to.unwrap_or(i64::MAX);
+    match db.get_mut(key) {
+        Some(MemObject::TimeSeries(series)) => {
+            let count = series.delete_range(from, to);
+            if count > 0 {
+                compact_deleted(db, key, from, to);
+            }
+            ReplyFrame::Usize(count)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::SeriesOptions;
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::del::del;
+    use crate::mem::time_series::madd::madd;
+
+    #[test]
+    fn test_del() {
+        let mut db = Db::new();
+        create(&mut db, "temp".to_owned(), &SeriesOptions::default());
+        let samples = (0..10)
+            .map(|timestamp| ("temp".to_owned(), Some(timestamp), 1.0))
+            .collect();
+        madd(&mut db, samples);
+        assert_eq!(del(&mut db, "temp", Some(3), Some(5)), ReplyFrame::Usize(3));
+        assert_eq!(del(&mut db, "temp", None, Some(3)), ReplyFrame::Usize(3));
+        assert_eq!(del(&mut db, "temp", None, None), ReplyFrame::Usize(4));
+    }
+}
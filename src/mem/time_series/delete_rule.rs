@@ -0,0 +1,63 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::{KEY_NOT_EXIST_ERR, RULE_NOT_EXIST_ERR};
+
+/// Delete a compaction rule.
+///
+/// Samples already compacted into destination series are kept.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if source key does not exist, or the rule does not exist.
+pub fn delete_rule(db: &mut Db, source: &str, dest: &str) -> ReplyFrame {
+    match db.get_mut(source) {
+        Some(MemObject::TimeSeries(series)) => {
+            let Some(index) = series.rules.iter().position(|rule| rule.dest == dest) else {
+                return ReplyFrame::ConstError(RULE_NOT_EXIST_ERR);
+            };
+            series.rules.remove(index);
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+    if let Some(MemObject::TimeSeries(dest_series)) = db.get_mut(dest) {
+        if dest_series.source.as_deref() == Some(source) {
+            dest_series.source = None;
+        }
+    }
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{Aggregator, SeriesOptions};
+    use crate::mem::db::Db;
+    use crate::mem::time_series::consts::RULE_NOT_EXIST_ERR;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::create_rule::create_rule;
+    use crate::mem::time_series::delete_rule::delete_rule;
+
+    #[test]
+    fn test_delete_rule() {
+        let mut db = Db::new();
+        for key in ["raw", "max"] {
+            create(&mut db, key.to_owned(), &SeriesOptions::default());
+        }
+        assert_eq!(
+            delete_rule(&mut db, "raw", "max"),
+            ReplyFrame::ConstError(RULE_NOT_EXIST_ERR)
+        );
+        create_rule(&mut db, "raw", "max", Aggregator::Max, 60, 0);
+        assert_eq!(delete_rule(&mut db, "raw", "max"), ReplyFrame::ok());
+        // Destination key can be used by a new rule.
+        assert_eq!(
+            create_rule(&mut db, "raw", "max", Aggregator::Min, 60, 0),
+            ReplyFrame::ok()
+        );
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::time_series::sample_to_reply;
+
+/// Get the sample with the highest timestamp from a time series.
+///
+/// Reply:
+/// - Array reply: timestamp and value of the latest sample, or empty array
+///   if time series is empty.
+/// - Error reply: if key does not exist.
+pub fn get(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::TimeSeries(series)) => series
+            .last()
+            .map_or(ReplyFrame::EmptyArray, sample_to_reply),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::SeriesOptions;
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::get::get;
+    use crate::mem::time_series::madd::madd;
+
+    #[test]
+    fn test_get() {
+        let mut db = Db::new();
+        assert!(matches!(get(&db, "temp"), ReplyFrame::ConstError(_)));
+        create(&mut db, "temp".to_owned(), &SeriesOptions::default());
+        assert_eq!(get(&db, "temp"), ReplyFrame::EmptyArray);
+        madd(&mut db, vec![("temp".to_owned(), Some(3), 1.5)]);
+        assert_eq!(
+            get(&db, "temp"),
+            ReplyFrame::Array(vec![ReplyFrame::I64(3), ReplyFrame::Double(1.5)])
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::{DuplicatePolicy, IncrByOptions};
+use crate::mem::db::Db;
+use crate::mem::time_series::consts::TIMESTAMP_TOO_OLD_ERR;
+use crate::mem::time_series::rule::compact;
+use crate::mem::time_series::{get_or_create, Sample};
+use crate::mem::util::now_millis;
+
+/// Increase the value of the latest sample by the given value.
+///
+/// If timestamp equals to the latest one, the latest sample is updated,
+/// otherwise a new sample is appended with the increased value.
+/// If key does not exist, a new time series is created with the series options.
+///
+/// Reply:
+/// - Integer reply: the timestamp of the upserted sample.
+/// - Error reply: if timestamp is older than the latest one.
+pub fn incr_by(db: &mut Db, options: &IncrByOptions) -> ReplyFrame {
+    let series = match get_or_create(db, &options.key, &options.series) {
+        Ok(series) => series,
+        Err(err) => return err,
+    };
+    let timestamp = options.timestamp.unwrap_or_else(now_millis);
+    let last = series.last();
+    if last.is_some_and(|last| timestamp < last.timestamp) {
+        return ReplyFrame::ConstError(TIMESTAMP_TOO_OLD_ERR);
+    }
+    let sample = Sample {
+        timestamp,
+        value: last.map_or(0.0, |last| last.value) + options.value,
+    };
+    match series.add(sample, Some(DuplicatePolicy::Last)) {
+        Ok(()) => {
+            compact(db, &options.key, timestamp);
+            ReplyFrame::I64(timestamp)
+        }
+        Err(err) => ReplyFrame::ConstError(err),
+    }
+}
+
+/// Decrease the value of the latest sample by the given value.
+///
+/// Reply:
+/// - Integer reply: the timestamp of the upserted sample.
+/// - Error reply: if timestamp is older than the latest one.
+pub fn decr_by(db: &mut Db, mut options: IncrByOptions) -> ReplyFrame {
+    options.value = -options.value;
+    incr_by(db, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{IncrByOptions, SeriesOptions};
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::time_series::incr_by::{decr_by, incr_by};
+    use crate::mem::time_series::Sample;
+
+    fn options(value: f64, timestamp: i64) -> IncrByOptions {
+        IncrByOptions {
+            key: "counter".to_owned(),
+            value,
+            timestamp: Some(timestamp),
+            series: SeriesOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_incr_by() {
+        let mut db = Db::new();
+        assert_eq!(incr_by(&mut db, &options(5.0, 10)), ReplyFrame::I64(10));
+        assert_eq!(incr_by(&mut db, &options(2.0, 10)), ReplyFrame::I64(10));
+        assert_eq!(decr_by(&mut db, options(1.0, 20)), ReplyFrame::I64(20));
+        assert!(matches!(
+            incr_by(&mut db, &options(1.0, 15)),
+            ReplyFrame::ConstError(_)
+        ));
+        let Some(MemObject::TimeSeries(series)) = db.get("counter") else {
+            panic!("expected time series");
+        };
+        assert_eq!(
+            series.range(0, 100),
+            vec![
+                Sample {
+                    timestamp: 10,
+                    value: 7.0
+                },
+                Sample {
+                    timestamp: 20,
+                    value: 6.0
+                },
+            ]
+        );
+    }
+}
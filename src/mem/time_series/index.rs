@@ -0,0 +1,270 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Index of time series keys by their labels.
+//!
+//! Keys are indexed when a time series is created, altered or renamed.
+//! Entries of keys which are deleted, flushed or overwritten by another type are
+//! removed when the key is written. Other stale entries are removed lazily when
+//! they are found in a query.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::cmd::time_series::LabelFilter;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::TimeSeriesObject;
+use crate::mem::Mem;
+
+#[derive(Debug, Default, Clone)]
+pub struct LabelIndex {
+    /// Keys of each label and value.
+    labels: HashMap<String, HashMap<String, HashSet<String>>>,
+
+    /// Indexed labels of each key.
+    keys: HashMap<String, Vec<(String, String)>>,
+}
+
+impl LabelIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index key with labels, old labels of key are replaced.
+    pub fn insert(&mut self, key: &str, labels: &[(String, String)]) {
+        if self.keys.get(key).is_some_and(|old| old == labels) {
+            return;
+        }
+        self.remove(key);
+        for (label, value) in labels {
+            self.labels
+                .entry(label.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .insert(key.to_owned());
+        }
+        self.keys.insert(key.to_owned(), labels.to_vec());
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        let Some(labels) = self.keys.remove(key) else {
+            return;
+        };
+        for (label, value) in labels {
+            if let Some(values) = self.labels.get_mut(&label) {
+                if let Some(keys) = values.get_mut(&value) {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        values.remove(&value);
+                    }
+                }
+                if values.is_empty() {
+                    self.labels.remove(&label);
+                }
+            }
+        }
+    }
+
+    /// Returns keys which have label with any of values.
+    fn keys_of(&self, label: &str, values: &[String]) -> HashSet<&String> {
+        self.labels.get(label).map_or_else(HashSet::new, |map| {
+            values
+                .iter()
+                .filter_map(|value| map.get(value))
+                .flatten()
+                .collect()
+        })
+    }
+
+    /// Returns sorted keys of time series which match all filters.
+    ///
+    /// Filters must contain at least one positive filter, like `label=value`.
+    pub fn query(&mut self, db: &Db, filters: &[LabelFilter]) -> Vec<String> {
+        let mut candidates: Option<HashSet<&String>> = None;
+        for filter in filters.iter().filter(|filter| filter.is_positive()) {
+            let keys = self.keys_of(&filter.label, &filter.values);
+            candidates = Some(match candidates {
+                Some(candidates) => candidates.intersection(&keys).copied().collect(),
+                None => keys,
+            });
+        }
+
+        let mut matched = BTreeSet::new();
+        let mut stale = Vec::new();
+        for key in candidates.unwrap_or_default() {
+            match db.get(key.as_str()) {
+                Some(MemObject::TimeSeries(series)) => {
+                    if self
+                        .keys
+                        .get(key)
+                        .is_some_and(|labels| labels != series.labels())
+                    {
+                        stale.push(key.clone());
+                    } else if series.matches(filters) {
+                        matched.insert(key.clone());
+                    }
+                }
+                _ => stale.push(key.clone()),
+            }
+        }
+        for key in stale {
+            // Re-index time series whose labels are changed, like being overwritten.
+            self.remove(&key);
+            if let Some(MemObject::TimeSeries(series)) = db.get(&key) {
+                self.insert(&key, series.labels());
+                if series.matches(filters) {
+                    matched.insert(key);
+                }
+            }
+        }
+        matched.into_iter().collect()
+    }
+}
+
+impl TimeSeriesObject {
+    /// Check whether labels of this time series match all filters.
+    #[must_use]
+    pub fn matches(&self, filters: &[LabelFilter]) -> bool {
+        filters
+            .iter()
+            .all(|filter| filter.matches(self.label(&filter.label)))
+    }
+}
+
+impl Mem {
+    /// Index labels of the time series stored at key.
+    pub(crate) fn index_time_series(&mut self, key: &str) {
+        if let Some(MemObject::TimeSeries(series)) = self.db.get(key) {
+            self.time_series_index.insert(key, series.labels());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::cmd::generic::GenericCommand;
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::string::StringCommand;
+    use crate::cmd::time_series::{Aggregator, LabelFilter, SeriesOptions, TimeSeriesCommand};
+    use crate::cmd::Command;
+    use crate::mem::db::{Db, MemObject};
+    use crate::mem::time_series::index::LabelIndex;
+    use crate::mem::time_series::TimeSeriesObject;
+    use crate::mem::Mem;
+
+    fn series(labels: &[(&str, &str)]) -> TimeSeriesObject {
+        TimeSeriesObject::new(&SeriesOptions {
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|&(label, value)| (label.to_owned(), value.to_owned()))
+                    .collect(),
+            ),
+            ..SeriesOptions::default()
+        })
+    }
+
+    fn filters(exprs: &[&str]) -> Vec<LabelFilter> {
+        exprs
+            .iter()
+            .map(|expr| LabelFilter::try_from(*expr).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_query() {
+        let mut db = Db::new();
+        let mut index = LabelIndex::new();
+        for (key, labels) in [
+            ("a", vec![("room", "1"), ("type", "temp")]),
+            ("b", vec![("room", "2"), ("type", "temp")]),
+            ("c", vec![("room", "2")]),
+        ] {
+            let series = series(&labels);
+            index.insert(key, series.labels());
+            db.insert(key.to_owned(), MemObject::TimeSeries(series));
+        }
+        assert_eq!(index.query(&db, &filters(&["type=temp"])), ["a", "b"]);
+        assert_eq!(index.query(&db, &filters(&["room=(1,2)", "type="])), ["c"]);
+        assert_eq!(
+            index.query(&db, &filters(&["room=2", "type!=humidity"])),
+            ["b", "c"]
+        );
+
+        // Stale keys are removed or re-indexed.
+        db.remove("a");
+        db.insert(
+            "b".to_owned(),
+            MemObject::TimeSeries(series(&[("room", "3")])),
+        );
+        assert!(index.query(&db, &filters(&["type=temp"])).is_empty());
+        assert!(!index.keys.contains_key("a"));
+        assert_eq!(index.query(&db, &filters(&["room=3"])), ["b"]);
+    }
+
+    #[test]
+    fn test_remove_deleted_keys() {
+        let (dispatcher_sender, _receiver) = mpsc::channel(1);
+        let (_sender, dispatcher_receiver) = mpsc::channel(1);
+        let mut mem = Mem::new(dispatcher_sender, dispatcher_receiver);
+        for key in ["raw", "max", "min"] {
+            let options = SeriesOptions {
+                labels: Some(vec![("type".to_owned(), "temp".to_owned())]),
+                ..SeriesOptions::default()
+            };
+            let command = TimeSeriesCommand::Create(key.to_owned(), Box::new(options));
+            mem.handle_db_command(Command::TimeSeries(command));
+        }
+        for dest in ["max", "min"] {
+            let command = TimeSeriesCommand::CreateRule(
+                "raw".to_owned(),
+                dest.to_owned(),
+                Aggregator::Max,
+                60,
+                0,
+            );
+            assert_eq!(
+                mem.handle_db_command(Command::TimeSeries(command)),
+                ReplyFrame::ok()
+            );
+        }
+
+        // Rules to the deleted key are dropped.
+        let command = GenericCommand::Delete(vec!["min".to_owned()]);
+        mem.handle_db_command(Command::Generic(command));
+        assert!(matches!(
+            mem.db.get("raw"),
+            Some(MemObject::TimeSeries(series)) if series.rules.len() == 1
+        ));
+
+        let command = GenericCommand::Delete(vec!["raw".to_owned()]);
+        assert_eq!(
+            mem.handle_db_command(Command::Generic(command)),
+            ReplyFrame::Usize(1)
+        );
+        assert!(!mem.time_series_index.keys.contains_key("raw"));
+        let command = TimeSeriesCommand::QueryIndex(filters(&["type=temp"]));
+        assert_eq!(
+            mem.handle_db_command(Command::TimeSeries(command)),
+            ReplyFrame::Array(vec![ReplyFrame::bulk(b"max".to_vec())])
+        );
+        // Destinations of rules from the deleted key are not compacted any more.
+        assert!(matches!(
+            mem.db.get("max"),
+            Some(MemObject::TimeSeries(series)) if series.source.is_none()
+        ));
+
+        let command = StringCommand::Set("max".to_owned(), b"value".to_vec());
+        mem.handle_db_command(Command::Str(command));
+        assert!(!mem.time_series_index.keys.contains_key("max"));
+
+        let command = GenericCommand::FlushDb(true);
+        mem.handle_db_command(Command::Generic(command));
+        assert!(mem.time_series_index.keys.is_empty());
+        assert!(mem.time_series_index.labels.is_empty());
+    }
+}
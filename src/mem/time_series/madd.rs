@@ -0,0 +1,70 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::time_series::rule::compact;
+use crate::mem::time_series::Sample;
+use crate::mem::util::now_millis;
+
+/// Append new samples to one or more time series.
+///
+/// Reply:
+/// - Array reply: for each sample, the timestamp of the upserted sample, or
+///   an error if key does not exist or the sample is rejected.
+pub fn madd(db: &mut Db, samples: Vec<(String, Option<i64>, f64)>) -> ReplyFrame {
+    let replies = samples
+        .into_iter()
+        .map(|(key, timestamp, value)| {
+            let timestamp = timestamp.unwrap_or_else(now_millis);
+            match db.get_mut(&key) {
+                Some(MemObject::TimeSeries(series)) => {
+                    match series.add(Sample { timestamp, value }, None) {
+                        Ok(()) => {
+                            compact(db, &key, timestamp);
+                            ReplyFrame::I64(timestamp)
+                        }
+                        Err(err) => ReplyFrame::ConstError(err),
+                    }
+                }
+                Some(_) => ReplyFrame::wrong_type_err(),
+                None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+            }
+        })
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::SeriesOptions;
+    use crate::mem::db::Db;
+    use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::madd::madd;
+
+    #[test]
+    fn test_madd() {
+        let mut db = Db::new();
+        create(&mut db, "a".to_owned(), &SeriesOptions::default());
+        let reply = madd(
+            &mut db,
+            vec![
+                ("a".to_owned(), Some(1), 1.0),
+                ("b".to_owned(), Some(1), 1.0),
+                ("a".to_owned(), Some(2), 2.0),
+            ],
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::I64(1),
+                ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+                ReplyFrame::I64(2),
+            ])
+        );
+    }
+}
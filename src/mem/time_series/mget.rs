@@ -0,0 +1,93 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::MultiSeriesOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::index::LabelIndex;
+use crate::mem::time_series::{labels_to_reply, sample_to_reply};
+
+/// Get the sample with the highest timestamp from each time series matching
+/// a filter list.
+///
+/// Options:
+/// - `WITHLABELS`: report all labels of each time series.
+/// - `SELECTED_LABELS`: report only these labels of each time series.
+///
+/// Reply:
+/// - Array reply: for each time series, an array of key, labels and
+///   the latest sample, or empty array if series is empty.
+pub fn mget(db: &Db, index: &mut LabelIndex, options: &MultiSeriesOptions) -> ReplyFrame {
+    let keys = index.query(db, &options.filters);
+    let replies = keys
+        .into_iter()
+        .filter_map(|key| match db.get(&key) {
+            Some(MemObject::TimeSeries(series)) => Some(ReplyFrame::Array(vec![
+                ReplyFrame::bulk(key.into_bytes()),
+                labels_to_reply(series, &options.labels),
+                series
+                    .last()
+                    .map_or(ReplyFrame::EmptyArray, sample_to_reply),
+            ])),
+            _ => None,
+        })
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{LabelFilter, LabelsReply, MultiSeriesOptions, SeriesOptions};
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::index::LabelIndex;
+    use crate::mem::time_series::madd::madd;
+    use crate::mem::time_series::mget::mget;
+
+    #[test]
+    fn test_mget() {
+        let mut db = Db::new();
+        let mut index = LabelIndex::new();
+        for (key, room) in [("a", "1"), ("b", "2")] {
+            let labels = vec![("room".to_owned(), room.to_owned())];
+            let options = SeriesOptions {
+                labels: Some(labels.clone()),
+                ..SeriesOptions::default()
+            };
+            create(&mut db, key.to_owned(), &options);
+            index.insert(key, &labels);
+        }
+        madd(&mut db, vec![("a".to_owned(), Some(1), 3.0)]);
+        let options = MultiSeriesOptions {
+            filters: vec![LabelFilter::try_from("room=(1,2)").unwrap()],
+            labels: LabelsReply::Selected(vec!["room".to_owned(), "type".to_owned()]),
+            group_by: None,
+        };
+        let room = |value: &[u8]| {
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"room".to_vec()),
+                    ReplyFrame::bulk(value.to_vec()),
+                ]),
+                ReplyFrame::Array(vec![ReplyFrame::bulk(b"type".to_vec()), ReplyFrame::null()]),
+            ])
+        };
+        assert_eq!(
+            mget(&db, &mut index, &options),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"a".to_vec()),
+                    room(b"1"),
+                    ReplyFrame::Array(vec![ReplyFrame::I64(1), ReplyFrame::Double(3.0)]),
+                ]),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"b".to_vec()),
+                    room(b"2"),
+                    ReplyFrame::EmptyArray,
+                ]),
+            ])
+        );
+    }
+}
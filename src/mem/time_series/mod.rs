@@ -1,3 +1,433 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::{DuplicatePolicy, LabelsReply, SeriesOptions, TimeSeriesCommand};
+use crate::cmd::Command;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::chunk::Chunk;
+use crate::mem::time_series::consts::{
+    DUPLICATE_BLOCKED_ERR, INVALID_CHUNK_SIZE_ERR, OLDER_THAN_RETENTION_ERR,
+};
+use crate::mem::time_series::rule::{CompactionRule, RuleLinks};
+use crate::mem::Mem;
+
+pub mod add;
+pub mod aggregation;
+pub mod alter;
+pub mod chunk;
+mod consts;
+pub mod create;
+pub mod create_rule;
+pub mod del;
+pub mod delete_rule;
+pub mod get;
+pub mod incr_by;
+pub mod index;
+pub mod madd;
+pub mod mget;
+pub mod mrange;
+pub mod query_index;
+pub mod range;
+pub mod rule;
+
+/// Default memory size of each data chunk, in bytes.
+pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// Time series stored in compressed chunks, sorted by timestamp.
+#[derive(Debug, Clone)]
+pub struct TimeSeriesObject {
+    /// Maximum age of samples, 0 means samples never expire.
+    retention: i64,
+    chunk_size: usize,
+    duplicate_policy: DuplicatePolicy,
+    labels: Vec<(String, String)>,
+    chunks: Vec<Chunk>,
+
+    /// Compaction rules whose source is this time series.
+    rules: Vec<CompactionRule>,
+    /// Source key if this time series is destination of a compaction rule.
+    source: Option<String>,
+}
+
+impl Mem {
+    pub fn handle_time_series_command(&mut self, command: TimeSeriesCommand) -> ReplyFrame {
+        match command {
+            TimeSeriesCommand::Add(options) => {
+                let reply = add::add(&mut self.db, &options);
+                self.index_time_series(&options.key);
+                reply
+            }
+            TimeSeriesCommand::Alter(key, options) => {
+                let reply = alter::alter(&mut self.db, &key, *options);
+                self.index_time_series(&key);
+                reply
+            }
+            TimeSeriesCommand::Create(key, options) => {
+                let reply = create::create(&mut self.db, key.clone(), &options);
+                self.index_time_series(&key);
+                reply
+            }
+            TimeSeriesCommand::CreateRule(source, dest, aggregator, bucket_duration, align) => {
+                create_rule::create_rule(
+                    &mut self.db,
+                    &source,
+                    &dest,
+                    aggregator,
+                    bucket_duration,
+                    align,
+                )
+            }
+            TimeSeriesCommand::DecrBy(options) => {
+                let key = options.key.clone();
+                let reply = incr_by::decr_by(&mut self.db, *options);
+                self.index_time_series(&key);
+                reply
+            }
+            TimeSeriesCommand::Del(key, from, to) => del::del(&mut self.db, &key, from, to),
+            TimeSeriesCommand::DeleteRule(source, dest) => {
+                delete_rule::delete_rule(&mut self.db, &source, &dest)
+            }
+            TimeSeriesCommand::Get(key) => get::get(&self.db, &key),
+            TimeSeriesCommand::IncrBy(options) => {
+                let reply = incr_by::incr_by(&mut self.db, &options);
+                self.index_time_series(&options.key);
+                reply
+            }
+            TimeSeriesCommand::MAdd(samples) => madd::madd(&mut self.db, samples),
+            TimeSeriesCommand::MGet(options) => {
+                mget::mget(&self.db, &mut self.time_series_index, &options)
+            }
+            TimeSeriesCommand::MRange(options, multi) => {
+                mrange::mrange(&self.db, &mut self.time_series_index, &options, &multi)
+            }
+            TimeSeriesCommand::MRevRange(options, multi) => {
+                mrange::mrev_range(&self.db, &mut self.time_series_index, &options, &multi)
+            }
+            TimeSeriesCommand::QueryIndex(filters) => {
+                query_index::query_index(&self.db, &mut self.time_series_index, &filters)
+            }
+            TimeSeriesCommand::Range(key, options) => range::range(&self.db, &key, &options),
+            TimeSeriesCommand::RevRange(key, options) => range::rev_range(&self.db, &key, &options),
+        }
+    }
+
+    /// Returns time series which may be removed or overwritten by command.
+    pub(crate) fn overwritten_time_series(&self, command: &Command) -> Vec<RuleLinks> {
+        command
+            .overwritten_keys()
+            .into_iter()
+            .filter_map(|key| rule::rule_links(&self.db, key))
+            .collect()
+    }
+
+    /// Remove index entries and compaction rules of time series which are not
+    /// stored at their keys any more.
+    pub(crate) fn unlink_time_series(&mut self, time_series: Vec<RuleLinks>) {
+        for links in time_series {
+            if !matches!(self.db.get(links.key()), Some(MemObject::TimeSeries(_))) {
+                self.time_series_index.remove(links.key());
+                rule::unlink_rules(&mut self.db, &links);
+            }
+        }
+    }
+}
+
+/// Check that options are valid to create a time series.
+fn check_options(options: &SeriesOptions) -> Result<(), ReplyFrame> {
+    match options.chunk_size {
+        Some(size) if size % 8 != 0 || !(48..=1_048_576).contains(&size) => {
+            Err(ReplyFrame::ConstError(INVALID_CHUNK_SIZE_ERR))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns time series at key, creates a new one with `options` if key does not exist.
+fn get_or_create<'a>(
+    db: &'a mut Db,
+    key: &str,
+    options: &SeriesOptions,
+) -> Result<&'a mut TimeSeriesObject, ReplyFrame> {
+    if !db.contains_key(key) {
+        check_options(options)?;
+        db.insert(
+            key.to_owned(),
+            MemObject::TimeSeries(TimeSeriesObject::new(options)),
+        );
+    }
+    match db.get_mut(key) {
+        Some(MemObject::TimeSeries(series)) => Ok(series),
+        _ => Err(ReplyFrame::wrong_type_err()),
+    }
+}
+
+/// Returns reply of a sample, with timestamp and value.
+#[must_use]
+fn sample_to_reply(sample: Sample) -> ReplyFrame {
+    ReplyFrame::Array(vec![
+        ReplyFrame::I64(sample.timestamp),
+        ReplyFrame::Double(sample.value),
+    ])
+}
+
+/// Returns reply of labels of time series, as an array of label-value pairs.
+///
+/// Value of an absent selected label is null.
+fn labels_to_reply(series: &TimeSeriesObject, labels: &LabelsReply) -> ReplyFrame {
+    let pair = |label: &str, value: Option<&str>| {
+        ReplyFrame::Array(vec![
+            ReplyFrame::bulk(label.as_bytes().to_vec()),
+            value.map_or_else(ReplyFrame::null, |value| {
+                ReplyFrame::bulk(value.as_bytes().to_vec())
+            }),
+        ])
+    };
+    match labels {
+        LabelsReply::Empty => ReplyFrame::EmptyArray,
+        LabelsReply::All => ReplyFrame::Array(
+            series
+                .labels
+                .iter()
+                .map(|(label, value)| pair(label, Some(value)))
+                .collect(),
+        ),
+        LabelsReply::Selected(names) => ReplyFrame::Array(
+            names
+                .iter()
+                .map(|name| pair(name, series.label(name)))
+                .collect(),
+        ),
+    }
+}
+
+impl TimeSeriesObject {
+    #[must_use]
+    pub fn new(options: &SeriesOptions) -> Self {
+        Self {
+            retention: options.retention.unwrap_or(0),
+            chunk_size: options.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+            duplicate_policy: options.duplicate_policy.unwrap_or(DuplicatePolicy::Block),
+            labels: options.labels.clone().unwrap_or_default(),
+            chunks: Vec::new(),
+            rules: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Update options which are specified.
+    pub fn alter(&mut self, options: SeriesOptions) {
+        if let Some(retention) = options.retention {
+            self.retention = retention;
+        }
+        if let Some(chunk_size) = options.chunk_size {
+            self.chunk_size = chunk_size;
+        }
+        if let Some(policy) = options.duplicate_policy {
+            self.duplicate_policy = policy;
+        }
+        if let Some(labels) = options.labels {
+            self.labels = labels;
+        }
+        self.trim();
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    /// Returns value of label.
+    #[must_use]
+    pub fn label(&self, name: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(label, _value)| label == name)
+            .map(|(_label, value)| value.as_str())
+    }
+
+    /// Returns total number of samples.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.chunks.iter().map(Chunk::len).sum()
+    }
+
+    #[must_use]
+    pub fn last(&self) -> Option<Sample> {
+        self.chunks.last().and_then(Chunk::last_sample)
+    }
+
+    /// Returns the minimum timestamp of samples within retention period.
+    fn min_timestamp(&self) -> i64 {
+        match self.last() {
+            Some(last) if self.retention > 0 => last.timestamp - self.retention,
+            _ => i64::MIN,
+        }
+    }
+
+    /// Add a sample, or update the existing sample with the same timestamp
+    /// according to duplicate policy.
+    ///
+    /// `on_duplicate` overrides duplicate policy of this time series.
+    pub fn add(
+        &mut self,
+        sample: Sample,
+        on_duplicate: Option<DuplicatePolicy>,
+    ) -> Result<(), &'static str> {
+        if let Some(last) = self.last() {
+            if sample.timestamp < self.min_timestamp() {
+                return Err(OLDER_THAN_RETENTION_ERR);
+            }
+            if sample.timestamp <= last.timestamp {
+                let policy = on_duplicate.unwrap_or(self.duplicate_policy);
+                return self.upsert(sample, policy);
+            }
+        }
+        match self.chunks.last_mut() {
+            Some(chunk) if chunk.size() < self.chunk_size => chunk.append(sample),
+            _ => {
+                let mut chunk = Chunk::new();
+                chunk.append(sample);
+                self.chunks.push(chunk);
+            }
+        }
+        self.trim();
+        Ok(())
+    }
+
+    /// Insert or update a sample which is not newer than the last sample.
+    fn upsert(&mut self, sample: Sample, policy: DuplicatePolicy) -> Result<(), &'static str> {
+        let index = self
+            .chunks
+            .iter()
+            .position(|chunk| chunk.last_timestamp() >= sample.timestamp)
+            .unwrap_or(self.chunks.len() - 1);
+        let mut samples = self.chunks[index].samples();
+        match samples.binary_search_by_key(&sample.timestamp, |old| old.timestamp) {
+            Ok(pos) => {
+                let old_value = samples[pos].value;
+                samples[pos].value = match policy {
+                    DuplicatePolicy::Block => return Err(DUPLICATE_BLOCKED_ERR),
+                    DuplicatePolicy::First => return Ok(()),
+                    DuplicatePolicy::Last => sample.value,
+                    DuplicatePolicy::Min => old_value.min(sample.value),
+                    DuplicatePolicy::Max => old_value.max(sample.value),
+                    DuplicatePolicy::Sum => old_value + sample.value,
+                };
+            }
+            Err(pos) => samples.insert(pos, sample),
+        }
+        self.chunks[index] = Chunk::from_samples(&samples);
+        Ok(())
+    }
+
+    /// Remove chunks which are entirely older than retention period.
+    fn trim(&mut self) {
+        let min_timestamp = self.min_timestamp();
+        self.chunks
+            .retain(|chunk| chunk.last_timestamp() >= min_timestamp);
+    }
+
+    /// Returns samples with timestamp in `[from, to]`, within retention period.
+    #[must_use]
+    pub fn range(&self, from: i64, to: i64) -> Vec<Sample> {
+        let from = from.max(self.min_timestamp());
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.last_timestamp() >= from && chunk.first_timestamp() <= to)
+            .flat_map(Chunk::samples)
+            .filter(|sample| sample.timestamp >= from && sample.timestamp <= to)
+            .collect()
+    }
+
+    /// Delete samples with timestamp in `[from, to]`, returns number of samples deleted.
+    ///
+    /// Samples older than retention period are not visible, they are not counted but
+    /// removed too, so that they do not show up again if the last sample is deleted.
+    pub fn delete_range(&mut self, from: i64, to: i64) -> usize {
+        let min_timestamp = self.min_timestamp();
+        let from = from.max(min_timestamp);
+        let mut deleted = 0;
+        for chunk in &mut self.chunks {
+            let has_expired = chunk.first_timestamp() < min_timestamp;
+            if !has_expired && (chunk.last_timestamp() < from || chunk.first_timestamp() > to) {
+                continue;
+            }
+            let samples = chunk.samples();
+            let kept: Vec<Sample> = samples
+                .iter()
+                .filter(|sample| sample.timestamp >= min_timestamp)
+                .filter(|sample| sample.timestamp < from || sample.timestamp > to)
+                .copied()
+                .collect();
+            deleted += samples
+                .iter()
+                .filter(|sample| sample.timestamp >= from && sample.timestamp <= to)
+                .count();
+            *chunk = Chunk::from_samples(&kept);
+        }
+        self.chunks.retain(|chunk| !chunk.is_empty());
+        deleted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::time_series::{DuplicatePolicy, SeriesOptions};
+    use crate::mem::time_series::consts::{DUPLICATE_BLOCKED_ERR, OLDER_THAN_RETENTION_ERR};
+    use crate::mem::time_series::{Sample, TimeSeriesObject};
+
+    const fn sample(timestamp: i64, value: f64) -> Sample {
+        Sample { timestamp, value }
+    }
+
+    #[test]
+    fn test_time_series_object() {
+        let options = SeriesOptions {
+            retention: Some(100),
+            chunk_size: Some(48),
+            ..SeriesOptions::default()
+        };
+        let mut series = TimeSeriesObject::new(&options);
+        for timestamp in 0..50 {
+            #[allow(clippy::cast_precision_loss)]
+            let value = (timestamp * 2) as f64 / 2.0;
+            assert!(series.add(sample(timestamp * 2, value), None).is_ok());
+        }
+        assert!(series.chunks.len() > 1);
+        assert_eq!(series.len(), 50);
+        assert_eq!(
+            series.add(sample(10, 2.0), None),
+            Err(DUPLICATE_BLOCKED_ERR)
+        );
+        assert!(series.add(sample(11, 2.0), None).is_ok());
+        assert!(series
+            .add(sample(12, 2.0), Some(DuplicatePolicy::Sum))
+            .is_ok());
+        assert_eq!(
+            series.range(10, 12),
+            vec![sample(10, 5.0), sample(11, 2.0), sample(12, 8.0)]
+        );
+        assert_eq!(series.delete_range(11, 20), 6);
+        assert_eq!(series.len(), 45);
+
+        // Samples older than retention period are removed.
+        assert!(series.add(sample(1000, 1.0), None).is_ok());
+        assert_eq!(series.range(0, i64::MAX), vec![sample(1000, 1.0)]);
+        assert_eq!(
+            series.add(sample(899, 1.0), None),
+            Err(OLDER_THAN_RETENTION_ERR)
+        );
+        assert_eq!(series.delete_range(0, 1500), 1);
+        assert!(series.range(i64::MIN, i64::MAX).is_empty());
+    }
+}
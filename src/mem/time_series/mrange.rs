@@ -0,0 +1,230 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::BTreeMap;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::{Aggregator, MultiSeriesOptions, RangeOptions};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::aggregation::aggregate;
+use crate::mem::time_series::index::LabelIndex;
+use crate::mem::time_series::range::range_samples;
+use crate::mem::time_series::{labels_to_reply, sample_to_reply, Sample, TimeSeriesObject};
+
+/// Query a range across multiple time series matching a filter list,
+/// in forward direction.
+///
+/// Options:
+/// - `WITHLABELS`: report all labels of each time series.
+/// - `SELECTED_LABELS`: report only these labels of each time series.
+/// - `GROUPBY label REDUCE reducer`: group series by value of label, samples
+///   of the same timestamp in a group are reduced into one sample.
+///
+/// Reply:
+/// - Array reply: for each time series or group, an array of key, labels and
+///   samples. Key of a group is `label=value`, and its labels contain the reducer
+///   and source keys.
+pub fn mrange(
+    db: &Db,
+    index: &mut LabelIndex,
+    options: &RangeOptions,
+    multi: &MultiSeriesOptions,
+) -> ReplyFrame {
+    query(db, index, options, multi, false)
+}
+
+/// Query a range across multiple time series matching a filter list,
+/// in reverse direction.
+///
+/// Reply:
+/// - Array reply: for each time series or group, an array of key, labels and
+///   samples from the latest one.
+pub fn mrev_range(
+    db: &Db,
+    index: &mut LabelIndex,
+    options: &RangeOptions,
+    multi: &MultiSeriesOptions,
+) -> ReplyFrame {
+    query(db, index, options, multi, true)
+}
+
+fn query(
+    db: &Db,
+    index: &mut LabelIndex,
+    options: &RangeOptions,
+    multi: &MultiSeriesOptions,
+    reverse: bool,
+) -> ReplyFrame {
+    let series_list: Vec<(String, &TimeSeriesObject)> = index
+        .query(db, &multi.filters)
+        .into_iter()
+        .filter_map(|key| match db.get(&key) {
+            Some(MemObject::TimeSeries(series)) => Some((key, series)),
+            _ => None,
+        })
+        .collect();
+
+    if let Some((label, reducer)) = &multi.group_by {
+        return group_by(&series_list, options, label, *reducer, reverse);
+    }
+    let replies = series_list
+        .into_iter()
+        .map(|(key, series)| {
+            let samples = range_samples(series, options, reverse);
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(key.into_bytes()),
+                labels_to_reply(series, &multi.labels),
+                samples_to_reply(samples),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+/// Group series by value of label, and reduce samples with the same timestamp
+/// in each group.
+///
+/// Series without the label are ignored.
+fn group_by(
+    series_list: &[(String, &TimeSeriesObject)],
+    options: &RangeOptions,
+    label: &str,
+    reducer: Aggregator,
+    reverse: bool,
+) -> ReplyFrame {
+    let mut groups: BTreeMap<&str, Vec<(&str, &TimeSeriesObject)>> = BTreeMap::new();
+    for (key, series) in series_list {
+        if let Some(value) = series.label(label) {
+            groups.entry(value).or_default().push((key, series));
+        }
+    }
+
+    let replies = groups
+        .into_iter()
+        .map(|(value, group)| {
+            let mut timestamps: BTreeMap<i64, Vec<Sample>> = BTreeMap::new();
+            for (_key, series) in &group {
+                for sample in range_samples(series, options, reverse) {
+                    timestamps.entry(sample.timestamp).or_default().push(sample);
+                }
+            }
+            let mut samples: Vec<Sample> = timestamps
+                .into_iter()
+                .map(|(timestamp, samples)| Sample {
+                    timestamp,
+                    value: aggregate(reducer, &samples),
+                })
+                .collect();
+            if reverse {
+                samples.reverse();
+            }
+            let sources: Vec<&str> = group.iter().map(|(key, _series)| *key).collect();
+            let labels = [
+                (label, value.to_owned()),
+                ("__reducer__", reducer.name().to_owned()),
+                ("__source__", sources.join(",")),
+            ];
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(format!("{label}={value}").into_bytes()),
+                ReplyFrame::Array(
+                    labels
+                        .into_iter()
+                        .map(|(name, value)| {
+                            ReplyFrame::Array(vec![
+                                ReplyFrame::bulk(name.as_bytes().to_vec()),
+                                ReplyFrame::bulk(value.into_bytes()),
+                            ])
+                        })
+                        .collect(),
+                ),
+                samples_to_reply(samples),
+            ])
+        })
+        .collect();
+    ReplyFrame::Array(replies)
+}
+
+fn samples_to_reply(samples: Vec<Sample>) -> ReplyFrame {
+    ReplyFrame::Array(samples.into_iter().map(sample_to_reply).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{
+        Aggregator, LabelFilter, MultiSeriesOptions, RangeOptions, SeriesOptions,
+    };
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::index::LabelIndex;
+    use crate::mem::time_series::madd::madd;
+    use crate::mem::time_series::mrange::{mrange, mrev_range};
+
+    fn sample(timestamp: i64, value: f64) -> ReplyFrame {
+        ReplyFrame::Array(vec![ReplyFrame::I64(timestamp), ReplyFrame::Double(value)])
+    }
+
+    #[test]
+    fn test_mrange() {
+        let mut db = Db::new();
+        let mut index = LabelIndex::new();
+        for (key, room) in [("a", "1"), ("b", "1"), ("c", "2")] {
+            let labels = vec![
+                ("room".to_owned(), room.to_owned()),
+                ("type".to_owned(), "temp".to_owned()),
+            ];
+            let options = SeriesOptions {
+                labels: Some(labels.clone()),
+                ..SeriesOptions::default()
+            };
+            create(&mut db, key.to_owned(), &options);
+            index.insert(key, &labels);
+        }
+        madd(
+            &mut db,
+            vec![
+                ("a".to_owned(), Some(1), 1.0),
+                ("a".to_owned(), Some(2), 2.0),
+                ("b".to_owned(), Some(2), 4.0),
+                ("c".to_owned(), Some(3), 8.0),
+            ],
+        );
+
+        let options = RangeOptions::default();
+        let mut multi = MultiSeriesOptions {
+            filters: vec![LabelFilter::try_from("room=1").unwrap()],
+            ..MultiSeriesOptions::default()
+        };
+        assert_eq!(
+            mrev_range(&db, &mut index, &options, &multi),
+            ReplyFrame::Array(vec![
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"a".to_vec()),
+                    ReplyFrame::EmptyArray,
+                    ReplyFrame::Array(vec![sample(2, 2.0), sample(1, 1.0)]),
+                ]),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::bulk(b"b".to_vec()),
+                    ReplyFrame::EmptyArray,
+                    ReplyFrame::Array(vec![sample(2, 4.0)]),
+                ]),
+            ])
+        );
+
+        multi.filters = vec![LabelFilter::try_from("type=temp").unwrap()];
+        multi.group_by = Some(("room".to_owned(), Aggregator::Sum));
+        let ReplyFrame::Array(groups) = mrange(&db, &mut index, &options, &multi) else {
+            panic!("expected array");
+        };
+        assert_eq!(groups.len(), 2);
+        let ReplyFrame::Array(group) = &groups[0] else {
+            panic!("expected array");
+        };
+        assert_eq!(group[0], ReplyFrame::bulk(b"room=1".to_vec()));
+        assert_eq!(
+            group[2],
+            ReplyFrame::Array(vec![sample(1, 1.0), sample(2, 6.0)])
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::LabelFilter;
+use crate::mem::db::Db;
+use crate::mem::time_series::index::LabelIndex;
+
+/// Get all time series keys matching a filter list.
+///
+/// Filters:
+/// - `label=value`: label equals value.
+/// - `label!=value`: label does not equal value.
+/// - `label=`: key does not have the label.
+/// - `label!=`: key has the label.
+/// - `label=(v1,v2,...)`: label equals one of values.
+/// - `label!=(v1,v2,...)`: label does not equal any of values.
+///
+/// Reply:
+/// - Array reply: sorted keys of time series matching all filters.
+pub fn query_index(db: &Db, index: &mut LabelIndex, filters: &[LabelFilter]) -> ReplyFrame {
+    let keys = index.query(db, filters);
+    ReplyFrame::Array(
+        keys.into_iter()
+            .map(|key| ReplyFrame::bulk(key.into_bytes()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{LabelFilter, SeriesOptions};
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::index::LabelIndex;
+    use crate::mem::time_series::query_index::query_index;
+
+    #[test]
+    fn test_query_index() {
+        let mut db = Db::new();
+        let mut index = LabelIndex::new();
+        let labels = vec![("type".to_owned(), "temp".to_owned())];
+        let options = SeriesOptions {
+            labels: Some(labels.clone()),
+            ..SeriesOptions::default()
+        };
+        create(&mut db, "temp".to_owned(), &options);
+        index.insert("temp", &labels);
+        let filters = [LabelFilter::try_from("type=temp").unwrap()];
+        assert_eq!(
+            query_index(&db, &mut index, &filters),
+            ReplyFrame::Array(vec![ReplyFrame::bulk(b"temp".to_vec())])
+        );
+    }
+}
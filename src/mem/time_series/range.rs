@@ -0,0 +1,144 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::time_series::{Align, RangeOptions};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::aggregation::aggregate_buckets;
+use crate::mem::time_series::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::time_series::{sample_to_reply, Sample, TimeSeriesObject};
+
+/// Query a range in forward direction.
+///
+/// Samples are filtered by `FILTER_BY_TS` and `FILTER_BY_VALUE` options first,
+/// then aggregated into buckets if AGGREGATION is specified.
+///
+/// Options:
+/// - `FILTER_BY_TS`: only samples with these timestamps are reported.
+/// - `FILTER_BY_VALUE`: only samples with value in `[min, max]` are reported.
+/// - COUNT: the maximum number of samples or buckets to report.
+/// - ALIGN: start timestamp of buckets, may be `start`, `end` or a timestamp.
+/// - AGGREGATION: aggregate samples into time buckets.
+///
+/// Reply:
+/// - Array reply: timestamp and value of each sample or bucket.
+/// - Error reply: if key does not exist.
+pub fn range(db: &Db, key: &str, options: &RangeOptions) -> ReplyFrame {
+    query(db, key, options, false)
+}
+
+/// Query a range in reverse direction.
+///
+/// Reply:
+/// - Array reply: timestamp and value of each sample or bucket, from the latest one.
+/// - Error reply: if key does not exist.
+pub fn rev_range(db: &Db, key: &str, options: &RangeOptions) -> ReplyFrame {
+    query(db, key, options, true)
+}
+
+fn query(db: &Db, key: &str, options: &RangeOptions, reverse: bool) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::TimeSeries(series)) => {
+            let samples = range_samples(series, options, reverse);
+            ReplyFrame::Array(samples.into_iter().map(sample_to_reply).collect())
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+/// Returns samples or aggregated buckets of time series in range.
+#[must_use]
+pub fn range_samples(
+    series: &TimeSeriesObject,
+    options: &RangeOptions,
+    reverse: bool,
+) -> Vec<Sample> {
+    let from = options.from.unwrap_or(0);
+    let to = options
+        .to
+        .unwrap_or_else(|| series.last().map_or(0, |last| last.timestamp));
+    let mut samples = series.range(from, to);
+    if !options.filter_by_ts.is_empty() {
+        samples.retain(|sample| options.filter_by_ts.contains(&sample.timestamp));
+    }
+    if let Some((min, max)) = options.filter_by_value {
+        samples.retain(|sample| sample.value >= min && sample.value <= max);
+    }
+    if let Some(aggregation) = &options.aggregation {
+        let align = match options.align {
+            Align::Zero => 0,
+            Align::Start => from,
+            Align::End => to,
+            Align::Timestamp(timestamp) => timestamp,
+        };
+        samples = aggregate_buckets(&samples, aggregation, align);
+    }
+    if reverse {
+        samples.reverse();
+    }
+    if let Some(count) = options.count {
+        samples.truncate(count);
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::time_series::{
+        Aggregation, Aggregator, Align, BucketTimestamp, RangeOptions, SeriesOptions,
+    };
+    use crate::mem::db::Db;
+    use crate::mem::time_series::create::create;
+    use crate::mem::time_series::madd::madd;
+    use crate::mem::time_series::range::{range, rev_range};
+
+    fn sample(timestamp: i64, value: f64) -> ReplyFrame {
+        ReplyFrame::Array(vec![ReplyFrame::I64(timestamp), ReplyFrame::Double(value)])
+    }
+
+    #[test]
+    fn test_range() {
+        let mut db = Db::new();
+        create(&mut db, "temp".to_owned(), &SeriesOptions::default());
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (0..10)
+            .map(|timestamp| ("temp".to_owned(), Some(timestamp * 10), timestamp as f64))
+            .collect();
+        madd(&mut db, samples);
+
+        let mut options = RangeOptions {
+            from: Some(20),
+            to: Some(50),
+            ..RangeOptions::default()
+        };
+        let ReplyFrame::Array(replies) = range(&db, "temp", &options) else {
+            panic!("expected array");
+        };
+        assert_eq!(replies.len(), 4);
+        options.filter_by_value = Some((3.0, 10.0));
+        options.count = Some(2);
+        assert_eq!(
+            rev_range(&db, "temp", &options),
+            ReplyFrame::Array(vec![sample(50, 5.0), sample(40, 4.0)])
+        );
+
+        let options = RangeOptions {
+            align: Align::Start,
+            from: Some(5),
+            aggregation: Some(Aggregation {
+                aggregator: Aggregator::Max,
+                bucket_duration: 30,
+                bucket_timestamp: BucketTimestamp::Start,
+                empty: false,
+            }),
+            ..RangeOptions::default()
+        };
+        assert_eq!(
+            range(&db, "temp", &options),
+            ReplyFrame::Array(vec![sample(5, 3.0), sample(35, 6.0), sample(65, 9.0),])
+        );
+    }
+}
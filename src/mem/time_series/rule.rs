@@ -0,0 +1,174 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Compaction rules which downsample a source time series into destination series.
+//!
+//! Each rule tracks the bucket of the latest sample in source series. When a
+//! sample of a newer bucket is added, samples of the previous bucket are
+//! aggregated and added to the destination series.
+//!
+//! Compacted buckets are aggregated again if their samples are updated or deleted later.
+
+use crate::cmd::time_series::{Aggregator, DuplicatePolicy};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::time_series::aggregation::{aggregate, bucket_start};
+use crate::mem::time_series::Sample;
+
+#[derive(Debug, Clone)]
+pub struct CompactionRule {
+    pub dest: String,
+    pub aggregator: Aggregator,
+    pub bucket_duration: i64,
+    /// Buckets start at this timestamp plus a multiple of bucket duration.
+    pub align: i64,
+    /// Start timestamp of the bucket not compacted yet.
+    pub current: Option<i64>,
+}
+
+impl CompactionRule {
+    #[must_use]
+    pub const fn new(
+        dest: String,
+        aggregator: Aggregator,
+        bucket_duration: i64,
+        align: i64,
+    ) -> Self {
+        Self {
+            dest,
+            aggregator,
+            bucket_duration,
+            align,
+            current: None,
+        }
+    }
+}
+
+/// Keys linked to a time series by compaction rules.
+#[derive(Debug)]
+pub struct RuleLinks {
+    key: String,
+    source: Option<String>,
+    dests: Vec<String>,
+}
+
+/// Returns keys linked to the time series at key, None if key is not a time series.
+#[must_use]
+pub fn rule_links(db: &Db, key: &str) -> Option<RuleLinks> {
+    let Some(MemObject::TimeSeries(series)) = db.get(key) else {
+        return None;
+    };
+    Some(RuleLinks {
+        key: key.to_owned(),
+        source: series.source.clone(),
+        dests: series.rules.iter().map(|rule| rule.dest.clone()).collect(),
+    })
+}
+
+impl RuleLinks {
+    #[must_use]
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// Drop compaction rules from or to a time series which is removed.
+pub fn unlink_rules(db: &mut Db, links: &RuleLinks) {
+    for dest in &links.dests {
+        if let Some(MemObject::TimeSeries(dest_series)) = db.get_mut(dest) {
+            if dest_series.source.as_ref() == Some(&links.key) {
+                dest_series.source = None;
+            }
+        }
+    }
+    if let Some(source) = &links.source {
+        if let Some(MemObject::TimeSeries(source_series)) = db.get_mut(source) {
+            source_series.rules.retain(|rule| rule.dest != links.key);
+        }
+    }
+}
+
+/// Apply compaction rules of time series at key, after a sample with `timestamp`
+/// is added or updated.
+pub fn compact(db: &mut Db, key: &str, timestamp: i64) {
+    let Some(MemObject::TimeSeries(series)) = db.get_mut(key) else {
+        return;
+    };
+    let mut buckets = Vec::new();
+    for (index, rule) in series.rules.iter_mut().enumerate() {
+        let start = bucket_start(timestamp, rule.bucket_duration, rule.align);
+        match rule.current {
+            Some(current) if current < start => {
+                buckets.push((index, current));
+                rule.current = Some(start);
+            }
+            // Sample of a compacted bucket is changed.
+            Some(current) if start < current => buckets.push((index, start)),
+            Some(_) => {}
+            None => rule.current = Some(start),
+        }
+    }
+    update_buckets(db, key, &buckets);
+}
+
+/// Apply compaction rules of time series at key, after samples in `[from, to]`
+/// are deleted.
+pub fn compact_deleted(db: &mut Db, key: &str, from: i64, to: i64) {
+    let Some(MemObject::TimeSeries(series)) = db.get(key) else {
+        return;
+    };
+    let mut buckets = Vec::new();
+    for (index, rule) in series.rules.iter().enumerate() {
+        let Some(current) = rule.current else {
+            continue;
+        };
+        if from >= current {
+            continue;
+        }
+        let Some(MemObject::TimeSeries(dest_series)) = db.get(&rule.dest) else {
+            continue;
+        };
+        // Compacted samples whose bucket overlaps with the deleted range.
+        let first_start = from.saturating_sub(rule.bucket_duration - 1);
+        buckets.extend(
+            dest_series
+                .range(first_start, to.min(current - 1))
+                .into_iter()
+                .map(|sample| (index, sample.timestamp)),
+        );
+    }
+    update_buckets(db, key, &buckets);
+}
+
+/// Aggregate samples in each `(rule_index, bucket_start)` bucket into destination
+/// series of the rule.
+///
+/// Compacted sample is removed if no samples are left in its bucket.
+fn update_buckets(db: &mut Db, key: &str, buckets: &[(usize, i64)]) {
+    if buckets.is_empty() {
+        return;
+    }
+    let Some(MemObject::TimeSeries(series)) = db.get(key) else {
+        return;
+    };
+    let compacted: Vec<(String, i64, Option<f64>)> = buckets
+        .iter()
+        .map(|&(index, start)| {
+            let rule = &series.rules[index];
+            let samples = series.range(start, start.saturating_add(rule.bucket_duration - 1));
+            let value = (!samples.is_empty()).then(|| aggregate(rule.aggregator, &samples));
+            (rule.dest.clone(), start, value)
+        })
+        .collect();
+    for (dest, timestamp, value) in compacted {
+        if let Some(MemObject::TimeSeries(dest_series)) = db.get_mut(&dest) {
+            if let Some(value) = value {
+                // Compacted sample is always accepted.
+                let _ = dest_series.add(Sample { timestamp, value }, Some(DuplicatePolicy::Last));
+            } else {
+                dest_series.delete_range(timestamp, timestamp);
+            }
+        }
+    }
+}
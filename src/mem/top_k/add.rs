@@ -0,0 +1,51 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::top_k::incr_items;
+
+/// Adds one or more items to a Top-K sketch.
+///
+/// Reply:
+/// - Array reply: for each item, the item expelled from the top list if any,
+///   or null otherwise.
+/// - Error reply: if key does not exist.
+pub fn add(db: &mut Db, key: &str, items: &[String]) -> ReplyFrame {
+    incr_items(db, key, items.iter().map(|item| (item, 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::add::add;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let items = ["a".to_owned(), "b".to_owned()];
+        assert!(matches!(
+            add(&mut db, "urls", &items),
+            ReplyFrame::ConstError(_)
+        ));
+        let options = ReserveOptions {
+            k: 1,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        };
+        reserve(&mut db, "urls".to_owned(), &options);
+        assert_eq!(
+            add(&mut db, "urls", &items),
+            ReplyFrame::Array(vec![ReplyFrame::null(), ReplyFrame::null()])
+        );
+        assert_eq!(
+            add(&mut db, "urls", &items[1..]),
+            ReplyFrame::Array(vec![ReplyFrame::bulk(b"a".to_vec())])
+        );
+    }
+}
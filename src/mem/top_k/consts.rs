@@ -0,0 +1,6 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const KEY_NOT_EXIST_ERR: &str = "ERR TopK: key does not exist";
+pub const KEY_EXISTS_ERR: &str = "ERR TopK: key already exists";
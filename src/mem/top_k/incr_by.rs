@@ -0,0 +1,57 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::top_k::incr_items;
+
+/// Increase the score of items in a Top-K sketch by increments.
+///
+/// Reply:
+/// - Array reply: for each item, the item expelled from the top list if any,
+///   or null otherwise.
+/// - Error reply: if key does not exist.
+pub fn incr_by(db: &mut Db, key: &str, items: &[(String, u64)]) -> ReplyFrame {
+    incr_items(
+        db,
+        key,
+        items.iter().map(|(item, increment)| (item, *increment)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::incr_by::incr_by;
+    use crate::mem::top_k::list::list;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_incr_by() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            k: 2,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        };
+        reserve(&mut db, "urls".to_owned(), &options);
+        let items = [("a".to_owned(), 5), ("b".to_owned(), 9)];
+        assert_eq!(
+            incr_by(&mut db, "urls", &items),
+            ReplyFrame::Array(vec![ReplyFrame::null(), ReplyFrame::null()])
+        );
+        assert_eq!(
+            list(&db, "urls", true),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"b".to_vec()),
+                ReplyFrame::I64(9),
+                ReplyFrame::bulk(b"a".to_vec()),
+                ReplyFrame::I64(5),
+            ])
+        );
+    }
+}
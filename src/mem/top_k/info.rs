@@ -0,0 +1,55 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::top_k::consts::KEY_NOT_EXIST_ERR;
+
+/// Returns number of required items (k), width, depth, and decay values.
+///
+/// Reply:
+/// - Array reply: pairs of field names and values.
+/// - Error reply: if key does not exist.
+pub fn info(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::TopK(top_k)) => ReplyFrame::Array(vec![
+            ReplyFrame::ConstSimple("k"),
+            ReplyFrame::Usize(top_k.k()),
+            ReplyFrame::ConstSimple("width"),
+            ReplyFrame::Usize(top_k.width()),
+            ReplyFrame::ConstSimple("depth"),
+            ReplyFrame::Usize(top_k.depth()),
+            ReplyFrame::ConstSimple("decay"),
+            ReplyFrame::Double(top_k.decay()),
+        ]),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::info::info;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_info() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            k: 5,
+            width: 20,
+            depth: 4,
+            decay: 0.8,
+        };
+        reserve(&mut db, "urls".to_owned(), &options);
+        let ReplyFrame::Array(fields) = info(&db, "urls") else {
+            panic!("expected array");
+        };
+        assert_eq!(fields[1], ReplyFrame::Usize(5));
+        assert_eq!(fields[7], ReplyFrame::Double(0.8));
+    }
+}
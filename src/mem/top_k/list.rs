@@ -0,0 +1,68 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::top_k::consts::KEY_NOT_EXIST_ERR;
+
+/// Return full list of items in Top-K list, sorted by count in descending order.
+///
+/// Options:
+/// - WITHCOUNT: count of each item is reported too.
+///
+/// Reply:
+/// - Array reply: items in top list, followed by their counts if `WITHCOUNT`
+///   is specified.
+/// - Error reply: if key does not exist.
+pub fn list(db: &Db, key: &str, with_count: bool) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::TopK(top_k)) => {
+            let mut replies = Vec::new();
+            for (item, count) in top_k.list() {
+                replies.push(ReplyFrame::bulk(item.as_bytes().to_vec()));
+                if with_count {
+                    replies.push(ReplyFrame::I64(i64::try_from(count).unwrap_or(i64::MAX)));
+                }
+            }
+            ReplyFrame::Array(replies)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::add::add;
+    use crate::mem::top_k::list::list;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_list() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            k: 3,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        };
+        reserve(&mut db, "urls".to_owned(), &options);
+        assert_eq!(list(&db, "urls", false), ReplyFrame::Array(vec![]));
+        add(
+            &mut db,
+            "urls",
+            &["a".to_owned(), "b".to_owned(), "b".to_owned()],
+        );
+        assert_eq!(
+            list(&db, "urls", false),
+            ReplyFrame::Array(vec![
+                ReplyFrame::bulk(b"b".to_vec()),
+                ReplyFrame::bulk(b"a".to_vec()),
+            ])
+        );
+    }
+}
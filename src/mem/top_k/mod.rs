@@ -1,3 +1,292 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+//! Top-K heavy hitters based on `HeavyKeeper` algorithm.
+//!
+//! Items are counted in `depth` arrays of `width` buckets, each bucket keeps
+//! fingerprint of an item and its count. When an item hits a bucket occupied by
+//! another item, count of the bucket is reduced with probability `decay^count`,
+//! so that counts of mouse flows decay fast while elephant flows are kept.
+//!
+//! The top `k` items and their estimated counts are kept in a min-heap, with
+//! a map from item to its slot in the heap.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::top_k::{ReserveOptions, TopKCommand};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::top_k::consts::KEY_NOT_EXIST_ERR;
+use crate::mem::util::hash_with_seed;
+use crate::mem::Mem;
+
+pub mod add;
+mod consts;
+pub mod incr_by;
+pub mod info;
+pub mod list;
+pub mod query;
+pub mod reserve;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Bucket {
+    fingerprint: u32,
+    count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TopKObject {
+    k: usize,
+    width: usize,
+    depth: usize,
+    decay: f64,
+    /// `depth` arrays of `width` buckets.
+    buckets: Vec<Bucket>,
+    /// Min-heap of top items with their counts.
+    top: Vec<(String, u64)>,
+    /// Slot of each top item in heap.
+    slots: HashMap<String, usize>,
+}
+
+impl Mem {
+    pub fn handle_top_k_command(&mut self, command: TopKCommand) -> ReplyFrame {
+        match command {
+            TopKCommand::Add(key, items) => add::add(&mut self.db, &key, &items),
+            TopKCommand::IncrBy(key, items) => incr_by::incr_by(&mut self.db, &key, &items),
+            TopKCommand::Info(key) => info::info(&self.db, &key),
+            TopKCommand::List(key, with_count) => list::list(&self.db, &key, with_count),
+            TopKCommand::Query(key, items) => query::query(&self.db, &key, &items),
+            TopKCommand::Reserve(key, options) => reserve::reserve(&mut self.db, key, &options),
+        }
+    }
+}
+
+impl TopKObject {
+    #[must_use]
+    pub fn new(options: &ReserveOptions) -> Self {
+        Self {
+            k: options.k,
+            width: options.width,
+            depth: options.depth,
+            decay: options.decay,
+            buckets: vec![Bucket::default(); options.width * options.depth],
+            top: Vec::with_capacity(options.k),
+            slots: HashMap::with_capacity(options.k),
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn k(&self) -> usize {
+        self.k
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Increase count of item, returns the item expelled from top list.
+    pub fn incr_by(&mut self, item: &str, increment: u64) -> Option<String> {
+        #[allow(clippy::cast_possible_truncation)]
+        let fingerprint = hash_with_seed(item, u64::MAX) as u32;
+        let mut rng = rand::thread_rng();
+        let mut max_count = 0;
+        for row in 0..self.depth {
+            #[allow(clippy::cast_possible_truncation)]
+            let column = (hash_with_seed(item, row as u64) % self.width as u64) as usize;
+            let bucket = &mut self.buckets[row * self.width + column];
+            if bucket.count == 0 {
+                bucket.fingerprint = fingerprint;
+                bucket.count = increment;
+            } else if bucket.fingerprint == fingerprint {
+                bucket.count += increment;
+            } else {
+                // Each unit of increment tries to decay the occupying item.
+                for remains in (1..=increment).rev() {
+                    let exponent = i32::try_from(bucket.count).unwrap_or(i32::MAX);
+                    if rng.gen::<f64>() < self.decay.powi(exponent) {
+                        bucket.count -= 1;
+                        if bucket.count == 0 {
+                            bucket.fingerprint = fingerprint;
+                            bucket.count = remains;
+                            break;
+                        }
+                    }
+                }
+            }
+            if bucket.fingerprint == fingerprint {
+                max_count = max_count.max(bucket.count);
+            }
+        }
+        self.update_top(item, max_count)
+    }
+
+    /// Update count of item in top list, returns the item expelled.
+    fn update_top(&mut self, item: &str, count: u64) -> Option<String> {
+        if let Some(&slot) = self.slots.get(item) {
+            if count > self.top[slot].1 {
+                self.top[slot].1 = count;
+                self.sift_down(slot);
+            }
+            return None;
+        }
+        if count == 0 {
+            return None;
+        }
+        if self.top.len() < self.k {
+            self.slots.insert(item.to_owned(), self.top.len());
+            self.top.push((item.to_owned(), count));
+            self.sift_up(self.top.len() - 1);
+            return None;
+        }
+        if count <= self.top.first()?.1 {
+            return None;
+        }
+        let (expelled, _count) = std::mem::replace(&mut self.top[0], (item.to_owned(), count));
+        self.slots.remove(&expelled);
+        self.slots.insert(item.to_owned(), 0);
+        self.sift_down(0);
+        Some(expelled)
+    }
+
+    fn sift_up(&mut self, mut slot: usize) {
+        while slot > 0 {
+            let parent = (slot - 1) / 2;
+            if self.top[parent].1 <= self.top[slot].1 {
+                break;
+            }
+            self.swap_slots(parent, slot);
+            slot = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut slot: usize) {
+        loop {
+            let mut min = slot;
+            for child in [2 * slot + 1, 2 * slot + 2] {
+                if child < self.top.len() && self.top[child].1 < self.top[min].1 {
+                    min = child;
+                }
+            }
+            if min == slot {
+                break;
+            }
+            self.swap_slots(slot, min);
+            slot = min;
+        }
+    }
+
+    fn swap_slots(&mut self, a: usize, b: usize) {
+        self.top.swap(a, b);
+        for slot in [a, b] {
+            if let Some(entry) = self.slots.get_mut(&self.top[slot].0) {
+                *entry = slot;
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, item: &str) -> bool {
+        self.slots.contains_key(item)
+    }
+
+    /// Returns top items sorted by count in descending order.
+    #[must_use]
+    pub fn list(&self) -> Vec<(&str, u64)> {
+        let mut list: Vec<(&str, u64)> = self
+            .top
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        list.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        list
+    }
+}
+
+/// Increase items of top-k at key, returns expelled items.
+fn incr_items<'a>(
+    db: &mut Db,
+    key: &str,
+    items: impl Iterator<Item = (&'a String, u64)>,
+) -> ReplyFrame {
+    match db.get_mut(key) {
+        Some(MemObject::TopK(top_k)) => ReplyFrame::Array(
+            items
+                .map(|(item, increment)| {
+                    top_k
+                        .incr_by(item, increment)
+                        .map_or_else(ReplyFrame::null, |expelled| {
+                            ReplyFrame::bulk(expelled.into_bytes())
+                        })
+                })
+                .collect(),
+        ),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::top_k::TopKObject;
+
+    #[test]
+    fn test_top_k_object() {
+        let mut top_k = TopKObject::new(&ReserveOptions {
+            k: 2,
+            width: 50,
+            depth: 5,
+            decay: 0.9,
+        });
+        assert_eq!(top_k.incr_by("a", 10), None);
+        assert_eq!(top_k.incr_by("b", 5), None);
+        assert_eq!(top_k.incr_by("c", 1), None);
+        assert_eq!(top_k.incr_by("c", 20), Some("b".to_owned()));
+        assert_eq!(top_k.list(), [("c", 21), ("a", 10)]);
+        assert!(!top_k.contains("b"));
+    }
+
+    #[test]
+    fn test_top_heap() {
+        let mut top_k = TopKObject::new(&ReserveOptions {
+            k: 10,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        });
+        for i in 0..10 {
+            assert_eq!(top_k.update_top(&format!("item{i}"), 100 - i), None);
+        }
+        assert_eq!(top_k.update_top("item0", 1), None);
+        assert_eq!(top_k.update_top("item9", 200), None);
+        assert_eq!(top_k.update_top("new", 92), None);
+        assert_eq!(top_k.update_top("new", 93), Some("item8".to_owned()));
+        assert_eq!(top_k.update_top("new", 95), None);
+        assert_eq!(top_k.update_top("other", 94), Some("item7".to_owned()));
+        assert_eq!(top_k.list()[0], ("item9", 200));
+        assert_eq!(top_k.list()[9], ("other", 94));
+        assert!(top_k.contains("new"));
+        assert!(!top_k.contains("item7"));
+        for (slot, (item, _count)) in top_k.top.iter().enumerate() {
+            assert_eq!(top_k.slots[item], slot);
+        }
+    }
+}
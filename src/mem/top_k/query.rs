@@ -0,0 +1,56 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::top_k::consts::KEY_NOT_EXIST_ERR;
+
+/// Checks whether one or more items are one of Top-K items.
+///
+/// Reply:
+/// - Array reply: for each item, 1 if item is in Top-K list, otherwise 0.
+/// - Error reply: if key does not exist.
+pub fn query(db: &Db, key: &str, items: &[String]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::TopK(top_k)) => ReplyFrame::Array(
+            items
+                .iter()
+                .map(|item| ReplyFrame::from_bool(top_k.contains(item)))
+                .collect(),
+        ),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::ConstError(KEY_NOT_EXIST_ERR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::add::add;
+    use crate::mem::top_k::query::query;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_query() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            k: 1,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        };
+        reserve(&mut db, "urls".to_owned(), &options);
+        add(
+            &mut db,
+            "urls",
+            &["a".to_owned(), "a".to_owned(), "b".to_owned()],
+        );
+        assert_eq!(
+            query(&db, "urls", &["a".to_owned(), "b".to_owned()]),
+            ReplyFrame::Array(vec![ReplyFrame::one(), ReplyFrame::zero()])
+        );
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::top_k::ReserveOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::top_k::consts::KEY_EXISTS_ERR;
+use crate::mem::top_k::TopKObject;
+
+/// Initializes a Top-K sketch with specified parameters.
+///
+/// Options:
+/// - width: number of counters kept in each array, default is 8.
+/// - depth: number of arrays, default is 7.
+/// - decay: the probability of reducing a counter in an occupied bucket,
+///   it is raised to power of its counter, default is 0.9.
+///
+/// Reply:
+/// - Simple string reply: `OK` if executed correctly.
+/// - Error reply: if key already exists.
+pub fn reserve(db: &mut Db, key: String, options: &ReserveOptions) -> ReplyFrame {
+    if db.contains_key(&key) {
+        return ReplyFrame::ConstError(KEY_EXISTS_ERR);
+    }
+    db.insert(key, MemObject::TopK(TopKObject::new(options)));
+    ReplyFrame::ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::top_k::ReserveOptions;
+    use crate::mem::db::Db;
+    use crate::mem::top_k::consts::KEY_EXISTS_ERR;
+    use crate::mem::top_k::reserve::reserve;
+
+    #[test]
+    fn test_reserve() {
+        let mut db = Db::new();
+        let options = ReserveOptions {
+            k: 3,
+            width: 8,
+            depth: 7,
+            decay: 0.9,
+        };
+        assert_eq!(
+            reserve(&mut db, "urls".to_owned(), &options),
+            ReplyFrame::ok()
+        );
+        assert_eq!(
+            reserve(&mut db, "urls".to_owned(), &options),
+            ReplyFrame::ConstError(KEY_EXISTS_ERR)
+        );
+    }
+}
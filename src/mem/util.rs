@@ -2,6 +2,8 @@
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 #[must_use]
 pub(super) const fn check_string_length(size: usize, append: usize) -> bool {
     // TODO(Shaohua): Limit string length to 512MB
@@ -48,9 +50,158 @@ pub const fn prune_index(len: usize, mut index: isize) -> Option<usize> {
     }
 }
 
+/// Returns current unix time in milliseconds.
+#[must_use]
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| {
+            i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Returns true if string matches the glob-style pattern.
+///
+/// Supported patterns, same as redis:
+/// - `?` matches any single character
+/// - `*` matches any sequence of characters
+/// - `[ae]`, `[^e]` and `[a-e]` match a set of characters
+/// - `\` escapes special characters
+#[must_use]
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let mut p = 0;
+    let mut s = 0;
+    // Positions in pattern and string to retry when the last `*` matches one more character.
+    let mut backtrack = None;
+    while s < string.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            backtrack = Some((p, s));
+            continue;
+        }
+        if p < pattern.len() {
+            if let Some(next) = glob_match_one(pattern, p, string[s]) {
+                p = next;
+                s += 1;
+                continue;
+            }
+        }
+        match backtrack {
+            Some((star_p, star_s)) => {
+                p = star_p;
+                s = star_s + 1;
+                backtrack = Some((star_p, s));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Match a single character at position `p` of pattern, returns position of next token.
+fn glob_match_one(pattern: &[u8], p: usize, c: u8) -> Option<usize> {
+    match pattern[p] {
+        b'?' => Some(p + 1),
+        b'[' => glob_match_class(pattern, p + 1, c),
+        b'\\' if p + 1 < pattern.len() => (pattern[p + 1] == c).then_some(p + 2),
+        pc => (pc == c).then_some(p + 1),
+    }
+}
+
+/// Match a character set starting after `[`, returns position after `]`.
+fn glob_match_class(pattern: &[u8], mut p: usize, c: u8) -> Option<usize> {
+    let negate = pattern.get(p) == Some(&b'^');
+    if negate {
+        p += 1;
+    }
+    let mut matched = false;
+    while p < pattern.len() && pattern[p] != b']' {
+        if pattern[p] == b'\\' && p + 1 < pattern.len() {
+            matched |= pattern[p + 1] == c;
+            p += 2;
+        } else if p + 2 < pattern.len() && pattern[p + 1] == b'-' {
+            let (start, end) = if pattern[p] <= pattern[p + 2] {
+                (pattern[p], pattern[p + 2])
+            } else {
+                (pattern[p + 2], pattern[p])
+            };
+            matched |= (start..=end).contains(&c);
+            p += 3;
+        } else {
+            matched |= pattern[p] == c;
+            p += 1;
+        }
+    }
+    // Skip the closing bracket, an unterminated set ends at end of pattern.
+    let next = (p + 1).min(pattern.len());
+    (matched != negate).then_some(next)
+}
+
+/// Format float like `%.17g` in C, which is used by redis `INCRBYFLOAT`.
+///
+/// Scientific notation is used if decimal exponent is less than -4 or not less than 17,
+/// digits are the shortest ones which round trip to the same value.
+#[must_use]
+pub fn format_float(value: f64) -> String {
+    let scientific = format!("{value:e}");
+    let Some((mantissa, exp)) = scientific.split_once('e') else {
+        return value.to_string();
+    };
+    let Ok(exp) = exp.parse::<i32>() else {
+        return value.to_string();
+    };
+    if (-4..17).contains(&exp) {
+        value.to_string()
+    } else {
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{mantissa}e{sign}{:02}", exp.abs())
+    }
+}
+
+/// `MurmurHash64A`, same as redis, which reads blocks in little endian.
+#[must_use]
+pub fn murmur_hash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let mut chunks = key.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &byte) in tail.iter().enumerate() {
+            h ^= u64::from(byte) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
+
+/// Hash item with seed, used by probabilistic structures which need
+/// independent hash functions.
+///
+/// Hash values are stable across builds, as they are saved in dumps.
+#[must_use]
+#[inline]
+pub fn hash_with_seed(item: &str, seed: u64) -> u64 {
+    murmur_hash64a(item.as_bytes(), seed)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{prune_index, prune_range};
+    use super::{
+        format_float, glob_match, hash_with_seed, murmur_hash64a, prune_index, prune_range,
+    };
 
     #[test]
     fn test_prune_range() {
@@ -68,4 +219,46 @@ mod tests {
         assert_eq!(prune_index(2, 3), None);
         assert_eq!(prune_index(2, -3), None);
     }
+
+    #[test]
+    fn test_format_float() {
+        assert_eq!(format_float(0.0), "0");
+        assert_eq!(format_float(10.6), "10.6");
+        assert_eq!(format_float(-5200.0), "-5200");
+        assert_eq!(format_float(1e16), "10000000000000000");
+        assert_eq!(format_float(1e17), "1e+17");
+        assert_eq!(format_float(-1.5e21), "-1.5e+21");
+        assert_eq!(format_float(0.0001), "0.0001");
+        assert_eq!(format_float(0.000_012_5), "1.25e-05");
+        assert_eq!(format_float(f64::MAX), "1.7976931348623157e+308");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"*", b"news"));
+        assert!(glob_match(b"news.*", b"news.tech"));
+        assert!(!glob_match(b"news.*", b"new.tech"));
+        assert!(glob_match(b"h?llo", b"hello"));
+        assert!(!glob_match(b"h?llo", b"hllo"));
+        assert!(glob_match(b"h*llo", b"heeeello"));
+        assert!(glob_match(b"h[ae]llo", b"hallo"));
+        assert!(!glob_match(b"h[ae]llo", b"hillo"));
+        assert!(glob_match(b"h[^e]llo", b"hallo"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"h[a-b]llo", b"hbllo"));
+        assert!(glob_match(b"h[b-a]llo", b"hallo"));
+        assert!(glob_match(b"h\\*llo", b"h*llo"));
+        assert!(!glob_match(b"h\\*llo", b"hello"));
+        assert!(glob_match(b"*a*b", b"xaxxb"));
+        assert!(!glob_match(b"*a*b", b"xaxxbc"));
+    }
+
+    #[test]
+    fn test_murmur_hash64a() {
+        assert_eq!(murmur_hash64a(b"", 0), 0);
+        assert_ne!(murmur_hash64a(b"hello", 0), murmur_hash64a(b"hellp", 0));
+        assert_eq!(hash_with_seed("hello", 7), murmur_hash64a(b"hello", 7));
+        assert_ne!(hash_with_seed("hello", 0), hash_with_seed("hello", 1));
+    }
 }
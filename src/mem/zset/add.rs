@@ -0,0 +1,193 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::AddOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::consts::SCORE_NAN_ERR;
+use crate::mem::zset::SortedSetObject;
+
+/// Adds all the specified members with the specified scores to the sorted set stored at key.
+///
+/// If a specified member is already a member of the sorted set, the score is updated
+/// and the element reinserted at the right position to ensure the correct ordering.
+///
+/// If key does not exist, a new sorted set with the specified members as sole members is created.
+///
+/// Options:
+/// - XX: Only update elements that already exist. Don't add new elements.
+/// - NX: Only add new elements. Don't update already existing elements.
+/// - LT: Only update existing elements if the new score is less than the current score.
+///   This flag doesn't prevent adding new elements.
+/// - GT: Only update existing elements if the new score is greater than the current score.
+///   This flag doesn't prevent adding new elements.
+/// - CH: Modify the return value from the number of new elements added, to the total number
+///   of elements changed.
+/// - INCR: When this option is specified `ZADD` acts like `ZINCRBY`.
+///   Only one score-element pair can be specified in this mode.
+///
+/// Reply, any of the following:
+/// - Nil reply: if the operation was aborted because of a conflict with one of
+///   the XX/NX/LT/GT options.
+/// - Integer reply: the number of new members when the CH option is not used.
+/// - Integer reply: the number of new or updated members when the CH option is used.
+/// - Double reply: the updated score of the member when the INCR option is used.
+#[allow(clippy::float_cmp)]
+pub fn add(
+    db: &mut Db,
+    key: String,
+    options: AddOptions,
+    elements: Vec<(f64, Vec<u8>)>,
+) -> ReplyFrame {
+    // A new key always gets at least one element, unless XX option is used.
+    let zset = match db.entry(key) {
+        Entry::Occupied(occupied) => match occupied.into_mut() {
+            MemObject::SortedSet(zset) => zset,
+            _ => return ReplyFrame::wrong_type_err(),
+        },
+        Entry::Vacant(_) if options.exist => {
+            return if options.increment {
+                ReplyFrame::Null
+            } else {
+                ReplyFrame::zero()
+            };
+        }
+        Entry::Vacant(vacant) => {
+            match vacant.insert(MemObject::SortedSet(SortedSetObject::new())) {
+                MemObject::SortedSet(zset) => zset,
+                _ => unreachable!(),
+            }
+        }
+    };
+
+    let mut added = 0;
+    let mut updated = 0;
+    let mut new_score = None;
+    for (score, member) in elements {
+        if let Some(current) = zset.score(&member) {
+            if options.not_exist {
+                continue;
+            }
+            let score = if options.increment {
+                current + score
+            } else {
+                score
+            };
+            if score.is_nan() {
+                return ReplyFrame::ConstError(SCORE_NAN_ERR);
+            }
+            if (options.greater_than && score <= current) || (options.less_than && score >= current)
+            {
+                continue;
+            }
+            new_score = Some(score);
+            if score != current {
+                zset.insert(member, score);
+                updated += 1;
+            }
+        } else {
+            if options.exist {
+                continue;
+            }
+            zset.insert(member, score);
+            added += 1;
+            new_score = Some(score);
+        }
+    }
+
+    if options.increment {
+        new_score.map_or(ReplyFrame::Null, ReplyFrame::Double)
+    } else if options.changed {
+        ReplyFrame::Usize(added + updated)
+    } else {
+        ReplyFrame::Usize(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::card::card;
+    use crate::mem::zset::score::score;
+
+    #[test]
+    fn test_add() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let options = AddOptions::default();
+        let reply = add(&mut db, key.clone(), options, vec![(1.0, b"one".to_vec())]);
+        assert_eq!(reply, ReplyFrame::one());
+        let reply = add(
+            &mut db,
+            key.clone(),
+            options,
+            vec![(1.0, b"uno".to_vec()), (2.0, b"two".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions {
+                changed: true,
+                ..options
+            },
+            vec![(2.0, b"two".to_vec()), (3.0, b"uno".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::one());
+        assert_eq!(card(&db, &key), ReplyFrame::Usize(3));
+
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions {
+                greater_than: true,
+                changed: true,
+                ..options
+            },
+            vec![(1.0, b"uno".to_vec()), (5.0, b"two".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::one());
+        assert_eq!(score(&db, &key, b"uno"), ReplyFrame::Double(3.0));
+
+        let increment = AddOptions {
+            increment: true,
+            ..options
+        };
+        let reply = add(
+            &mut db,
+            key.clone(),
+            increment,
+            vec![(2.5, b"one".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Double(3.5));
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions {
+                not_exist: true,
+                ..increment
+            },
+            vec![(2.5, b"one".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Null);
+
+        let other_key = "other".to_owned();
+        let reply = add(
+            &mut db,
+            other_key.clone(),
+            AddOptions {
+                exist: true,
+                ..options
+            },
+            vec![(1.0, b"one".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::zero());
+        assert!(!db.contains_key(&other_key));
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::PopSide;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::pop::pop_elements;
+use crate::mem::zset::remove_if_empty;
+
+/// `BZPOPMIN` and `BZPOPMAX` are the blocking variants of `ZPOPMIN` and `ZPOPMAX`.
+///
+/// An element is popped from the first sorted set that is non-empty,
+/// with the given keys being checked in the order that they are given.
+///
+/// This function does not block, a nil reply tells the caller to block the client
+/// until one of the keys is ready or the timeout is reached.
+///
+/// Reply, one of the following:
+/// - Nil reply: when no element could be popped and the timeout expired.
+/// - Array reply: the keyname, popped member, and its score.
+pub fn blocking_pop(db: &mut Db, keys: &[String], side: PopSide) -> ReplyFrame {
+    for key in keys {
+        let elements = match db.get_mut(key) {
+            Some(MemObject::SortedSet(zset)) => pop_elements(zset, side, 1),
+            Some(_) => return ReplyFrame::wrong_type_err(),
+            None => continue,
+        };
+        remove_if_empty(db, key);
+
+        if let Some((member, score)) = elements.into_iter().next() {
+            return ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(key.as_bytes().to_vec()),
+                ReplyFrame::Bulk(member),
+                ReplyFrame::Double(score),
+            ]);
+        }
+    }
+    ReplyFrame::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, PopSide};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::blocking_pop::blocking_pop;
+
+    #[test]
+    fn test_blocking_pop() {
+        let mut db = Db::new();
+        let keys = ["zset1".to_owned(), "zset2".to_owned()];
+        assert_eq!(blocking_pop(&mut db, &keys, PopSide::Min), ReplyFrame::Null);
+        let reply = add(
+            &mut db,
+            keys[1].clone(),
+            AddOptions::default(),
+            vec![(0.0, b"a".to_vec()), (1.0, b"b".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = blocking_pop(&mut db, &keys, PopSide::Min);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"zset2".to_vec()),
+                ReplyFrame::Bulk(b"a".to_vec()),
+                ReplyFrame::Double(0.0),
+            ])
+        );
+    }
+}
@@ -0,0 +1,19 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the sorted set cardinality (number of elements) of the sorted set stored at key.
+///
+/// Reply:
+/// - Integer reply: the cardinality (number of members) of the sorted set,
+///   or 0 if the key doesn't exist.
+pub fn card(db: &Db, key: &str) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => ReplyFrame::Usize(zset.len()),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
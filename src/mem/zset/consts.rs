@@ -0,0 +1,5 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+pub const SCORE_NAN_ERR: &str = "ERR resulting score is not a number (NaN)";
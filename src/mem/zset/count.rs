@@ -0,0 +1,61 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::ScoreBound;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the number of elements in the sorted set at key with a score between min and max.
+///
+/// The min and max arguments have the same semantic as described for `ZRANGEBYSCORE`.
+///
+/// Reply:
+/// - Integer reply: the number of members in the specified score range.
+pub fn count(db: &Db, key: &str, min: ScoreBound, max: ScoreBound) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => ReplyFrame::Usize(zset.count(min, max)),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, ScoreBound};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::count::count;
+
+    #[test]
+    fn test_count() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = count(
+            &db,
+            &key,
+            ScoreBound::Inclusive(f64::NEG_INFINITY),
+            ScoreBound::Inclusive(f64::INFINITY),
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = count(
+            &db,
+            &key,
+            ScoreBound::Exclusive(1.0),
+            ScoreBound::Inclusive(3.0),
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+    }
+}
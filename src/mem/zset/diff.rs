@@ -0,0 +1,79 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::zset::{elements_reply, InputSet};
+
+/// This command is similar to `ZDIFFSTORE`, but instead of storing the resulting sorted set,
+/// it is returned to the client.
+///
+/// Returns the difference between the first and all successive input sorted sets.
+/// Keys that do not exist are considered to be empty sets.
+///
+/// Reply:
+/// - Array reply: the result of the difference including, optionally,
+///   scores when the WITHSCORES option is used.
+pub fn diff(db: &Db, keys: &[String], with_scores: bool) -> ReplyFrame {
+    match diff_elements(db, keys) {
+        Ok(elements) => elements_reply(elements, with_scores),
+        Err(reply) => reply,
+    }
+}
+
+/// Returns difference between the first input set and all the successive ones,
+/// or error reply if any of them is not a set or sorted set.
+pub fn diff_elements(db: &Db, keys: &[String]) -> Result<Vec<(Vec<u8>, f64)>, ReplyFrame> {
+    let inputs = InputSet::from_keys(db, keys)?;
+    let Some((Some(first), others)) = inputs.split_first() else {
+        return Ok(Vec::new());
+    };
+    let mut elements = first.elements();
+    for other in others.iter().flatten() {
+        elements.retain(|(member, _score)| other.score(member).is_none());
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::diff::diff;
+
+    #[test]
+    fn test_diff() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        add(
+            &mut db,
+            key2.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        let keys = [key1, key2];
+        let reply = diff(&db, &keys, true);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"three".to_vec()),
+                ReplyFrame::Double(3.0),
+            ])
+        );
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::Db;
+use crate::mem::zset::diff::diff_elements;
+use crate::mem::zset::store;
+
+/// Computes the difference between the first and all successive input sorted sets
+/// and stores the result in destination.
+///
+/// If destination already exists, it is overwritten.
+///
+/// Reply:
+/// - Integer reply: the number of members in the resulting sorted set at destination.
+pub fn diff_store(db: &mut Db, destination: String, keys: &[String]) -> ReplyFrame {
+    match diff_elements(db, keys) {
+        Ok(elements) => store(db, destination, elements),
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::diff_store::diff_store;
+
+    #[test]
+    fn test_diff_store() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        add(&mut db, key2.clone(), options, vec![(1.0, b"one".to_vec())]);
+        let destination = "out".to_owned();
+        let reply = diff_store(&mut db, destination.clone(), &[key1.clone(), key2.clone()]);
+        assert_eq!(reply, ReplyFrame::one());
+        let reply = diff_store(&mut db, destination.clone(), &[key2, key1]);
+        assert_eq!(reply, ReplyFrame::zero());
+        assert!(!db.contains_key(&destination));
+    }
+}
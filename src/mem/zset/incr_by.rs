@@ -0,0 +1,46 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::AddOptions;
+use crate::mem::db::Db;
+use crate::mem::zset::add::add;
+
+/// Increments the score of member in the sorted set stored at key by increment.
+///
+/// If member does not exist in the sorted set, it is added with increment as its score
+/// (as if its previous score was 0.0). If key does not exist, a new sorted set
+/// with the specified member as its sole member is created.
+///
+/// Reply:
+/// - Bulk string reply: the new score of member as a double precision floating point number.
+pub fn incr_by(db: &mut Db, key: String, increment: f64, member: Vec<u8>) -> ReplyFrame {
+    let options = AddOptions {
+        increment: true,
+        ..AddOptions::default()
+    };
+    add(db, key, options, vec![(increment, member)])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::mem::db::Db;
+    use crate::mem::zset::consts::SCORE_NAN_ERR;
+    use crate::mem::zset::incr_by::incr_by;
+
+    #[test]
+    fn test_incr_by() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = incr_by(&mut db, key.clone(), 2.0, b"one".to_vec());
+        assert_eq!(reply, ReplyFrame::Double(2.0));
+        let reply = incr_by(&mut db, key.clone(), -0.5, b"one".to_vec());
+        assert_eq!(reply, ReplyFrame::Double(1.5));
+        let reply = incr_by(&mut db, key.clone(), f64::INFINITY, b"two".to_vec());
+        assert_eq!(reply, ReplyFrame::Double(f64::INFINITY));
+        let reply = incr_by(&mut db, key, f64::NEG_INFINITY, b"two".to_vec());
+        assert_eq!(reply, ReplyFrame::ConstError(SCORE_NAN_ERR));
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::SetOperation;
+use crate::mem::db::Db;
+use crate::mem::zset::{aggregate_score, elements_reply, weighted_score, InputSet};
+
+/// This command is similar to `ZINTERSTORE`, but instead of storing the resulting sorted set,
+/// it is returned to the client.
+///
+/// Keys that do not exist are considered to be empty sets, and members of sets
+/// are considered to have a score of 1.
+///
+/// Reply:
+/// - Array reply: the result of the intersection with, optionally, their scores
+///   when WITHSCORES is used.
+pub fn intersect(db: &Db, operation: &SetOperation) -> ReplyFrame {
+    match intersect_elements(db, operation) {
+        Ok(elements) => elements_reply(elements, operation.with_scores),
+        Err(reply) => reply,
+    }
+}
+
+/// Returns intersection of input sets, or error reply if any of them is not a set or sorted set.
+pub fn intersect_elements(
+    db: &Db,
+    operation: &SetOperation,
+) -> Result<Vec<(Vec<u8>, f64)>, ReplyFrame> {
+    let inputs = InputSet::from_keys(db, &operation.keys)?;
+    let Some(inputs) = inputs.into_iter().collect::<Option<Vec<_>>>() else {
+        return Ok(Vec::new());
+    };
+    let weight = |index: usize| operation.weights.get(index).copied().unwrap_or(1.0);
+    let Some((first, others)) = inputs.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut elements = Vec::new();
+    'outer: for (member, score) in first.elements() {
+        let mut score = weighted_score(score, weight(0));
+        for (index, other) in others.iter().enumerate() {
+            let Some(other_score) = other.score(&member) else {
+                continue 'outer;
+            };
+            let other_score = weighted_score(other_score, weight(index + 1));
+            score = aggregate_score(operation.aggregate, score, other_score);
+        }
+        elements.push((member, score));
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, Aggregate, SetOperation};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::intersect::intersect;
+
+    #[test]
+    fn test_intersect() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        add(
+            &mut db,
+            key2.clone(),
+            options,
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        let mut operation = SetOperation {
+            keys: vec![key1.clone(), key2],
+            weights: Vec::new(),
+            aggregate: Aggregate::Sum,
+            with_scores: true,
+        };
+        let reply = intersect(&db, &operation);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"one".to_vec()),
+                ReplyFrame::Double(2.0),
+                ReplyFrame::Bulk(b"two".to_vec()),
+                ReplyFrame::Double(4.0),
+            ])
+        );
+
+        operation.keys = vec![key1, "nonexist".to_owned()];
+        let reply = intersect(&db, &operation);
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::{Aggregate, SetOperation};
+use crate::mem::db::Db;
+use crate::mem::zset::intersect::intersect_elements;
+
+/// This command is similar to `ZINTER`, but instead of returning the result set,
+/// it returns just the cardinality of the result.
+///
+/// Keys that do not exist are considered to be empty sets.
+///
+/// When provided with the optional LIMIT argument (which defaults to 0 and means unlimited),
+/// if the intersection cardinality reaches limit partway through the computation,
+/// the algorithm will exit and yield limit as the cardinality.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting intersection.
+pub fn intersect_card(db: &Db, keys: &[String], limit: usize) -> ReplyFrame {
+    let operation = SetOperation {
+        keys: keys.to_vec(),
+        weights: Vec::new(),
+        aggregate: Aggregate::Sum,
+        with_scores: false,
+    };
+    match intersect_elements(db, &operation) {
+        Ok(elements) => {
+            let len = if limit == 0 {
+                elements.len()
+            } else {
+                elements.len().min(limit)
+            };
+            ReplyFrame::Usize(len)
+        }
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::intersect_card::intersect_card;
+
+    #[test]
+    fn test_intersect_card() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        add(
+            &mut db,
+            key2.clone(),
+            options,
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        let keys = [key1, key2];
+        assert_eq!(intersect_card(&db, &keys, 0), ReplyFrame::Usize(2));
+        assert_eq!(intersect_card(&db, &keys, 1), ReplyFrame::one());
+    }
+}
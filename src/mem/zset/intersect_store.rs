@@ -0,0 +1,63 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::SetOperation;
+use crate::mem::db::Db;
+use crate::mem::zset::intersect::intersect_elements;
+use crate::mem::zset::store;
+
+/// Computes the intersection of numkeys sorted sets given by the specified keys,
+/// and stores the result in destination.
+///
+/// If destination already exists, it is overwritten.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting sorted set.
+pub fn intersect_store(db: &mut Db, destination: String, operation: &SetOperation) -> ReplyFrame {
+    match intersect_elements(db, operation) {
+        Ok(elements) => store(db, destination, elements),
+        Err(reply) => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, Aggregate, SetOperation};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::intersect_store::intersect_store;
+    use crate::mem::zset::score::score;
+
+    #[test]
+    fn test_intersect_store() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        add(
+            &mut db,
+            key2.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (3.0, b"three".to_vec())],
+        );
+        let destination = "out".to_owned();
+        let operation = SetOperation {
+            keys: vec![key1, key2],
+            weights: vec![2.0, 3.0],
+            aggregate: Aggregate::Sum,
+            with_scores: false,
+        };
+        let reply = intersect_store(&mut db, destination.clone(), &operation);
+        assert_eq!(reply, ReplyFrame::one());
+        assert_eq!(score(&db, &destination, b"one"), ReplyFrame::Double(5.0));
+    }
+}
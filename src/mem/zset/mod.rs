@@ -1,3 +1,207 @@
 // Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU Affero General Public License
 // that can be found in the LICENSE file.
+
+use std::borrow::Cow;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::{Aggregate, SortedSetCommand};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::set::SetObject;
+use crate::mem::Mem;
+
+pub mod add;
+pub mod blocking_pop;
+pub mod card;
+mod consts;
+pub mod count;
+pub mod diff;
+pub mod diff_store;
+pub mod incr_by;
+pub mod intersect;
+pub mod intersect_card;
+pub mod intersect_store;
+pub mod multi_pop;
+pub mod multi_score;
+pub mod pop;
+pub mod random_member;
+pub mod range;
+pub mod range_store;
+pub mod rank;
+pub mod remove;
+pub mod remove_range;
+pub mod score;
+mod skip_list;
+mod sorted_set_object;
+pub mod union;
+pub mod union_store;
+
+pub use sorted_set_object::SortedSetObject;
+
+impl Mem {
+    pub fn handle_sorted_set_command(&mut self, command: SortedSetCommand) -> ReplyFrame {
+        match command {
+            SortedSetCommand::Add(key, options, elements) => {
+                add::add(&mut self.db, key, options, elements)
+            }
+            SortedSetCommand::Card(key) => card::card(&self.db, &key),
+            SortedSetCommand::Count(key, min, max) => count::count(&self.db, &key, min, max),
+            SortedSetCommand::IncrBy(key, increment, member) => {
+                incr_by::incr_by(&mut self.db, key, increment, member)
+            }
+            SortedSetCommand::MultiScore(key, members) => {
+                multi_score::multi_score(&self.db, &key, &members)
+            }
+            SortedSetCommand::Range(key, options) => range::range(&self.db, &key, &options),
+            SortedSetCommand::RangeStore(destination, key, options) => {
+                range_store::range_store(&mut self.db, destination, &key, &options)
+            }
+            SortedSetCommand::Rank(key, member, with_score) => {
+                rank::rank(&self.db, &key, &member, false, with_score)
+            }
+            SortedSetCommand::RevRank(key, member, with_score) => {
+                rank::rank(&self.db, &key, &member, true, with_score)
+            }
+            SortedSetCommand::Remove(key, members) => remove::remove(&mut self.db, &key, &members),
+            SortedSetCommand::RemoveRange(key, range_by) => {
+                remove_range::remove_range(&mut self.db, &key, &range_by)
+            }
+            SortedSetCommand::Score(key, member) => score::score(&self.db, &key, &member),
+            SortedSetCommand::Union(operation) => union::union(&self.db, &operation),
+            SortedSetCommand::UnionStore(destination, operation) => {
+                union_store::union_store(&mut self.db, destination, &operation)
+            }
+            SortedSetCommand::Intersect(operation) => intersect::intersect(&self.db, &operation),
+            SortedSetCommand::IntersectStore(destination, operation) => {
+                intersect_store::intersect_store(&mut self.db, destination, &operation)
+            }
+            SortedSetCommand::IntersectCard(keys, limit) => {
+                intersect_card::intersect_card(&self.db, &keys, limit)
+            }
+            SortedSetCommand::Diff(keys, with_scores) => diff::diff(&self.db, &keys, with_scores),
+            SortedSetCommand::DiffStore(destination, keys) => {
+                diff_store::diff_store(&mut self.db, destination, &keys)
+            }
+            SortedSetCommand::Pop(key, side, count) => pop::pop(&mut self.db, &key, side, count),
+            SortedSetCommand::MultiPop(keys, side, count) => {
+                multi_pop::multi_pop(&mut self.db, &keys, side, count)
+            }
+            SortedSetCommand::RandomMember(key, count) => {
+                random_member::random_member(&self.db, &key, count)
+            }
+            SortedSetCommand::BlockingPop(keys, side, _timeout) => {
+                blocking_pop::blocking_pop(&mut self.db, &keys, side)
+            }
+            SortedSetCommand::BlockingMultiPop(keys, side, count, _timeout) => {
+                multi_pop::multi_pop(&mut self.db, &keys, side, count)
+            }
+        }
+    }
+}
+
+/// Delete the sorted set stored at key if it has no elements left.
+pub fn remove_if_empty(db: &mut Db, key: &str) {
+    if matches!(db.get(key), Some(MemObject::SortedSet(zset)) if zset.is_empty()) {
+        db.remove(key);
+    }
+}
+
+/// Store elements as a sorted set at `destination`, overwriting any existing value.
+///
+/// The destination key is deleted if there are no elements.
+pub fn store(db: &mut Db, destination: String, elements: Vec<(Vec<u8>, f64)>) -> ReplyFrame {
+    if elements.is_empty() {
+        db.remove(&destination);
+        return ReplyFrame::zero();
+    }
+    let zset: SortedSetObject = elements.into_iter().collect();
+    let len = zset.len();
+    db.insert(destination, MemObject::SortedSet(zset));
+    ReplyFrame::Usize(len)
+}
+
+/// Returns elements ordered by score then by member, as array of members and optionally scores.
+pub fn elements_reply(mut elements: Vec<(Vec<u8>, f64)>, with_scores: bool) -> ReplyFrame {
+    elements.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    let mut array = Vec::with_capacity(elements.len());
+    for (member, score) in elements {
+        array.push(ReplyFrame::Bulk(member));
+        if with_scores {
+            array.push(ReplyFrame::Double(score));
+        }
+    }
+    ReplyFrame::Array(array)
+}
+
+/// Input of set operations, members of a plain set are considered to have a score of 1.
+pub enum InputSet<'a> {
+    Set(&'a SetObject),
+    SortedSet(&'a SortedSetObject),
+}
+
+impl<'a> InputSet<'a> {
+    /// Returns input sets of keys, None for keys that do not exist.
+    pub fn from_keys(db: &'a Db, keys: &[String]) -> Result<Vec<Option<Self>>, ReplyFrame> {
+        keys.iter()
+            .map(|key| match db.get(key) {
+                Some(MemObject::Set(set)) => Ok(Some(Self::Set(set))),
+                Some(MemObject::SortedSet(zset)) => Ok(Some(Self::SortedSet(zset))),
+                Some(_) => Err(ReplyFrame::wrong_type_err()),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Set(set) => set.len(),
+            Self::SortedSet(zset) => zset.len(),
+        }
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            Self::Set(set) => set.contains(member).then_some(1.0),
+            Self::SortedSet(zset) => zset.score(member),
+        }
+    }
+
+    pub fn elements(&self) -> Vec<(Vec<u8>, f64)> {
+        match self {
+            Self::Set(set) => set
+                .iter()
+                .map(|member| (Cow::into_owned(member), 1.0))
+                .collect(),
+            Self::SortedSet(zset) => zset
+                .iter()
+                .map(|(member, score)| (member.to_vec(), score))
+                .collect(),
+        }
+    }
+}
+
+/// Multiply score by weight of its input set, NaN (from `inf * 0`) is treated as 0.
+fn weighted_score(score: f64, weight: f64) -> f64 {
+    let score = score * weight;
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+/// Aggregate scores of the same member, NaN (from `inf + -inf`) is treated as 0.
+fn aggregate_score(aggregate: Aggregate, current: f64, score: f64) -> f64 {
+    match aggregate {
+        Aggregate::Sum => {
+            let sum = current + score;
+            if sum.is_nan() {
+                0.0
+            } else {
+                sum
+            }
+        }
+        Aggregate::Min => current.min(score),
+        Aggregate::Max => current.max(score),
+    }
+}
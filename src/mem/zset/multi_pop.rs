@@ -0,0 +1,91 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::PopSide;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::pop::pop_elements;
+use crate::mem::zset::remove_if_empty;
+
+/// Pops one or more elements, that are member-score pairs, from the first non-empty
+/// sorted set in the provided list of key names.
+///
+/// When the MIN modifier is used, the elements popped are those with the lowest scores
+/// from the first non-empty sorted set. The MAX modifier causes elements with
+/// the highest scores to be popped.
+///
+/// Reply, one of the following:
+/// - Nil reply: when no element could be popped.
+/// - Array reply: a two-element array with the first element being the name of the key
+///   from which elements were popped, and the second element is an array of the popped
+///   elements. Every entry in the elements array is also an array that contains
+///   the member and its score.
+pub fn multi_pop(db: &mut Db, keys: &[String], side: PopSide, count: usize) -> ReplyFrame {
+    for key in keys {
+        let elements = match db.get_mut(key) {
+            Some(MemObject::SortedSet(zset)) => pop_elements(zset, side, count),
+            Some(_) => return ReplyFrame::wrong_type_err(),
+            None => continue,
+        };
+        remove_if_empty(db, key);
+
+        let elements = elements
+            .into_iter()
+            .map(|(member, score)| {
+                ReplyFrame::Array(vec![ReplyFrame::Bulk(member), ReplyFrame::Double(score)])
+            })
+            .collect();
+        return ReplyFrame::Array(vec![
+            ReplyFrame::Bulk(key.as_bytes().to_vec()),
+            ReplyFrame::Array(elements),
+        ]);
+    }
+    ReplyFrame::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, PopSide};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::multi_pop::multi_pop;
+
+    #[test]
+    fn test_multi_pop() {
+        let mut db = Db::new();
+        let keys = ["notsuchkey".to_owned(), "myzset".to_owned()];
+        let reply = multi_pop(&mut db, &keys, PopSide::Min, 1);
+        assert_eq!(reply, ReplyFrame::Null);
+
+        let reply = add(
+            &mut db,
+            keys[1].clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = multi_pop(&mut db, &keys, PopSide::Max, 2);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"myzset".to_vec()),
+                ReplyFrame::Array(vec![
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(b"three".to_vec()),
+                        ReplyFrame::Double(3.0),
+                    ]),
+                    ReplyFrame::Array(vec![
+                        ReplyFrame::Bulk(b"two".to_vec()),
+                        ReplyFrame::Double(2.0),
+                    ]),
+                ]),
+            ])
+        );
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the scores associated with the specified members in the sorted set stored at key.
+///
+/// For every member that does not exist in the sorted set, a nil value is returned.
+///
+/// Reply, one of the following:
+/// - Nil reply: if the member does not exist in the sorted set.
+/// - Array reply: a list of string score values.
+pub fn multi_score(db: &Db, key: &str, members: &[Vec<u8>]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => {
+            let scores = members
+                .iter()
+                .map(|member| {
+                    zset.score(member)
+                        .map_or(ReplyFrame::Null, ReplyFrame::Double)
+                })
+                .collect();
+            ReplyFrame::Array(scores)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(vec![ReplyFrame::Null; members.len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::multi_score::multi_score;
+
+    #[test]
+    fn test_multi_score() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = multi_score(
+            &db,
+            &key,
+            &[b"one".to_vec(), b"two".to_vec(), b"nofield".to_vec()],
+        );
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Double(1.0),
+                ReplyFrame::Double(2.0),
+                ReplyFrame::Null,
+            ])
+        );
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::PopSide;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::{remove_if_empty, SortedSetObject};
+
+/// Removes and returns up to count members with the lowest scores (`ZPOPMIN`)
+/// or the highest scores (`ZPOPMAX`) in the sorted set stored at key.
+///
+/// When left unspecified, the default value for count is 1.
+///
+/// Reply:
+/// - Array reply: a list of popped elements and scores.
+pub fn pop(db: &mut Db, key: &str, side: PopSide, count: Option<usize>) -> ReplyFrame {
+    let elements = match db.get_mut(key) {
+        Some(MemObject::SortedSet(zset)) => pop_elements(zset, side, count.unwrap_or(1)),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::Array(Vec::new()),
+    };
+    remove_if_empty(db, key);
+
+    let mut array = Vec::with_capacity(elements.len() * 2);
+    for (member, score) in elements {
+        array.push(ReplyFrame::Bulk(member));
+        array.push(ReplyFrame::Double(score));
+    }
+    ReplyFrame::Array(array)
+}
+
+/// Pop up to `count` elements from one side of the sorted set.
+pub fn pop_elements(
+    zset: &mut SortedSetObject,
+    side: PopSide,
+    count: usize,
+) -> Vec<(Vec<u8>, f64)> {
+    (0..count)
+        .map_while(|_| match side {
+            PopSide::Min => zset.pop_first(),
+            PopSide::Max => zset.pop_last(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, PopSide};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::pop::pop;
+
+    #[test]
+    fn test_pop() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        let reply = pop(&mut db, &key, PopSide::Min, None);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"one".to_vec()),
+                ReplyFrame::Double(1.0),
+            ])
+        );
+        let reply = pop(&mut db, &key, PopSide::Max, Some(5));
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"three".to_vec()),
+                ReplyFrame::Double(3.0),
+                ReplyFrame::Bulk(b"two".to_vec()),
+                ReplyFrame::Double(2.0),
+            ])
+        );
+        assert!(!db.contains_key(&key));
+        let reply = pop(&mut db, &key, PopSide::Max, None);
+        assert_eq!(reply, ReplyFrame::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use rand::seq::{IteratorRandom, SliceRandom};
+
+use crate::cmd::reply_frame::{ReplyFrame, OOM_ERR, VALUE_OUT_OF_RANGE_ERR};
+use crate::mem::db::{Db, MemObject};
+
+/// When called with just the key argument, return a random element from the sorted set
+/// value stored at key.
+///
+/// If the provided count argument is positive, return an array of distinct elements.
+/// The array's length is either count or the sorted set's cardinality (ZCARD),
+/// whichever is lower.
+///
+/// If called with a negative count, the behavior changes and the command is allowed
+/// to return the same element multiple times. In this case, the number of returned
+/// elements is the absolute value of the specified count.
+///
+/// The optional WITHSCORES modifier changes the reply so it includes the respective scores
+/// of the randomly selected elements from the sorted set.
+///
+/// Reply, any of the following:
+/// - Nil reply: if the key doesn't exist.
+/// - Bulk string reply: without the additional count argument,
+///   the command returns a randomly selected member.
+/// - Array reply: when the additional count argument is passed, the command returns
+///   an array of members, or an array of members and their scores when WITHSCORES is used.
+pub fn random_member(db: &Db, key: &str, count: Option<(isize, bool)>) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => {
+            let mut rng = rand::thread_rng();
+            let Some((count, with_scores)) = count else {
+                return zset
+                    .iter()
+                    .choose(&mut rng)
+                    .map_or_else(ReplyFrame::null, |(member, _score)| {
+                        ReplyFrame::Bulk(member.to_vec())
+                    });
+            };
+
+            // Same limit as redis, to avoid overflow when computing reply length.
+            if count < -(isize::MAX / 2) {
+                return ReplyFrame::ConstError(VALUE_OUT_OF_RANGE_ERR);
+            }
+            let elements: Vec<_> = zset.iter().collect();
+            let num_picked = if count >= 0 {
+                count.unsigned_abs().min(elements.len())
+            } else if elements.is_empty() {
+                0
+            } else {
+                count.unsigned_abs()
+            };
+            let mut array = Vec::new();
+            let reply_len = if with_scores {
+                num_picked * 2
+            } else {
+                num_picked
+            };
+            if array.try_reserve_exact(reply_len).is_err() {
+                return ReplyFrame::ConstError(OOM_ERR);
+            }
+
+            let mut push_element = |(member, score): &(&[u8], f64)| {
+                array.push(ReplyFrame::Bulk(member.to_vec()));
+                if with_scores {
+                    array.push(ReplyFrame::Double(*score));
+                }
+            };
+            if count >= 0 {
+                elements
+                    .choose_multiple(&mut rng, num_picked)
+                    .for_each(&mut push_element);
+            } else {
+                for _i in 0..num_picked {
+                    if let Some(element) = elements.choose(&mut rng) {
+                        push_element(element);
+                    }
+                }
+            }
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => {
+            if count.is_some() {
+                ReplyFrame::Array(Vec::new())
+            } else {
+                ReplyFrame::Null
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::{ReplyFrame, VALUE_OUT_OF_RANGE_ERR};
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::random_member::random_member;
+
+    #[test]
+    fn test_random_member() {
+        let mut db = Db::new();
+        let key = "dadi".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![(1.0, b"uno".to_vec()), (2.0, b"due".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = random_member(&db, &key, None);
+        assert!(matches!(reply, ReplyFrame::Bulk(_)));
+        let reply = random_member(&db, &key, Some((5, true)));
+        assert!(matches!(reply, ReplyFrame::Array(ref array) if array.len() == 4));
+        let reply = random_member(&db, &key, Some((-5, false)));
+        assert!(matches!(reply, ReplyFrame::Array(ref array) if array.len() == 5));
+        let reply = random_member(&db, &key, Some((-isize::MAX, false)));
+        assert_eq!(reply, ReplyFrame::ConstError(VALUE_OUT_OF_RANGE_ERR));
+        let reply = random_member(&db, "nonexist", None);
+        assert_eq!(reply, ReplyFrame::Null);
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::{RangeBy, RangeOptions};
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::SortedSetObject;
+
+/// Returns the specified range of elements in the sorted set stored at key.
+///
+/// `ZRANGE` can perform different types of range queries: by index (rank),
+/// by the score, or by lexicographical order.
+///
+/// Options:
+/// - BYSCORE: returns the range of elements from the sorted set having scores
+///   equal or between `start` and `stop`.
+/// - BYLEX: returns the range of elements between the `start` and `stop` lexicographical
+///   closed range intervals, when all elements are inserted with the same score.
+/// - REV: reverses the ordering, so elements are ordered from high to low score.
+///   For BYSCORE and BYLEX, `start` is the max value and `stop` is the min value.
+/// - LIMIT offset count: only valid with BYSCORE or BYLEX, a negative count returns
+///   all elements from the offset.
+/// - WITHSCORES: supplements the reply with scores of elements.
+///
+/// Reply:
+/// - Array reply: a list of members in the specified range with, optionally,
+///   their scores when the WITHSCORES option is given.
+pub fn range(db: &Db, key: &str, options: &RangeOptions) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => {
+            let elements = range_elements(zset, options);
+            let mut array = Vec::with_capacity(elements.len());
+            for (member, score) in elements {
+                array.push(ReplyFrame::Bulk(member.to_vec()));
+                if options.with_scores {
+                    array.push(ReplyFrame::Double(score));
+                }
+            }
+            ReplyFrame::Array(array)
+        }
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Array(Vec::new()),
+    }
+}
+
+/// Returns `(member, score)` pairs of elements in the specified range.
+pub fn range_elements<'a>(
+    zset: &'a SortedSetObject,
+    options: &'a RangeOptions,
+) -> Vec<(&'a [u8], f64)> {
+    let reverse = options.reverse;
+    let iter = match &options.range_by {
+        RangeBy::Rank(start, stop) => return zset.range_by_rank(*start, *stop, reverse).collect(),
+        RangeBy::Score(start, stop) => {
+            let (min, max) = if reverse {
+                (*stop, *start)
+            } else {
+                (*start, *stop)
+            };
+            zset.range_by_score(min, max, reverse)
+        }
+        RangeBy::Lex(start, stop) => {
+            let (min, max) = if reverse {
+                (stop, start)
+            } else {
+                (start, stop)
+            };
+            zset.range_by_lex(min, max, reverse)
+        }
+    };
+    match options.limit {
+        Some((offset, _count)) if offset < 0 => Vec::new(),
+        Some((offset, count)) => {
+            let iter = iter.skip(offset.unsigned_abs());
+            if count < 0 {
+                iter.collect()
+            } else {
+                iter.take(count.unsigned_abs()).collect()
+            }
+        }
+        None => iter.collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, LexBound, RangeBy, RangeOptions, ScoreBound};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::range::range;
+
+    fn bulks(members: &[&[u8]]) -> ReplyFrame {
+        ReplyFrame::Array(
+            members
+                .iter()
+                .map(|member| ReplyFrame::Bulk(member.to_vec()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_range() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+
+        let mut options = RangeOptions {
+            range_by: RangeBy::Rank(0, -1),
+            reverse: false,
+            limit: None,
+            with_scores: false,
+        };
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"one", b"two", b"three"]));
+        options.range_by = RangeBy::Rank(-2, -1);
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"two", b"three"]));
+
+        options.range_by = RangeBy::Rank(0, 1);
+        options.with_scores = true;
+        let reply = range(&db, &key, &options);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"one".to_vec()),
+                ReplyFrame::Double(1.0),
+                ReplyFrame::Bulk(b"two".to_vec()),
+                ReplyFrame::Double(2.0),
+            ])
+        );
+
+        options.with_scores = false;
+        options.range_by = RangeBy::Score(
+            ScoreBound::Inclusive(f64::INFINITY),
+            ScoreBound::Exclusive(1.0),
+        );
+        options.reverse = true;
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"three", b"two"]));
+        options.limit = Some((1, 1));
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"two"]));
+    }
+
+    #[test]
+    fn test_range_by_lex() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let elements = [b"a", b"b", b"c", b"d", b"e", b"f", b"g"]
+            .iter()
+            .map(|member| (0.0, member.to_vec()))
+            .collect();
+        let reply = add(&mut db, key.clone(), AddOptions::default(), elements);
+        assert_eq!(reply, ReplyFrame::Usize(7));
+
+        let mut options = RangeOptions {
+            range_by: RangeBy::Lex(LexBound::Min, LexBound::Inclusive(b"c".to_vec())),
+            reverse: false,
+            limit: None,
+            with_scores: false,
+        };
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"a", b"b", b"c"]));
+        options.range_by = RangeBy::Lex(
+            LexBound::Exclusive(b"c".to_vec()),
+            LexBound::Exclusive(b"aaa".to_vec()),
+        );
+        options.reverse = true;
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"b"]));
+        options.range_by = RangeBy::Lex(LexBound::Inclusive(b"aaa".to_vec()), LexBound::Max);
+        options.reverse = false;
+        options.limit = Some((2, -1));
+        let reply = range(&db, &key, &options);
+        assert_eq!(reply, bulks(&[b"d", b"e", b"f", b"g"]));
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::RangeOptions;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::range::range_elements;
+use crate::mem::zset::store;
+
+/// This command is like `ZRANGE`, but stores the result in the destination key.
+///
+/// If destination already exists, it is overwritten.
+///
+/// Reply:
+/// - Integer reply: the number of elements in the resulting sorted set.
+pub fn range_store(
+    db: &mut Db,
+    destination: String,
+    key: &str,
+    options: &RangeOptions,
+) -> ReplyFrame {
+    let elements = match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => range_elements(zset, options)
+            .into_iter()
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect(),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => Vec::new(),
+    };
+    store(db, destination, elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, RangeBy, RangeOptions};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::range_store::range_store;
+    use crate::mem::zset::score::score;
+
+    #[test]
+    fn test_range_store() {
+        let mut db = Db::new();
+        let key = "srczset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+                (4.0, b"four".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(4));
+        let destination = "dstzset".to_owned();
+        let options = RangeOptions {
+            range_by: RangeBy::Rank(2, -1),
+            reverse: false,
+            limit: None,
+            with_scores: false,
+        };
+        let reply = range_store(&mut db, destination.clone(), &key, &options);
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        assert_eq!(score(&db, &destination, b"three"), ReplyFrame::Double(3.0));
+        assert_eq!(score(&db, &destination, b"one"), ReplyFrame::Null);
+    }
+}
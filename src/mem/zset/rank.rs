@@ -0,0 +1,73 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the rank of member in the sorted set stored at key.
+///
+/// With `ZRANK` the scores are ordered from low to high, with `ZREVRANK` from high to low.
+/// The rank (or index) is 0-based, which means that the member with the lowest score
+/// (or highest for `ZREVRANK`) has rank 0.
+///
+/// The optional WITHSCORE argument supplements the command's reply with the score
+/// of the element returned.
+///
+/// Reply, one of the following:
+/// - Nil reply: if the key does not exist or the member does not exist in the sorted set.
+/// - Integer reply: the rank of the member when WITHSCORE is not used.
+/// - Array reply: the rank and score of the member when WITHSCORE is used.
+pub fn rank(db: &Db, key: &str, member: &[u8], reverse: bool, with_score: bool) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => match zset.rank(member, reverse) {
+            Some((rank, score)) if with_score => {
+                ReplyFrame::Array(vec![ReplyFrame::Usize(rank), ReplyFrame::Double(score)])
+            }
+            Some((rank, _score)) => ReplyFrame::Usize(rank),
+            None => ReplyFrame::Null,
+        },
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::rank::rank;
+
+    #[test]
+    fn test_rank() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(3));
+        assert_eq!(
+            rank(&db, &key, b"three", false, false),
+            ReplyFrame::Usize(2)
+        );
+        assert_eq!(rank(&db, &key, b"four", false, false), ReplyFrame::Null);
+        assert_eq!(
+            rank(&db, &key, b"three", false, true),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(2), ReplyFrame::Double(3.0)])
+        );
+        assert_eq!(rank(&db, &key, b"one", true, false), ReplyFrame::Usize(2));
+        assert_eq!(
+            rank(&db, &key, b"two", true, true),
+            ReplyFrame::Array(vec![ReplyFrame::Usize(1), ReplyFrame::Double(2.0)])
+        );
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::remove_if_empty;
+
+/// Removes the specified members from the sorted set stored at key.
+///
+/// Non existing members are ignored.
+///
+/// An error is returned when key exists and does not hold a sorted set.
+///
+/// Reply:
+/// - Integer reply: the number of members removed from the sorted set,
+///   not including non-existing members.
+pub fn remove(db: &mut Db, key: &str, members: &[Vec<u8>]) -> ReplyFrame {
+    let count = match db.get_mut(key) {
+        Some(MemObject::SortedSet(zset)) => members
+            .iter()
+            .filter(|member| zset.remove(member).is_some())
+            .count(),
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::zero(),
+    };
+    remove_if_empty(db, key);
+    ReplyFrame::Usize(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::AddOptions;
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::remove::remove;
+
+    #[test]
+    fn test_remove() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = remove(&mut db, &key, &[b"two".to_vec(), b"four".to_vec()]);
+        assert_eq!(reply, ReplyFrame::one());
+        let reply = remove(&mut db, &key, &[b"one".to_vec()]);
+        assert_eq!(reply, ReplyFrame::one());
+        assert!(!db.contains_key(&key));
+    }
+}
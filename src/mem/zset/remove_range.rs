@@ -0,0 +1,83 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::RangeBy;
+use crate::mem::db::{Db, MemObject};
+use crate::mem::zset::remove_if_empty;
+
+/// Removes all elements in the sorted set stored at key within the specified range.
+///
+/// - `ZREMRANGEBYRANK` removes elements with rank between start and stop,
+///   negative ranks are offsets from the element with the highest score.
+/// - `ZREMRANGEBYSCORE` removes elements with a score between min and max (inclusive).
+/// - `ZREMRANGEBYLEX` removes elements between the lexicographical range specified
+///   by min and max, when all elements have the same score.
+///
+/// Reply:
+/// - Integer reply: the number of members removed.
+pub fn remove_range(db: &mut Db, key: &str, range_by: &RangeBy) -> ReplyFrame {
+    let count = match db.get_mut(key) {
+        Some(MemObject::SortedSet(zset)) => {
+            let members: Vec<Vec<u8>> = match range_by {
+                RangeBy::Rank(start, stop) => zset.range_by_rank(*start, *stop, false),
+                RangeBy::Score(min, max) => zset.range_by_score(*min, *max, false),
+                RangeBy::Lex(min, max) => zset.range_by_lex(min, max, false),
+            }
+            .map(|(member, _score)| member.to_vec())
+            .collect();
+            for member in &members {
+                zset.remove(member);
+            }
+            members.len()
+        }
+        Some(_) => return ReplyFrame::wrong_type_err(),
+        None => return ReplyFrame::zero(),
+    };
+    remove_if_empty(db, key);
+    ReplyFrame::Usize(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, LexBound, RangeBy, ScoreBound};
+    use crate::mem::db::Db;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::card::card;
+    use crate::mem::zset::remove_range::remove_range;
+
+    #[test]
+    fn test_remove_range() {
+        let mut db = Db::new();
+        let key = "myzset".to_owned();
+        let reply = add(
+            &mut db,
+            key.clone(),
+            AddOptions::default(),
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+                (4.0, b"four".to_vec()),
+            ],
+        );
+        assert_eq!(reply, ReplyFrame::Usize(4));
+        let reply = remove_range(&mut db, &key, &RangeBy::Rank(0, 1));
+        assert_eq!(reply, ReplyFrame::Usize(2));
+        let reply = remove_range(
+            &mut db,
+            &key,
+            &RangeBy::Score(
+                ScoreBound::Inclusive(f64::NEG_INFINITY),
+                ScoreBound::Exclusive(4.0),
+            ),
+        );
+        assert_eq!(reply, ReplyFrame::one());
+        assert_eq!(card(&db, &key), ReplyFrame::one());
+        let reply = remove_range(&mut db, &key, &RangeBy::Lex(LexBound::Min, LexBound::Max));
+        assert_eq!(reply, ReplyFrame::one());
+        assert!(!db.contains_key(&key));
+    }
+}
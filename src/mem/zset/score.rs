@@ -0,0 +1,24 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::mem::db::{Db, MemObject};
+
+/// Returns the score of member in the sorted set at key.
+///
+/// If member does not exist in the sorted set, or key does not exist, nil is returned.
+///
+/// Reply, one of the following:
+/// - Bulk string reply: the score of the member (a double-precision floating point number),
+///   represented as a string.
+/// - Nil reply: if member does not exist in the sorted set, or the key does not exist.
+pub fn score(db: &Db, key: &str, member: &[u8]) -> ReplyFrame {
+    match db.get(key) {
+        Some(MemObject::SortedSet(zset)) => zset
+            .score(member)
+            .map_or(ReplyFrame::Null, ReplyFrame::Double),
+        Some(_) => ReplyFrame::wrong_type_err(),
+        None => ReplyFrame::Null,
+    }
+}
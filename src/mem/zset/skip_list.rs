@@ -0,0 +1,467 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! A skip list ordered by `(score, member)`, similar to redis zskiplist.
+//!
+//! Each forward link records its span, the number of nodes it skips over,
+//! so that rank of a node and node at a specific rank can be found in `O(log(N))`.
+//!
+//! Nodes are stored in an arena and linked by indices, the header node is at index 0.
+
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+/// Max level of nodes, enough for 2^64 elements.
+const MAX_LEVEL: usize = 32;
+
+/// Probability of a node to be promoted to next level.
+const LEVEL_PROBABILITY: f64 = 0.25;
+
+/// Index of the header node.
+const HEAD: usize = 0;
+
+/// Placeholder of null link.
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    forward: usize,
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    member: Vec<u8>,
+    score: f64,
+    backward: usize,
+    levels: Vec<Level>,
+}
+
+impl Node {
+    fn new(member: Vec<u8>, score: f64, level: usize) -> Self {
+        Self {
+            member,
+            score,
+            backward: NIL,
+            levels: vec![
+                Level {
+                    forward: NIL,
+                    span: 0,
+                };
+                level
+            ],
+        }
+    }
+
+    /// Compare node with `(score, member)` pair.
+    fn cmp(&self, score: f64, member: &[u8]) -> Ordering {
+        self.score
+            .total_cmp(&score)
+            .then_with(|| self.member.as_slice().cmp(member))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SkipList {
+    nodes: Vec<Node>,
+    free_nodes: Vec<usize>,
+    tail: usize,
+    len: usize,
+    level: usize,
+}
+
+impl Default for SkipList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SkipList {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(Vec::new(), 0.0, MAX_LEVEL)],
+            free_nodes: Vec::new(),
+            tail: NIL,
+            len: 0,
+            level: 1,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a new node, the `(score, member)` pair must not exist in list.
+    pub fn insert(&mut self, score: f64, member: Vec<u8>) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0_usize; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+            loop {
+                let level = self.nodes[x].levels[i];
+                if level.forward != NIL
+                    && self.nodes[level.forward].cmp(score, &member) == Ordering::Less
+                {
+                    rank[i] += level.span;
+                    x = level.forward;
+                } else {
+                    break;
+                }
+            }
+            update[i] = x;
+        }
+
+        let level = Self::random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let new_node = self.alloc_node(Node::new(member, score, level));
+        for i in 0..level {
+            let prev_level = self.nodes[update[i]].levels[i];
+            self.nodes[new_node].levels[i] = Level {
+                forward: prev_level.forward,
+                span: prev_level.span - (rank[0] - rank[i]),
+            };
+            self.nodes[update[i]].levels[i] = Level {
+                forward: new_node,
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+        for (i, &prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[prev].levels[i].span += 1;
+        }
+
+        self.nodes[new_node].backward = if update[0] == HEAD { NIL } else { update[0] };
+        let next = self.nodes[new_node].levels[0].forward;
+        if next == NIL {
+            self.tail = new_node;
+        } else {
+            self.nodes[next].backward = new_node;
+        }
+        self.len += 1;
+    }
+
+    /// Remove node with `(score, member)` pair, returns false if not found.
+    pub fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            loop {
+                let forward = self.nodes[x].levels[i].forward;
+                if forward != NIL && self.nodes[forward].cmp(score, member) == Ordering::Less {
+                    x = forward;
+                } else {
+                    break;
+                }
+            }
+            update[i] = x;
+        }
+
+        let x = self.nodes[x].levels[0].forward;
+        if x == NIL || self.nodes[x].cmp(score, member) != Ordering::Equal {
+            return false;
+        }
+
+        for (i, &prev) in update.iter().enumerate().take(self.level) {
+            let x_level = self.nodes[x].levels.get(i).copied();
+            let prev_level = &mut self.nodes[prev].levels[i];
+            match x_level {
+                Some(x_level) if prev_level.forward == x => {
+                    prev_level.span += x_level.span;
+                    prev_level.span -= 1;
+                    prev_level.forward = x_level.forward;
+                }
+                _ => prev_level.span -= 1,
+            }
+        }
+        let next = self.nodes[x].levels[0].forward;
+        let backward = self.nodes[x].backward;
+        if next == NIL {
+            self.tail = backward;
+        } else {
+            self.nodes[next].backward = backward;
+        }
+        while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].forward == NIL {
+            self.level -= 1;
+        }
+        self.len -= 1;
+        self.free_node(x);
+        true
+    }
+
+    /// Returns 0-based rank of `(score, member)` pair.
+    #[must_use]
+    pub fn rank(&self, score: f64, member: &[u8]) -> Option<usize> {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            loop {
+                let level = self.nodes[x].levels[i];
+                if level.forward != NIL
+                    && self.nodes[level.forward].cmp(score, member) != Ordering::Greater
+                {
+                    rank += level.span;
+                    x = level.forward;
+                } else {
+                    break;
+                }
+            }
+            if x != HEAD && self.nodes[x].cmp(score, member) == Ordering::Equal {
+                return Some(rank - 1);
+            }
+        }
+        None
+    }
+
+    /// Returns node at 0-based `rank`.
+    fn node_by_rank(&self, rank: usize) -> Option<usize> {
+        let target = rank.checked_add(1)?;
+        let mut traversed = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            loop {
+                let level = self.nodes[x].levels[i];
+                if level.forward != NIL && traversed + level.span <= target {
+                    traversed += level.span;
+                    x = level.forward;
+                } else {
+                    break;
+                }
+            }
+            if traversed == target {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    /// Returns the first node which is not before the range, along with its rank.
+    ///
+    /// `before_range` must be true for a prefix of nodes and false for the rest.
+    fn first_node_where<F>(&self, before_range: F) -> Option<(usize, usize)>
+    where
+        F: Fn(f64, &[u8]) -> bool,
+    {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            loop {
+                let level = self.nodes[x].levels[i];
+                if level.forward != NIL && {
+                    let node = &self.nodes[level.forward];
+                    before_range(node.score, &node.member)
+                } {
+                    rank += level.span;
+                    x = level.forward;
+                } else {
+                    break;
+                }
+            }
+        }
+        let x = self.nodes[x].levels[0].forward;
+        (x != NIL).then_some((x, rank))
+    }
+
+    /// Returns the last node which is not after the range, along with its rank.
+    ///
+    /// `in_or_before_range` must be true for a prefix of nodes and false for the rest.
+    fn last_node_where<F>(&self, in_or_before_range: F) -> Option<(usize, usize)>
+    where
+        F: Fn(f64, &[u8]) -> bool,
+    {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            loop {
+                let level = self.nodes[x].levels[i];
+                if level.forward != NIL && {
+                    let node = &self.nodes[level.forward];
+                    in_or_before_range(node.score, &node.member)
+                } {
+                    rank += level.span;
+                    x = level.forward;
+                } else {
+                    break;
+                }
+            }
+        }
+        (x != HEAD).then(|| (x, rank - 1))
+    }
+
+    /// Returns an iterator starting from node at 0-based `rank`.
+    #[must_use]
+    pub fn iter_from_rank(&self, rank: usize, reverse: bool) -> Iter<'_> {
+        let node = self.node_by_rank(rank).unwrap_or(NIL);
+        Iter {
+            list: self,
+            node,
+            reverse,
+        }
+    }
+
+    /// Returns rank of the first node for which `before_range` returns false,
+    /// and an iterator starting from it.
+    pub fn iter_from_first<F>(&self, before_range: F, reverse: bool) -> (usize, Iter<'_>)
+    where
+        F: Fn(f64, &[u8]) -> bool,
+    {
+        let (node, rank) = self
+            .first_node_where(before_range)
+            .unwrap_or((NIL, self.len));
+        let iter = Iter {
+            list: self,
+            node,
+            reverse,
+        };
+        (rank, iter)
+    }
+
+    /// Returns rank of the last node for which `in_or_before_range` returns true,
+    /// and an iterator starting from it.
+    pub fn iter_from_last<F>(
+        &self,
+        in_or_before_range: F,
+        reverse: bool,
+    ) -> (Option<usize>, Iter<'_>)
+    where
+        F: Fn(f64, &[u8]) -> bool,
+    {
+        let (node, rank) = self
+            .last_node_where(in_or_before_range)
+            .map_or((NIL, None), |(node, rank)| (node, Some(rank)));
+        let iter = Iter {
+            list: self,
+            node,
+            reverse,
+        };
+        (rank, iter)
+    }
+
+    fn random_level() -> usize {
+        let mut rng = rand::thread_rng();
+        let mut level = 1;
+        while level < MAX_LEVEL && rng.gen_bool(LEVEL_PROBABILITY) {
+            level += 1;
+        }
+        level
+    }
+
+    fn alloc_node(&mut self, node: Node) -> usize {
+        if let Some(index) = self.free_nodes.pop() {
+            self.nodes[index] = node;
+            index
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    fn free_node(&mut self, index: usize) {
+        self.nodes[index] = Node::new(Vec::new(), 0.0, 0);
+        self.free_nodes.push(index);
+    }
+}
+
+/// Iterates `(member, score)` pairs from a node, in ascending or descending order.
+pub struct Iter<'a> {
+    list: &'a SkipList,
+    node: usize,
+    reverse: bool,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.node == NIL || self.node == HEAD {
+            return None;
+        }
+        let node = &self.list.nodes[self.node];
+        self.node = if self.reverse {
+            node.backward
+        } else {
+            node.levels[0].forward
+        };
+        Some((node.member.as_slice(), node.score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkipList;
+
+    fn collect(list: &SkipList) -> Vec<(Vec<u8>, f64)> {
+        list.iter_from_rank(0, false)
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list = SkipList::new();
+        for i in (0..1000).rev() {
+            list.insert(f64::from(i % 10), format!("m{i:04}").into_bytes());
+        }
+        assert_eq!(list.len(), 1000);
+        let values = collect(&list);
+        assert_eq!(values[0], (b"m0000".to_vec(), 0.0));
+        assert_eq!(values[1], (b"m0010".to_vec(), 0.0));
+        assert_eq!(values[999], (b"m0999".to_vec(), 9.0));
+
+        assert_eq!(list.rank(0.0, b"m0000"), Some(0));
+        assert_eq!(list.rank(9.0, b"m0999"), Some(999));
+        assert_eq!(list.rank(5.0, b"m0015"), Some(501));
+        assert_eq!(list.rank(5.0, b"m0016"), None);
+
+        for i in 0..500 {
+            assert!(list.remove(f64::from(i % 10), format!("m{i:04}").as_bytes()));
+        }
+        assert!(!list.remove(0.0, b"m0000"));
+        assert_eq!(list.len(), 500);
+        assert_eq!(list.rank(0.0, b"m0500"), Some(0));
+        let (member, score) = list.iter_from_rank(499, false).next().unwrap();
+        assert_eq!((member, score), (&b"m0999"[..], 9.0));
+        let reversed: Vec<_> = list.iter_from_rank(499, true).take(2).collect();
+        assert_eq!(reversed, vec![(&b"m0999"[..], 9.0), (&b"m0989"[..], 9.0)]);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut list = SkipList::new();
+        for i in 0..100 {
+            list.insert(f64::from(i), i.to_string().into_bytes());
+        }
+        let (rank, mut iter) = list.iter_from_first(|score, _member| score < 10.5, false);
+        assert_eq!(rank, 11);
+        assert_eq!(iter.next(), Some((&b"11"[..], 11.0)));
+        let (rank, mut iter) = list.iter_from_last(|score, _member| score <= 20.0, true);
+        assert_eq!(rank, Some(20));
+        assert_eq!(iter.next(), Some((&b"20"[..], 20.0)));
+        assert_eq!(iter.next(), Some((&b"19"[..], 19.0)));
+
+        let (rank, mut iter) = list.iter_from_first(|_score, _member| true, false);
+        assert_eq!(rank, 100);
+        assert_eq!(iter.next(), None);
+        let (rank, mut iter) = list.iter_from_last(|_score, _member| false, false);
+        assert_eq!(rank, None);
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+//! Sorted set object, elements are ordered by score, then by member lexicographically.
+//!
+//! Like redis, a dict maps members to scores, and a skip list keeps elements in order.
+
+use std::collections::HashMap;
+
+use crate::cmd::zset::{LexBound, ScoreBound};
+use crate::mem::zset::skip_list::SkipList;
+
+/// Iterator of `(member, score)` pairs.
+pub type Iter<'a> = Box<dyn Iterator<Item = (&'a [u8], f64)> + 'a>;
+
+#[derive(Debug, Default, Clone)]
+pub struct SortedSetObject {
+    dict: HashMap<Vec<u8>, f64>,
+    list: SkipList,
+}
+
+impl SortedSetObject {
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+
+    /// Name of internal encoding, as reported by `OBJECT ENCODING`.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::unused_self)]
+    pub const fn encoding(&self) -> &'static str {
+        "skiplist"
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.dict.get(member).copied()
+    }
+
+    /// Add a new member or update score of an existing member.
+    ///
+    /// Returns old score of the member.
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> Option<f64> {
+        let old_score = self.dict.insert(member.clone(), score);
+        if let Some(old_score) = old_score {
+            self.list.remove(old_score, &member);
+        }
+        self.list.insert(score, member);
+        old_score
+    }
+
+    /// Remove a member, returns its score.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let score = self.dict.remove(member)?;
+        self.list.remove(score, member);
+        Some(score)
+    }
+
+    /// Returns 0-based rank and score of member.
+    ///
+    /// If `reverse` is true, elements are ranked from highest score to lowest.
+    #[must_use]
+    pub fn rank(&self, member: &[u8], reverse: bool) -> Option<(usize, f64)> {
+        let score = self.score(member)?;
+        let rank = self.list.rank(score, member)?;
+        if reverse {
+            Some((self.len() - 1 - rank, score))
+        } else {
+            Some((rank, score))
+        }
+    }
+
+    /// Remove and return the element with the lowest score.
+    pub fn pop_first(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (member, score) = self.list.iter_from_rank(0, false).next()?;
+        let member = member.to_vec();
+        self.remove(&member);
+        Some((member, score))
+    }
+
+    /// Remove and return the element with the highest score.
+    pub fn pop_last(&mut self) -> Option<(Vec<u8>, f64)> {
+        let last = self.len().checked_sub(1)?;
+        let (member, score) = self.list.iter_from_rank(last, false).next()?;
+        let member = member.to_vec();
+        self.remove(&member);
+        Some((member, score))
+    }
+
+    /// Returns all elements in ascending order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_> {
+        Box::new(self.list.iter_from_rank(0, false))
+    }
+
+    /// Returns elements between inclusive 0-based `start` and `stop` ranks.
+    ///
+    /// Negative ranks are offsets from the end of sorted set.
+    #[must_use]
+    pub fn range_by_rank(&self, start: isize, stop: isize, reverse: bool) -> Iter<'_> {
+        let len = self.len();
+        let start = if start < 0 {
+            len.saturating_sub(start.unsigned_abs())
+        } else {
+            start.unsigned_abs()
+        };
+        let stop = if stop < 0 {
+            match len.checked_sub(stop.unsigned_abs()) {
+                Some(stop) => stop,
+                None => return Box::new(std::iter::empty()),
+            }
+        } else {
+            stop.unsigned_abs().min(len.saturating_sub(1))
+        };
+        if start > stop || start >= len {
+            return Box::new(std::iter::empty());
+        }
+
+        let count = stop - start + 1;
+        if reverse {
+            Box::new(self.list.iter_from_rank(len - 1 - start, true).take(count))
+        } else {
+            Box::new(self.list.iter_from_rank(start, false).take(count))
+        }
+    }
+
+    /// Returns elements with score between `min` and `max`.
+    #[must_use]
+    pub fn range_by_score(&self, min: ScoreBound, max: ScoreBound, reverse: bool) -> Iter<'_> {
+        if reverse {
+            let (_rank, iter) = self
+                .list
+                .iter_from_last(|score, _| max.lte_max(score), true);
+            Box::new(iter.take_while(move |(_, score)| min.gte_min(*score)))
+        } else {
+            let (_rank, iter) = self
+                .list
+                .iter_from_first(|score, _| !min.gte_min(score), false);
+            Box::new(iter.take_while(move |(_, score)| max.lte_max(*score)))
+        }
+    }
+
+    /// Returns elements with member between `min` and `max`.
+    ///
+    /// All elements are expected to have the same score.
+    #[must_use]
+    pub fn range_by_lex<'a>(
+        &'a self,
+        min: &'a LexBound,
+        max: &'a LexBound,
+        reverse: bool,
+    ) -> Iter<'a> {
+        if reverse {
+            let (_rank, iter) = self
+                .list
+                .iter_from_last(|_, member| max.lte_max(member), true);
+            Box::new(iter.take_while(move |(member, _)| min.gte_min(member)))
+        } else {
+            let (_rank, iter) = self
+                .list
+                .iter_from_first(|_, member| !min.gte_min(member), false);
+            Box::new(iter.take_while(move |(member, _)| max.lte_max(member)))
+        }
+    }
+
+    /// Returns number of elements with score between `min` and `max`.
+    #[must_use]
+    pub fn count(&self, min: ScoreBound, max: ScoreBound) -> usize {
+        let (first_rank, _iter) = self
+            .list
+            .iter_from_first(|score, _| !min.gte_min(score), false);
+        let (last_rank, _iter) = self
+            .list
+            .iter_from_last(|score, _| max.lte_max(score), false);
+        last_rank.map_or(0, |last_rank| (last_rank + 1).saturating_sub(first_rank))
+    }
+}
+
+impl FromIterator<(Vec<u8>, f64)> for SortedSetObject {
+    fn from_iter<T: IntoIterator<Item = (Vec<u8>, f64)>>(iter: T) -> Self {
+        let mut zset = Self::new();
+        for (member, score) in iter {
+            zset.insert(member, score);
+        }
+        zset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::zset::{LexBound, ScoreBound};
+    use crate::mem::zset::SortedSetObject;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut zset = SortedSetObject::new();
+        assert_eq!(zset.insert(b"a".to_vec(), 1.0), None);
+        assert_eq!(zset.insert(b"b".to_vec(), 2.0), None);
+        assert_eq!(zset.insert(b"c".to_vec(), 3.0), None);
+        assert_eq!(zset.insert(b"a".to_vec(), 4.0), Some(1.0));
+        assert_eq!(zset.len(), 3);
+        assert_eq!(zset.rank(b"a", false), Some((2, 4.0)));
+        assert_eq!(zset.rank(b"a", true), Some((0, 4.0)));
+        assert_eq!(zset.remove(b"b"), Some(2.0));
+        assert_eq!(zset.remove(b"b"), None);
+        let members: Vec<_> = zset.iter().collect();
+        assert_eq!(members, vec![(&b"c"[..], 3.0), (&b"a"[..], 4.0)]);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut zset = SortedSetObject::new();
+        for (i, member) in [b"a", b"b", b"c", b"d", b"e"].iter().enumerate() {
+            zset.insert(member.to_vec(), 1.0 + f64::from(u32::try_from(i).unwrap()));
+        }
+        let members: Vec<_> = zset.range_by_rank(-2, -1, false).map(|(m, _)| m).collect();
+        assert_eq!(members, vec![&b"d"[..], &b"e"[..]]);
+        let members: Vec<_> = zset.range_by_rank(0, 1, true).map(|(m, _)| m).collect();
+        assert_eq!(members, vec![&b"e"[..], &b"d"[..]]);
+        assert_eq!(zset.range_by_rank(3, 1, false).count(), 0);
+
+        let members: Vec<_> = zset
+            .range_by_score(
+                ScoreBound::Exclusive(1.0),
+                ScoreBound::Inclusive(3.0),
+                false,
+            )
+            .map(|(m, _)| m)
+            .collect();
+        assert_eq!(members, vec![&b"b"[..], &b"c"[..]]);
+        let members: Vec<_> = zset
+            .range_by_score(ScoreBound::Inclusive(2.0), ScoreBound::Exclusive(4.0), true)
+            .map(|(m, _)| m)
+            .collect();
+        assert_eq!(members, vec![&b"c"[..], &b"b"[..]]);
+        assert_eq!(
+            zset.count(
+                ScoreBound::Inclusive(f64::NEG_INFINITY),
+                ScoreBound::Inclusive(f64::INFINITY)
+            ),
+            5
+        );
+        assert_eq!(
+            zset.count(ScoreBound::Exclusive(2.0), ScoreBound::Exclusive(3.0)),
+            0
+        );
+
+        let mut zset = SortedSetObject::new();
+        for member in [b"a", b"b", b"c", b"d"] {
+            zset.insert(member.to_vec(), 0.0);
+        }
+        let min = LexBound::Exclusive(b"a".to_vec());
+        let max = LexBound::Inclusive(b"c".to_vec());
+        let members: Vec<_> = zset
+            .range_by_lex(&min, &max, false)
+            .map(|(m, _)| m)
+            .collect();
+        assert_eq!(members, vec![&b"b"[..], &b"c"[..]]);
+        let members: Vec<_> = zset
+            .range_by_lex(&LexBound::Min, &LexBound::Max, true)
+            .map(|(m, _)| m)
+            .collect();
+        assert_eq!(members.len(), 4);
+    }
+}
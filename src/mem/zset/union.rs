@@ -0,0 +1,127 @@
+// Copyright (c) 2024 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU Affero General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::cmd::reply_frame::ReplyFrame;
+use crate::cmd::zset::SetOperation;
+use crate::mem::db::Db;
+use crate::mem::zset::{aggregate_score, elements_reply, weighted_score, InputSet};
+
+/// This command is similar to `ZUNIONSTORE`, but instead of storing the resulting sorted set,
+/// it is returned to the client.
+///
+/// Keys that do not exist are considered to be empty sets, and members of sets
+/// are considered to have a score of 1.
+///
+/// Using the WEIGHTS option, it is possible to specify a multiplication factor
+/// for each input sorted set. With the AGGREGATE option, it is possible to specify
+/// how the results of the union are aggregated, defaults to SUM.
+///
+/// Reply:
+/// - Array reply: the result of the union with, optionally, their scores
+///   when WITHSCORES is used.
+pub fn union(db: &Db, operation: &SetOperation) -> ReplyFrame {
+    match union_elements(db, operation) {
+        Ok(elements) => elements_reply(elements, operation.with_scores),
+        Err(reply) => reply,
+    }
+}
+
+/// Returns union of input sets, or error reply if any of them is not a set or sorted set.
+pub fn union_elements(
+    db: &Db,
+    operation: &SetOperation,
+) -> Result<Vec<(Vec<u8>, f64)>, ReplyFrame> {
+    let inputs = InputSet::from_keys(db, &operation.keys)?;
+    let mut scores: HashMap<Vec<u8>, f64> = HashMap::new();
+    for (index, input) in inputs.iter().enumerate() {
+        let Some(input) = input else {
+            continue;
+        };
+        let weight = operation.weights.get(index).copied().unwrap_or(1.0);
+        for (member, score) in input.elements() {
+            let score = weighted_score(score, weight);
+            match scores.entry(member) {
+                Entry::Occupied(mut occupied) => {
+                    let current = occupied.get_mut();
+                    *current = aggregate_score(operation.aggregate, *current, score);
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(score);
+                }
+            }
+        }
+    }
+    Ok(scores.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::reply_frame::ReplyFrame;
+    use crate::cmd::zset::{AddOptions, Aggregate, SetOperation};
+    use crate::mem::db::Db;
+    use crate::mem::set;
+    use crate::mem::zset::add::add;
+    use crate::mem::zset::union::union;
+
+    #[test]
+    fn test_union() {
+        let mut db = Db::new();
+        let key1 = "zset1".to_owned();
+        let key2 = "zset2".to_owned();
+        let key3 = "set3".to_owned();
+        let options = AddOptions::default();
+        add(
+            &mut db,
+            key1.clone(),
+            options,
+            vec![(1.0, b"one".to_vec()), (2.0, b"two".to_vec())],
+        );
+        add(
+            &mut db,
+            key2.clone(),
+            options,
+            vec![
+                (1.0, b"one".to_vec()),
+                (2.0, b"two".to_vec()),
+                (3.0, b"three".to_vec()),
+            ],
+        );
+        let mut operation = SetOperation {
+            keys: vec![key1.clone(), key2.clone()],
+            weights: Vec::new(),
+            aggregate: Aggregate::Sum,
+            with_scores: true,
+        };
+        let reply = union(&db, &operation);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"one".to_vec()),
+                ReplyFrame::Double(2.0),
+                ReplyFrame::Bulk(b"three".to_vec()),
+                ReplyFrame::Double(3.0),
+                ReplyFrame::Bulk(b"two".to_vec()),
+                ReplyFrame::Double(4.0),
+            ])
+        );
+
+        set::add::add(&mut db, key3.clone(), vec![b"one".to_vec()]);
+        operation.keys = vec![key1, key3];
+        operation.weights = vec![2.0, 10.0];
+        operation.aggregate = Aggregate::Max;
+        let reply = union(&db, &operation);
+        assert_eq!(
+            reply,
+            ReplyFrame::Array(vec![
+                ReplyFrame::Bulk(b"two".to_vec()),
+                ReplyFrame::Double(4.0),
+                ReplyFrame::Bulk(b"one".to_vec()),
+                ReplyFrame::Double(10.0),
+            ])
+        );
+    }
+}
@@ -86,6 +86,7 @@ impl Session {
             };
             commands.push(command);
         }
+        self.skip_subscription_replies(&commands);
         let cmd = SessionToListenerCmd::Request {
             session_id: self.id,
             commands,
@@ -119,6 +120,44 @@ impl Session {
         Ok(())
     }
 
+    /// Subscription commands are replied with pushed frames, so do not wait for
+    /// replies of them.
+    fn skip_subscription_replies(&mut self, commands: &[Command]) {
+        let subscriptions = commands
+            .iter()
+            .filter(|command| command.is_subscription())
+            .count();
+        if subscriptions == 0 {
+            return;
+        }
+        if let Some(count) = self.frames_read.back_mut() {
+            *count = count.saturating_sub(subscriptions);
+            if *count == 0 {
+                self.frames_read.pop_back();
+            }
+        }
+    }
+
+    /// Write frames pushed by server to client immediately, without waiting for
+    /// replies of pending requests.
+    pub(super) async fn send_push_frames_to_client(
+        &mut self,
+        push_frames: Vec<ReplyFrame>,
+    ) -> Result<(), Error> {
+        log::debug!(
+            "{} length of push_frames: {}",
+            function_name!(),
+            push_frames.len()
+        );
+        let mut bytes = BytesMut::new();
+        for frame in &push_frames {
+            frame.to_bytes(&mut bytes);
+        }
+        self.stream.write(&bytes.freeze()).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
     pub(super) async fn send_frame_to_client(
         &mut self,
         reply_frame: ReplyFrame,
@@ -18,7 +18,11 @@ impl Session {
             function_name!()
         );
         assert_eq!(cmd.session_id, self.id);
-        self.send_frames_to_client(cmd.reply_frames).await
+        if cmd.push {
+            self.send_push_frames_to_client(cmd.reply_frames).await
+        } else {
+            self.send_frames_to_client(cmd.reply_frames).await
+        }
     }
 
     #[allow(clippy::unused_async)]
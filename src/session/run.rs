@@ -24,7 +24,12 @@ impl Session {
                         log::warn!("fuck err: {err:?}");
                     }
                 }
-                Some(cmd) = listener_receiver.recv() => {
+                cmd = listener_receiver.recv() => {
+                    let Some(cmd) = cmd else {
+                        // Listener closed this session, e.g. it is a too slow subscriber.
+                        self.status = Status::Disconnected;
+                        continue;
+                    };
                     if let Err(err) = self.handle_listener_cmd(cmd).await {
                         log::error!("Failed to handle server packet: {:?}", err);
                     }